@@ -0,0 +1,74 @@
+use std::env;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+///Embeds which build this is, so bug reports can name it. See `game_logic::version`.
+fn main() {
+  println!("cargo:rustc-env=TOK_GIT_HASH={}", git_hash());
+  println!("cargo:rustc-env=TOK_BUILD_DATE={}", build_date());
+  println!("cargo:rustc-env=TOK_FEATURES={}", features().join(","));
+
+  //rerun after a commit or checkout and after changes to the sources, which may make the build dirty
+  println!("cargo:rerun-if-changed=build.rs");
+  println!("cargo:rerun-if-changed=src");
+  println!("cargo:rerun-if-changed=../.git/HEAD");
+  println!("cargo:rerun-if-changed=../.git/refs");
+}
+
+///The abbreviated hash of the checked out commit, with `-dirty` if there are uncommitted changes
+fn git_hash() -> String {
+  let git = |args: &[&str]| {
+    Command::new("git")
+      .args(args)
+      .output()
+      .ok()
+      .filter(|output| output.status.success())
+      .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+  };
+  match git(&["rev-parse", "--short", "HEAD"]) {
+    Some(hash) if git(&["status", "--porcelain"]).is_some_and(|status| !status.is_empty()) => {
+      format!("{}-dirty", hash)
+    }
+    Some(hash) => hash,
+    //e.g. built from a source archive
+    None => "unknown".to_string(),
+  }
+}
+
+///Today's date like `2024-07-21`, in UTC
+fn build_date() -> String {
+  let days = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |since_epoch| since_epoch.as_secs() / 86400) as i64;
+
+  //convert days since 1970-01-01 to a calendar date, see http://howardhinnant.github.io/date_algorithms.html
+  let days = days + 719468;
+  let era = days.div_euclid(146097);
+  let day_of_era = days.rem_euclid(146097);
+  let year_of_era =
+    (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let shifted_month = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+  let month = if shifted_month < 10 {
+    shifted_month + 3
+  } else {
+    shifted_month - 9
+  };
+  let year = year_of_era + era * 400 + i64::from(month <= 2);
+  format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+///The enabled cargo features, like `clipboard` and `std`
+fn features() -> Vec<String> {
+  let mut features: Vec<String> = env::vars()
+    .filter_map(|(key, _)| {
+      key
+        .strip_prefix("CARGO_FEATURE_")
+        .map(|feature| feature.to_lowercase())
+    })
+    .filter(|feature| feature != "default")
+    .collect();
+  features.sort();
+  features
+}
@@ -1,10 +1,14 @@
-use std::fmt::{Debug, Display, Formatter};
-use std::ops::{Deref, DerefMut};
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+use core::ops::{Deref, DerefMut};
 
-use crate::position::{Move, Position};
+use crate::position::{Move, Num, Position};
+use crate::position::Dimension::{Column, Row};
+
+pub mod observed;
 
 ///An arena where something can stand
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct Arena<E>
 where
   E: Clone,
@@ -28,6 +32,33 @@ where
     self.enemies.retain(|enemy| enemy.deref() != at)
   }
 
+  ///Adds an enemy created by `enemy_factory` on each of the rows in the column
+  pub fn add_column<R, F>(&mut self, column: Num, rows: R, mut enemy_factory: F)
+  where
+    R: IntoIterator<Item = Num>,
+    F: FnMut(Position) -> E,
+  {
+    for row in rows {
+      self.add(enemy_factory(Position { row, column }));
+    }
+  }
+
+  pub fn clear_column(&mut self, column: Num) {
+    self.retain(|enemy| enemy.column != column)
+  }
+
+  pub fn clear_ring(&mut self, row: Num) {
+    self.retain(|enemy| enemy.row != row)
+  }
+
+  ///Keeps only the enemies for which the predicate returns true
+  pub fn retain<P>(&mut self, predicate: P)
+  where
+    P: FnMut(&E) -> bool,
+  {
+    self.enemies.retain(predicate)
+  }
+
   pub fn get_at(&self, at: &Position) -> Option<&E> {
     self.enemies.iter().find(|&enemy| enemy.deref() == at)
   }
@@ -39,6 +70,17 @@ where
       .find(|enemy| enemy.deref() as &Position == at)
   }
 
+  ///Returns the enemies which get moved by the given move
+  pub fn enemies_affected_by(&self, move_: &Move) -> Vec<&E> {
+    let affected = move_.affected_positions();
+    self
+      .enemies
+      .iter()
+      .filter(|enemy| affected.contains(enemy))
+      .collect()
+  }
+
+  #[cfg(feature = "std")]
   pub fn show(&self)
   where
     E: ToArenaSymbol,
@@ -56,6 +98,47 @@ where
       enemy.apply_move(move_);
     }
   }
+
+  ///Moves the enemies on the given rows of a column to the same rows of another column, replacing
+  /// the enemies there. Returns the number of moved enemies.
+  pub fn move_to_column(&mut self, from: Num, rows: &[Num], to: Num) -> usize {
+    let moved = self.copy_to_column(from, rows, to);
+    if from != to {
+      self.retain(|enemy| !(enemy.column == from && rows.contains(&enemy.row)));
+    }
+    moved
+  }
+
+  ///Copies the enemies on the given rows of a column to the same rows of another column, replacing
+  /// the enemies there. Returns the number of copied enemies.
+  pub fn copy_to_column(&mut self, from: Num, rows: &[Num], to: Num) -> usize {
+    let copies: Vec<E> = self
+      .enemies
+      .iter()
+      .filter(|enemy| enemy.column == from && rows.contains(&enemy.row))
+      .map(|enemy| {
+        let mut copy = enemy.clone();
+        copy.column = to;
+        copy
+      })
+      .collect();
+    let copied = copies.len();
+    for copy in copies {
+      self.add(copy);
+    }
+    copied
+  }
+
+  ///Exchanges the enemies of two columns
+  pub fn swap_columns(&mut self, a: Num, b: Num) {
+    for enemy in &mut self.enemies {
+      if enemy.column == a {
+        enemy.column = b;
+      } else if enemy.column == b {
+        enemy.column = a;
+      }
+    }
+  }
 }
 
 impl<E> Default for Arena<E>
@@ -73,7 +156,7 @@ impl<E> Display for Arena<E>
 where
   E: Clone + Deref<Target = Position> + ToArenaSymbol,
 {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
     let sym = |c, r| {
       if let Some(enemy) = self.get_at(&Position::at(r, c).expect("can not display")) {
         enemy.to_arena_symbol()
@@ -176,3 +259,255 @@ where
 pub trait ToArenaSymbol {
   fn to_arena_symbol(&self) -> char;
 }
+
+///Something placed on the arena which gets moved by the moves executed on it
+pub trait Layer {
+  type Item;
+
+  fn get_at(&self, at: &Position) -> Option<&Self::Item>;
+
+  fn apply_move(&mut self, move_: Move);
+}
+
+impl<E> Layer for Arena<E>
+where
+  E: Clone + DerefMut<Target = Position>,
+{
+  type Item = E;
+
+  fn get_at(&self, at: &Position) -> Option<&Self::Item> {
+    Arena::get_at(self, at)
+  }
+
+  fn apply_move(&mut self, move_: Move) {
+    Arena::apply_move(self, move_)
+  }
+}
+
+///A tuple of [`Layer`]s
+pub trait Layers {
+  ///the content of all layers at one cell
+  type Cell<'a>
+  where
+    Self: 'a;
+
+  fn cell_at(&self, at: &Position) -> Self::Cell<'_>;
+
+  fn apply_move(&mut self, move_: Move);
+}
+
+macro_rules! impl_layers {
+  ($($layer: ident $index: tt),+) => {
+    impl<$($layer),+> Layers for ($($layer,)+)
+    where
+      $($layer: Layer),+
+    {
+      type Cell<'a> = ($(Option<&'a $layer::Item>,)+) where Self: 'a;
+
+      fn cell_at(&self, at: &Position) -> Self::Cell<'_> {
+        ($(self.$index.get_at(at),)+)
+      }
+
+      fn apply_move(&mut self, move_: Move) {
+        $(self.$index.apply_move(move_);)+
+      }
+    }
+  };
+}
+
+impl_layers!(A 0, B 1);
+impl_layers!(A 0, B 1, C 2);
+impl_layers!(A 0, B 1, C 2, D 3);
+
+///Multiple layers on the same arena, like enemies standing on ground tiles, which are always moved
+/// together
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct LayeredArena<L> {
+  pub layers: L,
+}
+
+impl<L> LayeredArena<L>
+where
+  L: Layers,
+{
+  pub fn new(layers: L) -> Self {
+    Self { layers }
+  }
+
+  pub fn apply_move(&mut self, move_: Move) {
+    self.layers.apply_move(move_);
+  }
+
+  pub fn cell_at(&self, at: &Position) -> L::Cell<'_> {
+    self.layers.cell_at(at)
+  }
+
+  ///Returns the content of all layers for every cell of the arena
+  pub fn cells(&self) -> impl Iterator<Item = (Position, L::Cell<'_>)> {
+    (0..Row.size()).flat_map(move |row| {
+      (0..Column.size()).map(move |column| {
+        let position = Position { row, column };
+        (position, self.cell_at(&position))
+      })
+    })
+  }
+}
+
+impl<L> Deref for LayeredArena<L> {
+  type Target = L;
+
+  fn deref(&self) -> &Self::Target {
+    &self.layers
+  }
+}
+
+impl<L> DerefMut for LayeredArena<L> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.layers
+  }
+}
+
+#[cfg(test)]
+mod test_arena {
+  use crate::arena::{Arena, LayeredArena};
+  use crate::command::parse_quietly;
+  use crate::position::Position;
+  use crate::solving::{Enemy, SolvableArena};
+
+  fn enemy(position: Position) -> Enemy {
+    Enemy {
+      position,
+      required_attack: None,
+      hp: None,
+    }
+  }
+
+  #[test]
+  fn test_add_column() {
+    let mut arena = SolvableArena::default();
+    arena.add_column(2, [0, 1, 3], enemy);
+    arena.add_column(2, [1], enemy);
+
+    assert_eq!(3, arena.enemies.len());
+    assert!(arena.get_at(&Position::at(3, 2).unwrap()).is_some());
+    assert!(arena.get_at(&Position::at(2, 2).unwrap()).is_none());
+  }
+
+  #[test]
+  fn test_clear_column() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    arena.clear_column(1);
+    assert_eq!(1, arena.enemies.len());
+    assert!(arena.get_at(&Position::at(2, 2).unwrap()).is_some());
+  }
+
+  #[test]
+  fn test_clear_ring() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 14"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    arena.clear_ring(0);
+    assert_eq!(3, arena.enemies.len());
+    assert!(arena.enemies.iter().all(|enemy| enemy.row != 0));
+  }
+
+  #[test]
+  fn test_retain() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124 H", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    arena.retain(|enemy| enemy.required_attack.is_none());
+    assert_eq!(1, arena.enemies.len());
+  }
+
+  #[test]
+  fn test_move_and_copy_to_column() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124 H", "c5 23"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    assert_eq!(2, arena.move_to_column(1, &[0, 1, 2], 4));
+    assert_eq!(4, arena.enemies.len());
+    assert!(arena.get_at(&Position::at(3, 1).unwrap()).is_some());
+    for row in [0, 1] {
+      let enemy = arena.get_at(&Position::at(row, 4).unwrap()).expect("moved");
+      assert!(enemy.required_attack.is_some());
+    }
+    assert!(arena.get_at(&Position::at(2, 4).unwrap()).is_some());
+
+    assert_eq!(3, arena.copy_to_column(4, &[0, 1, 2, 3], 4));
+    assert_eq!(4, arena.enemies.len());
+    assert_eq!(3, arena.copy_to_column(4, &[0, 1, 2], 7));
+    assert_eq!(7, arena.enemies.len());
+  }
+
+  #[test]
+  fn test_swap_columns() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c3 12", "c9 4"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    arena.swap_columns(2, 8);
+    assert_eq!(3, arena.enemies.len());
+    assert!(arena.get_at(&Position::at(3, 2).unwrap()).is_some());
+    assert!(arena.get_at(&Position::at(0, 8).unwrap()).is_some());
+    assert!(arena.get_at(&Position::at(1, 8).unwrap()).is_some());
+  }
+
+  #[test]
+  fn test_enemies_affected_by() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 12", "c3 1", "c8 4"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let mut affected: Vec<Position> = arena
+      .enemies_affected_by(&"c2 1".parse().unwrap())
+      .into_iter()
+      .map(|enemy| enemy.position)
+      .collect();
+    affected.sort();
+    assert_eq!(
+      vec![
+        Position::at(0, 1).unwrap(),
+        Position::at(1, 1).unwrap(),
+        Position::at(3, 7).unwrap()
+      ],
+      affected
+    );
+  }
+
+  #[test]
+  fn test_layered_arena() {
+    let mut enemies = SolvableArena::default();
+    parse_quietly(&mut enemies, "c2 1").expect("parse error");
+    let mut ground: Arena<Enemy> = Arena::default();
+    ground.add_column(1, [0, 1], enemy);
+
+    let mut arena = LayeredArena::new((enemies, ground));
+    arena.apply_move("r1 1".parse().unwrap());
+
+    let moved = Position::at(0, 2).unwrap();
+    assert!(matches!(arena.cell_at(&moved), (Some(_), Some(_))));
+    let (enemy, tile) = arena.cell_at(&Position::at(1, 1).unwrap());
+    assert!(enemy.is_none());
+    assert!(tile.is_some());
+    assert_eq!(
+      2,
+      arena
+        .cells()
+        .filter(|(_, (enemy, tile))| enemy.is_some() || tile.is_some())
+        .count()
+    );
+  }
+}
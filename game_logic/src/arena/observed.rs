@@ -0,0 +1,181 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::ops::{Deref, DerefMut};
+
+use crate::arena::{Arena, LayeredArena, Layers};
+use crate::position::{Move, Position};
+
+///A change made to an [`Observed`] arena
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ArenaEvent {
+  Added(Position),
+  Removed(Position),
+  Moved(Move),
+  ///the arena has been changed in some other way, see [`Observed::modify`]
+  Changed,
+}
+
+///Identifies an observer registered with [`Observed::subscribe`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ObserverId(usize);
+
+type Observer = Box<dyn FnMut(&ArenaEvent) + Send>;
+
+///Wraps an arena and notifies observers about every change made through it, so things like the
+/// renderer or the history can react to changes without every call site having to inform them
+pub struct Observed<A> {
+  inner: A,
+  observers: Vec<(ObserverId, Observer)>,
+  next_id: usize,
+}
+
+impl<A> Observed<A> {
+  pub fn new(inner: A) -> Self {
+    Self {
+      inner,
+      observers: vec![],
+      next_id: 0,
+    }
+  }
+
+  pub fn subscribe<F>(&mut self, observer: F) -> ObserverId
+  where
+    F: FnMut(&ArenaEvent) + Send + 'static,
+  {
+    let id = ObserverId(self.next_id);
+    self.next_id += 1;
+    self.observers.push((id, Box::new(observer)));
+    id
+  }
+
+  pub fn unsubscribe(&mut self, id: ObserverId) {
+    self.observers.retain(|(observer_id, _)| *observer_id != id);
+  }
+
+  ///Makes arbitrary changes to the arena and notifies observers with [`ArenaEvent::Changed`]
+  pub fn modify<F, R>(&mut self, f: F) -> R
+  where
+    F: FnOnce(&mut A) -> R,
+  {
+    let result = f(&mut self.inner);
+    self.notify(ArenaEvent::Changed);
+    result
+  }
+
+  pub fn into_inner(self) -> A {
+    self.inner
+  }
+
+  fn notify(&mut self, event: ArenaEvent) {
+    for (_, observer) in &mut self.observers {
+      observer(&event);
+    }
+  }
+}
+
+impl<A, E> Observed<A>
+where
+  A: DerefMut<Target = Arena<E>>,
+  E: Clone + DerefMut<Target = Position>,
+{
+  pub fn add(&mut self, enemy: E) {
+    let position = *enemy;
+    self.inner.add(enemy);
+    self.notify(ArenaEvent::Added(position));
+  }
+
+  pub fn remove(&mut self, at: &Position) {
+    if self.inner.get_at(at).is_some() {
+      self.inner.remove(at);
+      self.notify(ArenaEvent::Removed(*at));
+    }
+  }
+
+  pub fn apply_move(&mut self, move_: Move) {
+    self.inner.apply_move(move_);
+    self.notify(ArenaEvent::Moved(move_));
+  }
+}
+
+impl<L> Observed<LayeredArena<L>>
+where
+  L: Layers,
+{
+  pub fn apply_move(&mut self, move_: Move) {
+    self.inner.apply_move(move_);
+    self.notify(ArenaEvent::Moved(move_));
+  }
+}
+
+impl<A> Deref for Observed<A> {
+  type Target = A;
+
+  fn deref(&self) -> &Self::Target {
+    &self.inner
+  }
+}
+
+impl<A> Debug for Observed<A>
+where
+  A: Debug,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("Observed")
+      .field("inner", &self.inner)
+      .field("observers", &self.observers.len())
+      .finish()
+  }
+}
+
+impl<A> Default for Observed<A>
+where
+  A: Default,
+{
+  fn default() -> Self {
+    Self::new(A::default())
+  }
+}
+
+#[cfg(test)]
+mod test_observed {
+  use std::sync::{Arc, Mutex};
+
+  use crate::arena::observed::{ArenaEvent, Observed};
+  use crate::position::{Move, Position};
+  use crate::solving::{Enemy, SolvableArena};
+
+  #[test]
+  fn test_events() {
+    let events = Arc::new(Mutex::new(vec![]));
+    let mut arena: Observed<SolvableArena> = Observed::default();
+    let recorded = events.clone();
+    let id = arena.subscribe(move |event| recorded.lock().unwrap().push(*event));
+
+    let position = Position::at(0, 1).unwrap();
+    let move_: Move = "r1 1".parse().unwrap();
+    arena.add(Enemy {
+      position,
+      required_attack: None,
+      hp: None,
+    });
+    arena.remove(&Position::at(3, 3).unwrap());
+    arena.apply_move(move_);
+    arena.remove(&Position::at(0, 2).unwrap());
+    arena.modify(|arena| arena.num_groups = Some(1));
+    arena.unsubscribe(id);
+    arena.apply_move(move_);
+
+    assert_eq!(
+      vec![
+        ArenaEvent::Added(position),
+        ArenaEvent::Moved(move_),
+        ArenaEvent::Removed(Position::at(0, 2).unwrap()),
+        ArenaEvent::Changed,
+      ],
+      *events.lock().unwrap()
+    );
+    assert!(arena.enemies.is_empty());
+  }
+}
@@ -0,0 +1,195 @@
+use crate::position::{Move, Num};
+use crate::solving::{SolvableArena, solve};
+use crate::solving::solve_cache::SolveCache;
+
+///Follows a fight while the player executes moves in game and re-solves the arena after every move
+/// with the turns which are left
+#[derive(Debug, Clone)]
+pub struct Assist {
+  arena: SolvableArena,
+  remaining_turns: Num,
+  cache: SolveCache,
+}
+
+impl Assist {
+  pub fn new(arena: SolvableArena, in_turns: Num) -> Self {
+    Self {
+      arena: arena.after_first_strike(),
+      remaining_turns: in_turns,
+      cache: SolveCache::new(),
+    }
+  }
+
+  pub fn arena(&self) -> &SolvableArena {
+    &self.arena
+  }
+
+  pub fn remaining_turns(&self) -> Num {
+    self.remaining_turns
+  }
+
+  ///Solves the current arena with the remaining turns
+  pub fn state(&mut self) -> AssistState {
+    match solve(
+      &self.arena,
+      self.remaining_turns,
+      false,
+      &mut self.cache,
+      None,
+    ) {
+      Some(solution) if solution.is_empty() => AssistState::Solved,
+      Some(solution) => AssistState::Solvable(solution),
+      None => AssistState::Unsolvable,
+    }
+  }
+
+  ///Records a move the player has executed and returns the new state
+  pub fn perform(&mut self, move_: Move) -> AssistState {
+    self.arena.apply_move(move_);
+    self.remaining_turns = self.remaining_turns.saturating_sub(1);
+    self.state()
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum AssistState {
+  Solved,
+  ///the arena can still be solved with the contained moves
+  Solvable(Vec<Move>),
+  Unsolvable,
+}
+
+///How an assisted fight went, see [`AssistSession`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct AssistOutcome {
+  pub num_moves: usize,
+  ///the number of moves of the optimal solution at the start, `None` if there wasn't one
+  pub optimal_moves: Option<usize>,
+  pub solved: bool,
+}
+
+impl AssistOutcome {
+  pub fn followed_optimal_line(&self) -> bool {
+    self.solved
+      && self
+        .optimal_moves
+        .is_some_and(|optimal_moves| self.num_moves <= optimal_moves)
+  }
+}
+
+///An assisted fight which is fed the moves of the player one at a time, keeping track of how it
+/// goes. Front-ends drive it from their input, like `assist in 3` does with the commands following it.
+#[derive(Debug, Clone)]
+pub struct AssistSession {
+  assist: Assist,
+  outcome: AssistOutcome,
+}
+
+impl AssistSession {
+  ///Starts assisting with the arena and returns the state at the start
+  pub fn start(arena: SolvableArena, in_turns: Num) -> (Self, AssistState) {
+    let mut assist = Assist::new(arena, in_turns);
+    let state = assist.state();
+    let outcome = AssistOutcome {
+      num_moves: 0,
+      optimal_moves: match &state {
+        AssistState::Solved => Some(0),
+        AssistState::Solvable(solution) => Some(solution.len()),
+        AssistState::Unsolvable => None,
+      },
+      solved: state == AssistState::Solved,
+    };
+    (Self { assist, outcome }, state)
+  }
+
+  ///Records a move the player has executed and returns the new state
+  pub fn perform(&mut self, move_: Move) -> AssistState {
+    let state = self.assist.perform(move_);
+    self.outcome.num_moves += 1;
+    self.outcome.solved = state == AssistState::Solved;
+    state
+  }
+
+  ///The arena after all performed moves
+  pub fn arena(&self) -> &SolvableArena {
+    self.assist.arena()
+  }
+
+  pub fn remaining_turns(&self) -> Num {
+    self.assist.remaining_turns()
+  }
+
+  ///Whether the fight is over because the arena is solved or no turns are left
+  pub fn is_finished(&self) -> bool {
+    self.outcome.solved || self.remaining_turns() == 0
+  }
+
+  pub fn outcome(&self) -> AssistOutcome {
+    self.outcome
+  }
+}
+
+#[cfg(test)]
+mod test_assist {
+  use crate::assist::{Assist, AssistSession, AssistState};
+  use crate::command::parse_quietly;
+  use crate::solving::SolvableArena;
+
+  #[test]
+  fn test_perform() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let mut assist = Assist::new(arena, 2);
+    assert!(matches!(assist.state(), AssistState::Solvable(solution) if solution.len() == 2));
+    assert_eq!(
+      AssistState::Unsolvable,
+      assist.perform("r1 1".parse().unwrap())
+    );
+    assert_eq!(1, assist.remaining_turns());
+  }
+
+  #[test]
+  fn test_recover() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    //a mistake can still be fixed if there are enough turns left
+    let mut assist = Assist::new(arena, 4);
+    let mut state = assist.perform("r1 1".parse().unwrap());
+    while let AssistState::Solvable(solution) = state {
+      state = assist.perform(solution[0]);
+    }
+    assert_eq!(AssistState::Solved, state);
+  }
+
+  #[test]
+  fn test_session() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let (mut session, mut state) = AssistSession::start(arena.clone(), 3);
+    assert_eq!(Some(2), session.outcome().optimal_moves);
+    while let AssistState::Solvable(solution) = state {
+      assert!(!session.is_finished());
+      state = session.perform(solution[0]);
+    }
+    assert_eq!(AssistState::Solved, state);
+    assert!(session.is_finished());
+    assert!(session.arena().is_solved());
+    assert!(session.outcome().followed_optimal_line());
+
+    //running out of turns ends the fight unsolved
+    let (mut session, state) = AssistSession::start(arena, 1);
+    assert_eq!(AssistState::Unsolvable, state);
+    session.perform("r1 1".parse().unwrap());
+    assert!(session.is_finished());
+    assert!(!session.outcome().followed_optimal_line());
+  }
+}
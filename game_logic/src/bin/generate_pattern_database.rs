@@ -0,0 +1,42 @@
+use std::env;
+use std::process::exit;
+use std::time::Instant;
+
+use game_logic::solving::pattern_database::{PATTERN_DATABASE_VAR, PatternDatabase};
+
+const USAGE: &str =
+  "usage: generate_pattern_database <output file> [max enemies = 8] [max moves = 2]";
+
+fn main() {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let Some(output) = args.first() else {
+    eprintln!("{}", USAGE);
+    exit(1);
+  };
+  let parse_arg = |index: usize, default: u8| match args.get(index) {
+    Some(arg) => arg.parse().unwrap_or_else(|e| {
+      eprintln!("invalid argument '{}': {}\n{}", arg, e, USAGE);
+      exit(1);
+    }),
+    None => default,
+  };
+  let max_enemies = parse_arg(1, 8);
+  let max_moves = parse_arg(2, 2);
+
+  let start = Instant::now();
+  let database = PatternDatabase::generate(max_enemies, max_moves);
+  println!(
+    "generated {} patterns in {:?}",
+    database.len(),
+    start.elapsed()
+  );
+
+  if let Err(e) = database.save(output) {
+    eprintln!("failed to save pattern database: {}", e);
+    exit(1);
+  }
+  println!(
+    "saved to {}, set {} to this path to use it instead of the embedded one",
+    output, PATTERN_DATABASE_VAR
+  );
+}
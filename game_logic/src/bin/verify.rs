@@ -0,0 +1,151 @@
+use std::cell::Cell;
+use std::env;
+use std::process::exit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use game_logic::fixture::{DEFAULT_FIXTURE_DIR, Fixture};
+use game_logic::solving::solve_cache::SolveCache;
+use game_logic::solving::solve_with;
+
+const USAGE: &str =
+  "usage: verify [fixture directory = fixtures] [timeout in seconds = 10] [threads = cpu cores]";
+
+struct Outcome {
+  turns: Option<usize>,
+  timed_out: bool,
+  time: Duration,
+  ///arenas the solver looked at
+  nodes: u64,
+}
+
+impl Outcome {
+  fn status(&self, fixture: &Fixture) -> &'static str {
+    match fixture.expected_turns {
+      _ if self.timed_out => "timeout",
+      None => "unknown",
+      Some(expected) if expected == self.turns => "ok",
+      Some(_) => "FAILED",
+    }
+  }
+}
+
+fn main() {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let dir = args
+    .first()
+    .map_or(DEFAULT_FIXTURE_DIR, String::as_str)
+    .to_string();
+  let parse_arg = |index: usize, default: u64| match args.get(index) {
+    Some(arg) => arg.parse().unwrap_or_else(|e| {
+      eprintln!("invalid argument '{}': {}\n{}", arg, e, USAGE);
+      exit(1);
+    }),
+    None => default,
+  };
+  let timeout = Duration::from_secs(parse_arg(1, 10));
+  let num_threads = parse_arg(
+    2,
+    thread::available_parallelism().map_or(1, |n| n.get() as u64),
+  )
+  .max(1);
+
+  let fixtures = Fixture::load_dir(&dir).unwrap_or_else(|e| {
+    eprintln!("failed to load fixtures from {}: {}\n{}", dir, e, USAGE);
+    exit(1);
+  });
+  println!(
+    "verifying {} fixtures on {} threads...",
+    fixtures.len(),
+    num_threads
+  );
+
+  let next_fixture = AtomicUsize::new(0);
+  let (outcome_sender, outcome_receiver) = channel();
+  thread::scope(|scope| {
+    for _ in 0..num_threads {
+      let outcome_sender = outcome_sender.clone();
+      let fixtures = &fixtures;
+      let next_fixture = &next_fixture;
+      scope.spawn(move || {
+        while let Some(fixture) = fixtures.get(next_fixture.fetch_add(1, Ordering::Relaxed)) {
+          let _ = outcome_sender.send((fixture, verify(fixture, timeout)));
+        }
+      });
+    }
+  });
+  drop(outcome_sender);
+
+  let mut outcomes: Vec<_> = outcome_receiver.into_iter().collect();
+  outcomes.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+  print_table(&outcomes);
+
+  let count = |status: &str| {
+    outcomes
+      .iter()
+      .filter(|(fixture, outcome)| outcome.status(fixture) == status)
+      .count()
+  };
+  let (failed, timed_out) = (count("FAILED"), count("timeout"));
+  println!(
+    "{} ok, {} failed, {} timed out, {} without expectation",
+    count("ok"),
+    failed,
+    timed_out,
+    count("unknown")
+  );
+  if failed + timed_out > 0 {
+    exit(1);
+  }
+}
+
+///Solves the fixture with a fresh cache. The timeout is checked whenever the solver starts searching
+/// with a new number of turns, so a single attempt may run over it.
+fn verify(fixture: &Fixture, timeout: Duration) -> Outcome {
+  let mut cache = SolveCache::new();
+  let start = Instant::now();
+  let timed_out = Cell::new(false);
+  let solution = solve_with(&fixture.arena, &fixture.params(), &mut cache, None, |_| {
+    timed_out.set(start.elapsed() > timeout);
+    !timed_out.get()
+  });
+  let stats = cache.stats();
+  Outcome {
+    turns: solution.map(|solution| solution.len()),
+    timed_out: timed_out.get(),
+    time: start.elapsed(),
+    nodes: stats.cache_hits + stats.cache_misses,
+  }
+}
+
+fn print_table(outcomes: &[(&Fixture, Outcome)]) {
+  let name_width = outcomes
+    .iter()
+    .map(|(fixture, _)| fixture.name.len())
+    .chain(["fixture".len()])
+    .max()
+    .unwrap_or_default();
+  let turns = |turns: Option<usize>| turns.map_or("-".to_string(), |turns| turns.to_string());
+
+  println!(
+    "{:<name_width$}  {:<7}  {:>5}  {:>8}  {:>10}  {:>10}",
+    "fixture", "result", "turns", "expected", "time (ms)", "nodes"
+  );
+  for (fixture, outcome) in outcomes {
+    println!(
+      "{:<name_width$}  {:<7}  {:>5}  {:>8}  {:>10.1}  {:>10}",
+      fixture.name,
+      outcome.status(fixture),
+      if outcome.timed_out {
+        "?".to_string()
+      } else {
+        turns(outcome.turns)
+      },
+      fixture.expected_turns.map_or("?".to_string(), turns),
+      outcome.time.as_secs_f64() * 1000.0,
+      outcome.nodes
+    );
+  }
+}
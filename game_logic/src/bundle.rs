@@ -0,0 +1,324 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::data_dir::{create_parent, data_file};
+use crate::loadout::{DEFAULT_LOADOUTS_FILE, LOADOUTS_VAR};
+use crate::preset::{DEFAULT_PRESETS_FILE, PRESETS_VAR};
+use crate::stats::{DEFAULT_STATS_FILE, STATS_VAR};
+
+///The environment variable pointing to the directory saved arenas are kept in
+pub const ARENAS_VAR: &str = "TOK_ARENAS";
+///The directory in the [data directory](crate::data_dir::data_dir) saved arenas are kept in if
+/// [`ARENAS_VAR`] isn't set
+pub const DEFAULT_ARENAS_DIRECTORY: &str = "arenas";
+///Where `export bundle` writes to if no file is given
+pub const DEFAULT_BUNDLE_FILE: &str = "tok_bundle.zip";
+///The folder of the bundle holding the saved arenas
+const ARENAS_FOLDER: &str = "arenas/";
+
+///The files of the setup which are bundled, under their name in the bundle, with the environment
+/// variable pointing to them and where they are if it isn't set
+const SETUP_FILES: [(&str, &str, &str); 4] = [
+  //the settings of the gui, see its `SETTINGS_VAR`
+  ("settings.txt", "TOK_SETTINGS", "settings.txt"),
+  ("presets.txt", PRESETS_VAR, DEFAULT_PRESETS_FILE),
+  ("loadouts.txt", LOADOUTS_VAR, DEFAULT_LOADOUTS_FILE),
+  ("stats.txt", STATS_VAR, DEFAULT_STATS_FILE),
+];
+
+///The setup of a user in a single zip file - the gui settings, presets, loadouts, statistics and
+/// saved arenas - to move it to another device without any online service
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Bundle {
+  ///the name of each file in the bundle and its content
+  files: Vec<(String, Vec<u8>)>,
+}
+
+impl Bundle {
+  ///Reads every file of the setup which exists, see [`ARENAS_VAR`] and the variables of the other
+  /// files
+  pub fn collect() -> io::Result<Self> {
+    let mut bundle = Self::default();
+    for (name, var, default) in SETUP_FILES {
+      match fs::read(data_file(var, default)) {
+        Ok(content) => bundle.add(name, content),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+      }
+    }
+    let arenas = match fs::read_dir(data_file(ARENAS_VAR, DEFAULT_ARENAS_DIRECTORY)) {
+      Ok(arenas) => arenas,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(bundle),
+      Err(e) => return Err(e),
+    };
+    let mut entries = arenas.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+      if entry.file_type()?.is_file() {
+        let name = format!("{}{}", ARENAS_FOLDER, entry.file_name().to_string_lossy());
+        bundle.add(name, fs::read(entry.path())?);
+      }
+    }
+    Ok(bundle)
+  }
+
+  pub fn add<S>(&mut self, name: S, content: Vec<u8>)
+  where
+    S: ToString,
+  {
+    self.files.push((name.to_string(), content));
+  }
+
+  pub fn files(&self) -> &[(String, Vec<u8>)] {
+    &self.files
+  }
+
+  ///Writes every file to where it belongs on this device, replacing what is there. Returns the
+  /// written files.
+  pub fn unpack(&self) -> Result<Vec<PathBuf>, BundleError> {
+    //nothing is written unless every file has a place
+    let destinations = self
+      .files
+      .iter()
+      .map(|(name, _)| destination(name).ok_or(BundleError::UnknownFile(name.clone())))
+      .collect::<Result<Vec<_>, _>>()?;
+    for (path, (_, content)) in destinations.iter().zip(&self.files) {
+      create_parent(path)?;
+      fs::write(path, content)?;
+    }
+    Ok(destinations)
+  }
+
+  pub fn save<P>(&self, path: P) -> io::Result<()>
+  where
+    P: AsRef<Path>,
+  {
+    fs::write(path, self.to_zip())
+  }
+
+  pub fn load<P>(path: P) -> Result<Self, BundleError>
+  where
+    P: AsRef<Path>,
+  {
+    Self::from_zip(&fs::read(path)?)
+  }
+
+  ///The files as zip archive. They are stored without compression, since setups are small and any
+  /// zip tool can still open them.
+  pub fn to_zip(&self) -> Vec<u8> {
+    let mut zip = vec![];
+    let mut central_directory = vec![];
+    for (name, content) in &self.files {
+      let offset = zip.len() as u32;
+      let crc = crc32(content);
+      let size = content.len() as u32;
+      //local file header
+      put_u32(&mut zip, LOCAL_HEADER_SIGNATURE);
+      put_header_fields(&mut zip, crc, size, name);
+      zip.extend_from_slice(name.as_bytes());
+      zip.extend_from_slice(content);
+
+      put_u32(&mut central_directory, CENTRAL_HEADER_SIGNATURE);
+      put_u16(&mut central_directory, ZIP_VERSION);
+      put_header_fields(&mut central_directory, crc, size, name);
+      put_u16(&mut central_directory, 0); //comment length
+      put_u16(&mut central_directory, 0); //disk number
+      put_u16(&mut central_directory, 0); //internal attributes
+      put_u32(&mut central_directory, 0); //external attributes
+      put_u32(&mut central_directory, offset);
+      central_directory.extend_from_slice(name.as_bytes());
+    }
+    let directory_offset = zip.len() as u32;
+    let directory_size = central_directory.len() as u32;
+    zip.extend(central_directory);
+    put_u32(&mut zip, END_SIGNATURE);
+    put_u16(&mut zip, 0); //number of this disk
+    put_u16(&mut zip, 0); //disk with the central directory
+    put_u16(&mut zip, self.files.len() as u16);
+    put_u16(&mut zip, self.files.len() as u16);
+    put_u32(&mut zip, directory_size);
+    put_u32(&mut zip, directory_offset);
+    put_u16(&mut zip, 0); //comment length
+    zip
+  }
+
+  ///Reads a zip archive written by [`Self::to_zip`]. Archives with compressed files, like ones
+  /// repacked by other tools, aren't supported.
+  pub fn from_zip(data: &[u8]) -> Result<Self, BundleError> {
+    let mut bundle = Self::default();
+    let mut offset = 0;
+    while read_u32(data, offset)? == LOCAL_HEADER_SIGNATURE {
+      let method = read_u16(data, offset + 8)?;
+      let crc = read_u32(data, offset + 14)?;
+      let compressed_size = read_u32(data, offset + 18)? as usize;
+      let size = read_u32(data, offset + 22)? as usize;
+      let name_length = read_u16(data, offset + 26)? as usize;
+      let extra_length = read_u16(data, offset + 28)? as usize;
+      let name_start = offset + LOCAL_HEADER_SIZE;
+      let name = String::from_utf8_lossy(read(data, name_start, name_length)?).into_owned();
+      if method != STORED || compressed_size != size {
+        return Err(BundleError::Compressed(name));
+      }
+      let content = read(data, name_start + name_length + extra_length, size)?;
+      if crc32(content) != crc {
+        return Err(BundleError::Corrupt(format!("{} is damaged", name)));
+      }
+      bundle.add(name, content.to_vec());
+      offset = name_start + name_length + extra_length + size;
+    }
+    Ok(bundle)
+  }
+}
+
+///Where a file of a bundle belongs on this device, None for files which aren't part of a setup.
+/// Saved arenas have to be directly in the arenas folder, so a bundle can't write anywhere else.
+fn destination(name: &str) -> Option<PathBuf> {
+  if let Some((_, var, default)) = SETUP_FILES.iter().find(|(file, ..)| *file == name) {
+    return Some(data_file(var, default));
+  }
+  let file = name.strip_prefix(ARENAS_FOLDER)?;
+  let valid = !file.is_empty() && file != "." && file != ".." && !file.contains(['/', '\\', ':']);
+  valid.then(|| data_file(ARENAS_VAR, DEFAULT_ARENAS_DIRECTORY).join(file))
+}
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x02014b50;
+const END_SIGNATURE: u32 = 0x06054b50;
+///The size of a local file header without the name
+const LOCAL_HEADER_SIZE: usize = 30;
+///2.0, the version needed to read files stored in folders
+const ZIP_VERSION: u16 = 20;
+///The compression method of files stored as they are
+const STORED: u16 = 0;
+
+///The fields local file headers and central directory headers share, from the version needed to
+/// extract to the length of the extra field
+fn put_header_fields(zip: &mut Vec<u8>, crc: u32, size: u32, name: &str) {
+  put_u16(zip, ZIP_VERSION);
+  put_u16(zip, 1 << 11); //the name is utf-8
+  put_u16(zip, STORED);
+  put_u16(zip, 0); //modification time
+  put_u16(zip, (1 << 5) | 1); //modification date, 1980-01-01
+  put_u32(zip, crc);
+  put_u32(zip, size);
+  put_u32(zip, size);
+  put_u16(zip, name.len() as u16);
+  put_u16(zip, 0); //extra field length
+}
+
+fn put_u16(zip: &mut Vec<u8>, value: u16) {
+  zip.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(zip: &mut Vec<u8>, value: u32) {
+  zip.extend_from_slice(&value.to_le_bytes());
+}
+
+fn read(data: &[u8], offset: usize, length: usize) -> Result<&[u8], BundleError> {
+  data
+    .get(offset..offset + length)
+    .ok_or(BundleError::Corrupt("the file ends too early".to_string()))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, BundleError> {
+  let bytes = read(data, offset, 2)?;
+  Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, BundleError> {
+  let bytes = read(data, offset, 4)?;
+  Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+///The CRC-32 checksum zip files store for each file
+fn crc32(data: &[u8]) -> u32 {
+  !data.iter().fold(!0, |crc, &byte| {
+    (0..8).fold(crc ^ byte as u32, |crc, _| {
+      (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg())
+    })
+  })
+}
+
+#[derive(Debug)]
+pub enum BundleError {
+  IO(io::Error),
+  ///the file isn't a bundle or was damaged, and why
+  Corrupt(String),
+  ///the bundle contains a compressed file
+  Compressed(String),
+  ///the bundle contains a file which isn't part of a setup
+  UnknownFile(String),
+}
+
+impl From<io::Error> for BundleError {
+  fn from(value: io::Error) -> Self {
+    Self::IO(value)
+  }
+}
+
+impl Display for BundleError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      BundleError::IO(e) => write!(f, "{}", e),
+      BundleError::Corrupt(reason) => write!(f, "not a valid bundle: {}", reason),
+      BundleError::Compressed(name) => write!(
+        f,
+        "{} is compressed, only bundles made with export bundle can be imported",
+        name
+      ),
+      BundleError::UnknownFile(name) => write!(f, "{} isn't part of a setup", name),
+    }
+  }
+}
+
+impl Error for BundleError {}
+
+#[cfg(test)]
+mod test_bundle {
+  use crate::bundle::{Bundle, BundleError, crc32, destination};
+
+  #[test]
+  fn test_zip() {
+    let mut bundle = Bundle::default();
+    bundle.add("presets.txt", b"speedrun optimize time\n".to_vec());
+    bundle.add("arenas/boss.txt", b"c3 12\n".to_vec());
+    bundle.add("stats.txt", vec![]);
+    let zip = bundle.to_zip();
+    assert_eq!(b"PK\x03\x04", &zip[..4]);
+    assert_eq!(bundle, Bundle::from_zip(&zip).expect("valid zip"));
+
+    let mut damaged = zip.clone();
+    let content = damaged
+      .windows(5)
+      .position(|window| window == b"c3 12")
+      .unwrap();
+    damaged[content] = b'c' + 1;
+    assert!(matches!(
+      Bundle::from_zip(&damaged),
+      Err(BundleError::Corrupt(_))
+    ));
+    assert!(matches!(
+      Bundle::from_zip(&zip[..40]),
+      Err(BundleError::Corrupt(_))
+    ));
+  }
+
+  #[test]
+  fn test_crc32() {
+    assert_eq!(0xcbf43926, crc32(b"123456789"));
+    assert_eq!(0, crc32(b""));
+  }
+
+  #[test]
+  fn test_destination() {
+    assert!(destination("presets.txt").is_some());
+    assert!(destination("arenas/boss.txt").is_some_and(|path| path.ends_with("boss.txt")));
+    assert_eq!(None, destination("arenas/../presets.txt"));
+    assert_eq!(None, destination("arenas/"));
+    assert_eq!(None, destination("../settings.txt"));
+    assert_eq!(None, destination("readme.txt"));
+  }
+}
@@ -0,0 +1,67 @@
+use core::fmt::{Display, Formatter};
+
+///An optional subsystem, which is only compiled in if its cargo feature is enabled
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Capability {
+  ///the cargo feature enabling it
+  pub name: &'static str,
+  pub description: &'static str,
+  pub available: bool,
+}
+
+impl Capability {
+  pub const fn new(name: &'static str, description: &'static str, available: bool) -> Self {
+    Self {
+      name,
+      description,
+      available,
+    }
+  }
+}
+
+///Like `+clipboard: copy solutions to the clipboard`, with a `-` if it isn't available
+impl Display for Capability {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    write!(
+      f,
+      "{}{}: {}",
+      if self.available { '+' } else { '-' },
+      self.name,
+      self.description
+    )
+  }
+}
+
+///The optional subsystems of this crate, so front-ends can hide what their build doesn't support
+pub const CAPABILITIES: [Capability; 6] = [
+  Capability::new(
+    "std",
+    "CLI commands, the solver service running solves on all cpu cores, fixtures and stats files",
+    cfg!(feature = "std"),
+  ),
+  Capability::new(
+    "cli",
+    "argument parsing and the one-shot solve command for scripts",
+    cfg!(feature = "cli"),
+  ),
+  Capability::new(
+    "line_editor",
+    "line editing and command history on the arrow keys in the CLI",
+    cfg!(feature = "line_editor"),
+  ),
+  Capability::new(
+    "clipboard",
+    "copy solutions to the clipboard",
+    cfg!(feature = "clipboard"),
+  ),
+  Capability::new(
+    "parallel",
+    "the parallel solver strategy, searching on all cpu cores",
+    cfg!(feature = "parallel"),
+  ),
+  Capability::new(
+    "pattern_db",
+    "the embedded pattern database, skipping hopeless branches of the search",
+    cfg!(feature = "pattern_db"),
+  ),
+];
@@ -0,0 +1,7 @@
+//maps and sets used by the solver, without the `std` feature the ordered collections of `alloc` are
+// used instead of the hashed ones
+
+#[cfg(not(feature = "std"))]
+pub use alloc::collections::{BTreeMap as Map, BTreeSet as Set};
+#[cfg(feature = "std")]
+pub use std::collections::{HashMap as Map, HashSet as Set};
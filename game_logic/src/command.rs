@@ -0,0 +1,2211 @@
+use std::error::Error;
+use std::fs;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+#[cfg(feature = "clipboard")]
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::assist::{AssistSession, AssistState};
+use crate::bundle::{Bundle, DEFAULT_BUNDLE_FILE};
+use crate::capabilities::CAPABILITIES;
+use crate::deep_link::arena_link;
+use crate::history::{UndoHistory, undo_count};
+use crate::import::{ARENA_FORMATS, format_by_name};
+use crate::loadout::{Loadout, LoadoutStore};
+use crate::selftest;
+use crate::session::{DEFAULT_SESSION_ADDRESS, SESSION_VAR, SessionClient, SessionError};
+use crate::position::{Move, Num, Position};
+use crate::preset::{PresetStore, SolverPreset};
+use crate::position::Dimension::{Column, Row};
+use crate::solving::{
+  Attack, Comparator, Coverage, Enemy, RequiredAttack, SolvableArena, SolveParams, Strategy,
+  TargetArea, solve_with, validate,
+};
+use crate::solving::attack_rules::{ATTACK_RULES_VAR, attack_rules, attack_rules_error};
+use crate::solving::challenge::{CHALLENGES, conflicts};
+use crate::solving::battle::plan_battle;
+use crate::solving::explain::explain;
+use crate::solving::first_strike::recommend_first_strike;
+use crate::solving::item::{Item, ItemStock};
+use crate::solving::lint::lint;
+use crate::solving::objective::ObjectivePattern;
+use crate::solving::search_tree::trace_solve;
+use crate::solving::what_if::{Edit, what_if};
+use crate::stats::{SolveRecord, StatsStore, StatsSummary};
+use crate::version;
+use crate::solving::solve_cache::SolveCache;
+
+///Adds a line to the messages of the outcome, formatted like `println!`
+macro_rules! say {
+  ($out:expr, $($arg:tt)*) => {
+    $out.messages.push(format!($($arg)*))
+  };
+}
+
+///What a command did, so front-ends can print, render or serialize it instead of the command
+/// printing it
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CommandOutcome {
+  ///what the command reports, like the arena after a change or the steps of a solution. Messages
+  /// may span multiple lines.
+  pub messages: Vec<String>,
+  ///whether the arena is different after the command, also when the session changed it
+  pub arena_changed: bool,
+  ///the solution the command found, like the one of `solve`
+  pub solution: Option<Vec<Move>>,
+}
+
+///Writes every message on its own line, like the CLI shows them
+impl Display for CommandOutcome {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    for message in &self.messages {
+      writeln!(f, "{}", message)?;
+    }
+    Ok(())
+  }
+}
+
+///What commands remember between each other while one arena is edited, like the turns the game
+/// grants for the current fight. Front-ends keep one next to each arena and pass it to [`parse`].
+#[derive(Debug, Default)]
+pub struct CommandContext {
+  ///the last solution found by `solve`, for `copy`
+  last_solution: Vec<Move>,
+  ///the turns the game grants for the current fight, set with `turns`
+  turn_budget: Option<TurnBudget>,
+  ///the preset solve options start from, chosen with `preset use`
+  active_preset: Option<SolverPreset>,
+  ///the shared session the arena is kept in sync with, chosen with `attach`
+  session: Option<SessionClient>,
+  ///where solves are recorded, for `stats`. Nothing is recorded without a store.
+  stats: Option<StatsStore>,
+  ///the fight followed with `assist`, with the record of its start. Commands are the moves of the
+  /// player until it is over.
+  assist: Option<(AssistSession, SolveRecord)>,
+  ///the arena before each command changing it, for `undo`
+  undo_history: UndoHistory,
+}
+
+impl CommandContext {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  ///Records solves into the store, see [`StatsStore::from_env`]
+  pub fn with_stats(mut self, stats: StatsStore) -> Self {
+    self.stats = Some(stats);
+    self
+  }
+
+  ///Whether a fight is followed with `assist`, so commands are taken as the moves of the player
+  pub fn is_assisting(&self) -> bool {
+    self.assist.is_some()
+  }
+
+  ///Remembers the arena before a change made without a command, like loading a file in the app, so
+  /// `undo` goes back to it as well
+  pub fn record_change(&mut self, before: SolvableArena) {
+    self.undo_history.record_change(before);
+  }
+}
+
+///Executes the command. While attached to a session, the arena is updated from the session before
+/// and shared with it after the command. While assisting, the command is the next move of the player
+/// instead, see [`CommandContext::is_assisting`].
+pub fn parse(
+  context: &mut CommandContext,
+  arena: &mut SolvableArena,
+  command: &str,
+) -> Result<CommandOutcome, ParseError> {
+  let mut outcome = CommandOutcome::default();
+  let initial = arena.clone();
+  sync_session(
+    &mut context.session,
+    arena,
+    &mut outcome,
+    |session, arena, out| {
+      let shared = session.fetch()?;
+      if shared != *arena {
+        *arena = shared;
+        say!(out, "the arena has been changed in the session");
+        show(out, arena);
+      }
+      Ok(())
+    },
+  );
+  let before = arena.clone();
+  if context.is_assisting() {
+    assist_step(context, arena, command, &mut outcome)?;
+  } else if let Some(count) = undo_count(command) {
+    *arena = context
+      .undo_history
+      .undo(count)
+      .ok_or(ParseError::illegal_argument(command, "nothing to undo"))?;
+    show(&mut outcome, arena);
+  } else {
+    execute(context, arena, command, &mut outcome)?;
+    //one undo step per command, no matter how many enemies it changed
+    if *arena != before {
+      context.undo_history.record_change(before.clone());
+    }
+  }
+  if *arena != before {
+    sync_session(
+      &mut context.session,
+      arena,
+      &mut outcome,
+      |session, arena, _| session.push(arena),
+    );
+  }
+  outcome.arena_changed = *arena != initial;
+  Ok(outcome)
+}
+
+///Executes the command without the session or what earlier commands remembered and drops what it
+/// reports, for building arenas from commands
+pub(crate) fn parse_quietly(arena: &mut SolvableArena, command: &str) -> Result<(), ParseError> {
+  execute(
+    &mut CommandContext::new(),
+    arena,
+    command,
+    &mut CommandOutcome::default(),
+  )
+}
+
+///Exchanges the arena with the attached session, if any. The session is detached if it fails.
+fn sync_session<F>(
+  session: &mut Option<SessionClient>,
+  arena: &mut SolvableArena,
+  out: &mut CommandOutcome,
+  sync: F,
+) where
+  F:
+    FnOnce(&mut SessionClient, &mut SolvableArena, &mut CommandOutcome) -> Result<(), SessionError>,
+{
+  if let Some(client) = session.as_mut() {
+    if let Err(e) = sync(client, arena, out) {
+      say!(out, "lost the session on {}: {}", client.address(), e);
+      *session = None;
+    }
+  }
+}
+
+fn execute(
+  context: &mut CommandContext,
+  arena: &mut SolvableArena,
+  command: &str,
+  out: &mut CommandOutcome,
+) -> Result<(), ParseError> {
+  let mut args = command.split_whitespace().peekable();
+  let cmd = args.next().ok_or(ParseError::missing_argument("command"))?;
+  if let Some(previewed_cmd) = cmd.strip_suffix('?').filter(|cmd| !cmd.is_empty()) {
+    return preview(out, arena, previewed_cmd, args.collect());
+  }
+  match cmd {
+    "help" | "h" | "?" => {
+      say!(out, "set enemy positions: c1 124 H/J/P");
+      say!(out, "set enemies with different weaknesses: c3 1H 2-3J 4");
+      say!(out, "set all enemies by count per column: q 0,2H,1,0,3J");
+      say!(
+        out,
+        "paste an arena from other tools: import / import rings .E..H....."
+      );
+      say!(out, "share the arena as link opening the app: link");
+      say!(out, "remove enemies: - c1 3 / - c1 / - r2 / - H");
+      say!(
+        out,
+        "correct entered enemies: move c3 12 -> c5 / duplicate c3 -> c9 / swap c3 c9"
+      );
+      say!(out, "set number of enemy groups: g 4");
+      say!(
+        out,
+        "solve: solve in 3 / solve fast in 3 / solve bidirectional in 5"
+      );
+      say!(out, "print the solution in one line: solve --compact in 3");
+      say!(out, "warn about solutions needing more turns than granted: turns 3 / turns 3 coins 100 / turns none");
+      say!(
+        out,
+        "avoid moves when solving: solve forbid c6 in 3 / solve forbid rows forbid c2 in 4"
+      );
+      say!(
+        out,
+        "choose what makes a solution the best one: solve optimize time in 3"
+      );
+      for comparator in Comparator::all() {
+        say!(out, "  {}: {}", comparator, comparator.description());
+      }
+      say!(
+        out,
+        "compare two strategies: compare dfs bidirectional in 4 / compare fast-dfs dfs"
+      );
+      for strategy in Strategy::all() {
+        say!(out, "  {}: {}", strategy, strategy.description());
+      }
+      say!(
+        out,
+        "export the search tree of a solve: trace tree.dot in 2 / trace tree.json fast in 2"
+      );
+      say!(
+        out,
+        "check a solution: check r3 -1, c4 2 / check r3 -1, c4 2 in 2"
+      );
+      say!(out, "find mistakes in the entered arena: lint");
+      say!(out, "copy the last solution to the clipboard: copy");
+      say!(out, "get help while executing moves in game: assist in 3");
+      say!(out, "set how many attacks enemies survive: hp c1 3 2");
+      say!(out, "plan a fight over multiple rounds: plan / plan in 3");
+      say!(
+        out,
+        "attack an enemy before the fight: first-strike c3 1 / first-strike none"
+      );
+      say!(
+        out,
+        "find the best enemy to attack before the fight: first-strike suggest in 2"
+      );
+      say!(
+        out,
+        "how the solution changes without an enemy or with another weakness: whatif remove c3 2 / whatif convert c3 2 J in 4"
+      );
+      say!(
+        out,
+        "cells which have to be occupied: target c3 12 / -target c3 1 / target none"
+      );
+      say!(
+        out,
+        "keep grouped columns as they are: lock c4 / -lock c4 / lock none"
+      );
+      say!(
+        out,
+        "items attacking a whole column: item fire-flower 2 / item ice-flower 0 / item none"
+      );
+      say!(
+        out,
+        "challenge runs: challenge no-jump / challenge no-hammer / challenge none"
+      );
+      say!(
+        out,
+        "whether you have a throwable hammer: +hammer / -hammer"
+      );
+      say!(
+        out,
+        "whether you have iron boots: +iron-boots / -iron-boots"
+      );
+      say!(
+        out,
+        "whether you can attack 2x2 areas on any rings: +wide-anywhere / -wide-anywhere"
+      );
+      say!(
+        out,
+        "equipment presets: loadout / loadout early-game / loadout save boss"
+      );
+      say!(
+        out,
+        "solve options for a way of playing: preset / preset use speedrun / preset save challenge forbid columns in 4 / preset none"
+      );
+      say!(
+        out,
+        "statistics of your solves: stats / stats history / stats history 20"
+      );
+      say!(
+        out,
+        "move your setup to another device: export bundle / export bundle setup.zip / import bundle setup.zip"
+      );
+      say!(out, "manually execute turns: e r2 5");
+      say!(out, "clear arena: clear");
+      say!(
+        out,
+        "preview a command without changing the arena: clear? / e? r3 -1 / c2? 14"
+      );
+      say!(out, "list and repeat earlier commands: history / !! / !3");
+      say!(out, "undo the last commands changing the arena: undo / undo 3");
+      say!(out, "check whether this build works correctly: selftest");
+      say!(out, "which build this is, for bug reports: version");
+      say!(
+        out,
+        "which optional features this build supports: capabilities"
+      );
+      say!(out, "which attacks defeat which enemies: rules");
+      say!(
+        out,
+        "share the arena with the gui started with {} set: attach / attach {} / attach none",
+        SESSION_VAR,
+        DEFAULT_SESSION_ADDRESS
+      );
+    }
+    "clear" => {
+      *arena = SolvableArena::default();
+      //the next fight starts, the preset and the session are kept
+      context.turn_budget = None;
+      context.last_solution.clear();
+      say!(out, "arena has been cleared");
+    }
+    "g" | "groups" => {
+      let arg = args
+        .next()
+        .ok_or(ParseError::missing_argument("number of groups"))?;
+      let num_groups = arg
+        .parse()
+        .map_err(|e| ParseError::error(arg, "not a number", e))?;
+      arena.num_groups = Some(num_groups);
+      say!(out, "set enemy groups to {}", num_groups);
+    }
+    "e" | "execute" | "run" => {
+      let move_: Move = args
+        .take(2)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .parse()
+        .map_err(|e| ParseError::error("move", "invalid move", e))?;
+      arena.apply_move(move_);
+      show(out, arena);
+    }
+    "solve" => {
+      let args: Vec<&str> = args.collect();
+      let compact = args.contains(&"--compact");
+      let params = parse_solve_params(
+        args
+          .into_iter()
+          .filter(|&arg| arg != "--compact")
+          .peekable(),
+        context.active_preset.as_ref(),
+      )?;
+
+      report_lint_warnings(out, arena);
+      say!(out, "solving...");
+      let start = Instant::now();
+      let solution = solve_with(arena, &params, &mut SolveCache::new(), None, |_| true);
+      record_stats(
+        context.stats.as_ref(),
+        out,
+        SolveRecord {
+          turns: solution.as_ref().map(Vec::len),
+          solve_time: Some(start.elapsed()),
+          ..SolveRecord::now(arena)
+        },
+      );
+      if let Some(solution) = solution {
+        if solution.is_empty() {
+          say!(out, "Arena is already solved!");
+          out.solution = Some(vec![]);
+          report_item_uses(out, arena, &solution);
+        } else {
+          //with a fixed number of turns the solution isn't necessarily the shortest one
+          let heading = match params.in_turns {
+            Some(_) => "Solution:".to_string(),
+            None => format!("solution was found in {} turns:", solution.len()),
+          };
+          if compact {
+            say!(out, "{} {}", heading, format_solution(&solution));
+          } else {
+            say!(out, "{}", heading);
+            for (index, explanation) in explain(arena, &solution).iter().enumerate() {
+              say!(out, "{}. {}", index + 1, explanation);
+            }
+          }
+          if let Some(warning) = context
+            .turn_budget
+            .and_then(|budget| budget.check(solution.len()))
+          {
+            say!(out, "{}", warning);
+          }
+          report_item_uses(out, arena, &solution);
+          out.solution = Some(solution.clone());
+          context.last_solution = solution;
+        }
+      } else {
+        say!(out, "no solution was found :(");
+        report_challenge_conflicts(out, arena);
+      }
+    }
+    "compare" => {
+      let mut next_strategy = || {
+        let arg = args
+          .next()
+          .ok_or(ParseError::missing_argument("strategy"))?;
+        Strategy::by_name(arg).ok_or(ParseError::illegal_argument(arg, "unknown strategy"))
+      };
+      let strategies = [next_strategy()?, next_strategy()?];
+      let params = parse_solve_params(args, context.active_preset.as_ref())?;
+      compare_strategies(out, arena, &params, strategies);
+    }
+    "turns" => {
+      let budget = &mut context.turn_budget;
+      match args.next() {
+        None => {}
+        Some("none") => *budget = None,
+        Some(turns_arg) => {
+          let turns = turns_arg
+            .parse::<Num>()
+            .map_err(|e| ParseError::error(turns_arg, "not a number", e))?;
+          let coins_per_extra_turn = match (args.next(), args.next()) {
+            (None, _) => None,
+            (Some("coins"), Some(coins_arg)) => Some(
+              coins_arg
+                .parse::<u32>()
+                .map_err(|e| ParseError::error(coins_arg, "not a number", e))?,
+            ),
+            (Some("coins"), None) => return Err(ParseError::missing_argument("coins per turn")),
+            (Some(arg), _) => return Err(ParseError::illegal_argument(arg, "expected coins")),
+          };
+          *budget = Some(TurnBudget {
+            turns,
+            coins_per_extra_turn,
+          });
+        }
+      }
+      match *budget {
+        Some(budget) => say!(out, "the game grants {}", budget),
+        None => say!(out, "no turn budget, solutions aren't checked against it"),
+      }
+    }
+    "trace" => {
+      let path = args
+        .next()
+        .ok_or(ParseError::missing_argument("output file"))?;
+      let params = parse_solve_params(args, context.active_preset.as_ref())?;
+      let in_turns = params
+        .in_turns
+        .ok_or(ParseError::missing_argument("in <number of turns>"))?;
+
+      say!(out, "solving...");
+      let (solution, tree) = trace_solve(arena, in_turns, params.fast);
+      let content = if path.ends_with(".json") {
+        tree.to_json()
+      } else {
+        tree.to_dot()
+      };
+      match fs::write(path, content) {
+        Ok(()) => say!(
+          out,
+          "{} with {} visited arenas written to {}",
+          if solution.is_some() {
+            "search tree"
+          } else {
+            "search tree without solution"
+          },
+          tree.nodes().len(),
+          path
+        ),
+        Err(e) => say!(out, "failed to write {}: {}", path, e),
+      }
+    }
+    "plan" => {
+      let params = parse_solve_params(args, context.active_preset.as_ref())?;
+
+      say!(out, "planning...");
+      match plan_battle(arena, &params, &mut SolveCache::new()) {
+        Some(plan) if plan.rounds.is_empty() => say!(out, "There are no enemies!"),
+        Some(plan) => say!(out, "{}", plan),
+        None => say!(out, "no solution was found :("),
+      }
+    }
+    "hp" => {
+      let column_arg = args.next().ok_or(ParseError::missing_argument("column"))?;
+      let rows_arg = args.next().ok_or(ParseError::missing_argument("rows"))?;
+      let hp_arg = args.next().ok_or(ParseError::missing_argument("hp"))?;
+      let hp = hp_arg
+        .parse::<Num>()
+        .map_err(|e| ParseError::error(hp_arg, "not a number", e))?;
+      for position in parse_positions(column_arg, rows_arg)? {
+        let enemy = arena
+          .get_at_mut(&position)
+          .ok_or(ParseError::illegal_argument(column_arg, "no enemy there"))?;
+        enemy.hp = Some(hp);
+      }
+      say!(out, "set hp to {}", hp);
+    }
+    "first-strike" | "fs" => match args.peek() {
+      Some(&"none") => {
+        arena.first_strike = None;
+        say!(out, "removed first strike");
+      }
+      Some(&"suggest") => {
+        args.next();
+        let params = parse_solve_params(args, context.active_preset.as_ref())?;
+
+        say!(out, "solving...");
+        match recommend_first_strike(arena, &params, &mut SolveCache::new()) {
+          Some(first_strike) => say!(
+            out,
+            "first-strike the enemy at {} to make this solvable in {}: {}",
+            first_strike.position,
+            first_strike.solution.len(),
+            first_strike
+              .solution
+              .iter()
+              .map(|m| m.to_string())
+              .collect::<Vec<_>>()
+              .join(", ")
+          ),
+          None => say!(out, "a first strike doesn't help"),
+        }
+      }
+      _ => {
+        let column_arg = args.next().ok_or(ParseError::missing_argument("column"))?;
+        let row_arg = args.next().ok_or(ParseError::missing_argument("row"))?;
+        let position = match parse_positions(column_arg, row_arg)?[..] {
+          [position] => position,
+          _ => return Err(ParseError::illegal_argument(row_arg, "expected one row")),
+        };
+        if arena.get_at(&position).is_none() {
+          return Err(ParseError::illegal_argument(
+            format!("{} {}", column_arg, row_arg),
+            "no enemy there",
+          ));
+        }
+        arena.first_strike = Some(position);
+        say!(out, "set first strike to {}", position);
+      }
+    },
+    "whatif" => {
+      let edit = match args.next() {
+        Some(edit_arg @ ("remove" | "convert")) => {
+          let column_arg = args.next().ok_or(ParseError::missing_argument("column"))?;
+          let row_arg = args.next().ok_or(ParseError::missing_argument("row"))?;
+          let position = match parse_positions(column_arg, row_arg)?[..] {
+            [position] => position,
+            _ => return Err(ParseError::illegal_argument(row_arg, "expected one row")),
+          };
+          if edit_arg == "remove" {
+            Edit::Remove(position)
+          } else {
+            let attack_arg = args
+              .next()
+              .ok_or(ParseError::missing_argument("weakness"))?;
+            let required_attack = match attack_arg {
+              "none" => None,
+              _ => Some(parse_required_attack(attack_arg)?),
+            };
+            Edit::Convert(position, required_attack)
+          }
+        }
+        Some(arg) => {
+          return Err(ParseError::illegal_argument(
+            arg,
+            "expected remove or convert",
+          ))
+        }
+        None => return Err(ParseError::missing_argument("remove or convert")),
+      };
+      let params = parse_solve_params(args, context.active_preset.as_ref())?;
+
+      say!(out, "solving...");
+      let what_if = what_if(arena, edit, &params, &mut SolveCache::new()).ok_or(
+        ParseError::illegal_argument(edit.position().to_string(), "no enemy there"),
+      )?;
+      let turns = |solution: &Option<Vec<Move>>| match solution {
+        Some(solution) => format!("{} turns", solution.len()),
+        None => "no solution".to_string(),
+      };
+      say!(out, "now: {}", turns(&what_if.before));
+      match what_if.turns_saved() {
+        Some(0) => say!(out, "after {}: {}, no change", edit, turns(&what_if.after)),
+        Some(saved) if saved > 0 => {
+          say!(
+            out,
+            "after {}: {}, {} fewer",
+            edit,
+            turns(&what_if.after),
+            saved
+          )
+        }
+        Some(saved) => say!(
+          out,
+          "after {}: {}, {} more",
+          edit,
+          turns(&what_if.after),
+          -saved
+        ),
+        None => say!(out, "after {}: {}", edit, turns(&what_if.after)),
+      }
+    }
+    "target" | "t" => {
+      let column_arg = args.next().ok_or(ParseError::missing_argument("column"))?;
+      if column_arg == "none" {
+        arena.objective = None;
+        say!(out, "removed all target cells");
+        return Ok(());
+      }
+      let rows_arg = args.next().ok_or(ParseError::missing_argument("rows"))?;
+      let objective = arena.objective.get_or_insert_with(ObjectivePattern::new);
+      for position in parse_positions(column_arg, rows_arg)? {
+        objective.add(position);
+      }
+      report_objective(out, arena);
+    }
+    "-target" | "-t" => {
+      let column_arg = args.next().ok_or(ParseError::missing_argument("column"))?;
+      let rows_arg = args.next().ok_or(ParseError::missing_argument("rows"))?;
+      if let Some(objective) = &mut arena.objective {
+        for position in parse_positions(column_arg, rows_arg)? {
+          objective.remove(&position);
+        }
+        if objective.is_empty() {
+          arena.objective = None;
+        }
+      }
+      report_objective(out, arena);
+    }
+    "lock" => {
+      let column_arg = args.next().ok_or(ParseError::missing_argument("column"))?;
+      if column_arg == "none" {
+        arena.locked_columns.clear();
+      } else {
+        arena.locked_columns.insert(parse_column(column_arg)?);
+      }
+      report_locked_columns(out, arena);
+    }
+    "-lock" | "unlock" => {
+      let column_arg = args.next().ok_or(ParseError::missing_argument("column"))?;
+      arena.locked_columns.remove(&parse_column(column_arg)?);
+      report_locked_columns(out, arena);
+    }
+    "item" | "items" => {
+      match args.next() {
+        None => {}
+        Some("none") => arena.items = ItemStock::default(),
+        Some(item_arg) => {
+          let item = Item::by_name(item_arg)
+            .ok_or(ParseError::illegal_argument(item_arg, "unknown item"))?;
+          let count_arg = args.next().unwrap_or("1");
+          let count = count_arg
+            .parse()
+            .map_err(|e| ParseError::error(count_arg, "not a number", e))?;
+          arena.items.set(item, count);
+        }
+      }
+      say!(out, "items: {}", arena.items);
+    }
+    "challenge" => {
+      let challenge_args: Vec<&str> = args.collect();
+      if challenge_args.contains(&"none") {
+        arena.forbidden_attacks.clear();
+      }
+      for &challenge_arg in challenge_args.iter().filter(|&&arg| arg != "none") {
+        let (_, attacks) = CHALLENGES
+          .iter()
+          .find(|(name, _)| *name == challenge_arg)
+          .ok_or(ParseError::illegal_argument(
+            challenge_arg,
+            "expected no-jump, no-hammer, no-iron-boots or none",
+          ))?;
+        arena.forbidden_attacks.extend(attacks.iter());
+      }
+      if arena.forbidden_attacks.is_empty() {
+        say!(out, "no challenge, all attacks are allowed");
+      } else {
+        say!(
+          out,
+          "forbidden attacks: {}",
+          arena
+            .forbidden_attacks
+            .iter()
+            .map(Attack::name)
+            .collect::<Vec<_>>()
+            .join(", ")
+        );
+        for conflict in conflicts(arena) {
+          say!(out, "the arena can't be solved: {}", conflict);
+        }
+      }
+    }
+    "attach" => match args.next() {
+      Some("none") => match context.session.take() {
+        Some(client) => say!(out, "detached from the session on {}", client.address()),
+        None => say!(out, "not attached to a session"),
+      },
+      address_arg => {
+        let address = address_arg.unwrap_or(DEFAULT_SESSION_ADDRESS);
+        let mut client = SessionClient::connect(address)
+          .map_err(|e| ParseError::error(address, "can't attach to the session", e))?;
+        *arena = client
+          .fetch()
+          .map_err(|e| ParseError::error(address, "can't attach to the session", e))?;
+        say!(out, "attached to the session on {}", client.address());
+        context.session = Some(client);
+        show(out, arena);
+      }
+    },
+    "check" => {
+      let mut args: Vec<&str> = args.collect();
+      let mut params = SolveParams::new();
+      if let [.., "in", turns_arg] = args[..] {
+        let turns = turns_arg
+          .parse::<Num>()
+          .map_err(|e| ParseError::error(turns_arg, "not a number", e))?;
+        params.in_turns = Some(turns);
+        args.truncate(args.len() - 2);
+      }
+      let moves_arg = args.join(" ");
+      let moves = moves_arg
+        .split(',')
+        .map(str::trim)
+        .filter(|move_arg| !move_arg.is_empty())
+        .map(|move_arg| {
+          move_arg
+            .parse::<Move>()
+            .map_err(|e| ParseError::error(move_arg, "invalid move", e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+      let report = validate(arena, &moves, &params);
+      show(out, &report.result);
+      say!(out, "{}", report);
+    }
+    "assist" => {
+      let arg = args.next().ok_or(ParseError::missing_argument("in"))?;
+      if arg != "in" {
+        return Err(ParseError::illegal_argument(arg, "expected in"));
+      }
+      let arg = args
+        .next()
+        .ok_or(ParseError::missing_argument("number of turns"))?;
+      let turns = arg
+        .parse::<Num>()
+        .map_err(|e| ParseError::error(arg, "not a number", e))?;
+      say!(
+        out,
+        "assisting with {} turns, enter the moves you execute in game or 'exit' to stop",
+        turns
+      );
+      let (session, state) = AssistSession::start(arena.clone(), turns);
+      report_assist_state(out, &session, &state);
+      let finished = session.is_finished();
+      context.assist = Some((session, SolveRecord::now(arena)));
+      if finished {
+        finish_assist(context, out);
+      }
+    }
+    "-" | "undo" => {
+      let arg = args
+        .next()
+        .ok_or(ParseError::missing_argument("column, ring or weakness"))?;
+      match (arg, args.next()) {
+        ("H" | "J" | "P", _) => {
+          let required_attack = parse_required_attack(arg)?;
+          arena.retain(|enemy| enemy.required_attack != Some(required_attack));
+        }
+        (ring_arg, None) if ring_arg.starts_with('r') => {
+          arena.clear_ring(parse_ring(ring_arg)?);
+        }
+        (column_arg, None) => {
+          arena.clear_column(parse_column(column_arg)?);
+        }
+        (column_arg, Some(rows_arg)) => {
+          for position in &parse_positions(column_arg, rows_arg)? {
+            arena.remove(position);
+          }
+        }
+      }
+      show(out, arena);
+    }
+    "+hammer" | "-hammer" | "+iron-boots" | "-iron-boots" | "+wide-anywhere" | "-wide-anywhere" => {
+      *arena
+        .available_equipment
+        .flag_mut(&cmd[1..])
+        .expect("flag is known") = cmd.starts_with('+');
+    }
+    "move" | "duplicate" => {
+      let args: Vec<&str> = args.collect();
+      let (column_arg, rows_arg, target_arg) = match args[..] {
+        [column_arg, "->", target_arg] => (column_arg, "all", target_arg),
+        [column_arg, rows_arg, "->", target_arg] => (column_arg, rows_arg, target_arg),
+        _ => {
+          return Err(ParseError::illegal_argument(
+            args.join(" "),
+            "expected <column> [rows] -> <column>, like c3 12 -> c5",
+          ))
+        }
+      };
+      let from = parse_column(column_arg)?;
+      let rows = parse_rows(column_arg, rows_arg)?;
+      let to = parse_column(target_arg)?;
+      let count = if cmd == "move" {
+        //the first strike moves along with its enemy
+        if let Some(first_strike) = &mut arena.first_strike {
+          if first_strike.column == from && rows.contains(&first_strike.row) {
+            first_strike.column = to;
+          }
+        }
+        arena.move_to_column(from, &rows, to)
+      } else {
+        arena.copy_to_column(from, &rows, to)
+      };
+      if count == 0 {
+        return Err(ParseError::illegal_argument(
+          format!("{} {}", column_arg, rows_arg),
+          "no enemies there",
+        ));
+      }
+      show(out, arena);
+    }
+    "swap" => {
+      let a_arg = args.next().ok_or(ParseError::missing_argument("column"))?;
+      let b_arg = args
+        .next()
+        .ok_or(ParseError::missing_argument("other column"))?;
+      let (a, b) = (parse_column(a_arg)?, parse_column(b_arg)?);
+      arena.swap_columns(a, b);
+      if let Some(first_strike) = &mut arena.first_strike {
+        if first_strike.column == a {
+          first_strike.column = b;
+        } else if first_strike.column == b {
+          first_strike.column = a;
+        }
+      }
+      show(out, arena);
+    }
+    "q" | "quick" => {
+      let entries = args.collect::<Vec<_>>().join(",");
+      let columns = parse_quick_entry(&entries)?;
+      arena.enemies.clear();
+      for (column, (count, required_attack)) in columns.into_iter().enumerate() {
+        //fill the outermost rings first
+        let rows = (Row.size() - count..Row.size()).collect::<Vec<_>>();
+        arena.add_column(column as Num, rows, |position| Enemy {
+          position,
+          required_attack,
+          hp: None,
+        });
+      }
+      show(out, arena);
+    }
+    "copy" => {
+      let solution = &context.last_solution;
+      if solution.is_empty() {
+        return Err(ParseError::illegal_argument(
+          "copy",
+          "no solution yet, solve the arena first",
+        ));
+      }
+      let text = format_solution(solution);
+      copy_to_clipboard(&text)?;
+      say!(out, "copied {}", text);
+    }
+    "export" => {
+      let what = args
+        .next()
+        .ok_or(ParseError::missing_argument("what to export"))?;
+      if what != "bundle" {
+        return Err(ParseError::illegal_argument(
+          what,
+          "only a bundle can be exported",
+        ));
+      }
+      let file = args.next().unwrap_or(DEFAULT_BUNDLE_FILE);
+      let bundle =
+        Bundle::collect().map_err(|e| ParseError::error(file, "failed to read the setup", e))?;
+      bundle
+        .save(file)
+        .map_err(|e| ParseError::error(file, "failed to export", e))?;
+      say!(out, "exported {} files to {}", bundle.files().len(), file);
+    }
+    "import" if args.peek() == Some(&"bundle") => {
+      args.next();
+      let file = args.next().unwrap_or(DEFAULT_BUNDLE_FILE);
+      let written = Bundle::load(file)
+        .and_then(|bundle| bundle.unpack())
+        .map_err(|e| ParseError::error(file, "failed to import", e))?;
+      for path in &written {
+        say!(out, "imported {}", path.display());
+      }
+      say!(out, "imported {} files, restart to use them", written.len());
+    }
+    "import" => {
+      let Some(format_arg) = args.next() else {
+        for format in ARENA_FORMATS {
+          say!(out, "import {} {}", format.name(), format.example());
+        }
+        return Ok(());
+      };
+      let format = format_by_name(format_arg)
+        .ok_or(ParseError::illegal_argument(format_arg, "unknown format"))?;
+      let input = args.collect::<Vec<_>>().join(" ");
+      let enemies = format
+        .parse(&input)
+        .map_err(|e| ParseError::error(input, "can't import", e))?;
+      arena.enemies.clear();
+      for enemy in enemies {
+        arena.add(enemy);
+      }
+      show(out, arena);
+    }
+    "link" => say!(out, "{}", arena_link(&arena.enemies)),
+    "lint" => {
+      if lint(arena).is_empty() {
+        say!(out, "no mistakes found");
+      }
+      report_lint_warnings(out, arena);
+    }
+    "version" | "about" => say!(out, "{}", version::about()),
+    "rules" => {
+      if let Some(e) = attack_rules_error() {
+        say!(out, "the rules of {} aren't used: {}", ATTACK_RULES_VAR, e);
+      }
+      say!(out, "{}", attack_rules().to_string().trim_end());
+    }
+    "capabilities" => {
+      for capability in CAPABILITIES {
+        say!(out, "{}", capability);
+      }
+    }
+    "selftest" => {
+      let results = selftest::run();
+      for result in &results {
+        say!(out, "{}", result);
+      }
+      let passed = results.iter().filter(|result| result.passed()).count();
+      say!(out, "{} of {} checks passed", passed, results.len());
+    }
+    "stats" => {
+      let Some(stats) = &context.stats else {
+        say!(out, "Solves aren't recorded here");
+        return Ok(());
+      };
+      let history = stats
+        .history()
+        .map_err(|e| ParseError::error("stats", "failed to read stats", e))?;
+      match args.next() {
+        None => say!(out, "{}", StatsSummary::of(&history)),
+        Some("history") => {
+          let count = args
+            .next()
+            .map(|arg| {
+              arg
+                .parse::<usize>()
+                .map_err(|e| ParseError::error(arg, "not a number", e))
+            })
+            .transpose()?
+            .unwrap_or(10);
+          for record in &history[history.len().saturating_sub(count)..] {
+            say!(
+              out,
+              "{} arena {:016x}: {}{}{}",
+              record.timestamp,
+              record.arena_hash,
+              record
+                .turns
+                .map_or("unsolved".to_string(), |turns| format!("{} turns", turns)),
+              record.solve_time.map_or(String::new(), |time| format!(
+                ", solved in {} ms",
+                time.as_millis()
+              )),
+              match record.followed_optimal {
+                Some(true) => ", optimal line followed",
+                Some(false) => ", optimal line missed",
+                None => "",
+              }
+            );
+          }
+        }
+        Some(arg) => return Err(ParseError::illegal_argument(arg, "expected history")),
+      }
+    }
+    "loadout" => {
+      let mut store = LoadoutStore::load_default()
+        .map_err(|e| ParseError::error("loadout", "failed to load custom loadouts", e))?;
+      match args.next() {
+        None => {
+          say!(out, "current equipment: {}", arena.available_equipment);
+          for loadout in store.all() {
+            say!(out, "{}", loadout);
+          }
+        }
+        Some("save") => {
+          let name = args
+            .next()
+            .ok_or(ParseError::missing_argument("loadout name"))?;
+          let loadout = Loadout {
+            name: name.to_string(),
+            equipment: arena.available_equipment,
+          };
+          match store.save(loadout) {
+            Ok(()) => say!(out, "saved loadout {}", name),
+            Err(e) => say!(out, "failed to save loadout {}: {}", name, e),
+          }
+        }
+        Some(name) => {
+          let loadout = store
+            .get(name)
+            .ok_or(ParseError::illegal_argument(name, "unknown loadout"))?;
+          arena.available_equipment = loadout.equipment;
+          say!(out, "equipped {}", loadout);
+        }
+      }
+    }
+    "preset" => {
+      let mut store = PresetStore::load_default()
+        .map_err(|e| ParseError::error("preset", "failed to load custom presets", e))?;
+      let active = &mut context.active_preset;
+      match args.next() {
+        None => {
+          match &*active {
+            Some(preset) => say!(out, "active preset: {}", preset.name),
+            None => say!(out, "no active preset"),
+          }
+          for preset in store.all() {
+            say!(out, "{}", preset);
+          }
+        }
+        Some("none") => {
+          *active = None;
+          say!(out, "solving with the default options");
+        }
+        Some("save") => {
+          let name = args
+            .next()
+            .ok_or(ParseError::missing_argument("preset name"))?;
+          let preset = SolverPreset {
+            name: name.to_string(),
+            params: parse_solve_options(args, SolveParams::new())?,
+          };
+          match store.save(preset) {
+            Ok(()) => say!(out, "saved preset {}", name),
+            Err(e) => say!(out, "failed to save preset {}: {}", name, e),
+          }
+        }
+        Some("use") => {
+          let name = args
+            .next()
+            .ok_or(ParseError::missing_argument("preset name"))?;
+          let preset = store
+            .get(name)
+            .ok_or(ParseError::illegal_argument(name, "unknown preset"))?;
+          say!(out, "solving with {}", preset);
+          *active = Some(preset);
+        }
+        Some(arg) => {
+          return Err(ParseError::illegal_argument(
+            arg,
+            "expected use, save or none",
+          ))
+        }
+      }
+    }
+    _ => {
+      add_enemies(arena, cmd, &args.collect::<Vec<_>>())?;
+      show(out, arena);
+    }
+  }
+  Ok(())
+}
+
+///The prompt of the CLI, summarizing what the solver would work with: the number of enemies, the
+/// number of groups, whether a throwable hammer is available and whether the arena is solved, like
+/// `[12E g3 +H ✗] > `
+pub fn prompt(arena: &SolvableArena) -> String {
+  format!(
+    "[{}E g{} {}H {}] > ",
+    arena.enemies.len(),
+    arena.num_groups(),
+    if arena.available_equipment.throwing_hammer {
+      '+'
+    } else {
+      '-'
+    },
+    if arena.is_solved() { '✓' } else { '✗' }
+  )
+}
+
+///Shows the arena next to how it would look after a command, without changing it. Only commands
+/// which change nothing but the arena can be previewed.
+fn preview(
+  out: &mut CommandOutcome,
+  arena: &SolvableArena,
+  cmd: &str,
+  args: Vec<&str>,
+) -> Result<(), ParseError> {
+  let previewable = match cmd {
+    "clear" | "g" | "groups" | "e" | "execute" | "run" | "hp" | "target" | "t" | "-target"
+    | "-t" | "lock" | "-lock" | "unlock" | "item" | "items" | "challenge" | "-" | "+hammer"
+    | "-hammer" | "+iron-boots" | "-iron-boots" | "+wide-anywhere" | "-wide-anywhere" | "move"
+    | "duplicate" | "swap" | "q" | "quick" => true,
+    "import" => args.first() != Some(&"bundle"),
+    "first-strike" | "fs" => args.first() != Some(&"suggest"),
+    //adding enemies to a column
+    _ => cmd
+      .strip_prefix('c')
+      .is_some_and(|column| !column.is_empty() && column.bytes().all(|byte| byte.is_ascii_digit())),
+  };
+  if !previewable {
+    return Err(ParseError::illegal_argument(
+      cmd,
+      "can't be previewed, only commands changing the arena can",
+    ));
+  }
+
+  let mut after = arena.clone();
+  let command = [cmd].into_iter().chain(args).collect::<Vec<_>>().join(" ");
+  //what the previewed command reports is left out, the arena after it says it all
+  parse_quietly(&mut after, &command)?;
+  say!(
+    out,
+    "{}",
+    side_by_side(
+      ("now", &arena.inner.to_string()),
+      (&format!("after {}", command), &after.inner.to_string())
+    )
+  );
+  Ok(())
+}
+
+///The turns the game grants for a fight
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+struct TurnBudget {
+  turns: Num,
+  ///what the player has to pay for each turn beyond the granted ones, if known
+  coins_per_extra_turn: Option<u32>,
+}
+
+impl TurnBudget {
+  ///Returns a warning if a solution needs more turns than granted
+  fn check(&self, solution_turns: usize) -> Option<String> {
+    let extra_turns = solution_turns.checked_sub(self.turns as usize)?;
+    if extra_turns == 0 {
+      return None;
+    }
+    let mut warning = format!(
+      "WARNING: the solution needs {} turns, but the game only grants {}!",
+      solution_turns, self.turns
+    );
+    if let Some(coins) = self.coins_per_extra_turn {
+      warning += &format!(
+        " {} more turns cost {} coins.",
+        extra_turns,
+        extra_turns as u64 * coins as u64
+      );
+    }
+    Some(warning)
+  }
+}
+
+impl Display for TurnBudget {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} turns", self.turns)?;
+    if let Some(coins) = self.coins_per_extra_turn {
+      write!(f, ", each extra turn costs {} coins", coins)?;
+    }
+    Ok(())
+  }
+}
+
+///Puts two texts with a title each next to each other
+fn side_by_side(left: (&str, &str), right: (&str, &str)) -> String {
+  let lines = |(title, text): (&str, &str)| {
+    [title]
+      .into_iter()
+      .chain(text.lines())
+      .map(str::to_string)
+      .collect::<Vec<_>>()
+  };
+  let (left, right) = (lines(left), lines(right));
+  let width = left
+    .iter()
+    .map(|line| line.chars().count())
+    .max()
+    .unwrap_or(0)
+    + 4;
+  (0..left.len().max(right.len()))
+    .map(|index| {
+      let left = left.get(index).map_or("", String::as_str);
+      let right = right.get(index).map_or("", String::as_str);
+      format!("{:<width$}{}", left, right, width = width)
+        .trim_end()
+        .to_string()
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+///Reports the arena like [`crate::arena::Arena::show`] prints it
+fn show(out: &mut CommandOutcome, arena: &SolvableArena) {
+  say!(out, "{}", arena.inner);
+}
+
+///Adds enemies like `c1 124 H` or `c3 1H 2J 4` does, without printing anything. Each entry is a set
+/// of rows, optionally followed by the weakness of these rows. A weakness as last entry applies to
+/// all rows without one.
+pub(crate) fn add_enemies(
+  arena: &mut SolvableArena,
+  column_arg: &str,
+  entry_args: &[&str],
+) -> Result<(), ParseError> {
+  let column = parse_column(column_arg)?;
+  let (entry_args, default_attack) = match entry_args {
+    [entry_args @ .., attack_arg] if matches!(*attack_arg, "H" | "J" | "P") => {
+      (entry_args, Some(parse_required_attack(attack_arg)?))
+    }
+    _ => (entry_args, None),
+  };
+  if entry_args.is_empty() {
+    return Err(ParseError::missing_argument("rows"));
+  }
+
+  let mut enemies: Vec<Enemy> = vec![];
+  for entry_arg in entry_args {
+    let (rows_arg, required_attack) = match entry_arg.strip_suffix(['H', 'J', 'P']) {
+      Some(rows_arg) => (
+        rows_arg,
+        Some(parse_required_attack(&entry_arg[rows_arg.len()..])?),
+      ),
+      None => (*entry_arg, default_attack),
+    };
+    for row in parse_rows(column_arg, rows_arg)? {
+      if enemies.iter().any(|enemy| enemy.row == row) {
+        return Err(ParseError::illegal_argument(
+          entry_args.join(" "),
+          format!("row {} is given twice", row + 1),
+        ));
+      }
+      enemies.push(Enemy {
+        position: Position { row, column },
+        required_attack,
+        hp: None,
+      });
+    }
+  }
+  for enemy in enemies {
+    arena.add(enemy);
+  }
+  Ok(())
+}
+
+fn report_objective(out: &mut CommandOutcome, arena: &SolvableArena) {
+  match &arena.objective {
+    Some(objective) => say!(
+      out,
+      "target cells: {}",
+      objective
+        .cells()
+        .iter()
+        .map(|cell| cell.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+    ),
+    None => say!(out, "no target cells, all enemies have to be attackable"),
+  }
+}
+
+///Solves the arena with both strategies and prints their results side by side, warning if they
+/// disagree on whether the arena can be solved
+fn compare_strategies(
+  out: &mut CommandOutcome,
+  arena: &SolvableArena,
+  params: &SolveParams,
+  strategies: [Strategy; 2],
+) {
+  say!(out, "comparing...");
+  let outcomes = strategies.map(|strategy| {
+    let mut cache = SolveCache::new();
+    let start = Instant::now();
+    let solution = solve_with(
+      arena,
+      &SolveParams {
+        strategy,
+        ..*params
+      },
+      &mut cache,
+      None,
+      |_| true,
+    );
+    let stats = cache.stats();
+    (
+      strategy,
+      solution,
+      start.elapsed(),
+      stats.cache_hits + stats.cache_misses,
+    )
+  });
+
+  let name_width = strategies
+    .iter()
+    .map(|strategy| strategy.name().len())
+    .chain(["strategy".len()])
+    .max()
+    .unwrap_or_default();
+  say!(
+    out,
+    "{:<name_width$}  {:>5}  {:>10}  {:>10}  solution",
+    "strategy",
+    "turns",
+    "time (ms)",
+    "nodes"
+  );
+  for (strategy, solution, time, nodes) in &outcomes {
+    say!(
+      out,
+      "{:<name_width$}  {:>5}  {:>10.1}  {:>10}  {}",
+      strategy.name(),
+      solution
+        .as_ref()
+        .map_or("-".to_string(), |solution| solution.len().to_string()),
+      time.as_secs_f64() * 1000.0,
+      nodes,
+      solution
+        .as_deref()
+        .map_or("none".to_string(), format_solution)
+    );
+  }
+
+  let [(_, first, ..), (_, second, ..)] = &outcomes;
+  match (first, second) {
+    (Some(first), Some(second)) if first.len() != second.len() => say!(
+      out,
+      "both found a solution, {} needs {} turns less",
+      if first.len() < second.len() {
+        strategies[0]
+      } else {
+        strategies[1]
+      },
+      first.len().abs_diff(second.len())
+    ),
+    (Some(_), Some(_)) | (None, None) => say!(out, "both agree"),
+    _ => say!(
+      out,
+      "DISAGREEMENT: only {} found a solution",
+      if first.is_some() {
+        strategies[0]
+      } else {
+        strategies[1]
+      }
+    ),
+  }
+}
+
+///Warns about inputs which can't happen in the game, since the solution would be useless then
+fn report_lint_warnings(out: &mut CommandOutcome, arena: &SolvableArena) {
+  for warning in lint(arena) {
+    say!(out, "warning: {}", warning);
+  }
+}
+
+///Writes moves like `r3 -1, c4 2`, which `check` reads as well
+fn format_solution(solution: &[Move]) -> String {
+  solution
+    .iter()
+    .map(|m| m.to_string())
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(text: &str) -> Result<(), ParseError> {
+  //on some platforms the text is only available as long as the clipboard lives
+  static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+  let mut clipboard = CLIPBOARD.lock().expect("clipboard is poisoned");
+  let clipboard = match &mut *clipboard {
+    Some(clipboard) => clipboard,
+    None => clipboard.insert(
+      arboard::Clipboard::new()
+        .map_err(|e| ParseError::error("copy", "failed to access the clipboard", e))?,
+    ),
+  };
+  clipboard
+    .set_text(text)
+    .map_err(|e| ParseError::error("copy", "failed to copy", e))
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_text: &str) -> Result<(), ParseError> {
+  Err(ParseError::illegal_argument(
+    "copy",
+    "the CLI has been built without the clipboard feature",
+  ))
+}
+
+///Explains why no solution was found if attacks are forbidden
+fn report_challenge_conflicts(out: &mut CommandOutcome, arena: &SolvableArena) {
+  if arena.forbidden_attacks.is_empty() {
+    return;
+  }
+  let conflicts = conflicts(arena);
+  if conflicts.is_empty() {
+    say!(
+      out,
+      "no solution only uses the allowed attacks, try more turns or `challenge none`"
+    );
+  }
+  for conflict in conflicts {
+    say!(out, "{}", conflict);
+  }
+}
+
+///Prints which columns have to be attacked with an item after executing the solution
+fn report_item_uses(out: &mut CommandOutcome, arena: &SolvableArena, solution: &[Move]) {
+  if arena.items.is_empty() {
+    return;
+  }
+  let mut solved = arena.clone();
+  for &move_ in solution {
+    solved.inner.apply_move(move_);
+  }
+  let Some(coverage) = Coverage::find(&solved) else {
+    return;
+  };
+  for area in coverage.areas() {
+    if let (Some(item), TargetArea::Long { column }) = (area.item, &area.target_area) {
+      say!(out, "use 1 {} on c{}", item, column + 1);
+    }
+  }
+}
+
+fn report_locked_columns(out: &mut CommandOutcome, arena: &SolvableArena) {
+  if arena.locked_columns.is_empty() {
+    say!(out, "no locked columns");
+  } else {
+    say!(
+      out,
+      "locked columns: {}",
+      arena
+        .locked_columns
+        .iter()
+        .map(|column| format!("c{}", column + 1))
+        .collect::<Vec<_>>()
+        .join(", ")
+    );
+  }
+}
+
+///Takes the next step of the fight followed with `assist`: a move the player executed in game, or
+/// `exit` to stop early. The arena follows the moves.
+fn assist_step(
+  context: &mut CommandContext,
+  arena: &mut SolvableArena,
+  command: &str,
+  out: &mut CommandOutcome,
+) -> Result<(), ParseError> {
+  let Some((session, _)) = &mut context.assist else {
+    return Ok(());
+  };
+  let command = command.trim();
+  if command != "exit" {
+    let move_ = command
+      .parse::<Move>()
+      .map_err(|e| ParseError::error(command, "expected a move or exit", e))?;
+    let state = session.perform(move_);
+    *arena = session.arena().clone();
+    show(out, arena);
+    report_assist_state(out, session, &state);
+    if !session.is_finished() {
+      return Ok(());
+    }
+  }
+  finish_assist(context, out);
+  Ok(())
+}
+
+fn report_assist_state(out: &mut CommandOutcome, session: &AssistSession, state: &AssistState) {
+  match state {
+    AssistState::Solved => say!(out, "Arena is solved!"),
+    AssistState::Solvable(solution) => say!(
+      out,
+      "{} turns left, continue with: {}",
+      session.remaining_turns(),
+      solution
+        .iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+    ),
+    AssistState::Unsolvable => say!(
+      out,
+      "WARNING: the arena can't be solved in the remaining {} turns anymore",
+      session.remaining_turns()
+    ),
+  }
+}
+
+///Stops assisting and records how the fight went
+fn finish_assist(context: &mut CommandContext, out: &mut CommandOutcome) {
+  let Some((session, record)) = context.assist.take() else {
+    return;
+  };
+  let outcome = session.outcome();
+  record_stats(
+    context.stats.as_ref(),
+    out,
+    SolveRecord {
+      turns: outcome.solved.then_some(outcome.num_moves),
+      followed_optimal: Some(outcome.followed_optimal_line()),
+      ..record
+    },
+  );
+  say!(out, "stopped assisting");
+}
+
+///Records the solve if the context has a store
+fn record_stats(stats: Option<&StatsStore>, out: &mut CommandOutcome, record: SolveRecord) {
+  if let Some(Err(e)) = stats.map(|stats| stats.record(record)) {
+    say!(out, "failed to record stats: {}", e);
+  }
+}
+
+///Parses solve options, starting from the options of the active preset
+fn parse_solve_params<'a, I>(
+  args: Peekable<I>,
+  active_preset: Option<&SolverPreset>,
+) -> Result<SolveParams, ParseError>
+where
+  I: Iterator<Item = &'a str>,
+{
+  let base = active_preset.map_or_else(SolveParams::new, |preset| preset.params);
+  parse_solve_options(args, base)
+}
+
+///Parses solve options like `fast optimize time forbid r2 in 3`, changing the given params
+pub(crate) fn parse_solve_options<'a, I>(
+  args: Peekable<I>,
+  params: SolveParams,
+) -> Result<SolveParams, ParseError>
+where
+  I: Iterator<Item = &'a str>,
+{
+  let mut args = args;
+  let mut params = params;
+  while let Some(&arg) = args.peek() {
+    if arg == "fast" {
+      params.fast = true;
+    } else if arg == "deterministic" {
+      params.deterministic = true;
+    } else if let Some(strategy) = Strategy::by_name(arg) {
+      params.strategy = strategy;
+    } else if arg == "optimize" {
+      args.next();
+      let comparator_arg = args
+        .peek()
+        .copied()
+        .ok_or(ParseError::missing_argument("what to optimize"))?;
+      params.comparator = Comparator::by_name(comparator_arg).ok_or(
+        ParseError::illegal_argument(comparator_arg, "unknown optimization"),
+      )?;
+    } else if arg == "forbid" {
+      args.next();
+      let class_arg = args.peek().copied().ok_or(ParseError::missing_argument(
+        "rows, columns, ring or column",
+      ))?;
+      params.move_filter = match class_arg {
+        "rows" => params.move_filter.forbid_rows(),
+        "columns" => params.move_filter.forbid_columns(),
+        _ if class_arg.starts_with('r') => params.move_filter.forbid_ring(parse_ring(class_arg)?),
+        _ => params.move_filter.forbid_column(parse_column(class_arg)?),
+      };
+    } else {
+      break;
+    }
+    args.next();
+  }
+  if let Some(arg) = args.next() {
+    if arg != "in" {
+      return Err(ParseError::illegal_argument(arg, "expected in"));
+    }
+    let arg = args
+      .next()
+      .ok_or(ParseError::missing_argument("number of turns"))?;
+    let turns = arg
+      .parse::<Num>()
+      .map_err(|e| ParseError::error(arg, "not a number", e))?;
+    params.in_turns = Some(turns);
+  }
+  Ok(params)
+}
+
+fn parse_positions(column_arg: &str, rows_arg: &str) -> Result<Vec<Position>, ParseError> {
+  let column = parse_column(column_arg)?;
+  Ok(
+    parse_rows(column_arg, rows_arg)?
+      .into_iter()
+      .map(|row| Position { row, column })
+      .collect(),
+  )
+}
+
+fn parse_required_attack(arg: &str) -> Result<RequiredAttack, ParseError> {
+  match arg {
+    "H" => Ok(RequiredAttack::Hammer),
+    "J" => Ok(RequiredAttack::Jump),
+    "P" => Ok(RequiredAttack::IronBootsOrHammer),
+    _ => Err(ParseError::illegal_argument(arg, "expected H, J or P")),
+  }
+}
+
+fn parse_column(column_arg: &str) -> Result<Num, ParseError> {
+  if !column_arg.starts_with('c') {
+    return Err(ParseError::unknown_command(column_arg));
+  }
+
+  let column_number_arg = &column_arg[1..];
+  let column_number = column_number_arg
+    .parse::<Num>()
+    .map_err(|e| ParseError::error(column_arg, "invalid column number", e))?
+    .saturating_sub(1);
+  Column
+    .adapt(column_number)
+    .map_err(|e| ParseError::error(column_arg, "out of bounds", e))
+}
+
+fn parse_ring(ring_arg: &str) -> Result<Num, ParseError> {
+  let ring_number = ring_arg[1..]
+    .parse::<Num>()
+    .map_err(|e| ParseError::error(ring_arg, "invalid ring number", e))?
+    .saturating_sub(1);
+  Row
+    .adapt(ring_number)
+    .map_err(|e| ParseError::error(ring_arg, "out of bounds", e))
+}
+
+///Parses the enemy counts per column, going clockwise from column 1, like `0,2H,1`
+fn parse_quick_entry(entries: &str) -> Result<Vec<(Num, Option<RequiredAttack>)>, ParseError> {
+  let entries: Vec<&str> = entries
+    .split(',')
+    .map(str::trim)
+    .filter(|entry| !entry.is_empty())
+    .collect();
+  if entries.is_empty() {
+    return Err(ParseError::missing_argument("enemy counts"));
+  }
+  if entries.len() > Column.size() as usize {
+    return Err(ParseError::illegal_argument(
+      entries.join(","),
+      format!("there are only {} columns", Column.size()),
+    ));
+  }
+
+  entries
+    .into_iter()
+    .map(|entry| {
+      let (count_arg, required_attack_arg) = entry.split_at(
+        entry
+          .find(|c: char| !c.is_ascii_digit())
+          .unwrap_or(entry.len()),
+      );
+      let count = count_arg
+        .parse::<Num>()
+        .map_err(|e| ParseError::error(entry, "enemy count has to be a number", e))?;
+      if count > Row.size() {
+        return Err(ParseError::illegal_argument(
+          entry,
+          format!("a column holds at most {} enemies", Row.size()),
+        ));
+      }
+      let required_attack = (!required_attack_arg.is_empty())
+        .then(|| parse_required_attack(required_attack_arg))
+        .transpose()?;
+      Ok((count, required_attack))
+    })
+    .collect()
+}
+
+///Parses the rows of a column, numbered from 1 for the innermost ring. Rows can be given as digits
+/// like `124`, as ranges like `1-3`, as lists of both like `1,3-4` or as `all`.
+fn parse_rows(column_arg: &str, rows_arg: &str) -> Result<Vec<Num>, ParseError> {
+  let invalid =
+    |reason: String| ParseError::illegal_argument(format!("{} {}", column_arg, rows_arg), reason);
+  if rows_arg == "all" {
+    return Ok((0..Row.size()).collect());
+  }
+
+  let mut rows = vec![];
+  for part in rows_arg.split(',') {
+    let part_rows = match part.split_once('-') {
+      Some((from_arg, to_arg)) => {
+        let from = parse_row_number(from_arg).map_err(&invalid)?;
+        let to = parse_row_number(to_arg).map_err(&invalid)?;
+        if from > to {
+          return Err(invalid(format!(
+            "row range {} has to go from low to high",
+            part
+          )));
+        }
+        (from..=to).collect()
+      }
+      None if part.is_empty() => return Err(invalid("empty row in list".to_string())),
+      None => part
+        .split_inclusive(|_| true)
+        .map(parse_row_number)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(&invalid)?,
+    };
+    for row in part_rows {
+      if rows.contains(&row) {
+        return Err(invalid(format!("row {} is given twice", row + 1)));
+      }
+      rows.push(row);
+    }
+  }
+  Ok(rows)
+}
+
+///Parses a single row number, which starts at 1
+fn parse_row_number(row_arg: &str) -> Result<Num, String> {
+  row_arg
+    .parse::<Num>()
+    .ok()
+    .and_then(|row_number| row_number.checked_sub(1))
+    .filter(|&row| row < Row.size())
+    .ok_or(format!(
+      "'{}' is no row, rows go from 1 to {}, like 124, 1-3, 1,2,4 or all",
+      row_arg,
+      Row.size()
+    ))
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+  MissingArgument { argument_name: String },
+  UnknownCommand { command: String },
+  IllegalArgument { argument: String, reason: String },
+}
+
+impl ParseError {
+  pub fn missing_argument<S>(argument_name: S) -> Self
+  where
+    S: ToString,
+  {
+    Self::MissingArgument {
+      argument_name: argument_name.to_string(),
+    }
+  }
+
+  pub fn unknown_command<S>(command: S) -> Self
+  where
+    S: ToString,
+  {
+    Self::UnknownCommand {
+      command: command.to_string(),
+    }
+  }
+
+  pub fn illegal_argument<A, R>(argument: A, reason: R) -> Self
+  where
+    A: ToString,
+    R: ToString,
+  {
+    Self::IllegalArgument {
+      argument: argument.to_string(),
+      reason: reason.to_string(),
+    }
+  }
+
+  pub fn error<A, R, E>(argument: A, detail_message: R, error: E) -> Self
+  where
+    A: ToString,
+    R: ToString,
+    E: Error,
+  {
+    Self::illegal_argument(
+      argument,
+      format!("{}: {}", detail_message.to_string(), error),
+    )
+  }
+}
+
+impl Display for ParseError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ParseError::MissingArgument { argument_name } => {
+        write!(f, "missing argument: {}", argument_name)
+      }
+      ParseError::UnknownCommand { command } => {
+        write!(f, "unknown command: {}", command)
+      }
+      ParseError::IllegalArgument { argument, reason } => {
+        write!(f, "Illegal argument '{}': {}", argument, reason)
+      }
+    }
+  }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod test_command {
+  use std::env;
+  use std::fs;
+
+  use crate::command::{
+    CommandContext, ParseError, TurnBudget, parse_quietly, parse_rows, parse_solve_params, prompt,
+    side_by_side,
+  };
+  use crate::parse;
+  use crate::position::Position;
+  use crate::solving::move_filter::MoveFilter;
+  use crate::solving::{RequiredAttack, SolvableArena};
+  use crate::stats::StatsStore;
+
+  #[test]
+  fn test_forbid() {
+    let params = parse_solve_params(
+      "forbid rows fast forbid c6 in 3"
+        .split_whitespace()
+        .peekable(),
+      None,
+    )
+    .expect("parse error");
+    assert_eq!(Some(3), params.in_turns);
+    assert!(params.fast);
+    assert_eq!(
+      MoveFilter::new().forbid_rows().forbid_column(5),
+      params.move_filter
+    );
+    assert!(parse_solve_params("forbid".split_whitespace().peekable(), None).is_err());
+    assert!(parse_solve_params("forbid x3".split_whitespace().peekable(), None).is_err());
+  }
+
+  #[test]
+  fn test_quick_entry() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    parse(&mut context, &mut arena, "c7 1").expect("parse error");
+    parse(&mut context, &mut arena, "q 0,2H, 1,0,4J").expect("parse error");
+
+    assert_eq!(7, arena.enemies.len());
+    assert!(arena.get_at(&Position::at(0, 6).unwrap()).is_none());
+    for row in [2, 3] {
+      let enemy = arena.get_at(&Position::at(row, 1).unwrap()).expect("enemy");
+      assert_eq!(Some(RequiredAttack::Hammer), enemy.required_attack);
+    }
+    assert!(arena.get_at(&Position::at(3, 2).unwrap()).is_some());
+    assert_eq!(
+      4,
+      arena
+        .enemies
+        .iter()
+        .filter(|enemy| enemy.column == 4 && enemy.required_attack == Some(RequiredAttack::Jump))
+        .count()
+    );
+  }
+
+  #[test]
+  fn test_rows() {
+    assert_eq!(vec![0, 1, 3], parse_rows("c1", "124").unwrap());
+    assert_eq!(vec![0, 1, 2], parse_rows("c1", "1-3").unwrap());
+    assert_eq!(vec![3, 0, 1], parse_rows("c1", "4,1-2").unwrap());
+    assert_eq!(vec![0, 1, 2, 3], parse_rows("c1", "all").unwrap());
+    for rows in [
+      "0", "5", "11", "3-1", "1,,2", "1-", "x", "", "1,1-2", "12345",
+    ] {
+      assert!(
+        parse_rows("c1", rows).is_err(),
+        "{} should be invalid",
+        rows
+      );
+    }
+
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    parse(&mut context, &mut arena, "c3 2-4").expect("parse error");
+    assert_eq!(3, arena.enemies.len());
+    assert!(parse(&mut context, &mut arena, "c1 0").is_err());
+  }
+
+  #[test]
+  fn test_weakness_per_row() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    parse(&mut context, &mut arena, "c3 1H 2-3J 4").expect("parse error");
+    parse(&mut context, &mut arena, "c5 1P 34 J").expect("parse error");
+    let required_attack = |row, column| {
+      arena
+        .get_at(&Position::at(row, column).unwrap())
+        .expect("enemy")
+        .required_attack
+    };
+    assert_eq!(Some(RequiredAttack::Hammer), required_attack(0, 2));
+    assert_eq!(Some(RequiredAttack::Jump), required_attack(2, 2));
+    assert_eq!(None, required_attack(3, 2));
+    assert_eq!(
+      Some(RequiredAttack::IronBootsOrHammer),
+      required_attack(0, 4)
+    );
+    assert_eq!(Some(RequiredAttack::Jump), required_attack(3, 4));
+    assert_eq!(7, arena.enemies.len());
+
+    for cmd in ["c1 1H 1J", "c1 1X", "c1 H", "c1"] {
+      assert!(
+        parse(&mut context, &mut arena, cmd).is_err(),
+        "{} should be invalid",
+        cmd
+      );
+    }
+  }
+
+  #[test]
+  fn test_move_and_swap() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    parse(&mut context, &mut arena, "c3 1H 2J 4").expect("parse error");
+    parse(&mut context, &mut arena, "first-strike c3 2").expect("parse error");
+    parse(&mut context, &mut arena, "move c3 1-2 -> c5").expect("parse error");
+    assert_eq!(Position::at(1, 4).ok(), arena.first_strike);
+    let enemy = arena.get_at(&Position::at(0, 4).unwrap()).expect("moved");
+    assert_eq!(Some(RequiredAttack::Hammer), enemy.required_attack);
+
+    parse(&mut context, &mut arena, "swap c5 c3").expect("parse error");
+    assert_eq!(Position::at(1, 2).ok(), arena.first_strike);
+    assert!(arena.get_at(&Position::at(3, 4).unwrap()).is_some());
+    parse(&mut context, &mut arena, "duplicate c3 -> c9").expect("parse error");
+    assert_eq!(5, arena.enemies.len());
+
+    for cmd in [
+      "move c3 4 -> c5",
+      "move c3 12 c5",
+      "duplicate c3 ->",
+      "swap c3",
+    ] {
+      assert!(
+        parse(&mut context, &mut arena, cmd).is_err(),
+        "{} should be invalid",
+        cmd
+      );
+    }
+  }
+
+  #[test]
+  fn test_preview() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    parse(&mut context, &mut arena, "c2 14").expect("parse error");
+    for cmd in [
+      "clear?",
+      "e? r1 3",
+      "c3? 2",
+      "move? c2 -> c5",
+      "hp? c2 1 3",
+      "-? c2",
+      "-hammer?",
+    ] {
+      parse(&mut context, &mut arena, cmd).expect("parse error");
+    }
+    assert_eq!(2, arena.enemies.len());
+    assert!(arena
+      .enemies
+      .iter()
+      .all(|enemy| enemy.column == 1 && enemy.hp.is_none()));
+
+    for cmd in [
+      "solve? in 2",
+      "stats?",
+      "first-strike? suggest in 1",
+      "c3? 5",
+      "attach? 127.0.0.1:1",
+      "copy?",
+      "check?",
+      "history?",
+      "undo?",
+    ] {
+      assert!(
+        parse(&mut context, &mut arena, cmd).is_err(),
+        "{} should be invalid",
+        cmd
+      );
+    }
+    //a failed preview doesn't keep other commands silent
+    assert!(
+      !parse(&mut context, &mut arena, "c3 2")
+        .unwrap()
+        .messages
+        .is_empty()
+    );
+  }
+
+  #[test]
+  fn test_empty_command() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    for cmd in ["", " ", "\t \n"] {
+      assert!(matches!(
+        parse(&mut context, &mut arena, cmd),
+        Err(ParseError::MissingArgument { .. })
+      ));
+    }
+    assert!(parse_quietly(&mut arena, "  ").is_err());
+  }
+
+  #[test]
+  fn test_outcome() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    let outcome = parse(&mut context, &mut arena, "c2 14").expect("parse error");
+    assert!(outcome.arena_changed);
+    assert_eq!(vec![arena.inner.to_string()], outcome.messages);
+    assert_eq!(None, outcome.solution);
+
+    let outcome = parse(&mut context, &mut arena, "link").expect("parse error");
+    assert!(!outcome.arena_changed);
+    assert_eq!(1, outcome.messages.len());
+    assert_eq!(format!("{}\n", outcome.messages[0]), outcome.to_string());
+
+    let outcome = parse(&mut context, &mut arena, "c3? 2").expect("parse error");
+    assert!(!outcome.arena_changed);
+    assert!(outcome.messages[0].contains("after c3 2"));
+  }
+
+  #[test]
+  fn test_what_if() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    parse(&mut context, &mut arena, "c2 14").expect("parse error");
+    let before = arena.clone();
+    for cmd in [
+      "whatif remove c2 1",
+      "whatif convert c2 4 J in 2",
+      "whatif convert c2 4 none",
+    ] {
+      parse(&mut context, &mut arena, cmd).expect("parse error");
+    }
+    assert_eq!(before, arena);
+
+    for cmd in [
+      "whatif remove c3 1",
+      "whatif remove c2",
+      "whatif convert c2 1",
+      "whatif convert c2 1 X",
+      "whatif add c2 1",
+      "whatif? remove c2 1",
+    ] {
+      assert!(
+        parse(&mut context, &mut arena, cmd).is_err(),
+        "{} should be invalid",
+        cmd
+      );
+    }
+  }
+
+  #[test]
+  fn test_turn_budget() {
+    let budget = TurnBudget {
+      turns: 3,
+      coins_per_extra_turn: Some(100),
+    };
+    assert_eq!(None, budget.check(3));
+    let warning = budget.check(5).expect("exceeds the budget");
+    assert!(warning.contains("needs 5 turns"));
+    assert!(warning.contains("2 more turns cost 200 coins"));
+
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    for cmd in ["turns x", "turns 3 coins", "turns 3 gold 5"] {
+      assert!(
+        parse(&mut context, &mut arena, cmd).is_err(),
+        "{} should be invalid",
+        cmd
+      );
+    }
+
+    //the budget belongs to the fight in this context only
+    parse(&mut context, &mut arena, "turns 3").expect("parse error");
+    let budget = |context: &mut CommandContext, arena: &mut SolvableArena| {
+      parse(context, arena, "turns")
+        .expect("parse error")
+        .to_string()
+    };
+    assert!(budget(&mut context, &mut arena).contains("grants 3"));
+    assert!(budget(&mut CommandContext::new(), &mut arena).contains("no turn budget"));
+    parse(&mut context, &mut arena, "clear").expect("parse error");
+    assert!(budget(&mut context, &mut arena).contains("no turn budget"));
+  }
+
+  #[test]
+  fn test_prompt() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    assert_eq!("[0E g0 +H ✓] > ", prompt(&arena));
+    for cmd in ["c1 1", "c4 1", "c7 2", "-hammer"] {
+      parse(&mut context, &mut arena, cmd).expect("parse error");
+    }
+    assert_eq!("[3E g1 -H ✗] > ", prompt(&arena));
+    parse(&mut context, &mut arena, "g 2").expect("parse error");
+    assert_eq!("[3E g2 -H ✗] > ", prompt(&arena));
+  }
+
+  #[test]
+  fn test_compare() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    parse(&mut context, &mut arena, "c1 1").expect("parse error");
+    parse(&mut context, &mut arena, "compare dfs bidirectional in 2").expect("parse error");
+    for cmd in [
+      "compare",
+      "compare dfs",
+      "compare dfs magic",
+      "compare dfs dfs in",
+    ] {
+      assert!(
+        parse(&mut context, &mut arena, cmd).is_err(),
+        "{} should be invalid",
+        cmd
+      );
+    }
+  }
+
+  #[test]
+  fn test_side_by_side() {
+    assert_eq!(
+      "a       c\nbbbb    d\n        e",
+      side_by_side(("a", "bbbb"), ("c", "d\ne"))
+    );
+  }
+
+  #[test]
+  fn test_quick_entry_invalid() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    for cmd in ["q", "q 5", "q 1X", "q 0,0,0,0,0,0,0,0,0,0,0,0,1"] {
+      assert!(
+        parse(&mut context, &mut arena, cmd).is_err(),
+        "{} should be invalid",
+        cmd
+      );
+    }
+  }
+
+  #[test]
+  fn test_stats() {
+    let mut arena = SolvableArena::default();
+    parse(&mut CommandContext::new(), &mut arena, "c2 1234").expect("parse error");
+    //without a store nothing is written
+    let outcome = parse(&mut CommandContext::new(), &mut arena, "stats").expect("parse error");
+    assert!(outcome.to_string().contains("aren't recorded"));
+
+    let path = env::temp_dir().join(format!("tok_command_stats_{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let mut context = CommandContext::new().with_stats(StatsStore::new(&path));
+    parse(&mut context, &mut arena, "solve in 1").expect("parse error");
+    let history = StatsStore::new(&path)
+      .history()
+      .expect("failed to read stats");
+    assert_eq!(1, history.len());
+    assert!(history[0].turns.is_some());
+    let outcome = parse(&mut context, &mut arena, "stats").expect("parse error");
+    assert!(outcome.to_string().contains("1 solve"));
+    let _ = fs::remove_file(&path);
+  }
+
+  #[test]
+  fn test_assist() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    let path = env::temp_dir().join(format!("tok_assist_stats_{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let mut context = CommandContext::new().with_stats(StatsStore::new(&path));
+    let outcome = parse(&mut context, &mut arena, "assist in 3").expect("parse error");
+    assert!(context.is_assisting());
+    assert!(outcome.to_string().contains("continue with"));
+
+    //while assisting, commands are moves
+    assert!(parse(&mut context, &mut arena, "help").is_err());
+    let outcome = parse(&mut context, &mut arena, "r1 1").expect("parse error");
+    assert!(outcome.arena_changed);
+    assert!(context.is_assisting());
+    parse(&mut context, &mut arena, "exit").expect("parse error");
+    assert!(!context.is_assisting());
+    let history = StatsStore::new(&path)
+      .history()
+      .expect("failed to read stats");
+    assert_eq!(Some(false), history[0].followed_optimal);
+    let _ = fs::remove_file(&path);
+  }
+}
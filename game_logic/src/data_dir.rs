@@ -0,0 +1,77 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+///The environment variable pointing to the directory [`data_dir`] returns
+pub const DATA_DIR_VAR: &str = "TOK_DATA_DIR";
+///The folder of the app in the data directory of the user
+const APP_FOLDER: &str = "tok";
+
+///The directory the files of the user are kept in, like the statistics and the settings of the gui:
+/// the directory [`DATA_DIR_VAR`] points to, or a `tok` folder in the data directory of the user,
+/// like `~/.local/share/tok` on linux. Falls back to the working directory if the system doesn't
+/// tell where the data directory is.
+pub fn data_dir() -> PathBuf {
+  path_var(DATA_DIR_VAR)
+    .or_else(|| system_data_dir().map(|directory| directory.join(APP_FOLDER)))
+    .unwrap_or_default()
+}
+
+///Where a file of the user is kept: at the path the environment variable points to, or under the
+/// name in [`data_dir`]
+pub fn data_file(var: &str, name: &str) -> PathBuf {
+  path_var(var).unwrap_or_else(|| data_dir().join(name))
+}
+
+///Creates the directory the file is in, so the file can be written to the [`data_dir`] before it
+/// exists
+pub fn create_parent(path: &Path) -> io::Result<()> {
+  match path
+    .parent()
+    .filter(|parent| !parent.as_os_str().is_empty())
+  {
+    Some(directory) => fs::create_dir_all(directory),
+    None => Ok(()),
+  }
+}
+
+fn path_var(var: &str) -> Option<PathBuf> {
+  env::var_os(var)
+    .filter(|path| !path.is_empty())
+    .map(PathBuf::from)
+}
+
+#[cfg(windows)]
+fn system_data_dir() -> Option<PathBuf> {
+  path_var("APPDATA")
+}
+
+#[cfg(target_os = "macos")]
+fn system_data_dir() -> Option<PathBuf> {
+  path_var("HOME").map(|home| home.join("Library/Application Support"))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn system_data_dir() -> Option<PathBuf> {
+  path_var("XDG_DATA_HOME").or_else(|| path_var("HOME").map(|home| home.join(".local/share")))
+}
+
+#[cfg(test)]
+mod test_data_dir {
+  use std::env;
+  use std::fs;
+
+  use crate::data_dir::create_parent;
+
+  #[test]
+  fn test_create_parent() {
+    let directory = env::temp_dir().join(format!("tok_data_{}", std::process::id()));
+    let path = directory.join("nested").join("stats.txt");
+    create_parent(&path).expect("failed to create the directory");
+    fs::write(&path, "").expect("failed to write into the directory");
+    //files without a directory are in the working directory, which exists
+    create_parent("stats.txt".as_ref()).expect("nothing to create");
+    let _ = fs::remove_dir_all(&directory);
+  }
+}
@@ -0,0 +1,194 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+use crate::import::{ArenaFormat, ImportError, RingString};
+use crate::position::Move;
+use crate::solving::Enemy;
+
+///The scheme of links opening the app with an arena, see [`arena_link`]
+pub const SCHEME: &str = "tok-solver";
+const ARENA_PREFIX: &str = "tok-solver://arena/";
+///Starts the fragment of a [`solution_link`]
+const SOLUTION_FRAGMENT: &str = "#solution=";
+
+///A link opening the app with the enemies, like `tok-solver://arena/.E........../...` with the
+/// rings in the compact [`RingString`] notation. The rings are separated by `/`, which chat apps
+/// and browsers leave as it is.
+pub fn arena_link(enemies: &[Enemy]) -> String {
+  format!(
+    "{}{}",
+    ARENA_PREFIX,
+    RingString::format(enemies).replace('|', "/")
+  )
+}
+
+///An [`arena_link`] which shows a solution of the arena as well, like
+/// `tok-solver://arena/...#solution=r3_-1,c4_2`. The moves are in the fragment, which never leaves
+/// the device opening the link, so web pages can read them without a backend.
+pub fn solution_link(enemies: &[Enemy], solution: &[Move]) -> String {
+  let moves: Vec<String> = solution
+    .iter()
+    .map(|move_| move_.to_string().replace(' ', "_"))
+    .collect();
+  format!(
+    "{}{}{}",
+    arena_link(enemies),
+    SOLUTION_FRAGMENT,
+    moves.join(",")
+  )
+}
+
+///Whether the text is a link of the app, like a command line argument the system opens a clicked
+/// link with
+pub fn is_link(text: &str) -> bool {
+  text
+    .get(..SCHEME.len() + 1)
+    .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}:", SCHEME)))
+}
+
+///Reads the enemies of an [`arena_link`]. Escaped characters are decoded and anything browsers add
+/// after the notation, like a trailing slash or a query, is ignored.
+pub fn parse_arena_link(link: &str) -> Result<Vec<Enemy>, LinkError> {
+  let link = link.trim();
+  let notation = link
+    .get(..ARENA_PREFIX.len())
+    .filter(|prefix| prefix.eq_ignore_ascii_case(ARENA_PREFIX))
+    .map(|_| &link[ARENA_PREFIX.len()..])
+    .ok_or(LinkError::NotAnArenaLink)?;
+  let notation = notation.split(['?', '#']).next().unwrap_or_default();
+  RingString
+    .parse(&percent_decode(notation)?)
+    .map_err(LinkError::Import)
+}
+
+///Reads the moves of a [`solution_link`], which are empty if the link has no solution
+pub fn parse_solution(link: &str) -> Result<Vec<Move>, LinkError> {
+  let Some((_, moves)) = link.trim().split_once(SOLUTION_FRAGMENT) else {
+    return Ok(Vec::new());
+  };
+  percent_decode(moves)?
+    .split(',')
+    .filter(|move_| !move_.is_empty())
+    .map(|move_| {
+      move_
+        .replace('_', " ")
+        .parse()
+        .map_err(|_| LinkError::InvalidMove(move_.into()))
+    })
+    .collect()
+}
+
+///Decodes the `%XX` escapes of the text
+fn percent_decode(text: &str) -> Result<String, LinkError> {
+  let mut bytes = Vec::with_capacity(text.len());
+  let mut rest = text.as_bytes();
+  while let Some((&byte, tail)) = rest.split_first() {
+    if byte == b'%' {
+      let escape = tail
+        .get(..2)
+        .and_then(|hex| core::str::from_utf8(hex).ok())
+        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        .ok_or(LinkError::InvalidEscape)?;
+      bytes.push(escape);
+      rest = &tail[2..];
+    } else {
+      bytes.push(byte);
+      rest = tail;
+    }
+  }
+  String::from_utf8(bytes).map_err(|_| LinkError::InvalidEscape)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum LinkError {
+  NotAnArenaLink,
+  ///a `%` isn't followed by two hex digits, or the decoded link isn't utf-8
+  InvalidEscape,
+  Import(ImportError),
+  ///a move of the solution can't be read
+  InvalidMove(String),
+}
+
+impl Display for LinkError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      LinkError::NotAnArenaLink => write!(f, "not a link starting with {}", ARENA_PREFIX),
+      LinkError::InvalidEscape => write!(f, "invalid escaped character"),
+      LinkError::Import(e) => write!(f, "invalid arena: {}", e),
+      LinkError::InvalidMove(move_) => write!(f, "invalid move in the solution: {}", move_),
+    }
+  }
+}
+
+impl Error for LinkError {}
+
+#[cfg(test)]
+mod test_deep_link {
+  use crate::deep_link::{
+    LinkError, arena_link, is_link, parse_arena_link, parse_solution, solution_link,
+  };
+  use crate::import::{ArenaFormat, RingString};
+  use crate::position::Move;
+
+  #[test]
+  fn test_round_trip() {
+    let mut enemies = RingString.parse(RingString.example()).unwrap();
+    let link = arena_link(&enemies);
+    assert_eq!(
+      "tok-solver://arena/.E........../.E........../.EH........./.E..........",
+      link
+    );
+    assert!(is_link(&link));
+    let mut parsed = parse_arena_link(&link).unwrap();
+    parsed.sort();
+    enemies.sort();
+    assert_eq!(enemies, parsed);
+  }
+
+  #[test]
+  fn test_solution() {
+    let enemies = RingString.parse(RingString.example()).unwrap();
+    let solution: Vec<Move> = ["r3 -1", "c4 2"]
+      .into_iter()
+      .map(|move_| move_.parse().unwrap())
+      .collect();
+    let link = solution_link(&enemies, &solution);
+    assert!(link.starts_with(&arena_link(&enemies)));
+    assert!(link.ends_with("#solution=r3_-1,c4_2"));
+    assert_eq!(solution, parse_solution(&link).unwrap());
+    assert_eq!(5, parse_arena_link(&link).unwrap().len());
+
+    assert_eq!(Ok(vec![]), parse_solution(&arena_link(&enemies)));
+    assert_eq!(
+      solution,
+      parse_solution("tok-solver://arena/#solution=r3%20-1,c4_2").unwrap()
+    );
+    assert_eq!(
+      Err(LinkError::InvalidMove("x3_1".into())),
+      parse_solution("tok-solver://arena/#solution=x3_1")
+    );
+  }
+
+  #[test]
+  fn test_browser_changes() {
+    let escaped =
+      "TOK-SOLVER://arena/.E..........%7C.E..........%7C.EH.........%7C.E........../?utm=chat";
+    assert_eq!(5, parse_arena_link(escaped).unwrap().len());
+    assert_eq!(
+      Err(LinkError::InvalidEscape),
+      parse_arena_link("tok-solver://arena/%7")
+    );
+    assert_eq!(
+      Err(LinkError::NotAnArenaLink),
+      parse_arena_link("https://example.com/arena/")
+    );
+    assert!(matches!(
+      parse_arena_link("tok-solver://arena/E.E"),
+      Err(LinkError::Import(_))
+    ));
+    assert!(!is_link("arena.txt"));
+  }
+}
@@ -0,0 +1,246 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::command::{ParseError, add_enemies};
+use crate::import::ARENA_FORMATS;
+use crate::position::Num;
+use crate::solving::{SolvableArena, SolveParams};
+
+///The directory fixtures are loaded from by default, relative to the `game_logic` crate
+pub const DEFAULT_FIXTURE_DIR: &str = "fixtures";
+
+///An arena with the number of turns it's known to need, used to check changes to the solver.
+///
+///Fixture files hold one command per line: enemies like `c2 124 H`, the number of groups like `g 3`,
+/// equipment flags like `-hammer`, an optional turn limit like `in 3` and the expected result like
+/// `expect 2` or `expect none`. Empty lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Fixture {
+  pub name: String,
+  pub arena: SolvableArena,
+  ///the maximum number of turns, or `None` to solve in as few turns as possible
+  pub in_turns: Option<Num>,
+  ///the number of turns of the optimal solution, `Some(None)` if there is none and `None` if unknown
+  pub expected_turns: Option<Option<usize>>,
+}
+
+impl Fixture {
+  pub fn parse(name: &str, content: &str) -> Result<Self, FixtureError> {
+    let mut fixture = Self {
+      name: name.to_string(),
+      arena: SolvableArena::default(),
+      in_turns: None,
+      expected_turns: None,
+    };
+    for (index, line) in content.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      fixture.apply(line).map_err(|e| FixtureError::InvalidLine {
+        fixture: name.to_string(),
+        line: index + 1,
+        error: e,
+      })?;
+    }
+    Ok(fixture)
+  }
+
+  fn apply(&mut self, line: &str) -> Result<(), ParseError> {
+    let args: Vec<&str> = line.split_whitespace().collect();
+    match args[..] {
+      ["expect", "none"] => self.expected_turns = Some(None),
+      ["expect", turns] => {
+        let turns = turns
+          .parse()
+          .map_err(|e| ParseError::error(turns, "not a number", e))?;
+        self.expected_turns = Some(Some(turns));
+      }
+      ["in", turns] => {
+        let turns = turns
+          .parse()
+          .map_err(|e| ParseError::error(turns, "not a number", e))?;
+        self.in_turns = Some(turns);
+      }
+      ["g", num_groups] => {
+        let num_groups = num_groups
+          .parse()
+          .map_err(|e| ParseError::error(num_groups, "not a number", e))?;
+        self.arena.num_groups = Some(num_groups);
+      }
+      [flag] if flag.starts_with(['+', '-']) => {
+        *self
+          .arena
+          .available_equipment
+          .flag_mut(&flag[1..])
+          .ok_or(ParseError::unknown_command(flag))? = flag.starts_with('+');
+      }
+      [column_arg, ref entry_args @ ..] => add_enemies(&mut self.arena, column_arg, entry_args)?,
+      _ => return Err(ParseError::unknown_command(line)),
+    }
+    Ok(())
+  }
+
+  ///How the fixture's arena has to be solved to compare the solution to the expected turns
+  pub fn params(&self) -> SolveParams {
+    SolveParams {
+      in_turns: self.in_turns,
+      ..Default::default()
+    }
+  }
+
+  ///Loads a fixture file, named after the file without its extension
+  pub fn load<P>(path: P) -> Result<Self, FixtureError>
+  where
+    P: AsRef<Path>,
+  {
+    let path = path.as_ref();
+    let name = path
+      .file_stem()
+      .map_or(String::new(), |stem| stem.to_string_lossy().to_string());
+    Self::parse(&name, &fs::read_to_string(path)?)
+  }
+
+  ///Loads every `.txt` file in the directory, sorted by name
+  pub fn load_dir<P>(dir: P) -> Result<Vec<Self>, FixtureError>
+  where
+    P: AsRef<Path>,
+  {
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir)? {
+      let path = entry?.path();
+      if path.extension().is_some_and(|extension| extension == "txt") {
+        paths.push(path);
+      }
+    }
+    paths.sort();
+    paths.iter().map(Self::load).collect()
+  }
+}
+
+///Reads an arena from a file, which may be a fixture or use one of the [`ARENA_FORMATS`]
+pub fn load_arena<P>(path: P) -> Result<SolvableArena, FixtureError>
+where
+  P: AsRef<Path>,
+{
+  let content = fs::read_to_string(path)?;
+  match Fixture::parse("arena", &content) {
+    Ok(fixture) => Ok(fixture.arena),
+    Err(e) => {
+      let enemies = ARENA_FORMATS
+        .iter()
+        .find_map(|format| format.parse(content.trim()).ok())
+        .ok_or(e)?;
+      let mut arena = SolvableArena::default();
+      for enemy in enemies {
+        arena.add(enemy);
+      }
+      Ok(arena)
+    }
+  }
+}
+
+#[derive(Debug)]
+pub enum FixtureError {
+  IO(io::Error),
+  InvalidLine {
+    fixture: String,
+    line: usize,
+    error: ParseError,
+  },
+}
+
+impl From<io::Error> for FixtureError {
+  fn from(value: io::Error) -> Self {
+    Self::IO(value)
+  }
+}
+
+impl Display for FixtureError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      FixtureError::IO(e) => write!(f, "IO error: {}", e),
+      FixtureError::InvalidLine {
+        fixture,
+        line,
+        error,
+      } => write!(f, "{}, line {}: {}", fixture, line, error),
+    }
+  }
+}
+
+impl Error for FixtureError {}
+
+#[cfg(test)]
+mod test_fixture {
+  use std::env;
+  use std::fs;
+  use std::path::Path;
+
+  use crate::fixture::{DEFAULT_FIXTURE_DIR, Fixture, load_arena};
+  use crate::solving::solve_with;
+  use crate::solving::solve_cache::SolveCache;
+
+  #[test]
+  fn test_parse() {
+    let fixture = Fixture::parse(
+      "test",
+      "# two enemies missing\nc2 124\nc3 3 J\n\ng 1\n-hammer\nin 2\nexpect 1\n",
+    )
+    .expect("valid fixture");
+    assert_eq!(4, fixture.arena.enemies.len());
+    assert_eq!(Some(1), fixture.arena.num_groups);
+    assert!(!fixture.arena.available_equipment.throwing_hammer);
+    assert_eq!(Some(2), fixture.in_turns);
+    assert_eq!(Some(Some(1)), fixture.expected_turns);
+
+    assert!(Fixture::parse("test", "c2 124\nsolve in 3").is_err());
+    assert!(Fixture::parse("test", "+sword").is_err());
+  }
+
+  #[test]
+  fn test_load_arena() {
+    let path = env::temp_dir().join(format!("tok_arena_{}.txt", std::process::id()));
+    for (content, num_enemies) in [("c2 124\nc3 3\n", 4), ("2 / 2 / 2,3H / 2\n", 5)] {
+      fs::write(&path, content).expect("failed to write arena");
+      let arena = load_arena(&path).expect("valid arena");
+      assert_eq!(num_enemies, arena.enemies.len());
+    }
+    fs::write(&path, "solve in 3").expect("failed to write arena");
+    assert!(load_arena(&path).is_err());
+    let _ = fs::remove_file(&path);
+  }
+
+  #[test]
+  fn test_repository_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_FIXTURE_DIR);
+    let fixtures = Fixture::load_dir(dir).expect("fixtures should be valid");
+    assert!(!fixtures.is_empty());
+    //the larger fixtures take too long for a unit test, run the verify binary for them
+    let quick = fixtures
+      .iter()
+      .filter(|fixture| match fixture.expected_turns {
+        Some(Some(turns)) => turns <= 2,
+        Some(None) => fixture.in_turns.is_some_and(|in_turns| in_turns <= 2),
+        None => false,
+      });
+    for fixture in quick {
+      let solution = solve_with(
+        &fixture.arena,
+        &fixture.params(),
+        &mut SolveCache::new(),
+        None,
+        |_| true,
+      );
+      assert_eq!(
+        fixture.expected_turns,
+        Some(solution.map(|solution| solution.len())),
+        "{}",
+        fixture.name
+      );
+    }
+  }
+}
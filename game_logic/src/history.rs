@@ -0,0 +1,188 @@
+use std::fmt::{Display, Formatter};
+
+use crate::command::ParseError;
+use crate::solving::SolvableArena;
+
+///The commands entered into the CLI, numbered from 1 like `history` lists them
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CommandHistory {
+  commands: Vec<String>,
+}
+
+impl CommandHistory {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  ///Replaces a reference to an earlier command with that command: `!!` repeats the last command
+  /// and `!<n>` the command with number n. Any other line is returned as it is.
+  pub fn expand(&self, line: &str) -> Result<String, ParseError> {
+    let Some(reference) = line.strip_prefix('!') else {
+      return Ok(line.to_string());
+    };
+    let command = if reference == "!" {
+      self
+        .commands
+        .last()
+        .ok_or(ParseError::illegal_argument(line, "no commands yet"))?
+    } else {
+      let number = reference
+        .parse::<usize>()
+        .map_err(|e| ParseError::error(line, "expected !! or !<number>", e))?;
+      number
+        .checked_sub(1)
+        .and_then(|index| self.commands.get(index))
+        .ok_or(ParseError::illegal_argument(
+          line,
+          "no command with this number, see history",
+        ))?
+    };
+    Ok(command.clone())
+  }
+
+  ///Remembers an expanded command, empty lines are skipped
+  pub fn push(&mut self, command: &str) {
+    if !command.is_empty() {
+      self.commands.push(command.to_string());
+    }
+  }
+
+  pub fn commands(&self) -> &[String] {
+    &self.commands
+  }
+}
+
+///The arena before each change, so changes can be undone. Kept in the [`crate::CommandContext`], every
+/// command or action changing the arena is one undo step.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct UndoHistory {
+  ///the arena before each change, the latest last
+  undo_steps: Vec<SolvableArena>,
+}
+
+impl UndoHistory {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  ///Remembers the arena as it was before a command or action changed it. Every change is one undo
+  /// step, no matter how many enemies it changed.
+  pub fn record_change(&mut self, before: SolvableArena) {
+    self.undo_steps.push(before);
+  }
+
+  ///How many changes can be undone
+  pub fn undo_steps(&self) -> usize {
+    self.undo_steps.len()
+  }
+
+  ///Goes back `count` changes of the arena, or as many as there are. Returns the arena before the
+  /// earliest of them, None if there is nothing to undo.
+  pub fn undo(&mut self, count: usize) -> Option<SolvableArena> {
+    let start = self.undo_steps.len().saturating_sub(count);
+    self.undo_steps.drain(start..).next()
+  }
+}
+
+///The number of commands `undo` or `undo 3` steps back. None for other commands, also for `undo`
+/// followed by enemies like `undo c3 2`, which is another name for `-`.
+pub(crate) fn undo_count(command: &str) -> Option<usize> {
+  let mut args = command.split_whitespace();
+  if args.next() != Some("undo") {
+    return None;
+  }
+  match (args.next(), args.next()) {
+    (None, _) => Some(1),
+    (Some(arg), None) => arg.parse().ok(),
+    (Some(_), Some(_)) => None,
+  }
+}
+
+///Lists the commands with their numbers, one per line
+impl Display for CommandHistory {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    for (index, command) in self.commands.iter().enumerate() {
+      writeln!(f, "{:>4}  {}", index + 1, command)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test_history {
+  use crate::command::parse_quietly;
+  use crate::history::{CommandHistory, UndoHistory, undo_count};
+  use crate::solving::SolvableArena;
+  use crate::{CommandContext, parse};
+
+  #[test]
+  fn test_expand() {
+    let mut history = CommandHistory::new();
+    assert!(history.expand("!!").is_err());
+    assert_eq!("c1 12", history.expand("c1 12").unwrap());
+
+    history.push("c1 12");
+    history.push("");
+    history.push("solve in 2");
+    assert_eq!(2, history.commands().len());
+    assert_eq!("solve in 2", history.expand("!!").unwrap());
+    assert_eq!("c1 12", history.expand("!1").unwrap());
+    for line in ["!0", "!3", "!x", "!"] {
+      assert!(history.expand(line).is_err(), "{} should be invalid", line);
+    }
+    assert_eq!("   1  c1 12\n   2  solve in 2\n", history.to_string());
+  }
+
+  #[test]
+  fn test_undo_history() {
+    let mut history = UndoHistory::new();
+    let mut arena = SolvableArena::default();
+    for cmd in ["c1 1234", "c3 2", "e r2 1"] {
+      history.record_change(arena.clone());
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    assert_eq!(3, history.undo_steps());
+
+    let mut expected = SolvableArena::default();
+    parse_quietly(&mut expected, "c1 1234").expect("parse error");
+    assert_eq!(Some(expected), history.undo(2));
+    assert_eq!(Some(SolvableArena::default()), history.undo(3));
+    assert_eq!(None, history.undo(1));
+  }
+
+  #[test]
+  fn test_undo() {
+    let mut context = CommandContext::new();
+    let mut arena = SolvableArena::default();
+    for cmd in ["c1 1234", "c3 2", "solve in 1", "e r2 1"] {
+      parse(&mut context, &mut arena, cmd).expect("parse error");
+    }
+
+    //the four enemies of the first command are one step
+    let mut expected = SolvableArena::default();
+    parse_quietly(&mut expected, "c1 1234").expect("parse error");
+    let outcome = parse(&mut context, &mut arena, "undo 2").expect("parse error");
+    assert!(outcome.arena_changed);
+    assert_eq!(expected, arena);
+    parse(&mut context, &mut arena, "undo").expect("parse error");
+    assert_eq!(SolvableArena::default(), arena);
+    assert!(parse(&mut context, &mut arena, "undo").is_err());
+
+    //undo followed by enemies still removes them like -
+    parse(&mut context, &mut arena, "c3 12").expect("parse error");
+    parse(&mut context, &mut arena, "undo c3 2").expect("parse error");
+    let mut expected = SolvableArena::default();
+    parse_quietly(&mut expected, "c3 1").expect("parse error");
+    assert_eq!(expected, arena);
+    parse(&mut context, &mut arena, "undo").expect("parse error");
+    parse_quietly(&mut expected, "c3 2").expect("parse error");
+    assert_eq!(expected, arena);
+    assert!(parse(&mut context, &mut arena, "undo 3x").is_err());
+
+    assert_eq!(Some(1), undo_count("undo"));
+    assert_eq!(Some(3), undo_count("undo 3"));
+    assert_eq!(None, undo_count("undo r2"));
+    assert_eq!(None, undo_count("history"));
+    assert_eq!(None, undo_count("- c3 2"));
+  }
+}
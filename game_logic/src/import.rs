@@ -0,0 +1,246 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+use crate::position::Dimension::{Column, Row};
+use crate::position::{Num, Position};
+use crate::solving::{Enemy, RequiredAttack};
+
+///A text format other tools describe arenas in. Implementations are listed in [`ARENA_FORMATS`], so
+/// the `import` command can choose them by name.
+pub trait ArenaFormat: Sync {
+  ///The name users select the format with
+  fn name(&self) -> &'static str;
+
+  ///An example of the format for help texts
+  fn example(&self) -> &'static str;
+
+  ///Reads all enemies described by the input
+  fn parse(&self, input: &str) -> Result<Vec<Enemy>, ImportError>;
+}
+
+///Every format which can be imported
+pub static ARENA_FORMATS: &[&dyn ArenaFormat] = &[&RingString, &ClockwiseList];
+
+pub fn format_by_name(name: &str) -> Option<&'static dyn ArenaFormat> {
+  ARENA_FORMATS
+    .iter()
+    .copied()
+    .find(|format| format.name() == name)
+}
+
+///One symbol per cell, ring by ring from the inside out and clockwise from column 1 within a ring.
+/// `.` `-` `_` and `0` are empty cells, `E` `X` and `1` enemies and `H` `J` `P` enemies with that
+/// weakness. Whitespace, `/` and `|` may be used to separate the rings.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct RingString;
+
+impl RingString {
+  ///Describes the enemies in this format, the compact way to share an arena. Enemies without a
+  /// weakness are `E`, the rings are separated by `|`.
+  pub fn format(enemies: &[Enemy]) -> String {
+    let mut symbols = vec!['.'; (Row.size() * Column.size()) as usize];
+    for enemy in enemies {
+      let index =
+        enemy.position.row as usize * Column.size() as usize + enemy.position.column as usize;
+      symbols[index] = enemy.required_attack.map_or('E', |attack| attack.symbol());
+    }
+    symbols
+      .chunks(Column.size() as usize)
+      .map(|ring| ring.iter().collect::<String>())
+      .collect::<Vec<_>>()
+      .join("|")
+  }
+}
+
+impl ArenaFormat for RingString {
+  fn name(&self) -> &'static str {
+    "rings"
+  }
+
+  fn example(&self) -> &'static str {
+    ".E..........|.E..........|.EH.........|.E.........."
+  }
+
+  fn parse(&self, input: &str) -> Result<Vec<Enemy>, ImportError> {
+    let symbols: Vec<char> = input
+      .chars()
+      .filter(|&symbol| !symbol.is_whitespace() && symbol != '/' && symbol != '|')
+      .collect();
+    let num_cells = (Row.size() * Column.size()) as usize;
+    if symbols.len() != num_cells {
+      return Err(ImportError::InvalidLength {
+        expected: num_cells,
+        actual: symbols.len(),
+      });
+    }
+
+    let mut enemies = Vec::new();
+    for (index, symbol) in symbols.into_iter().enumerate() {
+      let required_attack = match symbol.to_ascii_uppercase() {
+        '.' | '-' | '_' | '0' => continue,
+        'E' | 'X' | '1' => None,
+        weakness => Some(parse_weakness(weakness)?),
+      };
+      enemies.push(Enemy {
+        position: Position {
+          row: (index / Column.size() as usize) as Num,
+          column: (index % Column.size() as usize) as Num,
+        },
+        required_attack,
+        hp: None,
+      });
+    }
+    Ok(enemies)
+  }
+}
+
+///The occupied column numbers of every ring in clockwise order, ring by ring from the inside out
+/// separated by `/`. A column number may be followed by the weakness of the enemy.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ClockwiseList;
+
+impl ArenaFormat for ClockwiseList {
+  fn name(&self) -> &'static str {
+    "clockwise"
+  }
+
+  fn example(&self) -> &'static str {
+    "2 / 2 / 2,3H / 2"
+  }
+
+  fn parse(&self, input: &str) -> Result<Vec<Enemy>, ImportError> {
+    let rings: Vec<&str> = input.split('/').collect();
+    if rings.len() > Row.size() as usize {
+      return Err(ImportError::InvalidEntry(format!(
+        "{} rings, expected at most {}",
+        rings.len(),
+        Row.size()
+      )));
+    }
+
+    let mut enemies = Vec::new();
+    for (row, ring) in rings.into_iter().enumerate() {
+      for entry in ring
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|entry| !entry.is_empty())
+      {
+        let (number, weakness) = match entry.char_indices().last() {
+          Some((index, weakness)) if weakness.is_ascii_alphabetic() => {
+            (&entry[..index], Some(weakness.to_ascii_uppercase()))
+          }
+          _ => (entry, None),
+        };
+        let column = number
+          .parse::<Num>()
+          .ok()
+          .filter(|column| (1..=Column.size()).contains(column))
+          .ok_or_else(|| ImportError::InvalidEntry(entry.into()))?;
+        enemies.push(Enemy {
+          position: Position {
+            row: row as Num,
+            column: column - 1,
+          },
+          required_attack: weakness.map(parse_weakness).transpose()?,
+          hp: None,
+        });
+      }
+    }
+    Ok(enemies)
+  }
+}
+
+fn parse_weakness(symbol: char) -> Result<RequiredAttack, ImportError> {
+  match symbol {
+    'H' => Ok(RequiredAttack::Hammer),
+    'J' => Ok(RequiredAttack::Jump),
+    'P' => Ok(RequiredAttack::IronBootsOrHammer),
+    _ => Err(ImportError::InvalidSymbol(symbol)),
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum ImportError {
+  InvalidLength { expected: usize, actual: usize },
+  InvalidSymbol(char),
+  InvalidEntry(String),
+}
+
+impl Display for ImportError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      ImportError::InvalidLength { expected, actual } => {
+        write!(f, "expected {} cells, got {}", expected, actual)
+      }
+      ImportError::InvalidSymbol(symbol) => write!(f, "invalid symbol '{}'", symbol),
+      ImportError::InvalidEntry(entry) => write!(f, "invalid entry: {}", entry),
+    }
+  }
+}
+
+impl Error for ImportError {}
+
+#[cfg(test)]
+mod test_import {
+  use crate::import::{ARENA_FORMATS, ClockwiseList, ImportError, RingString, format_by_name};
+  use crate::import::ArenaFormat;
+  use crate::position::Position;
+  use crate::solving::RequiredAttack;
+
+  #[test]
+  fn test_examples_agree() {
+    let mut imported: Vec<_> = ARENA_FORMATS
+      .iter()
+      .map(|format| {
+        let mut enemies = format.parse(format.example()).expect("examples are valid");
+        enemies.sort();
+        enemies
+      })
+      .collect();
+    assert_eq!(5, imported[0].len());
+    imported.dedup();
+    assert_eq!(1, imported.len());
+
+    let hammer = imported[0]
+      .iter()
+      .find(|enemy| enemy.required_attack.is_some())
+      .expect("example has a weakness");
+    assert_eq!(Position { row: 2, column: 2 }, hammer.position);
+    assert_eq!(Some(RequiredAttack::Hammer), hammer.required_attack);
+    assert!(format_by_name("clockwise").is_some());
+  }
+
+  #[test]
+  fn test_format() {
+    let example = RingString.example();
+    let mut enemies = RingString.parse(example).unwrap();
+    assert_eq!(example, RingString::format(&enemies));
+    enemies.reverse();
+    assert_eq!(example, RingString::format(&enemies));
+    assert_eq!(
+      "............|............|............|............",
+      RingString::format(&[])
+    );
+  }
+
+  #[test]
+  fn test_invalid() {
+    assert_eq!(
+      Err(ImportError::InvalidLength {
+        expected: 48,
+        actual: 3
+      }),
+      RingString.parse("E.E")
+    );
+    assert_eq!(
+      Err(ImportError::InvalidSymbol('Q')),
+      RingString.parse(&"Q".repeat(48))
+    );
+    assert!(ClockwiseList.parse("13").is_err());
+    assert!(ClockwiseList.parse("1 / 2 / 3 / 4 / 5").is_err());
+    assert!(ClockwiseList.parse("2X").is_err());
+  }
+}
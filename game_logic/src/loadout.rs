@@ -0,0 +1,217 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::data_dir::{create_parent, data_file};
+use crate::solving::AvailableEquipment;
+
+///The environment variable pointing to the file custom loadouts are stored in
+pub const LOADOUTS_VAR: &str = "TOK_LOADOUTS";
+///The file in the [data directory](crate::data_dir::data_dir) custom loadouts are stored in if
+/// [`LOADOUTS_VAR`] isn't set
+pub const DEFAULT_LOADOUTS_FILE: &str = "loadouts.txt";
+
+///A named set of equipment, so it doesn't have to be toggled flag by flag every session
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Loadout {
+  pub name: String,
+  pub equipment: AvailableEquipment,
+}
+
+impl Loadout {
+  ///The loadouts which are always available
+  pub fn presets() -> Vec<Loadout> {
+    vec![
+      Loadout {
+        name: "early-game".to_string(),
+        equipment: AvailableEquipment {
+          throwing_hammer: false,
+          iron_boots: false,
+          wide_attacks_anywhere: false,
+        },
+      },
+      Loadout {
+        name: "endgame".to_string(),
+        equipment: AvailableEquipment {
+          throwing_hammer: true,
+          iron_boots: true,
+          wide_attacks_anywhere: false,
+        },
+      },
+    ]
+  }
+}
+
+///Parses a loadout in the format `<name> +hammer -iron-boots ...`. Flags which aren't listed are
+/// taken from the default equipment.
+impl TryFrom<&str> for Loadout {
+  type Error = LoadoutError;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    let mut parts = value.split_whitespace();
+    let name = parts
+      .next()
+      .ok_or(LoadoutError::InvalidFormat(value.to_string()))?;
+    let mut equipment = AvailableEquipment::default();
+    for flag in parts {
+      let (present, name) = match flag.split_at_checked(1) {
+        Some(("+", name)) => (true, name),
+        Some(("-", name)) => (false, name),
+        _ => return Err(LoadoutError::InvalidFormat(value.to_string())),
+      };
+      *equipment
+        .flag_mut(name)
+        .ok_or(LoadoutError::InvalidFormat(value.to_string()))? = present;
+    }
+    Ok(Self {
+      name: name.to_string(),
+      equipment,
+    })
+  }
+}
+
+impl Display for Loadout {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} {}", self.name, self.equipment)
+  }
+}
+
+///The custom loadouts saved in a file, one per line
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct LoadoutStore {
+  path: PathBuf,
+  custom: Vec<Loadout>,
+}
+
+impl LoadoutStore {
+  ///Loads the custom loadouts from the file [`LOADOUTS_VAR`] points to, or from
+  /// [`DEFAULT_LOADOUTS_FILE`]
+  pub fn load_default() -> Result<Self, LoadoutError> {
+    Self::load(data_file(LOADOUTS_VAR, DEFAULT_LOADOUTS_FILE))
+  }
+
+  ///Loads the custom loadouts from the given file, which doesn't have to exist yet
+  pub fn load<P>(path: P) -> Result<Self, LoadoutError>
+  where
+    P: AsRef<Path>,
+  {
+    let path = path.as_ref().to_path_buf();
+    let content = match fs::read_to_string(&path) {
+      Ok(content) => content,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+      Err(e) => return Err(e.into()),
+    };
+    let custom = content
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+      .map(Loadout::try_from)
+      .collect::<Result<_, _>>()?;
+    Ok(Self { path, custom })
+  }
+
+  ///Custom loadouts shadow presets with the same name
+  pub fn get(&self, name: &str) -> Option<Loadout> {
+    self.all().into_iter().find(|loadout| loadout.name == name)
+  }
+
+  ///All custom loadouts followed by the presets which aren't shadowed by them
+  pub fn all(&self) -> Vec<Loadout> {
+    let mut all = self.custom.clone();
+    all.extend(
+      Loadout::presets()
+        .into_iter()
+        .filter(|preset| !self.custom.iter().any(|custom| custom.name == preset.name)),
+    );
+    all
+  }
+
+  ///Adds or replaces a custom loadout and writes all custom loadouts to the file
+  pub fn save(&mut self, loadout: Loadout) -> io::Result<()> {
+    match self
+      .custom
+      .iter_mut()
+      .find(|custom| custom.name == loadout.name)
+    {
+      Some(custom) => *custom = loadout,
+      None => self.custom.push(loadout),
+    }
+    let content: String = self
+      .custom
+      .iter()
+      .map(|loadout| format!("{}\n", loadout))
+      .collect();
+    create_parent(&self.path)?;
+    fs::write(&self.path, content)
+  }
+}
+
+#[derive(Debug)]
+pub enum LoadoutError {
+  IO(io::Error),
+  InvalidFormat(String),
+}
+
+impl From<io::Error> for LoadoutError {
+  fn from(value: io::Error) -> Self {
+    Self::IO(value)
+  }
+}
+
+impl Display for LoadoutError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      LoadoutError::IO(e) => write!(f, "IO error: {}", e),
+      LoadoutError::InvalidFormat(line) => write!(
+        f,
+        "Invalid loadout '{}', needs to be '<name> [+|-]<{}> ...'",
+        line,
+        AvailableEquipment::FLAGS.join("|")
+      ),
+    }
+  }
+}
+
+impl Error for LoadoutError {}
+
+#[cfg(test)]
+mod test_loadout {
+  use std::env;
+  use std::fs;
+
+  use crate::loadout::{Loadout, LoadoutStore};
+
+  #[test]
+  fn test_parse() {
+    let loadout = Loadout::try_from("boss -hammer +wide-anywhere").expect("valid loadout");
+    assert_eq!("boss", loadout.name);
+    assert!(!loadout.equipment.throwing_hammer);
+    assert!(loadout.equipment.iron_boots);
+    assert!(loadout.equipment.wide_attacks_anywhere);
+    assert_eq!(
+      loadout,
+      Loadout::try_from(loadout.to_string().as_str()).unwrap()
+    );
+
+    assert!(Loadout::try_from("boss +sword").is_err());
+    assert!(Loadout::try_from("boss hammer").is_err());
+  }
+
+  #[test]
+  fn test_save_and_load() {
+    let path = env::temp_dir().join(format!("tok_loadouts_{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let mut store = LoadoutStore::load(&path).expect("missing file is empty");
+    assert!(store.get("early-game").is_some());
+    let custom = Loadout::try_from("early-game +hammer").unwrap();
+    store.save(custom.clone()).expect("failed to save");
+
+    let store = LoadoutStore::load(&path).expect("failed to load");
+    assert_eq!(Some(custom), store.get("early-game"));
+    assert_eq!(Loadout::presets().len(), store.all().len());
+    let _ = fs::remove_file(&path);
+  }
+}
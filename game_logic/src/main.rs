@@ -1,20 +1,285 @@
-use std::io::{stdin, stdout, Write};
+use std::path::PathBuf;
+use std::process::exit;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use game_logic::solving::SolvableArena;
+use clap::builder::PossibleValuesParser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use game_logic::CommandContext;
+use game_logic::fixture::load_arena;
+use game_logic::history::CommandHistory;
+use game_logic::position::{Move, Num};
+use game_logic::solver_service::{JobResult, SolverService};
+use game_logic::solving::attack_rules::attack_rules_error;
+use game_logic::solving::explain::explain;
+use game_logic::solving::{Comparator, SolvableArena, SolveParams, Strategy};
+use game_logic::stats::StatsStore;
+
+///Exit code of `solve` if a solution was found
+const EXIT_SOLVED: i32 = 0;
+///Exit code of `solve` if the arena can't be solved (in the given number of turns)
+const EXIT_NO_SOLUTION: i32 = 1;
+//clap exits with 2 on invalid arguments
+///Exit code of `solve` if the arena file can't be read
+const EXIT_INVALID_ARENA: i32 = 3;
+///Exit code of `solve` if the timeout ran out before the search finished
+const EXIT_TIMEOUT: i32 = 4;
+///Asks for the next move while a fight is followed with `assist`
+const ASSIST_PROMPT: &str = "assist> ";
+
+///Solves ring battles of Paper Mario: The Origami King. Without a command, the arena is entered
+/// interactively, see `help`.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+  #[command(subcommand)]
+  command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  ///Solves an arena file and exits with 0 if a solution was found, 1 if there is none, 3 if the
+  /// arena file is invalid and 4 on timeout
+  Solve(SolveArgs),
+}
+
+#[derive(Args)]
+struct SolveArgs {
+  ///the arena to solve, a fixture or one of the formats `import` understands
+  #[arg(long)]
+  arena: PathBuf,
+  ///only search solutions with this number of turns
+  #[arg(long)]
+  turns: Option<Num>,
+  #[arg(long, value_enum, default_value_t = Format::Text)]
+  format: Format,
+  ///give up after this time, like 5s, 500ms or 2m
+  #[arg(long, value_parser = parse_duration)]
+  timeout: Option<Duration>,
+  ///the solver strategy, the default one if missing
+  #[arg(long, value_parser = PossibleValuesParser::new(Strategy::all().map(|strategy| strategy.name())))]
+  strategy: Option<String>,
+  ///what makes a solution better than another one with as many moves, the smallest amounts if missing
+  #[arg(long, value_parser = PossibleValuesParser::new(Comparator::all().map(|comparator| comparator.name())))]
+  optimize: Option<String>,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Format {
+  ///the moves with what they achieve, like the interactive `solve`
+  Text,
+  ///a single json object with the status, the moves and the time taken
+  Json,
+}
 
 fn main() {
+  if let Some(e) = attack_rules_error() {
+    eprintln!(
+      "failed to load the attack rules, using the default ones: {}",
+      e
+    );
+  }
+  match Cli::parse().command {
+    None => run_interactive(),
+    Some(Command::Solve(args)) => solve_once(&args),
+  }
+}
+
+///Solves the arena file of the arguments, prints the result in the requested format and exits
+fn solve_once(args: &SolveArgs) -> ! {
+  let arena = match load_arena(&args.arena) {
+    Ok(arena) => arena,
+    Err(e) => {
+      eprintln!("failed to load {}: {}", args.arena.display(), e);
+      exit(EXIT_INVALID_ARENA);
+    }
+  };
+  let mut params = SolveParams::new();
+  params.in_turns = args.turns;
+  if let Some(strategy) = args.strategy.as_deref().and_then(Strategy::by_name) {
+    params.strategy = strategy;
+  }
+  if let Some(comparator) = args.optimize.as_deref().and_then(Comparator::by_name) {
+    params.comparator = comparator;
+  }
+
+  let start = Instant::now();
+  let service = SolverService::with_workers(1);
+  let mut job = service.submit(arena.clone(), params);
+  let result = loop {
+    if let Some(result) = job.try_result() {
+      break result.clone();
+    }
+    if args
+      .timeout
+      .is_some_and(|timeout| start.elapsed() >= timeout)
+    {
+      //the search stops within a few arenas, dropping the service waits for that
+      job.cancel();
+      break JobResult::Cancelled;
+    }
+    thread::sleep(Duration::from_millis(10));
+  };
+  let elapsed = start.elapsed();
+
+  let (status, exit_code) = match &result {
+    JobResult::Solved(_) => ("solved", EXIT_SOLVED),
+    JobResult::Unsolvable => ("unsolvable", EXIT_NO_SOLUTION),
+    JobResult::Cancelled => ("timeout", EXIT_TIMEOUT),
+  };
+  match args.format {
+    Format::Text => match &result {
+      JobResult::Solved(solution) if solution.is_empty() => println!("Arena is already solved!"),
+      JobResult::Solved(solution) => {
+        println!("solution was found in {} turns:", solution.len());
+        for (index, explanation) in explain(&arena, solution).iter().enumerate() {
+          println!("{}. {}", index + 1, explanation);
+        }
+      }
+      JobResult::Unsolvable => println!("no solution was found :("),
+      JobResult::Cancelled => println!("no solution was found within {:?}", elapsed),
+    },
+    Format::Json => {
+      let solution: &[Move] = match &result {
+        JobResult::Solved(solution) => solution,
+        _ => &[],
+      };
+      println!(
+        "{{\"status\":\"{}\",\"turns\":{},\"moves\":[{}],\"time_ms\":{}}}",
+        status,
+        match result {
+          JobResult::Solved(_) => solution.len().to_string(),
+          _ => "null".to_string(),
+        },
+        solution
+          .iter()
+          .map(|move_| format!("\"{}\"", move_))
+          .collect::<Vec<_>>()
+          .join(","),
+        elapsed.as_millis()
+      );
+    }
+  }
+  //exit while the service is still alive, dropping it would wait for a cancelled job to notice
+  exit(exit_code)
+}
+
+///Parses a duration like `5s`, `500ms` or `2m`. Plain numbers are seconds.
+fn parse_duration(arg: &str) -> Result<Duration, String> {
+  let (number, unit) = arg
+    .find(|c: char| !c.is_ascii_digit() && c != '.')
+    .map_or((arg, "s"), |index| arg.split_at(index));
+  let number: f64 = number
+    .parse()
+    .map_err(|_| format!("'{}' is not a duration like 5s", arg))?;
+  let seconds = match unit {
+    "ms" => number / 1000.0,
+    "s" => number,
+    "m" => number * 60.0,
+    _ => return Err(format!("unknown unit '{}', expected ms, s or m", unit)),
+  };
+  Duration::try_from_secs_f64(seconds).map_err(|e| e.to_string())
+}
+
+///Reads commands until the input ends
+fn run_interactive() {
+  let mut context = CommandContext::new().with_stats(StatsStore::from_env());
   let mut arena = SolvableArena::default();
+  let mut input = Input::new();
+  let mut history = CommandHistory::new();
   arena.show();
   loop {
+    let prompt = if context.is_assisting() {
+      ASSIST_PROMPT.to_string()
+    } else {
+      game_logic::prompt(&arena)
+    };
+    let Some(line) = input.read_line(&prompt) else {
+      break;
+    };
+    let command = match history.expand(line.trim()) {
+      Ok(command) => command,
+      Err(e) => {
+        eprintln!("{}", e);
+        continue;
+      }
+    };
+    if command.is_empty() {
+      continue;
+    }
+    if command != line.trim() {
+      //show what is repeated
+      println!("{}", command);
+    }
+    input.add_history_entry(&command);
+    history.push(&command);
+
+    //while assisting, every command is a move of the player
+    if command == "history" && !context.is_assisting() {
+      print!("{}", history);
+    } else {
+      match game_logic::parse(&mut context, &mut arena, &command) {
+        Ok(outcome) => print!("{}", outcome),
+        Err(e) => eprintln!("{}", e),
+      }
+    }
+  }
+}
+
+///Reads the commands, with line editing if the `line_editor` feature is enabled
+struct Input {
+  #[cfg(feature = "line_editor")]
+  editor: rustyline::DefaultEditor,
+}
+
+impl Input {
+  #[cfg(feature = "line_editor")]
+  fn new() -> Self {
+    Self {
+      editor: rustyline::DefaultEditor::new().expect("failed to create line editor"),
+    }
+  }
+
+  #[cfg(not(feature = "line_editor"))]
+  fn new() -> Self {
+    Self {}
+  }
+
+  ///Reads the next line after showing the prompt, None once the input ended
+  #[cfg(feature = "line_editor")]
+  fn read_line(&mut self, prompt: &str) -> Option<String> {
+    use rustyline::error::ReadlineError;
+
+    match self.editor.readline(prompt) {
+      Ok(line) => Some(line),
+      Err(ReadlineError::Interrupted | ReadlineError::Eof) => None,
+      Err(e) => panic!("failed to read command line: {}", e),
+    }
+  }
+
+  ///Reads the next line after showing the prompt, None once the input ended
+  #[cfg(not(feature = "line_editor"))]
+  fn read_line(&mut self, prompt: &str) -> Option<String> {
+    use std::io::{stdin, stdout, Write};
+
     let mut line = String::new();
 
-    print!("> ");
+    print!("{}", prompt);
     stdout().flush().expect("failed to flush stdout");
-    stdin()
+    let read = stdin()
       .read_line(&mut line)
       .expect("failed to read command line");
-    if let Err(e) = game_logic::parse(&mut arena, line.trim()) {
-      eprintln!("{}", e);
-    }
+    (read > 0).then_some(line)
   }
+
+  ///Lets the arrow keys bring back an expanded command
+  #[cfg(feature = "line_editor")]
+  fn add_history_entry(&mut self, command: &str) {
+    //the history is only kept in memory, which can't fail
+    let _ = self.editor.add_history_entry(command);
+  }
+
+  #[cfg(not(feature = "line_editor"))]
+  fn add_history_entry(&mut self, _command: &str) {}
 }
@@ -1,8 +1,11 @@
-use std::any::type_name;
-use std::convert::Infallible;
-use std::error::Error;
-use std::fmt::{Debug, Display, Formatter};
-use std::str::FromStr;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::any::type_name;
+use core::convert::Infallible;
+use core::error::Error;
+use core::fmt::{Debug, Display, Formatter};
+use core::str::FromStr;
 
 use enum_assoc::Assoc;
 
@@ -16,7 +19,7 @@ impl<T> ToNum for T where T: TryInto<Num> + Copy {}
 
 pub type NumErr<N> = OutOfBoundsError<N, <N as TryInto<Num>>::Error>;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct Position {
   pub row: Num,
   pub column: Num,
@@ -34,43 +37,45 @@ impl Position {
   }
 
   pub fn apply_move(&mut self, move_: Move) {
+    //calculate with wider types, so no amount or coordinate can overflow
+    let amount = move_.amount as u16;
+    let coordinate = move_.coordinate as u16;
+    let column_size = Column.size() as u16;
     match move_.dimension {
       Row => {
-        if self.row != move_.coordinate {
+        if self.row as u16 != coordinate {
           return;
         }
 
-        let d_size = Column.size();
         let offset = if move_.in_positive_direction {
-          move_.amount
+          amount % column_size
         } else {
-          d_size - move_.amount % d_size
+          column_size - amount % column_size
         };
-        self.column = (self.column + offset) % d_size;
+        self.column = ((self.column as u16 + offset) % column_size) as Num;
       }
       Column => {
         let mut in_positive_direction = move_.in_positive_direction;
-        if self.column == (move_.coordinate + Column.size() / 2) % Column.size() {
+        if self.column as u16 == (coordinate + column_size / 2) % column_size {
           in_positive_direction = !in_positive_direction;
-        } else if self.column != move_.coordinate {
+        } else if self.column as u16 != coordinate {
           return;
         }
 
-        let d_size = Row.size();
+        let d_size = Row.size() as u16;
         let dd_size = 2 * d_size;
         let offset = if in_positive_direction {
-          move_.amount
+          amount % dd_size
         } else {
-          dd_size - move_.amount % dd_size
+          dd_size - amount % dd_size
         };
-        let mirror_row = (self.row + offset) % dd_size;
+        let mirror_row = (self.row as u16 + offset) % dd_size;
         if mirror_row < d_size {
-          self.row = mirror_row;
+          self.row = mirror_row as Num;
         } else {
-          self.row = d_size * 2 - 1 - mirror_row;
-          self.column = (self.column + Column.size() / 2) % Column.size();
+          self.row = (dd_size - 1 - mirror_row) as Num;
+          self.column = ((self.column as u16 + column_size / 2) % column_size) as Num;
         }
-        self.row = mirror_row.min(d_size * 2 - 1 - mirror_row)
       }
     }
   }
@@ -100,6 +105,12 @@ impl Position {
   }
 }
 
+impl Display for Position {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    write!(f, "c{}r{}", self.column as u16 + 1, self.row as u16 + 1)
+  }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct Move {
@@ -111,6 +122,7 @@ pub struct Move {
 }
 
 impl Move {
+  ///Creates a move. The amount may be at most the [`Dimension::move_period`] of the dimension.
   pub fn new<C, A>(
     dimension: Dimension,
     coordinate: C,
@@ -121,14 +133,22 @@ impl Move {
     C: ToNum,
     A: TryInto<Num>,
   {
+    let amount = amount
+      .try_into()
+      .map_err(|e| MoveCreationError::Amount(e))?;
+    if amount > dimension.move_period() {
+      return Err(MoveCreationError::AmountOutOfRange {
+        amount,
+        period: dimension.move_period(),
+      });
+    }
+
     Ok(Self {
       dimension,
       coordinate: dimension
         .adapt(coordinate)
         .map_err(|e| MoveCreationError::Coordinate(e))?,
-      amount: amount
-        .try_into()
-        .map_err(|e| MoveCreationError::Amount(e))?,
+      amount,
       in_positive_direction,
     })
   }
@@ -163,6 +183,31 @@ impl Move {
     }
     self
   }
+
+  ///Returns all cells whose content gets moved. A column move also shifts the opposite column, since
+  /// both form a line through the center of the arena.
+  pub fn affected_positions(&self) -> Vec<Position> {
+    let normalized = self.normalized();
+    if normalized.amount == 0 {
+      return vec![];
+    }
+
+    match self.dimension {
+      Row => (0..Column.size())
+        .map(|column| Position {
+          row: self.coordinate,
+          column,
+        })
+        .collect(),
+      Column => {
+        let opposite_column = (self.coordinate + Column.size() / 2) % Column.size();
+        [self.coordinate, opposite_column]
+          .into_iter()
+          .flat_map(|column| (0..Row.size()).map(move |row| Position { row, column }))
+          .collect()
+      }
+    }
+  }
 }
 
 impl FromStr for Move {
@@ -218,6 +263,14 @@ impl FromStr for Move {
         },
       )
     })?;
+    if amount > dimension.move_period() {
+      return Err(MoveParseError::new(
+        s,
+        MoveParseErrorDetails::AmountOutOfRange {
+          period: dimension.move_period(),
+        },
+      ));
+    }
     Ok(Move {
       dimension,
       coordinate,
@@ -228,7 +281,7 @@ impl FromStr for Move {
 }
 
 impl Display for Move {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
     let normalized = self.normalized();
     write!(
       f,
@@ -237,7 +290,7 @@ impl Display for Move {
         Row => 'r',
         Column => 'c',
       },
-      normalized.coordinate + 1,
+      normalized.coordinate as u16 + 1,
       if normalized.in_positive_direction {
         ""
       } else {
@@ -264,7 +317,7 @@ impl MoveParseError {
 }
 
 impl Display for MoveParseError {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
     let (description, details) = match &self.details {
       MoveParseErrorDetails::InvalidFormat => (
         "Invalid format".to_string(),
@@ -284,6 +337,10 @@ impl Display for MoveParseError {
       MoveParseErrorDetails::InvalidCoordinate(e) => {
         ("Invalid coordinate".to_string(), e.to_string())
       }
+      MoveParseErrorDetails::AmountOutOfRange { period } => (
+        "Invalid amount".to_string(),
+        format!("can be at most {}", period),
+      ),
     };
     write!(f, "{} for '{}': {}", description, self.value, details)
   }
@@ -300,12 +357,17 @@ pub enum MoveParseErrorDetails {
     conversion_error: <Num as FromStr>::Err,
   },
   InvalidCoordinate(OutOfBoundsError<Num, Infallible>),
+  ///the amount is larger than the [`Dimension::move_period`]
+  AmountOutOfRange {
+    period: Num,
+  },
 }
 
 #[derive(Debug)]
 pub enum MoveCreationError<C, E, A> {
   Coordinate(OutOfBoundsError<C, E>),
   Amount(A),
+  AmountOutOfRange { amount: Num, period: Num },
 }
 
 impl<C, E, A> Display for MoveCreationError<C, E, A>
@@ -314,7 +376,7 @@ where
   E: Display,
   A: Display,
 {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
     match self {
       MoveCreationError::Coordinate(e) => {
         write!(f, "invalid coordinate: {}", e)
@@ -322,6 +384,9 @@ where
       MoveCreationError::Amount(e) => {
         write!(f, "invalid amount: {}", e)
       }
+      MoveCreationError::AmountOutOfRange { amount, period } => {
+        write!(f, "invalid amount: {} is larger than {}", amount, period)
+      }
     }
   }
 }
@@ -335,7 +400,7 @@ where
 }
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default, Assoc)]
-#[func(pub fn size(& self) -> Num)]
+#[func(pub const fn size(& self) -> Num)]
 #[func(pub fn name(& self) -> & str)]
 #[func(pub fn changes(&self) -> Self)]
 pub enum Dimension {
@@ -370,6 +435,14 @@ impl Dimension {
     Ok(num)
   }
 
+  ///The amount after which a move of this dimension has moved everything back to where it was
+  pub const fn move_period(&self) -> Num {
+    match self {
+      Row => Column.size(),
+      Column => 2 * Row.size(),
+    }
+  }
+
   ///gets the next coordinate in the positive direction
   pub fn next(&self, coordinate: Num) -> Num {
     (coordinate + 1) % self.size()
@@ -377,7 +450,7 @@ impl Dimension {
 }
 
 impl Display for Dimension {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
     write!(f, "{}", self.name())
   }
 }
@@ -394,7 +467,7 @@ where
   N: Display,
   E: Display,
 {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
     if let Some(conversion_error) = &self.conversion_error {
       write!(
         f,
@@ -443,7 +516,9 @@ mod test_dimension {
 mod test_move {
   use std::str::FromStr;
 
-  use crate::position::{Dimension, Move};
+  use crate::position::{Dimension, Move, Position};
+  use crate::position::Dimension::{Column, Row};
+  use crate::solving::all_moves;
 
   #[test]
   fn test_parse() {
@@ -469,6 +544,64 @@ mod test_move {
     );
   }
 
+  #[test]
+  fn test_affected_positions_row() {
+    let affected = Move::from_str("r2 3").unwrap().affected_positions();
+    assert_eq!(12, affected.len());
+    assert!(affected.iter().all(|position| position.row == 1));
+  }
+
+  #[test]
+  fn test_affected_positions_column_mirror() {
+    let affected = Move::from_str("c2 1").unwrap().affected_positions();
+    assert_eq!(8, affected.len());
+    for row in 0..4 {
+      assert!(affected.contains(&Position::at(row, 1).unwrap()));
+      assert!(affected.contains(&Position::at(row, 7).unwrap()));
+    }
+
+    //the opposite column affects the same cells
+    let mut mirrored = Move::from_str("c8 -1").unwrap().affected_positions();
+    let mut affected = affected;
+    mirrored.sort();
+    affected.sort();
+    assert_eq!(affected, mirrored);
+  }
+
+  #[test]
+  fn test_affected_positions_full_turn() {
+    assert!(Move::from_str("r1 12")
+      .unwrap()
+      .affected_positions()
+      .is_empty());
+    assert!(Move::from_str("c3 8")
+      .unwrap()
+      .affected_positions()
+      .is_empty());
+  }
+
+  #[test]
+  fn test_affected_positions_complete() {
+    for move_ in all_moves() {
+      let affected = move_.affected_positions();
+      for row in 0..Row.size() {
+        for column in 0..Column.size() {
+          let position = Position::at(row, column).unwrap();
+          let mut moved = position;
+          moved.apply_move(move_);
+          if moved != position {
+            assert!(
+              affected.contains(&position),
+              "{:?} moved by {} but isn't affected",
+              position,
+              move_
+            );
+          }
+        }
+      }
+    }
+  }
+
   #[test]
   fn test_display() {
     assert_eq!("c1 4", Move::from_str("c1 4").unwrap().to_string());
@@ -510,3 +643,68 @@ mod test_position {
     assert_eq!(1, position.column);
   }
 }
+
+#[cfg(test)]
+mod test_move_math {
+  use proptest::prelude::*;
+
+  use crate::position::{Dimension, Move, Num, Position};
+  use crate::position::Dimension::{Column, Row};
+
+  fn any_move() -> impl Strategy<Value = Move> {
+    (
+      prop_oneof![Just(Row), Just(Column)],
+      any::<Num>(),
+      any::<Num>(),
+      any::<bool>(),
+    )
+      .prop_map(
+        |(dimension, coordinate, amount, in_positive_direction)| Move {
+          dimension,
+          coordinate,
+          amount,
+          in_positive_direction,
+        },
+      )
+  }
+
+  proptest! {
+    #[test]
+    fn apply_move_stays_in_bounds(move_ in any_move(), row in 0..Row.size(), column in 0..Column.size()) {
+      let mut position = Position::at(row, column).unwrap();
+      position.apply_move(move_);
+      prop_assert!(position.row < Row.size());
+      prop_assert!(position.column < Column.size());
+    }
+
+    #[test]
+    fn normalized_and_display_dont_panic(move_ in any_move()) {
+      let _ = move_.normalized().to_string();
+    }
+
+    #[test]
+    fn parse_doesnt_panic(s in "[rc-]?[0-9]{0,4} -?[0-9]{0,4}") {
+      let _ = s.parse::<Move>();
+    }
+
+    #[test]
+    fn new_validates_amount(dimension in prop_oneof![Just(Row), Just(Column)], amount in any::<u16>()) {
+      let move_ = Move::new(dimension, 0, amount, true);
+      prop_assert_eq!(amount <= dimension.move_period() as u16, move_.is_ok());
+    }
+
+    #[test]
+    fn full_period_is_identity(dimension in prop_oneof![Just(Row), Just(Column)], row in 0..Row.size(), column in 0..Column.size()) {
+      let mut position = Position::at(row, column).unwrap();
+      position.apply_move(Move::new(dimension, 0, dimension.move_period(), false).unwrap());
+      prop_assert_eq!(Position::at(row, column).unwrap(), position);
+    }
+  }
+
+  #[test]
+  fn test_large_amount_rejected() {
+    assert!("r1 250".parse::<Move>().is_err());
+    assert!("c1 9".parse::<Move>().is_err());
+    assert!(Move::new(Dimension::Column, 0, 8, true).is_ok());
+  }
+}
@@ -0,0 +1,208 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::command::{ParseError, parse_solve_options};
+use crate::data_dir::{create_parent, data_file};
+use crate::solving::SolveParams;
+
+///The environment variable pointing to the file custom presets are stored in
+pub const PRESETS_VAR: &str = "TOK_PRESETS";
+///The file in the [data directory](crate::data_dir::data_dir) custom presets are stored in if
+/// [`PRESETS_VAR`] isn't set
+pub const DEFAULT_PRESETS_FILE: &str = "presets.txt";
+
+///Named options for the solver, so switching between ways of playing is a single command
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SolverPreset {
+  pub name: String,
+  pub params: SolveParams,
+}
+
+impl SolverPreset {
+  ///The presets which are always available
+  pub fn presets() -> Vec<SolverPreset> {
+    ["casual fast heuristic", "speedrun optimize time"]
+      .into_iter()
+      .map(|preset| SolverPreset::try_from(preset).expect("presets are valid"))
+      .collect()
+  }
+}
+
+///Parses a preset in the format `<name> <solve options>`, like `speedrun optimize time in 3`
+impl TryFrom<&str> for SolverPreset {
+  type Error = PresetError;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    let mut parts = value.split_whitespace();
+    let name = parts
+      .next()
+      .ok_or(PresetError::InvalidFormat(value.to_string(), None))?;
+    let params = parse_solve_options(parts.peekable(), SolveParams::new())
+      .map_err(|e| PresetError::InvalidFormat(value.to_string(), Some(e)))?;
+    Ok(Self {
+      name: name.to_string(),
+      params,
+    })
+  }
+}
+
+impl Display for SolverPreset {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} {}", self.name, self.params)
+  }
+}
+
+///The custom presets saved in a file, one per line
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PresetStore {
+  path: PathBuf,
+  custom: Vec<SolverPreset>,
+}
+
+impl PresetStore {
+  ///Loads the custom presets from the file [`PRESETS_VAR`] points to, or from
+  /// [`DEFAULT_PRESETS_FILE`]
+  pub fn load_default() -> Result<Self, PresetError> {
+    Self::load(data_file(PRESETS_VAR, DEFAULT_PRESETS_FILE))
+  }
+
+  ///Loads the custom presets from the given file, which doesn't have to exist yet
+  pub fn load<P>(path: P) -> Result<Self, PresetError>
+  where
+    P: AsRef<Path>,
+  {
+    let path = path.as_ref().to_path_buf();
+    let content = match fs::read_to_string(&path) {
+      Ok(content) => content,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+      Err(e) => return Err(e.into()),
+    };
+    let custom = content
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+      .map(SolverPreset::try_from)
+      .collect::<Result<_, _>>()?;
+    Ok(Self { path, custom })
+  }
+
+  ///Custom presets shadow the built-in ones with the same name
+  pub fn get(&self, name: &str) -> Option<SolverPreset> {
+    self.all().into_iter().find(|preset| preset.name == name)
+  }
+
+  ///All custom presets followed by the built-in ones which aren't shadowed by them
+  pub fn all(&self) -> Vec<SolverPreset> {
+    let mut all = self.custom.clone();
+    all.extend(
+      SolverPreset::presets()
+        .into_iter()
+        .filter(|preset| !self.custom.iter().any(|custom| custom.name == preset.name)),
+    );
+    all
+  }
+
+  ///Adds or replaces a custom preset and writes all custom presets to the file
+  pub fn save(&mut self, preset: SolverPreset) -> io::Result<()> {
+    match self
+      .custom
+      .iter_mut()
+      .find(|custom| custom.name == preset.name)
+    {
+      Some(custom) => *custom = preset,
+      None => self.custom.push(preset),
+    }
+    let content: String = self
+      .custom
+      .iter()
+      .map(|preset| format!("{}\n", preset))
+      .collect();
+    create_parent(&self.path)?;
+    fs::write(&self.path, content)
+  }
+}
+
+#[derive(Debug)]
+pub enum PresetError {
+  IO(io::Error),
+  ///the line and why its options are invalid, if it has a name
+  InvalidFormat(String, Option<ParseError>),
+}
+
+impl From<io::Error> for PresetError {
+  fn from(value: io::Error) -> Self {
+    Self::IO(value)
+  }
+}
+
+impl Display for PresetError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PresetError::IO(e) => write!(f, "IO error: {}", e),
+      PresetError::InvalidFormat(line, None) => write!(
+        f,
+        "Invalid preset '{}', needs to be '<name> <solve options>'",
+        line
+      ),
+      PresetError::InvalidFormat(line, Some(e)) => {
+        write!(f, "Invalid preset '{}': {}", line, e)
+      }
+    }
+  }
+}
+
+impl Error for PresetError {}
+
+#[cfg(test)]
+mod test_preset {
+  use std::env;
+  use std::fs;
+
+  use crate::preset::{PresetStore, SolverPreset};
+  use crate::solving::move_filter::MoveFilter;
+  use crate::solving::{Comparator, Strategy};
+
+  #[test]
+  fn test_parse() {
+    let preset =
+      SolverPreset::try_from("challenge bidirectional optimize switches forbid columns in 4")
+        .expect("valid preset");
+    assert_eq!("challenge", preset.name);
+    assert_eq!(Strategy::BIDIRECTIONAL, preset.params.strategy);
+    assert_eq!(
+      Comparator::by_name("switches"),
+      Some(preset.params.comparator)
+    );
+    assert_eq!(
+      MoveFilter::new().forbid_columns(),
+      preset.params.move_filter
+    );
+    assert_eq!(Some(4), preset.params.in_turns);
+    assert_eq!(
+      preset,
+      SolverPreset::try_from(preset.to_string().as_str()).unwrap()
+    );
+
+    assert!(SolverPreset::try_from("").is_err());
+    assert!(SolverPreset::try_from("challenge optimize luck").is_err());
+  }
+
+  #[test]
+  fn test_save_and_load() {
+    let path = env::temp_dir().join(format!("tok_presets_{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let mut store = PresetStore::load(&path).expect("missing file is empty");
+    assert!(store.get("speedrun").is_some());
+    let custom = SolverPreset::try_from("speedrun fast optimize time").unwrap();
+    store.save(custom.clone()).expect("failed to save");
+
+    let store = PresetStore::load(&path).expect("failed to load");
+    assert_eq!(Some(custom), store.get("speedrun"));
+    assert_eq!(SolverPreset::presets().len(), store.all().len());
+    let _ = fs::remove_file(&path);
+  }
+}
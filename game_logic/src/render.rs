@@ -0,0 +1,317 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::arena::ToArenaSymbol;
+use crate::position::Dimension::{Column, Row};
+use crate::position::{Move, Position};
+use crate::solving::{Coverage, Enemy, RequiredAttack, SolvableArena};
+
+const SIZE: f64 = 400.0;
+const CENTER: f64 = SIZE / 2.0;
+const INNER_RADIUS: f64 = 40.0;
+const RING_WIDTH: f64 = 38.0;
+const ENEMY_RADIUS: f64 = 11.0;
+
+///sine and cosine of multiples of 15°, since `core` has no trigonometric functions
+const SIN_COS_15: [(f64, f64); 24] = {
+  const S15: f64 = 0.258_819_045_102_520_8;
+  const S30: f64 = 0.5;
+  const S45: f64 = core::f64::consts::FRAC_1_SQRT_2;
+  const S60: f64 = 0.866_025_403_784_438_6;
+  const S75: f64 = 0.965_925_826_289_068_3;
+  [
+    (0.0, 1.0),
+    (S15, S75),
+    (S30, S60),
+    (S45, S45),
+    (S60, S30),
+    (S75, S15),
+    (1.0, 0.0),
+    (S75, -S15),
+    (S60, -S30),
+    (S45, -S45),
+    (S30, -S60),
+    (S15, -S75),
+    (0.0, -1.0),
+    (-S15, -S75),
+    (-S30, -S60),
+    (-S45, -S45),
+    (-S60, -S30),
+    (-S75, -S15),
+    (-1.0, 0.0),
+    (-S75, S15),
+    (-S60, S30),
+    (-S45, S45),
+    (-S30, S60),
+    (-S15, S75),
+  ]
+};
+
+///How pictures of the arena tell apart which attack an enemy needs
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum EnemyMarking {
+  ///a color per attack
+  #[default]
+  Colors,
+  ///a shape per attack, in colors which stay distinct with color blindness. Enemies needing a jump
+  /// or iron boots are hollow, so nothing relies on telling red from green.
+  Shapes,
+}
+
+impl EnemyMarking {
+  pub const ALL: [EnemyMarking; 2] = [EnemyMarking::Colors, EnemyMarking::Shapes];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      EnemyMarking::Colors => "colors",
+      EnemyMarking::Shapes => "shapes",
+    }
+  }
+
+  pub fn by_name(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|marking| marking.name() == name)
+  }
+
+  ///The color of an enemy needing the attack, as red, green and blue
+  pub fn color(&self, attack: Option<RequiredAttack>) -> [u8; 3] {
+    match (self, attack) {
+      (EnemyMarking::Colors, None) => [0xd9, 0x41, 0x3b],
+      (EnemyMarking::Colors, Some(RequiredAttack::Hammer)) => [0xe8, 0x89, 0x2c],
+      (EnemyMarking::Colors, Some(RequiredAttack::Jump)) => [0x3b, 0x7d, 0xd9],
+      (EnemyMarking::Colors, Some(RequiredAttack::IronBootsOrHammer)) => [0x9b, 0x4f, 0xd1],
+      //the okabe-ito palette
+      (EnemyMarking::Shapes, None) => [0x00, 0x72, 0xb2],
+      (EnemyMarking::Shapes, Some(RequiredAttack::Hammer)) => [0xe6, 0x9f, 0x00],
+      (EnemyMarking::Shapes, Some(RequiredAttack::Jump)) => [0x56, 0xb4, 0xe9],
+      (EnemyMarking::Shapes, Some(RequiredAttack::IronBootsOrHammer)) => [0xcc, 0x79, 0xa7],
+    }
+  }
+
+  pub fn shape(&self, attack: Option<RequiredAttack>) -> EnemyShape {
+    match (self, attack) {
+      (EnemyMarking::Colors, _) | (EnemyMarking::Shapes, None) => EnemyShape::Disc,
+      (EnemyMarking::Shapes, Some(RequiredAttack::Jump)) => EnemyShape::Ring,
+      (EnemyMarking::Shapes, Some(RequiredAttack::Hammer)) => EnemyShape::Square,
+      (EnemyMarking::Shapes, Some(RequiredAttack::IronBootsOrHammer)) => EnemyShape::Frame,
+    }
+  }
+}
+
+///The shapes enemies are drawn as, see [`EnemyMarking::shape`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum EnemyShape {
+  Disc,
+  ///a hollow circle
+  Ring,
+  Square,
+  ///a hollow square
+  Frame,
+}
+
+impl EnemyShape {
+  pub fn is_hollow(&self) -> bool {
+    matches!(self, EnemyShape::Ring | EnemyShape::Frame)
+  }
+}
+
+///Draws the arena as a scalable vector image: every cell covered by the `coverage` is filled, the
+/// cells the `next_move` moves are outlined and the move is written in the center. Enemies are
+/// drawn with the `marking` and their symbol. Column 1 is right of the top, like in the console
+/// output.
+pub fn render_svg(
+  arena: &SolvableArena,
+  coverage: Option<&Coverage>,
+  next_move: Option<Move>,
+  marking: EnemyMarking,
+) -> String {
+  let moved = next_move
+    .map(|move_| move_.affected_positions())
+    .unwrap_or_default();
+
+  let mut svg = String::new();
+  let _ = writeln!(
+    svg,
+    r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {size} {size}" width="{size}" height="{size}">"#,
+    size = SIZE
+  );
+  let _ = writeln!(
+    svg,
+    r##"  <rect width="{size}" height="{size}" fill="#2b2238"/>"##,
+    size = SIZE
+  );
+
+  for row in 0..Row.size() {
+    for column in 0..Column.size() {
+      let position = Position { row, column };
+      let fill = if coverage.is_some_and(|coverage| coverage.covers(&position)) {
+        "#6fbf73"
+      } else if row < Row.size() / 2 {
+        "#8c7a5b"
+      } else {
+        "#6e5f47"
+      };
+      let stroke = if moved.contains(&position) {
+        r##"stroke="#ffd84d" stroke-width="3""##
+      } else {
+        r##"stroke="#2b2238" stroke-width="1""##
+      };
+      let _ = writeln!(
+        svg,
+        r#"  <path d="{}" fill="{}" {}/>"#,
+        cell_path(&position),
+        fill,
+        stroke
+      );
+    }
+  }
+
+  for enemy in &arena.enemies {
+    let (x, y) = point(
+      INNER_RADIUS + (enemy.row as f64 + 0.5) * RING_WIDTH,
+      enemy.column as usize * 2 + 1,
+    );
+    write_enemy(&mut svg, enemy, (x, y), marking);
+    let _ = writeln!(
+      svg,
+      r#"  <text x="{:.1}" y="{:.1}" font-family="sans-serif" font-size="12" text-anchor="middle" dominant-baseline="central" fill="white">{}</text>"#,
+      x,
+      y,
+      enemy.to_arena_symbol()
+    );
+  }
+
+  if let Some(next_move) = next_move {
+    let _ = writeln!(
+      svg,
+      r#"  <text x="{c}" y="{c}" font-family="sans-serif" font-size="16" text-anchor="middle" dominant-baseline="central" fill="white">{}</text>"#,
+      next_move,
+      c = CENTER
+    );
+  }
+
+  svg.push_str("</svg>\n");
+  svg
+}
+
+///Adds the shape of the enemy centered at the point, outlined dark unless it is hollow
+fn write_enemy(svg: &mut String, enemy: &Enemy, (x, y): (f64, f64), marking: EnemyMarking) {
+  let [red, green, blue] = marking.color(enemy.required_attack);
+  let color = format!("#{:02x}{:02x}{:02x}", red, green, blue);
+  let shape = marking.shape(enemy.required_attack);
+  let paint = if shape.is_hollow() {
+    format!(r#"fill="none" stroke="{}" stroke-width="4""#, color)
+  } else {
+    format!(r##"fill="{}" stroke="#1a1423""##, color)
+  };
+  let _ = match shape {
+    EnemyShape::Disc | EnemyShape::Ring => writeln!(
+      svg,
+      r#"  <circle cx="{:.1}" cy="{:.1}" r="{}" {}/>"#,
+      x, y, ENEMY_RADIUS, paint
+    ),
+    EnemyShape::Square | EnemyShape::Frame => writeln!(
+      svg,
+      r#"  <rect x="{:.1}" y="{:.1}" width="{size}" height="{size}" {}/>"#,
+      x - ENEMY_RADIUS,
+      y - ENEMY_RADIUS,
+      paint,
+      size = ENEMY_RADIUS * 2.0
+    ),
+  };
+}
+
+///The point with the given distance from the center, at the given multiple of 15° clockwise from the
+/// top
+fn point(radius: f64, angle_index: usize) -> (f64, f64) {
+  let (sin, cos) = SIN_COS_15[angle_index % SIN_COS_15.len()];
+  (CENTER + radius * sin, CENTER - radius * cos)
+}
+
+fn cell_path(position: &Position) -> String {
+  let inner = INNER_RADIUS + position.row as f64 * RING_WIDTH;
+  let outer = inner + RING_WIDTH;
+  let start = position.column as usize * 2;
+  let end = start + 2;
+  let (x1, y1) = point(outer, start);
+  let (x2, y2) = point(outer, end);
+  let (x3, y3) = point(inner, end);
+  let (x4, y4) = point(inner, start);
+  format!(
+    "M {:.1} {:.1} A {o} {o} 0 0 1 {:.1} {:.1} L {:.1} {:.1} A {i} {i} 0 0 0 {:.1} {:.1} Z",
+    x1,
+    y1,
+    x2,
+    y2,
+    x3,
+    y3,
+    x4,
+    y4,
+    o = outer,
+    i = inner
+  )
+}
+
+#[cfg(test)]
+mod test_render {
+  use crate::command::parse_quietly;
+  use crate::position::Move;
+  use crate::render::{EnemyMarking, EnemyShape, render_svg};
+  use crate::solving::{Coverage, RequiredAttack, SolvableArena};
+
+  #[test]
+  fn test_render() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 1234", "c4 12 J"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    let coverage = Coverage::find(&arena).expect("arena is solved");
+    let move_: Move = "r1 2".parse().unwrap();
+
+    let svg = render_svg(&arena, Some(&coverage), Some(move_), EnemyMarking::Colors);
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.ends_with("</svg>\n"));
+    assert_eq!(48, svg.matches("<path").count());
+    assert_eq!(6, svg.matches("<circle").count());
+    assert_eq!(12, svg.matches("#ffd84d").count());
+    assert!(svg.contains(">r1 2</text>"));
+  }
+
+  #[test]
+  fn test_markings() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 1", "c4 1 J", "c6 1 H", "c8 1 P"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let svg = render_svg(&arena, None, None, EnemyMarking::Colors);
+    assert_eq!(4, svg.matches("<circle").count());
+    assert!(svg.contains("#d9413b"));
+
+    let svg = render_svg(&arena, None, None, EnemyMarking::Shapes);
+    assert_eq!(2, svg.matches("<circle").count());
+    //the background is a rect as well
+    assert_eq!(3, svg.matches("<rect").count());
+    assert_eq!(2, svg.matches(r#"fill="none""#).count());
+    assert!(!svg.contains("#d9413b"));
+    assert!(svg.contains(">J</text>"));
+
+    //no two attacks look the same
+    for marking in EnemyMarking::ALL {
+      let looks: Vec<_> = [
+        None,
+        Some(RequiredAttack::Hammer),
+        Some(RequiredAttack::Jump),
+        Some(RequiredAttack::IronBootsOrHammer),
+      ]
+      .into_iter()
+      .map(|attack| (marking.color(attack), marking.shape(attack)))
+      .collect();
+      for (index, look) in looks.iter().enumerate() {
+        assert!(!looks[index + 1..].contains(look));
+      }
+      assert_eq!(Some(marking), EnemyMarking::by_name(marking.name()));
+    }
+    assert!(EnemyShape::Frame.is_hollow());
+  }
+}
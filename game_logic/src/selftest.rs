@@ -0,0 +1,172 @@
+use std::fmt::{Display, Formatter};
+
+use crate::fixture::Fixture;
+use crate::position::Move;
+use crate::solving::solve_cache::SolveCache;
+use crate::solving::{SolvableArena, all_moves, solve_with};
+
+///The fixtures compiled into the binary, so the self test also works where the fixture files aren't
+/// available, like on android
+const FIXTURES: [(&str, &str); 7] = [
+  ("mixed_rings", include_str!("../fixtures/mixed_rings.txt")),
+  ("no_hammer", include_str!("../fixtures/no_hammer.txt")),
+  ("scattered", include_str!("../fixtures/scattered.txt")),
+  (
+    "scattered_outer_ring",
+    include_str!("../fixtures/scattered_outer_ring.txt"),
+  ),
+  (
+    "single_rotation",
+    include_str!("../fixtures/single_rotation.txt"),
+  ),
+  ("solved", include_str!("../fixtures/solved.txt")),
+  ("two_groups", include_str!("../fixtures/two_groups.txt")),
+];
+
+///Arenas in the fixture format, with whether all enemies can be attacked in them
+const COVERAGE_CASES: [(&str, &str, bool); 6] = [
+  ("empty arena", "", true),
+  ("full column", "c6 1234", true),
+  ("2x2 area on the inner rings", "c1 12\nc2 12", true),
+  ("2x2 area on the outer rings", "c1 34\nc2 34", false),
+  (
+    "2x2 area on the outer rings with wide-anywhere",
+    "c1 34\nc2 34\n+wide-anywhere",
+    true,
+  ),
+  ("scattered enemies", "c1 1\nc4 1\nc7 2", false),
+];
+
+///The outcome of a single consistency check
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CheckResult {
+  pub name: String,
+  ///what went wrong, None if the check passed
+  pub failure: Option<String>,
+}
+
+impl CheckResult {
+  fn new(name: &str, failure: Option<String>) -> Self {
+    Self {
+      name: name.to_string(),
+      failure,
+    }
+  }
+
+  pub fn passed(&self) -> bool {
+    self.failure.is_none()
+  }
+}
+
+impl Display for CheckResult {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match &self.failure {
+      None => write!(f, "ok      {}", self.name),
+      Some(failure) => write!(f, "FAILED  {}: {}", self.name, failure),
+    }
+  }
+}
+
+///Runs consistency checks of the core logic: whether moves can be undone and repeat after their
+/// period, whether the coverage of known arenas is detected and whether the solver finds the known
+/// solutions of the fixtures. Helps to find out whether a build is broken.
+pub fn run() -> Vec<CheckResult> {
+  let mut results = vec![check_move_inverses(), check_move_periods()];
+  results.extend(
+    COVERAGE_CASES
+      .iter()
+      .map(|&(name, content, solved)| check_coverage(name, content, solved)),
+  );
+  results.extend(
+    FIXTURES
+      .iter()
+      .map(|&(name, content)| check_fixture(name, content)),
+  );
+  results
+}
+
+///An arena with an enemy on every ring, spread over different columns
+fn canned_arena() -> SolvableArena {
+  Fixture::parse("canned", "c1 1\nc2 12\nc5 3\nc8 4\nc12 24")
+    .expect("canned arena is valid")
+    .arena
+}
+
+fn check_move_inverses() -> CheckResult {
+  let arena = canned_arena();
+  let failure = all_moves().find_map(|move_| {
+    let inverse = Move {
+      in_positive_direction: !move_.in_positive_direction,
+      ..move_
+    };
+    let mut moved = arena.clone();
+    moved.apply_move(move_);
+    moved.apply_move(inverse);
+    (moved != arena).then(|| format!("{} isn't undone by {}", move_, inverse))
+  });
+  CheckResult::new("moves are undone by their inverse", failure)
+}
+
+fn check_move_periods() -> CheckResult {
+  let arena = canned_arena();
+  let failure = all_moves()
+    .filter(|move_| move_.amount == 1)
+    .find_map(|move_| {
+      let period = move_.dimension.move_period();
+      let mut moved = arena.clone();
+      for _ in 0..period {
+        moved.apply_move(move_);
+      }
+      (moved != arena).then(|| format!("{} repeated {} times changes the arena", move_, period))
+    });
+  CheckResult::new("moves repeat after their period", failure)
+}
+
+fn check_coverage(name: &str, content: &str, solved: bool) -> CheckResult {
+  let name = format!("coverage: {}", name);
+  let arena = match Fixture::parse(&name, content) {
+    Ok(fixture) => fixture.arena,
+    Err(e) => return CheckResult::new(&name, Some(e.to_string())),
+  };
+  let failure = (arena.is_solved() != solved).then(|| {
+    if solved {
+      "enemies aren't detected as attackable".to_string()
+    } else {
+      "enemies are wrongly detected as attackable".to_string()
+    }
+  });
+  CheckResult::new(&name, failure)
+}
+
+fn check_fixture(name: &str, content: &str) -> CheckResult {
+  let name = format!("fixture: {}", name);
+  let fixture = match Fixture::parse(&name, content) {
+    Ok(fixture) => fixture,
+    Err(e) => return CheckResult::new(&name, Some(e.to_string())),
+  };
+  let turns = solve_with(
+    &fixture.arena,
+    &fixture.params(),
+    &mut SolveCache::new(),
+    None,
+    |_| true,
+  )
+  .map(|solution| solution.len());
+  let failure = fixture
+    .expected_turns
+    .filter(|&expected| expected != turns)
+    .map(|expected| format!("expected {:?} turns, got {:?}", expected, turns));
+  CheckResult::new(&name, failure)
+}
+
+#[cfg(test)]
+mod test_selftest {
+  use crate::selftest::run;
+
+  #[test]
+  fn test_selftest() {
+    for result in run() {
+      assert!(result.passed(), "{}", result);
+    }
+  }
+}
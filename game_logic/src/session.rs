@@ -0,0 +1,390 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::command::{ParseError, parse_quietly};
+use crate::solving::challenge::CHALLENGES;
+use crate::solving::item::Item;
+use crate::solving::{AvailableEquipment, SolvableArena};
+
+///The environment variable with the address the gui shares its arena on, like `127.0.0.1:7878`
+pub const SESSION_VAR: &str = "TOK_SESSION";
+///The address sessions are shared on and attached to if none is given
+pub const DEFAULT_SESSION_ADDRESS: &str = "127.0.0.1:7878";
+///Separates the commands of an arena sent in one line
+const SEPARATOR: &str = ";";
+///The names of the commands [`arena_commands`] builds arenas with, besides adding enemies with
+/// `c<column>` and changing the equipment with `+<flag>` and `-<flag>`
+const ARENA_COMMANDS: [&str; 7] = [
+  "hp",
+  "g",
+  "first-strike",
+  "target",
+  "lock",
+  "item",
+  "challenge",
+];
+
+///The commands building the arena from an empty one, like `c2 1H 4`, `g 3` and `-hammer`
+pub fn arena_commands(arena: &SolvableArena) -> Vec<String> {
+  let mut commands = vec![];
+  let mut enemies = arena.enemies.clone();
+  enemies.sort_by_key(|enemy| (enemy.column, enemy.row));
+  for enemy in &enemies {
+    let weakness = enemy
+      .required_attack
+      .map_or(String::new(), |weakness| weakness.symbol().to_string());
+    commands.push(format!(
+      "c{} {}{}",
+      enemy.column + 1,
+      enemy.row + 1,
+      weakness
+    ));
+  }
+  for (enemy, hp) in enemies.iter().filter_map(|enemy| Some((enemy, enemy.hp?))) {
+    commands.push(format!("hp c{} {} {}", enemy.column + 1, enemy.row + 1, hp));
+  }
+  if let Some(num_groups) = arena.num_groups {
+    commands.push(format!("g {}", num_groups));
+  }
+  let mut equipment = arena.available_equipment;
+  let mut default_equipment = AvailableEquipment::default();
+  for flag in AvailableEquipment::FLAGS {
+    let enabled = *equipment.flag_mut(flag).expect("flags are valid");
+    if enabled != *default_equipment.flag_mut(flag).expect("flags are valid") {
+      commands.push(format!("{}{}", if enabled { '+' } else { '-' }, flag));
+    }
+  }
+  if let Some(position) = arena.first_strike {
+    commands.push(format!(
+      "first-strike c{} {}",
+      position.column + 1,
+      position.row + 1
+    ));
+  }
+  if let Some(objective) = &arena.objective {
+    for cell in objective.cells() {
+      commands.push(format!("target c{} {}", cell.column + 1, cell.row + 1));
+    }
+  }
+  for column in &arena.locked_columns {
+    commands.push(format!("lock c{}", column + 1));
+  }
+  for item in Item::ALL {
+    if arena.items.get(item) > 0 {
+      commands.push(format!("item {} {}", item.name(), arena.items.get(item)));
+    }
+  }
+  let challenges: Vec<&str> = CHALLENGES
+    .iter()
+    .filter(|(_, attacks)| {
+      attacks
+        .iter()
+        .all(|attack| arena.forbidden_attacks.contains(attack))
+    })
+    .map(|(name, _)| *name)
+    .collect();
+  if !challenges.is_empty() {
+    commands.push(format!("challenge {}", challenges.join(" ")));
+  }
+  commands
+}
+
+///Builds an arena from the commands of [`arena_commands`]. Other commands are refused, as they
+/// come from another process and could write files or start long solves.
+pub fn arena_from_commands<'a, I>(commands: I) -> Result<SolvableArena, ParseError>
+where
+  I: IntoIterator<Item = &'a str>,
+{
+  let mut arena = SolvableArena::default();
+  for command in commands.into_iter().map(str::trim) {
+    if command.is_empty() {
+      continue;
+    }
+    if !is_arena_command(command) {
+      return Err(ParseError::illegal_argument(
+        command,
+        "only commands building an arena can be shared",
+      ));
+    }
+    parse_quietly(&mut arena, command)?;
+  }
+  Ok(arena)
+}
+
+///Whether the command is one [`arena_commands`] builds arenas with
+fn is_arena_command(command: &str) -> bool {
+  let name = command.split_whitespace().next().unwrap_or_default();
+  if let Some(flag) = name.strip_prefix(['+', '-']) {
+    return AvailableEquipment::FLAGS.contains(&flag);
+  }
+  let is_column = name
+    .strip_prefix('c')
+    .is_some_and(|column| !column.is_empty() && column.bytes().all(|byte| byte.is_ascii_digit()));
+  is_column || ARENA_COMMANDS.contains(&name)
+}
+
+///Shares an arena with terminals attached to it, so the gui shows what is entered there. Clients
+/// send one request per line: `get` is answered with the commands building the arena, `set` followed
+/// by such commands replaces it and is answered with `ok`. Any other command in a `set` is answered
+/// with an error, see [`arena_from_commands`].
+///
+///The server keeps accepting clients until the process ends.
+pub struct SessionServer {
+  address: SocketAddr,
+  arena: Arc<Mutex<SolvableArena>>,
+}
+
+impl SessionServer {
+  ///Starts sharing the arena on the address. Arenas set by clients are passed to `on_change`, from
+  /// the thread serving the client.
+  pub fn start<A, F>(address: A, arena: SolvableArena, on_change: F) -> io::Result<Self>
+  where
+    A: ToSocketAddrs,
+    F: FnMut(SolvableArena) + Send + 'static,
+  {
+    let listener = TcpListener::bind(address)?;
+    let address = listener.local_addr()?;
+    let arena = Arc::new(Mutex::new(arena));
+    let on_change = Arc::new(Mutex::new(on_change));
+    let shared = arena.clone();
+    thread::spawn(move || {
+      for stream in listener.incoming().flatten() {
+        let arena = shared.clone();
+        let on_change = on_change.clone();
+        thread::spawn(move || {
+          //the client is gone on errors, there is nobody to tell
+          let _ = serve(stream, &arena, |arena| {
+            (on_change.lock().expect("session observer panicked"))(arena)
+          });
+        });
+      }
+    });
+    Ok(Self { address, arena })
+  }
+
+  ///Where clients can attach, with the actual port if the server was started on port 0
+  pub fn address(&self) -> SocketAddr {
+    self.address
+  }
+
+  ///Replaces the arena clients get after it has been changed by the owner of the server
+  pub fn publish(&self, arena: &SolvableArena) {
+    *self.arena.lock().expect("session client panicked") = arena.clone();
+  }
+}
+
+impl std::fmt::Debug for SessionServer {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SessionServer")
+      .field("address", &self.address)
+      .finish()
+  }
+}
+
+///Answers the requests of a client until it disconnects
+fn serve<F>(stream: TcpStream, arena: &Mutex<SolvableArena>, mut on_change: F) -> io::Result<()>
+where
+  F: FnMut(SolvableArena),
+{
+  let mut writer = stream.try_clone()?;
+  for line in BufReader::new(stream).lines() {
+    let line = line?;
+    let response = match line.trim().split_once(' ').unwrap_or((line.trim(), "")) {
+      ("get", _) => {
+        let arena = arena.lock().expect("session owner panicked");
+        arena_commands(&arena).join(SEPARATOR)
+      }
+      ("set", commands) => match arena_from_commands(commands.split(SEPARATOR)) {
+        Ok(changed) => {
+          *arena.lock().expect("session owner panicked") = changed.clone();
+          on_change(changed);
+          "ok".to_string()
+        }
+        Err(e) => format!("error {}", e),
+      },
+      (request, _) => format!("error unknown request '{}'", request),
+    };
+    writeln!(writer, "{}", response)?;
+  }
+  Ok(())
+}
+
+///A terminal attached to the arena of a [`SessionServer`]
+#[derive(Debug)]
+pub struct SessionClient {
+  address: SocketAddr,
+  reader: BufReader<TcpStream>,
+  writer: TcpStream,
+}
+
+impl SessionClient {
+  pub fn connect<A>(address: A) -> Result<Self, SessionError>
+  where
+    A: ToSocketAddrs,
+  {
+    let stream = TcpStream::connect(address)?;
+    Ok(Self {
+      address: stream.peer_addr()?,
+      reader: BufReader::new(stream.try_clone()?),
+      writer: stream,
+    })
+  }
+
+  pub fn address(&self) -> SocketAddr {
+    self.address
+  }
+
+  ///The arena as the server currently shows it
+  pub fn fetch(&mut self) -> Result<SolvableArena, SessionError> {
+    let response = self.request("get")?;
+    arena_from_commands(response.split(SEPARATOR)).map_err(SessionError::InvalidArena)
+  }
+
+  ///Replaces the arena of the server
+  pub fn push(&mut self, arena: &SolvableArena) -> Result<(), SessionError> {
+    let response = self.request(&format!("set {}", arena_commands(arena).join(SEPARATOR)))?;
+    match response.as_str() {
+      "ok" => Ok(()),
+      _ => Err(SessionError::Rejected(response)),
+    }
+  }
+
+  fn request(&mut self, request: &str) -> Result<String, SessionError> {
+    writeln!(self.writer, "{}", request)?;
+    let mut response = String::new();
+    if self.reader.read_line(&mut response)? == 0 {
+      return Err(SessionError::IO(io::ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(response.trim_end().to_string())
+  }
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+  IO(io::Error),
+  ///the server sent an arena which can't be read
+  InvalidArena(ParseError),
+  ///the server refused the request, with its answer
+  Rejected(String),
+}
+
+impl From<io::Error> for SessionError {
+  fn from(value: io::Error) -> Self {
+    Self::IO(value)
+  }
+}
+
+impl Display for SessionError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      SessionError::IO(e) => write!(f, "IO error: {}", e),
+      SessionError::InvalidArena(e) => write!(f, "the session sent an invalid arena: {}", e),
+      SessionError::Rejected(response) => write!(f, "the session refused: {}", response),
+    }
+  }
+}
+
+impl Error for SessionError {}
+
+#[cfg(test)]
+mod test_session {
+  use std::env;
+  use std::io::{BufRead, BufReader, Write};
+  use std::net::TcpStream;
+  use std::sync::mpsc;
+
+  use crate::command::parse_quietly;
+  use crate::session::{SessionClient, SessionServer, arena_commands, arena_from_commands};
+  use crate::solving::SolvableArena;
+
+  fn arena(commands: &[&str]) -> SolvableArena {
+    let mut arena = SolvableArena::default();
+    for cmd in commands {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    arena
+  }
+
+  #[test]
+  fn test_arena_commands() {
+    let arena = arena(&[
+      "c2 1H 4",
+      "c12 3J",
+      "hp c2 4 3",
+      "g 3",
+      "-hammer",
+      "+wide-anywhere",
+      "first-strike c12 3",
+      "target c5 12",
+      "lock c7",
+      "item ice-flower 2",
+      "challenge no-jump",
+    ]);
+    let commands = arena_commands(&arena);
+    assert_eq!(
+      Some(arena),
+      arena_from_commands(commands.iter().map(String::as_str)).ok()
+    );
+    assert!(arena_commands(&SolvableArena::default()).is_empty());
+  }
+
+  #[test]
+  fn test_round_trip() {
+    let (sender, receiver) = mpsc::channel();
+    let shown = arena(&["c3 12"]);
+    let server = SessionServer::start("127.0.0.1:0", shown.clone(), move |arena| {
+      sender.send(arena).unwrap()
+    })
+    .expect("failed to start session");
+
+    let mut client = SessionClient::connect(server.address()).expect("failed to attach");
+    assert_eq!(shown, client.fetch().unwrap());
+
+    let entered = arena(&["c3 12", "c9 4P", "g 2"]);
+    client.push(&entered).unwrap();
+    assert_eq!(entered, receiver.recv().unwrap());
+    assert_eq!(entered, client.fetch().unwrap());
+
+    let moved = arena(&["c4 12"]);
+    server.publish(&moved);
+    assert_eq!(moved, client.fetch().unwrap());
+  }
+
+  #[test]
+  fn test_only_arena_commands() {
+    assert!(arena_from_commands(["c2 1H", "-hammer", "+wide-anywhere", "hp c2 1 3"]).is_ok());
+    for command in [
+      "trace /tmp/x in 1",
+      "solve in 100",
+      "clear",
+      "-",
+      "c",
+      "cx 1",
+      "+sword",
+    ] {
+      assert!(arena_from_commands([command]).is_err(), "{}", command);
+    }
+
+    let server = SessionServer::start("127.0.0.1:0", SolvableArena::default(), |_| {})
+      .expect("failed to start session");
+    let trace = env::temp_dir().join(format!("tok_session_trace_{}", std::process::id()));
+    let stream = TcpStream::connect(server.address()).expect("failed to attach");
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut writer = stream;
+    writeln!(writer, "set c3 12;trace {} in 1", trace.display()).unwrap();
+    let mut response = String::new();
+    reader.read_line(&mut response).unwrap();
+    assert!(response.starts_with("error"), "{}", response);
+    assert!(!trace.exists());
+    //the arena isn't changed by a refused request
+    writeln!(writer, "get").unwrap();
+    response.clear();
+    reader.read_line(&mut response).unwrap();
+    assert_eq!("", response.trim_end());
+  }
+}
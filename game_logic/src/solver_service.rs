@@ -0,0 +1,569 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::position::{Move, Num};
+use crate::solving::cancel::CancelToken;
+use crate::solving::{SolvableArena, SolveParams, solve_with};
+use crate::solving::solve_cache::SolveCache;
+
+type ResultKey = (SolvableArena, SolveParams);
+
+///Remembers the solutions found by the workers. Holds at most a fixed amount of results, evicting the
+/// least recently used one when full.
+#[derive(Debug)]
+struct ResultCache {
+  entry_limit: usize,
+  entries: HashMap<ResultKey, (Option<Vec<Move>>, u64)>,
+  ///the keys of all entries by the time they were last used
+  recently_used: BTreeMap<u64, ResultKey>,
+  time: u64,
+}
+
+impl ResultCache {
+  const DEFAULT_ENTRY_LIMIT: usize = 1 << 10;
+
+  fn with_entry_limit(entry_limit: usize) -> Self {
+    Self {
+      entry_limit: entry_limit.max(1),
+      entries: HashMap::new(),
+      recently_used: BTreeMap::new(),
+      time: 0,
+    }
+  }
+
+  fn get(&mut self, key: &ResultKey) -> Option<Option<Vec<Move>>> {
+    let time = self.tick();
+    let (solution, last_used) = self.entries.get_mut(key)?;
+    let key = self
+      .recently_used
+      .remove(last_used)
+      .expect("every entry has a time");
+    self.recently_used.insert(time, key);
+    *last_used = time;
+    Some(solution.clone())
+  }
+
+  fn insert(&mut self, key: ResultKey, solution: Option<Vec<Move>>) {
+    let time = self.tick();
+    if let Some((_, last_used)) = self.entries.insert(key.clone(), (solution, time)) {
+      self.recently_used.remove(&last_used);
+    } else if self.entries.len() > self.entry_limit {
+      let (_, oldest) = self.recently_used.pop_first().expect("cache isn't empty");
+      self.entries.remove(&oldest);
+    }
+    self.recently_used.insert(time, key);
+  }
+
+  fn clear(&mut self) {
+    self.entries.clear();
+    self.recently_used.clear();
+  }
+
+  fn tick(&mut self) -> u64 {
+    self.time += 1;
+    self.time
+  }
+}
+
+///Runs solves on a pool of worker threads, so every front-end shares the same concurrency,
+/// cancellation and caching behavior
+#[derive(Debug)]
+pub struct SolverService {
+  job_sender: Option<Sender<Job>>,
+  workers: Vec<JoinHandle<()>>,
+  cache: Arc<Mutex<ResultCache>>,
+}
+
+impl SolverService {
+  ///Creates a service with one worker per available cpu core
+  pub fn new() -> Self {
+    Self::with_workers(
+      thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1),
+    )
+  }
+
+  pub fn with_workers(num_workers: usize) -> Self {
+    Self::with_config(num_workers, SolveCache::DEFAULT_ENTRY_LIMIT)
+  }
+
+  ///Creates a service with the given number of workers, each keeping a [`SolveCache`] with the given
+  /// entry limit
+  pub fn with_config(num_workers: usize, cache_entry_limit: usize) -> Self {
+    let (job_sender, job_receiver) = channel::<Job>();
+    let job_receiver = Arc::new(Mutex::new(job_receiver));
+    let cache = Arc::new(Mutex::new(ResultCache::with_entry_limit(
+      ResultCache::DEFAULT_ENTRY_LIMIT,
+    )));
+
+    let workers = (0..num_workers.max(1))
+      .map(|_| {
+        let job_receiver = job_receiver.clone();
+        let cache = cache.clone();
+        thread::spawn(move || {
+          //every worker keeps its own arena cache since looking up whether an arena is solved
+          // happens far too often to share it behind a lock
+          let mut arena_cache = SolveCache::with_entry_limit(cache_entry_limit);
+          loop {
+            //release the lock before running the job so other workers can pick up jobs
+            let job = job_receiver.lock().expect("job queue is poisoned").recv();
+            match job {
+              Ok(job) => job.run(&cache, &mut arena_cache),
+              Err(_) => break, //service has been dropped
+            }
+          }
+        })
+      })
+      .collect();
+
+    Self {
+      job_sender: Some(job_sender),
+      workers,
+      cache,
+    }
+  }
+
+  ///Queues a solve and returns a [`JobHandle`] to track its progress, cancel it or wait for the result
+  pub fn submit(&self, arena: SolvableArena, params: SolveParams) -> JobHandle {
+    let state = Arc::new(JobState::default());
+    let (result_sender, result_receiver) = channel();
+    let job = Job {
+      arena,
+      params,
+      state: state.clone(),
+      result_sender,
+    };
+
+    if let Err(e) = self
+      .job_sender
+      .as_ref()
+      .expect("sender only gets removed on drop")
+      .send(job)
+    {
+      //all workers died, report the job as cancelled instead of hanging forever
+      let job = e.0;
+      job.state.finished.store(true, Ordering::Release);
+      let _ = job.result_sender.send(JobResult::Cancelled);
+    }
+
+    JobHandle {
+      state,
+      result_receiver,
+      result: None,
+    }
+  }
+
+  ///Forgets all results computed so far
+  pub fn clear_cache(&self) {
+    self.cache.lock().expect("cache is poisoned").clear();
+  }
+}
+
+impl Default for SolverService {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Drop for SolverService {
+  fn drop(&mut self) {
+    //closing the channel makes the workers stop once the queue is empty
+    drop(self.job_sender.take());
+    for worker in self.workers.drain(..) {
+      let _ = worker.join();
+    }
+  }
+}
+
+#[derive(Debug)]
+struct Job {
+  arena: SolvableArena,
+  params: SolveParams,
+  state: Arc<JobState>,
+  result_sender: Sender<JobResult>,
+}
+
+impl Job {
+  fn run(self, cache: &Mutex<ResultCache>, arena_cache: &mut SolveCache) {
+    let key = (self.arena, self.params);
+    let cached = cache.lock().expect("cache is poisoned").get(&key);
+    let result = match cached {
+      Some(solution) => JobResult::from(solution),
+      None => {
+        let (arena, params) = &key;
+        self.state.start();
+        arena_cache.count_lookups_in(Some(self.state.nodes.clone()));
+        let solution = solve_with(
+          arena,
+          params,
+          arena_cache,
+          Some(&self.state.cancelled),
+          |in_turns| {
+            self.state.searched_turns.store(in_turns, Ordering::Release);
+            true
+          },
+        );
+        arena_cache.count_lookups_in(None);
+
+        if self.state.cancelled.is_cancelled() {
+          JobResult::Cancelled
+        } else {
+          cache
+            .lock()
+            .expect("cache is poisoned")
+            .insert(key, solution.clone());
+          JobResult::from(solution)
+        }
+      }
+    };
+
+    self.state.finished.store(true, Ordering::Release);
+    let _ = self.result_sender.send(result);
+  }
+}
+
+#[derive(Debug, Default)]
+struct JobState {
+  ///stops the search of the job, also in the middle of an attempt
+  cancelled: CancelToken,
+  finished: AtomicBool,
+  searched_turns: AtomicU8,
+  ///the arenas looked at so far, counted by the [`SolveCache`] of the solving thread
+  nodes: Arc<AtomicU64>,
+  started: OnceLock<Instant>,
+}
+
+impl JobState {
+  fn start(&self) {
+    let _ = self.started.set(Instant::now());
+  }
+
+  fn progress(&self) -> JobProgress {
+    JobProgress {
+      searched_turns: self.searched_turns.load(Ordering::Acquire),
+      nodes: self.nodes.load(Ordering::Relaxed),
+      elapsed: self
+        .started
+        .get()
+        .map_or(Duration::ZERO, |started| started.elapsed()),
+      finished: self.finished.load(Ordering::Acquire),
+    }
+  }
+}
+
+///A handle to a solve submitted to a [`SolverService`]
+#[derive(Debug)]
+pub struct JobHandle {
+  state: Arc<JobState>,
+  result_receiver: Receiver<JobResult>,
+  result: Option<JobResult>,
+}
+
+impl JobHandle {
+  ///Stops the job. A running search notices it within a few arenas and the job finishes with
+  /// [`JobResult::Cancelled`].
+  pub fn cancel(&self) {
+    self.state.cancelled.cancel();
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.state.cancelled.is_cancelled()
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.state.finished.load(Ordering::Acquire)
+  }
+
+  pub fn progress(&self) -> JobProgress {
+    self.state.progress()
+  }
+
+  ///Returns the result if the job has finished, without blocking
+  pub fn try_result(&mut self) -> Option<&JobResult> {
+    if self.result.is_none() {
+      self.result = self.result_receiver.try_recv().ok();
+    }
+    self.result.as_ref()
+  }
+
+  ///Blocks until the job has finished
+  pub fn wait(mut self) -> JobResult {
+    if let Some(result) = self.result.take() {
+      return result;
+    }
+    self.result_receiver.recv().unwrap_or(JobResult::Cancelled)
+  }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct JobProgress {
+  ///the number of turns the job is currently searching solutions in
+  pub searched_turns: Num,
+  ///the number of arenas looked at so far
+  pub nodes: u64,
+  ///the time since the search started, zero while it's still queued or its result was cached
+  pub elapsed: Duration,
+  pub finished: bool,
+}
+
+impl JobProgress {
+  ///The average number of arenas looked at per second
+  pub fn nodes_per_second(&self) -> f64 {
+    if self.elapsed.is_zero() {
+      0.0
+    } else {
+      self.nodes as f64 / self.elapsed.as_secs_f64()
+    }
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum JobResult {
+  Solved(Vec<Move>),
+  Unsolvable,
+  Cancelled,
+}
+
+impl From<Option<Vec<Move>>> for JobResult {
+  fn from(value: Option<Vec<Move>>) -> Self {
+    match value {
+      Some(solution) => Self::Solved(solution),
+      None => Self::Unsolvable,
+    }
+  }
+}
+
+///Solves the arena on a new thread and streams solutions as they are found: first any solution found
+/// in fast mode, then every strictly better one until the optimal solution according to the
+/// [`SolveParams`] has been sent. Dropping the returned [`AnytimeSolve`] stops the search.
+pub fn solve_anytime(arena: SolvableArena, params: SolveParams) -> AnytimeSolve {
+  let state = Arc::new(JobState::default());
+  let (solution_sender, solution_receiver) = channel();
+  let thread_state = state.clone();
+  thread::spawn(move || {
+    send_improving_solutions(&arena, params, &thread_state, &solution_sender);
+    thread_state.finished.store(true, Ordering::Release);
+  });
+
+  AnytimeSolve {
+    state,
+    solution_receiver,
+  }
+}
+
+fn send_improving_solutions(
+  arena: &SolvableArena,
+  params: SolveParams,
+  state: &JobState,
+  solution_sender: &Sender<Vec<Move>>,
+) {
+  state.start();
+  let mut cache = SolveCache::new();
+  cache.count_lookups_in(Some(state.nodes.clone()));
+  let mut solve = |params: &SolveParams| {
+    solve_with(
+      arena,
+      params,
+      &mut cache,
+      Some(&state.cancelled),
+      |in_turns| {
+        state.searched_turns.store(in_turns, Ordering::Release);
+        true
+      },
+    )
+  };
+
+  let mut fast_params = params.fast(true);
+  fast_params.deterministic = false;
+  let Some(mut best) = solve(&fast_params) else {
+    return;
+  };
+  if solution_sender.send(best.clone()).is_err() {
+    return;
+  }
+
+  //the first number of turns any solution is found with is the optimal one, so improve the solution
+  // quickly in fast mode first and only then search for the optimal solution with those turns
+  for in_turns in 1..=best.len() as Num {
+    let Some(fast_solution) = solve(&fast_params.in_turns(in_turns)) else {
+      continue;
+    };
+    let optimal_params = params.in_turns(in_turns).fast(false);
+    for solution in [Some(fast_solution), solve(&optimal_params)]
+      .into_iter()
+      .flatten()
+    {
+      if params.comparator.is_better(&solution, &best) {
+        best = solution;
+        if solution_sender.send(best.clone()).is_err() {
+          return;
+        }
+      }
+    }
+    break;
+  }
+}
+
+///The strictly improving solutions of [`solve_anytime`]. Iterating blocks until the next solution is
+/// found and ends once the search is done.
+#[derive(Debug)]
+pub struct AnytimeSolve {
+  state: Arc<JobState>,
+  solution_receiver: Receiver<Vec<Move>>,
+}
+
+impl AnytimeSolve {
+  ///Returns the next solution if one has been found already, without blocking
+  pub fn try_next(&mut self) -> Option<Vec<Move>> {
+    self.solution_receiver.try_recv().ok()
+  }
+
+  ///How far the search got. It's finished once every solution has been found or it was stopped.
+  pub fn progress(&self) -> JobProgress {
+    self.state.progress()
+  }
+}
+
+impl Iterator for AnytimeSolve {
+  type Item = Vec<Move>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.solution_receiver.recv().ok()
+  }
+}
+
+impl Drop for AnytimeSolve {
+  fn drop(&mut self) {
+    self.state.cancelled.cancel();
+  }
+}
+
+#[cfg(test)]
+mod test_solver_service {
+  use std::thread;
+  use std::time::Duration;
+
+  use crate::command::parse_quietly;
+  use crate::solver_service::{JobResult, ResultCache, SolverService, solve_anytime};
+  use crate::solving::{SolvableArena, SolveParams, is_better, solve};
+
+  #[test]
+  fn test_submit() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let service = SolverService::with_workers(2);
+    let handle = service.submit(arena.clone(), SolveParams::new());
+    let unsolvable = service.submit(arena, SolveParams::new().in_turns(0));
+
+    match handle.wait() {
+      JobResult::Solved(solution) => assert_eq!(1, solution.len()),
+      result => panic!("expected a solution, got {:?}", result),
+    }
+    assert_eq!(JobResult::Unsolvable, unsolvable.wait());
+  }
+
+  #[test]
+  fn test_result_cache_evicts_least_recently_used() {
+    let key = |turns| (SolvableArena::default(), SolveParams::new().in_turns(turns));
+    let mut cache = ResultCache::with_entry_limit(2);
+    cache.insert(key(1), None);
+    cache.insert(key(2), Some(vec![]));
+    assert_eq!(Some(None), cache.get(&key(1)));
+
+    cache.insert(key(3), None);
+    assert_eq!(2, cache.entries.len());
+    assert_eq!(None, cache.get(&key(2)));
+    assert_eq!(Some(None), cache.get(&key(1)));
+    assert_eq!(Some(None), cache.get(&key(3)));
+
+    //overwriting an entry doesn't evict anything
+    cache.insert(key(3), Some(vec![]));
+    assert_eq!(2, cache.entries.len());
+    assert_eq!(Some(None), cache.get(&key(1)));
+  }
+
+  #[test]
+  fn test_cancel_running_job() {
+    let mut arena = SolvableArena::default();
+    for cmd in [
+      "c2 12", "c3 4", "c5 12", "c8 12", "c9 123", "c11 3", "c11 4",
+    ] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    //a single attempt with this many turns takes far longer than the test
+    let service = SolverService::with_workers(1);
+    let handle = service.submit(arena, SolveParams::new().in_turns(6));
+    while handle.progress().nodes == 0 {
+      thread::sleep(Duration::from_millis(1));
+    }
+    handle.cancel();
+    assert!(handle.is_cancelled());
+    assert_eq!(JobResult::Cancelled, handle.wait());
+  }
+
+  #[test]
+  fn test_solve_anytime() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let solutions: Vec<_> = solve_anytime(arena.clone(), SolveParams::new().in_turns(4)).collect();
+    assert!(!solutions.is_empty());
+    for pair in solutions.windows(2) {
+      assert!(is_better(&pair[1], &pair[0]));
+    }
+    assert_eq!(
+      solve(&arena, 2, false, None, None).as_ref(),
+      solutions.last()
+    );
+  }
+
+  #[test]
+  fn test_solve_anytime_unsolvable() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    assert_eq!(
+      0,
+      solve_anytime(arena, SolveParams::new().in_turns(0)).count()
+    );
+  }
+
+  #[test]
+  fn test_progress() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let service = SolverService::with_workers(1);
+    let mut handle = service.submit(arena.clone(), SolveParams::new());
+    while handle.try_result().is_none() {
+      thread::sleep(Duration::from_millis(1));
+    }
+    let progress = handle.progress();
+    assert!(progress.finished);
+    assert_eq!(1, progress.searched_turns);
+    assert!(progress.nodes > 0);
+
+    let mut anytime = solve_anytime(arena, SolveParams::new());
+    assert!(anytime.by_ref().count() > 0);
+    let progress = anytime.progress();
+    assert!(progress.finished);
+    assert!(progress.nodes > 0);
+    assert!(progress.nodes_per_second() > 0.0);
+  }
+}
@@ -1,21 +1,72 @@
-use std::borrow::Borrow;
-use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
-use std::fmt::{Display, Formatter};
-use std::hash::Hash;
-use std::ops::{Deref, DerefMut};
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{Display, Formatter};
+use core::hash::Hash;
+use core::ops::{Deref, DerefMut};
 
 use enum_assoc::Assoc;
 
-use crate::arena::{Arena, ToArenaSymbol};
+use crate::arena::{Arena, Layer, ToArenaSymbol};
+use crate::collections::Set;
 use crate::position::{Move, Num, Position};
 use crate::position::Dimension::{Column, Row};
-
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+use crate::solving::area_template::{AreaShape, available_templates, can_attack};
+use crate::solving::attack_rules::attack_rules;
+use crate::solving::bidirectional::explore;
+use crate::solving::cancel::CancelToken;
+use crate::solving::challenge::allows_area;
+use crate::solving::item::{Item, ItemStock};
+use crate::solving::lower_bound::lower_bound;
+use crate::solving::move_filter::MoveFilter;
+use crate::solving::objective::ObjectivePattern;
+use crate::solving::search_tree::{NodeOutcome, SearchTracer};
+use crate::solving::solve_cache::SolveCache;
+pub use crate::solving::comparator::{Comparator, SolutionComparator};
+pub use crate::solving::strategy::{SolverStrategy, Strategy};
+
+pub mod area_template;
+pub mod attack_rules;
+pub mod battle;
+pub mod bidirectional;
+pub mod cancel;
+pub mod cell_permutation;
+pub mod challenge;
+pub mod comparator;
+pub mod explain;
+pub mod first_strike;
+pub mod item;
+pub mod lint;
+pub mod lower_bound;
+pub mod move_filter;
+pub mod objective;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod pattern_database;
+pub mod search_tree;
+pub mod solve_cache;
+pub mod strategy;
+pub mod what_if;
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
 pub struct SolvableArena {
   pub inner: Arena<Enemy>,
   pub num_groups: Option<Num>,
   pub available_equipment: AvailableEquipment,
+  ///an enemy attacked before the fight starts
+  pub first_strike: Option<Position>,
+  ///cells which have to be occupied instead of all enemies being attackable
+  pub objective: Option<ObjectivePattern>,
+  ///columns which are already grouped, so the solver doesn't move them and attacks each one as a whole
+  pub locked_columns: BTreeSet<Num>,
+  ///items which may be used to attack columns regardless of weaknesses
+  pub items: ItemStock,
+  ///attacks a challenge run doesn't use, see [`challenge`]
+  pub forbidden_attacks: BTreeSet<Attack>,
 }
 
 impl SolvableArena {
@@ -32,7 +83,55 @@ impl SolvableArena {
   }
 
   pub fn is_solved(&self) -> bool {
-    Coverage::find(self).is_some()
+    match &self.objective {
+      Some(objective) => objective.is_fulfilled(self),
+      None => Coverage::find(self).is_some(),
+    }
+  }
+
+  ///Whether the move leaves all [`SolvableArena::locked_columns`] as they are. Rotating a ring moves
+  /// every column, so no ring can be rotated while a column is locked.
+  pub fn allows_move(&self, move_: Move) -> bool {
+    self.locked_columns.is_empty()
+      || move_
+        .affected_positions()
+        .iter()
+        .all(|position| !self.locked_columns.contains(&position.column))
+  }
+
+  ///Returns a copy with the enemies sorted by position, so arenas with the same enemies are equal
+  /// regardless of the order the enemies have been added in
+  pub fn canonical(&self) -> Self {
+    let mut canonical = self.clone();
+    canonical.enemies.sort_by_key(|enemy| enemy.position);
+    canonical
+  }
+
+  ///Returns the arena as it is at the start of the fight, with the [`SolvableArena::first_strike`]
+  /// enemy attacked
+  pub fn after_first_strike(&self) -> Self {
+    let mut arena = self.clone();
+    if let Some(position) = arena.first_strike.take() {
+      if let Some(enemy) = arena.get_at(&position).copied() {
+        arena.remove(&position);
+        if let Some(survivor) = enemy.attacked() {
+          arena.add(survivor);
+        }
+      }
+    }
+    arena
+  }
+}
+
+impl Layer for SolvableArena {
+  type Item = Enemy;
+
+  fn get_at(&self, at: &Position) -> Option<&Self::Item> {
+    self.inner.get_at(at)
+  }
+
+  fn apply_move(&mut self, move_: Move) {
+    self.inner.apply_move(move_)
   }
 }
 
@@ -50,10 +149,12 @@ impl DerefMut for SolvableArena {
   }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct AvailableEquipment {
   pub throwing_hammer: bool,
   pub iron_boots: bool,
+  ///attacks hitting 2x2 areas on any rings instead of only on the inner two, like some boss arms
+  pub wide_attacks_anywhere: bool,
 }
 
 impl Default for AvailableEquipment {
@@ -61,10 +162,41 @@ impl Default for AvailableEquipment {
     Self {
       throwing_hammer: true,
       iron_boots: true,
+      wide_attacks_anywhere: false,
+    }
+  }
+}
+
+impl AvailableEquipment {
+  ///The names of the equipment flags, as used by [`AvailableEquipment::flag_mut`]
+  pub const FLAGS: [&'static str; 3] = ["hammer", "iron-boots", "wide-anywhere"];
+
+  ///The field of the equipment flag with the given name, if there is one
+  pub fn flag_mut(&mut self, name: &str) -> Option<&mut bool> {
+    match name {
+      "hammer" => Some(&mut self.throwing_hammer),
+      "iron-boots" => Some(&mut self.iron_boots),
+      "wide-anywhere" => Some(&mut self.wide_attacks_anywhere),
+      _ => None,
     }
   }
 }
 
+///Lists all flags like `+hammer -iron-boots`
+impl Display for AvailableEquipment {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    let mut equipment = *self;
+    for (index, name) in Self::FLAGS.into_iter().enumerate() {
+      if index > 0 {
+        write!(f, " ")?;
+      }
+      let present = *equipment.flag_mut(name).expect("flags are known");
+      write!(f, "{}{}", if present { '+' } else { '-' }, name)?;
+    }
+    Ok(())
+  }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Coverage {
   areas: Vec<EnemyArea>,
@@ -80,13 +212,21 @@ impl Coverage {
     A: Borrow<SolvableArena>,
   {
     let arena = arena.borrow();
-    let mut enemies: Vec<_> = arena
-      .enemies
-      .iter()
-      .map(|enemy| (enemy, RingPosition::from(&enemy.position)))
-      .collect();
-    enemies.sort_by_cached_key(|(_, ring_pos)| *ring_pos);
-    Self::default().finalize(enemies.into_iter(), arena, arena.num_groups() as usize)
+    //enemies on the outer rings can be covered by fewer areas, so cover them first
+    let mut enemies: Vec<_> = arena.enemies.iter().collect();
+    enemies.sort_by_key(|enemy| RingPosition::from(&enemy.position));
+    //locked columns are attacked as a whole
+    let mut coverage = Self::default();
+    for &column in &arena.locked_columns {
+      if arena.enemies.iter().any(|enemy| enemy.column == column) {
+        coverage.push(EnemyArea::new(TargetArea::Long { column }));
+      }
+    }
+    coverage.finalize(enemies.into_iter(), arena, arena.num_groups() as usize)
+  }
+
+  pub fn areas(&self) -> &[EnemyArea] {
+    &self.areas
   }
 
   fn finalize<'a, E>(
@@ -96,124 +236,101 @@ impl Coverage {
     num_groups: usize,
   ) -> Option<Self>
   where
-    E: Iterator<Item = (&'a Enemy, RingPosition)> + Clone,
+    E: Iterator<Item = &'a Enemy> + Clone,
   {
     let equipment = &arena.available_equipment;
-    //cover all enemies which are in the outer rings
-    while let Some((enemy, ring_position)) = enemy_iterator.next() {
-      match ring_position {
-        RingPosition::Outer => {
-          //hammer is not available on outer rings
-          if let Some(required_attack) = &enemy.required_attack {
-            let equipment_present = match required_attack {
-              RequiredAttack::IronBootsOrHammer => {
-                equipment.iron_boots || equipment.throwing_hammer
-              }
-              RequiredAttack::Hammer => equipment.throwing_hammer,
-              RequiredAttack::Jump => true,
-            };
-            if !equipment_present {
-              return None;
-            }
-          }
-
-          //check if enemy is already covered
-          if let Some(covering_area) = self.get_covering_area_mut(enemy) {
-            covering_area.limit_attacks(enemy).ok()?;
-            continue;
-          }
-
-          //check if another group is available
-          if self.len() >= num_groups {
-            return None;
-          }
-
-          self.push(EnemyArea::long(enemy));
+    while let Some(enemy) = enemy_iterator.next() {
+      //check if enemy is already covered
+      if let Some(index) = self.areas.iter().position(|area| area.covers(enemy)) {
+        let covering_area = &mut self.areas[index];
+        if covering_area.item.is_some()
+          || (can_attack(covering_area, enemy, equipment)
+            && covering_area.limit_attacks(enemy).is_ok()
+            && allows_area(covering_area, &arena.forbidden_attacks, equipment))
+        {
+          continue;
         }
-        RingPosition::Inner => {
-          //check if enemy is already covered
-          if let Some(covering_area) = self.get_covering_area_mut(enemy) {
-            //enemies which require a hammer covered by long areas require a throwing hammer
-            if !Self::hammer_enemy_can_be_covered(enemy, covering_area, equipment) {
-              return None;
-            }
-
-            covering_area.limit_attacks(enemy).ok()?;
-            continue;
-          }
-
-          //check if another group is available
-          if self.len() >= num_groups {
-            return None;
-          }
-
-          //jump enemies can't be covered by wide areas (hammer only)
-          let long_area_required = enemy.required_attack == Some(RequiredAttack::Jump);
-
-          macro_rules! try_finalize_with {
-            ($area: expr) => {
-              let mut next_coverage = self.clone();
-              next_coverage.push($area);
-              if let Some(finalized) =
-                next_coverage.finalize(enemy_iterator.clone(), arena, num_groups)
-              {
-                return Some(finalized);
-              }
-            };
-          }
-
-          if !long_area_required {
-            //try left-bound wide area
-            let left_area = EnemyArea::wide(enemy, true);
-            if self.can_hold(&left_area) {
-              try_finalize_with!(left_area);
-            }
-
-            //try right-bound wide area
-            let right_area = EnemyArea::wide(enemy, false);
-            if self.can_hold(&right_area) {
-              try_finalize_with!(right_area);
-            }
-          }
-
-          //try long area
-          let long_area = EnemyArea::long(enemy);
-          if Self::hammer_enemy_can_be_covered(enemy, &long_area, equipment) {
-            try_finalize_with!(long_area);
-          }
 
+        //attacking the column with an item works regardless of the weaknesses in it
+        let item = self.unused_item(&arena.items)?;
+        let covering_area = &mut self.areas[index];
+        if !matches!(covering_area.target_area, TargetArea::Long { .. }) {
           return None;
         }
+        covering_area.item = Some(item);
+        covering_area.attack_whitelist = None;
+        continue;
+      }
+
+      //check if another group is available
+      if self.len() >= num_groups {
+        return None;
+      }
+
+      //try every area which can cover the enemy, starting with the most flexible ones
+      let mut candidates: Vec<TargetArea> = available_templates(*equipment)
+        .flat_map(|template| template.areas_covering(enemy))
+        .filter(|area| can_attack(area, enemy, equipment))
+        //long areas may cross columns of wide areas on other rings
+        .filter(|area| matches!(area, TargetArea::Long { .. }) || self.can_hold(area))
+        .collect();
+      candidates.sort_by_key(|area| matches!(area, TargetArea::Long { .. }));
+      let mut candidates: Vec<EnemyArea> = candidates.into_iter().map(EnemyArea::new).collect();
+      //only use an item if the column can't be attacked otherwise
+      if !candidates
+        .iter()
+        .any(|area| matches!(area.target_area, TargetArea::Long { .. }))
+      {
+        if let Some(item) = self.unused_item(&arena.items) {
+          candidates.push(EnemyArea {
+            item: Some(item),
+            ..EnemyArea::new(TargetArea::long(enemy))
+          });
+        }
+      }
+      for mut next_area in candidates {
+        let mut next_coverage = self.clone();
+        if next_area.item.is_none() {
+          let _ = next_area.limit_attacks(enemy);
+        }
+        if !allows_area(&next_area, &arena.forbidden_attacks, equipment) {
+          continue;
+        }
+        next_coverage.push(next_area);
+        if let Some(finalized) = next_coverage.finalize(enemy_iterator.clone(), arena, num_groups) {
+          return Some(finalized);
+        }
       }
+
+      return None;
     }
 
     Some(self)
   }
 
-  fn hammer_enemy_can_be_covered(
-    enemy: &Enemy,
-    covering_area: &EnemyArea,
-    equipment: &AvailableEquipment,
-  ) -> bool {
-    //enemies which require a hammer covered by long areas require a throwing hammer
-    !matches!(
-      (
-        &covering_area.target_area,
-        &enemy.required_attack,
-        equipment.throwing_hammer,
-      ),
-      (TargetArea::Long { .. }, Some(RequiredAttack::Hammer), false)
-    )
+  ///An item of the stock which isn't used by any area yet
+  fn unused_item(&self, stock: &ItemStock) -> Option<Item> {
+    if stock.is_empty() {
+      return None;
+    }
+    Item::ALL.into_iter().find(|&item| {
+      self
+        .areas
+        .iter()
+        .filter(|area| area.item == Some(item))
+        .count()
+        < stock.get(item) as usize
+    })
   }
 
   pub fn can_hold(&self, area: &TargetArea) -> bool {
-    let mut covered_columns = HashSet::new();
+    let mut covered_columns = Set::new();
     for area in &self.areas {
       match area.target_area {
         TargetArea::Long { column } => {
           covered_columns.insert(column);
         }
-        TargetArea::Wide { left_column } => {
+        TargetArea::Wide { left_column, .. } => {
           covered_columns.insert(left_column);
           covered_columns.insert(TargetArea::right_column(left_column));
         }
@@ -221,7 +338,7 @@ impl Coverage {
     }
     !match area {
       TargetArea::Long { column } => covered_columns.contains(column),
-      TargetArea::Wide { left_column } => {
+      TargetArea::Wide { left_column, .. } => {
         covered_columns.contains(left_column)
           || covered_columns.contains(&Column.next(*left_column))
       }
@@ -255,8 +372,9 @@ impl DerefMut for Coverage {
   }
 }
 
+///Whether a position is on the inner or the outer two rings
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
-enum RingPosition {
+pub enum RingPosition {
   Outer,
   Inner,
 }
@@ -275,6 +393,8 @@ impl From<&Position> for RingPosition {
 pub struct EnemyArea {
   pub target_area: TargetArea,
   pub attack_whitelist: Option<Vec<Attack>>,
+  ///the item the area is attacked with, which ignores the weaknesses of the enemies
+  pub item: Option<Item>,
 }
 
 impl EnemyArea {
@@ -285,7 +405,7 @@ impl EnemyArea {
   }
 
   pub fn wide(enemy: &Enemy, left_bound: bool) -> Self {
-    let mut res = Self::new(TargetArea::wide(enemy, left_bound));
+    let mut res = Self::new(TargetArea::wide(enemy, left_bound, 0));
     let _ = res.limit_attacks(enemy);
     res
   }
@@ -294,6 +414,7 @@ impl EnemyArea {
     Self {
       target_area,
       attack_whitelist: None,
+      item: None,
     }
   }
 
@@ -327,26 +448,40 @@ impl DerefMut for EnemyArea {
   }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum TargetArea {
-  Long { column: Num },
-  Wide { left_column: Num },
+  Long {
+    column: Num,
+  },
+  ///covers the rings `inner_ring` and `inner_ring + 1`
+  Wide {
+    left_column: Num,
+    inner_ring: Num,
+  },
 }
 
 impl TargetArea {
+  pub fn shape(&self) -> AreaShape {
+    match self {
+      TargetArea::Long { .. } => AreaShape::Long,
+      TargetArea::Wide { .. } => AreaShape::Wide,
+    }
+  }
+
   pub fn long(position: &Position) -> Self {
     Self::Long {
       column: position.column,
     }
   }
 
-  pub fn wide(position: &Position, left_bound: bool) -> Self {
+  pub fn wide(position: &Position, left_bound: bool, inner_ring: Num) -> Self {
     Self::Wide {
       left_column: if left_bound {
         (position.column + Column.size() - 1) % Column.size()
       } else {
         position.column
       },
+      inner_ring,
     }
   }
 
@@ -357,8 +492,11 @@ impl TargetArea {
   pub fn covers(&self, position: &Position) -> bool {
     match self {
       TargetArea::Long { column } => position.column == *column,
-      TargetArea::Wide { left_column } => {
-        position.row < Row.size() / 2
+      TargetArea::Wide {
+        left_column,
+        inner_ring,
+      } => {
+        (*inner_ring..inner_ring + 2).contains(&position.row)
           && (position.column == *left_column
             || position.column == (*left_column + 1) % Column.size())
       }
@@ -367,81 +505,280 @@ impl TargetArea {
 }
 
 impl Display for TargetArea {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
     match self {
       TargetArea::Long { column } => write!(f, "c{}", column + 1),
-      TargetArea::Wide { left_column } => {
+      TargetArea::Wide {
+        left_column,
+        inner_ring: 0,
+      } => {
         write!(f, "h{}{}", left_column + 1, Column.next(*left_column) + 1)
       }
+      TargetArea::Wide {
+        left_column,
+        inner_ring,
+      } => write!(
+        f,
+        "h{}{}r{}{}",
+        left_column + 1,
+        Column.next(*left_column) + 1,
+        inner_ring + 1,
+        inner_ring + 2
+      ),
     }
   }
 }
 
-//TODO add option to interrupt
-pub fn solve<'a, C>(
+///The maximum number of turns tried when solving without a turn limit
+pub const MAX_TURNS: Num = 100;
+
+///Describes how an arena should be solved, shared by all front-ends
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct SolveParams {
+  ///the maximum number of turns, or `None` to solve in as few turns as possible
+  pub in_turns: Option<Num>,
+  ///be happy with any solution, even if it isn't optimal
+  pub fast: bool,
+  pub strategy: Strategy,
+  ///decides which of the solutions with the fewest moves is the best one
+  pub comparator: Comparator,
+  ///moves the solver must not use
+  pub move_filter: MoveFilter,
+  ///always return the first of all optimal solutions in the order of the comparator, regardless
+  /// of the strategy. Overrides `fast`, since which solution is found first depends on the search order.
+  pub deterministic: bool,
+}
+
+impl SolveParams {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn in_turns(mut self, in_turns: Num) -> Self {
+    self.in_turns = Some(in_turns);
+    self
+  }
+
+  pub fn fast(mut self, fast: bool) -> Self {
+    self.fast = fast;
+    self
+  }
+
+  pub fn strategy(mut self, strategy: Strategy) -> Self {
+    self.strategy = strategy;
+    self
+  }
+
+  pub fn comparator(mut self, comparator: Comparator) -> Self {
+    self.comparator = comparator;
+    self
+  }
+
+  pub fn deterministic(mut self, deterministic: bool) -> Self {
+    self.deterministic = deterministic;
+    self
+  }
+
+  pub fn move_filter(mut self, move_filter: MoveFilter) -> Self {
+    self.move_filter = move_filter;
+    self
+  }
+
+  ///Whether strategies may return the first solution they find
+  pub fn stop_at_first(&self) -> bool {
+    self.fast && !self.deterministic
+  }
+}
+
+///Writes the params like the options of `solve`, e.g. `fast optimize time forbid r2 in 3`, leaving
+/// out defaults
+impl Display for SolveParams {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    let mut options: Vec<String> = Vec::new();
+    if self.fast {
+      options.push("fast".to_string());
+    }
+    if self.deterministic {
+      options.push("deterministic".to_string());
+    }
+    if self.strategy != Strategy::default() {
+      options.push(self.strategy.to_string());
+    }
+    if self.comparator != Comparator::default() {
+      options.push(format!("optimize {}", self.comparator));
+    }
+    if !self.move_filter.is_empty() {
+      options.push(self.move_filter.to_string());
+    }
+    if let Some(in_turns) = self.in_turns {
+      options.push(format!("in {}", in_turns));
+    }
+    write!(f, "{}", options.join(" "))
+  }
+}
+
+///Solves the arena according to the given [`SolveParams`]. Without a turn limit, the number of turns
+/// is increased until a solution is found. `before_attempt` is called with the number of turns before
+/// every attempt and may return false to stop searching. Once the [`CancelToken`] is cancelled, the
+/// strategy stops in the middle of its search and `None` is returned.
+pub fn solve_with<F>(
+  arena: &SolvableArena,
+  params: &SolveParams,
+  cache: &mut SolveCache,
+  cancel: Option<&CancelToken>,
+  mut before_attempt: F,
+) -> Option<Vec<Move>>
+where
+  F: FnMut(Num) -> bool,
+{
+  let arena = &arena.after_first_strike();
+  let mut may_attempt =
+    |in_turns| !cancel.is_some_and(CancelToken::is_cancelled) && before_attempt(in_turns);
+  if let Some(in_turns) = params.in_turns {
+    if !may_attempt(in_turns) {
+      return None;
+    }
+    return solve_in(arena, in_turns, params, cache, cancel);
+  }
+
+  for in_turns in 1..=MAX_TURNS {
+    if !may_attempt(in_turns) {
+      return None;
+    }
+    if let Some(solution) = solve_in(arena, in_turns, params, &mut *cache, cancel) {
+      return Some(solution);
+    }
+  }
+  None
+}
+
+fn solve_in(
+  arena: &SolvableArena,
+  in_turns: Num,
+  params: &SolveParams,
+  cache: &mut SolveCache,
+  cancel: Option<&CancelToken>,
+) -> Option<Vec<Move>> {
+  params
+    .strategy
+    .solver()
+    .solve(arena, in_turns, params, cache, cancel)
+}
+
+///Solves the arena depth first in at most `in_turns` turns. Once the [`CancelToken`] is cancelled,
+/// the search stops and returns `None` like when there is no solution.
+pub fn solve<'a, C, K>(
   arena: &SolvableArena,
   in_turns: Num,
   fast: bool,
   arena_solved_cache: C,
+  cancel: K,
 ) -> Option<Vec<Move>>
 where
-  C: Into<Option<&'a mut HashMap<SolvableArena, bool>>>,
+  C: Into<Option<&'a mut SolveCache>>,
+  K: Into<Option<&'a CancelToken>>,
 {
-  let mut new_cache = HashMap::new();
+  let mut new_cache = SolveCache::new();
   let cache = match arena_solved_cache.into() {
     Some(cache) => cache,
     None => &mut new_cache,
   };
+  search(
+    arena,
+    in_turns,
+    fast,
+    &MoveFilter::new(),
+    Comparator::default(),
+    cache,
+    cancel.into(),
+    &mut (),
+  )
+}
+
+///The depth first search behind [`solve`], reporting every visited arena to the tracer. Gives up
+/// with `None` once the token is cancelled.
+#[allow(clippy::too_many_arguments)]
+fn search<T>(
+  arena: &SolvableArena,
+  in_turns: Num,
+  fast: bool,
+  filter: &MoveFilter,
+  comparator: Comparator,
+  cache: &mut SolveCache,
+  cancel: Option<&CancelToken>,
+  tracer: &mut T,
+) -> Option<Vec<Move>>
+where
+  T: SearchTracer,
+{
+  if cancel.is_some_and(CancelToken::is_cancelled) {
+    return None;
+  }
+  if arena.first_strike.is_some() {
+    return search(
+      &arena.after_first_strike(),
+      in_turns,
+      fast,
+      filter,
+      comparator,
+      cache,
+      cancel,
+      tracer,
+    );
+  }
 
   if let Some(solved) = cache.get(arena) {
-    if *solved {
+    if solved {
+      tracer.outcome(NodeOutcome::Solved);
       return Some(vec![]);
     }
   } else if arena.is_solved() {
     cache.insert(arena.clone(), true);
+    tracer.outcome(NodeOutcome::Solved);
     return Some(vec![]);
   } else {
     cache.insert(arena.clone(), false);
   }
 
   if in_turns == 0 {
+    tracer.outcome(NodeOutcome::OutOfTurns);
+    return None;
+  }
+  let lower_bound = lower_bound(arena);
+  if lower_bound > in_turns {
+    tracer.outcome(NodeOutcome::PrunedByLowerBound(lower_bound));
     return None;
   }
 
   let mut best_solution: Option<Vec<Move>> = None;
-  for dimension in [Row, Column] {
-    for coordinate in 0..dimension.size() {
-      for amount in 1..=dimension.changes().size() {
-        let move_ = Move::new(dimension, coordinate, amount, true).unwrap();
-        let mut arena_clone = arena.clone();
-        arena_clone.apply_move(move_);
-
-        if let Some(mut solution) = solve(&arena_clone, in_turns - 1, fast, &mut *cache) {
-          solution.insert(0, move_);
-
-          if fast {
-            return Some(solution);
-          }
-
-          if let Some(current_best) = &best_solution {
-            //solution is better if it is shorter and has a lower sum of absolute shortest amounts
-            match solution.len().cmp(&current_best.len()) {
-              Ordering::Less => {
-                best_solution = Some(solution);
-              }
-              Ordering::Equal => {
-                if solution.iter().map(|m| m.normalized().amount).sum::<Num>()
-                  < current_best.iter().map(|m| m.normalized().amount).sum()
-                {
-                  best_solution = Some(solution);
-                }
-              }
-              Ordering::Greater => {}
-            }
-          } else {
-            best_solution = Some(solution);
-          }
-        }
+  for move_ in allowed_moves(arena, filter) {
+    let mut arena_clone = arena.clone();
+    arena_clone.apply_move(move_);
+
+    tracer.node(Some(move_), in_turns - 1);
+    let solution = search(
+      &arena_clone,
+      in_turns - 1,
+      fast,
+      filter,
+      comparator,
+      &mut *cache,
+      cancel,
+      tracer,
+    );
+    tracer.finish(solution.is_some());
+    if let Some(mut solution) = solution {
+      solution.insert(0, move_);
+
+      if fast {
+        return Some(solution);
+      }
+
+      if best_solution
+        .as_ref()
+        .is_none_or(|current_best| comparator.is_better(&solution, current_best))
+      {
+        best_solution = Some(solution);
       }
     }
   }
@@ -449,10 +786,244 @@ where
   best_solution
 }
 
+///A solved arena reachable from another one, see [`reachable_solved_states`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SolvedState {
+  ///the arena after the moves, with its enemies in [canonical](SolvableArena::canonical) order
+  pub arena: SolvableArena,
+  ///the best sequence of moves reaching the arena
+  pub moves: Vec<Move>,
+}
+
+///All distinct solved arenas reachable in at most `max_moves` moves, each with the best sequence
+/// of moves reaching it, best sequences first. Unlike [`solve`], which stops at the best solution,
+/// this lists the alternatives, like endings with the enemies grouped differently. Explores like the
+/// forward half of [`bidirectional::solve_bidirectional`], so it is only feasible for ~3 moves.
+pub fn reachable_solved_states(arena: &SolvableArena, max_moves: Num) -> Vec<SolvedState> {
+  let arena = arena.after_first_strike();
+  let moves: Vec<Move> = allowed_moves(&arena, &MoveFilter::new()).collect();
+  let mut states: Vec<SolvedState> = explore(
+    arena.canonical(),
+    max_moves,
+    &moves,
+    Comparator::default(),
+    |arena, move_| {
+      let mut next = arena.clone();
+      next.apply_move(move_);
+      next.canonical()
+    },
+  )
+  .into_iter()
+  .flatten()
+  .filter(|(arena, _)| arena.is_solved())
+  .map(|(arena, moves)| SolvedState { arena, moves })
+  .collect();
+  states.sort_by(|state, other| compare_solutions(&state.moves, &other.moves));
+  states
+}
+
+///The moves the solver may try on the arena: all moves the filter allows which don't disturb locked
+/// columns, in the order of [`all_moves`]
+pub fn allowed_moves<'a>(
+  arena: &'a SolvableArena,
+  filter: &'a MoveFilter,
+) -> impl Iterator<Item = Move> + Clone + 'a {
+  all_moves().filter(move |&move_| filter.allows(move_) && arena.allows_move(move_))
+}
+
+///All moves the solver tries, in the order it tries them
+pub fn all_moves() -> impl Iterator<Item = Move> + Clone {
+  [Row, Column].into_iter().flat_map(|dimension| {
+    (0..dimension.size()).flat_map(move |coordinate| {
+      (1..=dimension.changes().size())
+        .map(move |amount| Move::new(dimension, coordinate, amount, true).unwrap())
+    })
+  })
+}
+
+///A solution is better if it comes first in the order of [`compare_solutions`], the order of the
+/// default [`Comparator`]
+pub fn is_better(solution: &[Move], current_best: &[Move]) -> bool {
+  compare_solutions(solution, current_best).is_lt()
+}
+
+///Orders solutions by quality: shorter solutions come first, then those with a lower sum of absolute
+/// shortest amounts. Equally good solutions are ordered move by move with [`compare_moves`], so there
+/// is always exactly one best solution.
+pub fn compare_solutions(solution: &[Move], other: &[Move]) -> Ordering {
+  solution
+    .len()
+    .cmp(&other.len())
+    .then_with(|| amount_sum(solution).cmp(&amount_sum(other)))
+    .then_with(|| {
+      solution
+        .iter()
+        .zip(other)
+        .map(|(move_, other_move)| compare_moves(move_, other_move))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+    })
+}
+
+///The tie-breaking order of moves, comparing their normalized forms by dimension (rows first), then
+/// coordinate, then direction (positive first), then amount
+pub fn compare_moves(move_: &Move, other: &Move) -> Ordering {
+  let key = |move_: &Move| {
+    let normalized = move_.normalized();
+    (
+      normalized.dimension,
+      normalized.coordinate,
+      !normalized.in_positive_direction,
+      normalized.amount,
+    )
+  };
+  key(move_).cmp(&key(other))
+}
+
+fn amount_sum(moves: &[Move]) -> Num {
+  moves.iter().map(|m| m.normalized().amount).sum()
+}
+
+///Checks whether the given moves solve the arena within the turn limit of the [`SolveParams`]
+pub fn validate(arena: &SolvableArena, moves: &[Move], params: &SolveParams) -> ValidationReport {
+  let mut errors = vec![];
+  if let Some(in_turns) = params.in_turns {
+    if moves.len() > in_turns as usize {
+      errors.push(ValidationError::TurnBudgetExceeded {
+        move_index: in_turns as usize,
+        in_turns,
+      });
+    }
+  }
+
+  let mut current = arena.after_first_strike();
+  let mut solved_after = current.is_solved().then_some(0);
+  for (index, move_) in moves.iter().enumerate() {
+    current.apply_move(*move_);
+    if solved_after.is_none() && current.is_solved() {
+      solved_after = Some(index + 1);
+    }
+  }
+
+  if !current.is_solved() {
+    let mut fully_equipped = current.clone();
+    fully_equipped.available_equipment = AvailableEquipment {
+      throwing_hammer: true,
+      iron_boots: true,
+      wide_attacks_anywhere: true,
+    };
+    errors.push(if fully_equipped.is_solved() {
+      ValidationError::MissingEquipment
+    } else {
+      ValidationError::Unsolved
+    });
+  }
+
+  ValidationReport {
+    num_moves: moves.len(),
+    solved_after,
+    result: current,
+    errors,
+  }
+}
+
+///The outcome of [`validate`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ValidationReport {
+  pub num_moves: usize,
+  ///the number of moves after which the arena was solved first, if at all
+  pub solved_after: Option<usize>,
+  ///the arena after all moves have been executed
+  pub result: SolvableArena,
+  pub errors: Vec<ValidationError>,
+}
+
+impl ValidationReport {
+  pub fn is_valid(&self) -> bool {
+    self.errors.is_empty()
+  }
+}
+
+impl Display for ValidationReport {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    if self.is_valid() {
+      write!(f, "the moves solve the arena")?;
+      if let Some(solved_after) = self.solved_after.filter(|&n| n < self.num_moves) {
+        write!(
+          f,
+          ", but it is already solved after {} of {} moves",
+          solved_after, self.num_moves
+        )?;
+      }
+      return Ok(());
+    }
+
+    write!(
+      f,
+      "the moves are invalid: {}",
+      self
+        .errors
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+    )?;
+    if let Some(solved_after) = self.solved_after {
+      write!(f, " (the arena was solved after {} moves)", solved_after)?;
+    }
+    Ok(())
+  }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ValidationError {
+  ///there are more moves than turns available, starting with the move at `move_index`
+  TurnBudgetExceeded { move_index: usize, in_turns: Num },
+  ///the arena would be solved, but not with the available equipment
+  MissingEquipment,
+  ///the arena is not solved after all moves
+  Unsolved,
+}
+
+impl Display for ValidationError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      ValidationError::TurnBudgetExceeded {
+        move_index,
+        in_turns,
+      } => write!(
+        f,
+        "move {} exceeds the limit of {} turns",
+        move_index + 1,
+        in_turns
+      ),
+      ValidationError::MissingEquipment => {
+        write!(f, "the arena can't be solved with the available equipment")
+      }
+      ValidationError::Unsolved => write!(f, "the arena is not solved"),
+    }
+  }
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct Enemy {
   pub position: Position,
   pub required_attack: Option<RequiredAttack>,
+  ///the number of attack rounds needed to defeat the enemy, `None` if it is defeated by the first one
+  pub hp: Option<Num>,
+}
+
+impl Enemy {
+  ///Returns the enemy surviving an attack round, if any
+  pub fn attacked(mut self) -> Option<Self> {
+    match self.hp {
+      Some(hp) if hp > 1 => {
+        self.hp = Some(hp - 1);
+        Some(self)
+      }
+      _ => None,
+    }
+  }
 }
 
 impl Deref for Enemy {
@@ -485,12 +1056,9 @@ pub enum RequiredAttack {
 }
 
 impl RequiredAttack {
+  ///The attacks defeating enemies with this weakness, see [`attack_rules`]
   pub fn attacks(&self) -> Vec<Attack> {
-    match self {
-      RequiredAttack::IronBootsOrHammer => vec![Attack::IronBoots, Attack::Hammer],
-      RequiredAttack::Jump => vec![Attack::Jump, Attack::IronBoots],
-      RequiredAttack::Hammer => vec![Attack::Hammer],
-    }
+    attack_rules().attacks(*self).to_vec()
   }
 }
 
@@ -506,13 +1074,30 @@ impl ToAttackVec for &RequiredAttack {
   }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Attack {
   Jump,
   Hammer,
   IronBoots,
 }
 
+impl Attack {
+  pub const ALL: [Attack; 3] = [Attack::Jump, Attack::Hammer, Attack::IronBoots];
+
+  ///The name used by commands and the attack rules, like `iron-boots`
+  pub fn name(&self) -> &'static str {
+    match self {
+      Attack::Jump => "jump",
+      Attack::Hammer => "hammer",
+      Attack::IronBoots => "iron-boots",
+    }
+  }
+
+  pub fn by_name(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|attack| attack.name() == name)
+  }
+}
+
 impl ToAttackVec for Attack {
   fn to_attack_vec(self) -> Vec<Attack> {
     vec![self]
@@ -564,14 +1149,15 @@ impl ToArenaSymbol for Enemy {
 
 #[cfg(test)]
 mod test_coverage {
-  use crate::parse;
+  use crate::command::parse_quietly;
+  use crate::solving::item::Item;
   use crate::solving::{AvailableEquipment, Coverage, SolvableArena};
 
   #[test]
   fn test_solved() {
     let mut arena = SolvableArena::default();
     for cmd in ["c2 1234", "c4 12", "c5 12"] {
-      parse(&mut arena, cmd).expect("parse error");
+      parse_quietly(&mut arena, cmd).expect("parse error");
     }
 
     assert!(Coverage::find(&arena).is_some())
@@ -581,7 +1167,7 @@ mod test_coverage {
   fn test_unsolved() {
     let mut arena = SolvableArena::default();
     for cmd in ["c2 124", "c3 3", "c4 12", "c5 12"] {
-      parse(&mut arena, cmd).expect("parse error");
+      parse_quietly(&mut arena, cmd).expect("parse error");
     }
 
     assert!(Coverage::find(&arena).is_none())
@@ -591,7 +1177,7 @@ mod test_coverage {
   fn test_too_few_enemies() {
     let mut arena = SolvableArena::default();
     for cmd in ["c2 124", "c4 12", "c5 1"] {
-      parse(&mut arena, cmd).expect("parse error");
+      parse_quietly(&mut arena, cmd).expect("parse error");
     }
 
     assert!(Coverage::find(&arena).is_some())
@@ -600,7 +1186,7 @@ mod test_coverage {
   #[test]
   fn test_ninja_covered() {
     let mut arena = SolvableArena::default();
-    parse(&mut arena, "c2 1234 J").expect("parse error");
+    parse_quietly(&mut arena, "c2 1234 J").expect("parse error");
 
     assert!(Coverage::find(&arena).is_some());
   }
@@ -609,7 +1195,7 @@ mod test_coverage {
   fn test_ninja_uncovered() {
     let mut arena = SolvableArena::default();
     for cmd in ["c2 12 J", "c3 12 J"] {
-      parse(&mut arena, cmd).expect("parse error");
+      parse_quietly(&mut arena, cmd).expect("parse error");
     }
 
     assert!(Coverage::find(&arena).is_none());
@@ -625,7 +1211,7 @@ mod test_coverage {
       ..Default::default()
     };
     for cmd in ["c4 1 H", "c4 23"] {
-      parse(&mut arena, cmd).expect("parse error");
+      parse_quietly(&mut arena, cmd).expect("parse error");
     }
 
     Coverage::find(&arena)
@@ -633,13 +1219,38 @@ mod test_coverage {
       .expect_err("no coverage should exists");
   }
 
+  #[test]
+  fn test_item_covers_column() {
+    let mut arena = SolvableArena {
+      available_equipment: AvailableEquipment {
+        throwing_hammer: false,
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    for cmd in ["c4 1 H", "c4 23"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    arena.items.set(Item::IceFlower, 1);
+
+    let coverage = Coverage::find(&arena).expect("coverage should exist");
+    assert_eq!(
+      vec![Some(Item::IceFlower)],
+      coverage
+        .areas()
+        .iter()
+        .map(|area| area.item)
+        .collect::<Vec<_>>()
+    );
+  }
+
   #[test]
   fn test_iron_boots_required() {
     let mut arena = SolvableArena::default();
     for cmd in [
       "c2 12", "c3 12", "c5 12", "c5 3 P", "c5 4 J", "c8 12", "c9 12",
     ] {
-      parse(&mut arena, cmd).unwrap();
+      parse_quietly(&mut arena, cmd).unwrap();
     }
 
     Coverage::find(&arena).expect("coverage should exist");
@@ -648,18 +1259,22 @@ mod test_coverage {
 
 #[cfg(test)]
 mod test_solve {
-  use crate::parse;
+  use crate::command::parse_quietly;
   use crate::position::Move;
-  use crate::solving::{SolvableArena, solve};
+  use crate::solving::move_filter::MoveFilter;
+  use crate::solving::solve_cache::SolveCache;
+  use crate::solving::{
+    SolvableArena, SolveParams, Strategy, compare_moves, reachable_solved_states, solve, solve_with,
+  };
 
   #[test]
   fn test_simple_solve() {
     let mut arena = SolvableArena::default();
     for cmd in ["c2 124", "c3 3"] {
-      parse(&mut arena, cmd).expect("parse error");
+      parse_quietly(&mut arena, cmd).expect("parse error");
     }
 
-    let solution = solve(&arena, 1, false, None).expect("is solvable");
+    let solution = solve(&arena, 1, false, None, None).expect("is solvable");
     assert_eq!("r3 -1", steps(&solution));
   }
 
@@ -667,13 +1282,110 @@ mod test_solve {
   fn test_two_steps() {
     let mut arena = SolvableArena::default();
     for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
-      parse(&mut arena, cmd).expect("parse error");
+      parse_quietly(&mut arena, cmd).expect("parse error");
     }
 
-    let solution = solve(&arena, 2, false, None).expect("is solvable");
+    let solution = solve(&arena, 2, false, None, None).expect("is solvable");
     assert_eq!("r3 -1, c4 -1", steps(&solution));
   }
 
+  #[test]
+  fn test_deterministic_strategies_agree() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let solutions: Vec<_> = Strategy::all()
+      .map(|strategy| {
+        let params = SolveParams::new()
+          .in_turns(2)
+          .fast(true)
+          .strategy(strategy)
+          .deterministic(true);
+        solve_with(&arena, &params, &mut SolveCache::new(), None, |_| true).expect("is solvable")
+      })
+      .collect();
+    assert!(solutions.windows(2).all(|pair| pair[0] == pair[1]));
+  }
+
+  #[test]
+  fn test_move_filter() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    let filter = MoveFilter::new().forbid_ring(2);
+    let params = SolveParams::new().move_filter(filter);
+
+    let solution =
+      solve_with(&arena, &params, &mut SolveCache::new(), None, |_| true).expect("is solvable");
+    assert_ne!("r3 -1", steps(&solution));
+    assert!(solution.iter().all(|&move_| filter.allows(move_)));
+  }
+
+  #[test]
+  fn test_locked_column() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 1234", "c4 123", "c10 1"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    arena.locked_columns.insert(1);
+
+    let solution = solve(&arena, 2, false, None, None).expect("is solvable");
+    assert!(solution.iter().all(|&move_| arena.allows_move(move_)));
+    let mut solved = arena.clone();
+    solution.iter().for_each(|&move_| solved.apply_move(move_));
+    assert!(solved.is_solved());
+
+    //the locked column has to be attacked as a whole
+    arena.num_groups = Some(1);
+    arena.locked_columns.insert(3);
+    assert!(!arena.is_solved());
+  }
+
+  #[test]
+  fn test_tie_breaking_order() {
+    let moves: Vec<Move> = ["r1 1", "r1 2", "r1 -1", "r2 1", "c1 1"]
+      .into_iter()
+      .map(|m| m.parse().unwrap())
+      .collect();
+    for pair in moves.windows(2) {
+      assert!(compare_moves(&pair[0], &pair[1]).is_lt());
+    }
+    //equivalent moves are equal
+    assert!(compare_moves(&"r1 11".parse().unwrap(), &moves[2]).is_eq());
+  }
+
+  #[test]
+  fn test_reachable_solved_states() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    assert!(reachable_solved_states(&arena, 0).is_empty());
+
+    let states = reachable_solved_states(&arena, 2);
+    assert_eq!("r3 -1", steps(&states[0].moves));
+    assert!(states.len() > 1);
+    for (index, state) in states.iter().enumerate() {
+      assert!(state.arena.is_solved());
+      assert!(state.moves.len() <= 2);
+      let mut reached = arena.clone();
+      for &move_ in &state.moves {
+        reached.apply_move(move_);
+      }
+      assert_eq!(state.arena, reached.canonical());
+      assert!(states[..index]
+        .iter()
+        .all(|other| other.arena != state.arena));
+    }
+
+    let solved = reachable_solved_states(&states[0].arena, 0);
+    assert_eq!(1, solved.len());
+    assert!(solved[0].moves.is_empty());
+  }
+
   fn steps<M>(moves: M) -> String
   where
     M: AsRef<[Move]>,
@@ -690,10 +1402,10 @@ mod test_solve {
   fn test_example_1() {
     let mut arena = SolvableArena::default();
     for cmd in ["c2 23", "c6 1234", "c8 14"] {
-      parse(&mut arena, cmd).unwrap();
+      parse_quietly(&mut arena, cmd).unwrap();
     }
 
-    solve(&arena, 3, true, None).expect("is solvable in 3");
+    solve(&arena, 3, true, None, None).expect("is solvable in 3");
   }
 
   #[test]
@@ -702,9 +1414,79 @@ mod test_solve {
     for cmd in [
       "c2 12", "c3 4", "c5 12", "c8 12", "c9 123", "c11 3", "c11 4",
     ] {
-      parse(&mut arena, cmd).unwrap();
+      parse_quietly(&mut arena, cmd).unwrap();
+    }
+
+    solve(&arena, 3, true, None, None).expect("is solvable in 3");
+  }
+}
+
+#[cfg(test)]
+mod test_validate {
+  use crate::command::parse_quietly;
+  use crate::position::Move;
+  use crate::solving::{SolvableArena, SolveParams, ValidationError, validate};
+
+  fn moves(moves: &[&str]) -> Vec<Move> {
+    moves
+      .iter()
+      .map(|m| m.parse().expect("invalid move"))
+      .collect()
+  }
+
+  #[test]
+  fn test_valid() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let report = validate(&arena, &moves(&["r3 -1", "c4 -1"]), &SolveParams::new());
+    assert!(report.is_valid());
+    assert_eq!(Some(2), report.solved_after);
+  }
+
+  #[test]
+  fn test_turn_budget() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let report = validate(
+      &arena,
+      &moves(&["r3 -1", "c4 -1"]),
+      &SolveParams::new().in_turns(1),
+    );
+    assert_eq!(
+      vec![ValidationError::TurnBudgetExceeded {
+        move_index: 1,
+        in_turns: 1
+      }],
+      report.errors
+    );
+  }
+
+  #[test]
+  fn test_unsolved() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let report = validate(&arena, &moves(&["r3 1"]), &SolveParams::new());
+    assert_eq!(vec![ValidationError::Unsolved], report.errors);
+    assert_eq!(None, report.solved_after);
+  }
+
+  #[test]
+  fn test_missing_equipment() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["-hammer", "c4 1 H", "c4 23"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
     }
 
-    solve(&arena, 3, true, None).expect("is solvable in 3");
+    let report = validate(&arena, &[], &SolveParams::new());
+    assert_eq!(vec![ValidationError::MissingEquipment], report.errors);
   }
 }
@@ -0,0 +1,113 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::position::Num;
+use crate::solving::attack_rules::attack_rules;
+use crate::solving::{AvailableEquipment, Enemy, TargetArea};
+
+///The shape of an area one group of enemies can be attacked in
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AreaShape {
+  ///a whole column, attacked with a jump or a thrown hammer
+  Long,
+  ///two rings of two neighbouring columns, attacked with a hammer
+  Wide,
+}
+
+///An area shape together with where it can be placed and the equipment it needs. The coverage
+/// algorithm only knows about templates, so new items only need a new entry in [`AREA_TEMPLATES`].
+#[derive(Debug, Copy, Clone)]
+pub struct AreaTemplate {
+  pub shape: AreaShape,
+  ///the innermost ring the area may start at, ignored for long areas which cover all rings
+  pub inner_rings: &'static [Num],
+  pub is_available: fn(&AvailableEquipment) -> bool,
+}
+
+impl AreaTemplate {
+  ///All areas of this template which cover the given enemy
+  pub fn areas_covering(&self, enemy: &Enemy) -> Vec<TargetArea> {
+    match self.shape {
+      AreaShape::Long => vec![TargetArea::long(enemy)],
+      AreaShape::Wide => self
+        .inner_rings
+        .iter()
+        .filter(|&&inner_ring| (inner_ring..inner_ring + 2).contains(&enemy.row))
+        .flat_map(|&inner_ring| {
+          [true, false].map(|left_bound| TargetArea::wide(enemy, left_bound, inner_ring))
+        })
+        .collect(),
+    }
+  }
+}
+
+///Every area shape the solver knows about
+pub const AREA_TEMPLATES: &[AreaTemplate] = &[
+  AreaTemplate {
+    shape: AreaShape::Long,
+    inner_rings: &[],
+    is_available: |_| true,
+  },
+  AreaTemplate {
+    shape: AreaShape::Wide,
+    inner_rings: &[0],
+    is_available: |_| true,
+  },
+  //some attacks hit 2x2 areas on the outer rings as well
+  AreaTemplate {
+    shape: AreaShape::Wide,
+    inner_rings: &[1, 2],
+    is_available: |equipment| equipment.wide_attacks_anywhere,
+  },
+];
+
+///The templates which can be used with the given equipment
+pub fn available_templates(
+  equipment: AvailableEquipment,
+) -> impl Iterator<Item = &'static AreaTemplate> {
+  AREA_TEMPLATES
+    .iter()
+    .filter(move |template| (template.is_available)(&equipment))
+}
+
+///Whether an enemy can be attacked within the given area with the given equipment, see
+/// [`attack_rules`]
+pub fn can_attack(area: &TargetArea, enemy: &Enemy, equipment: &AvailableEquipment) -> bool {
+  attack_rules().allows(area, enemy, equipment)
+}
+
+#[cfg(test)]
+mod test_area_template {
+  use crate::command::parse_quietly;
+  use crate::solving::{AvailableEquipment, Coverage, SolvableArena};
+
+  #[test]
+  fn test_wide_anywhere() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 34", "c3 34", "c6 1234"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    arena.num_groups = Some(2);
+    assert!(Coverage::find(&arena).is_none());
+
+    arena.available_equipment = AvailableEquipment {
+      wide_attacks_anywhere: true,
+      ..Default::default()
+    };
+    let coverage = Coverage::find(&arena).expect("wide area should cover the outer rings");
+    assert_eq!(2, coverage.len());
+  }
+
+  #[test]
+  fn test_wide_anywhere_middle_rings() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 23", "c3 23"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    arena.num_groups = Some(1);
+    assert!(Coverage::find(&arena).is_none());
+
+    arena.available_equipment.wide_attacks_anywhere = true;
+    assert!(Coverage::find(&arena).is_some());
+  }
+}
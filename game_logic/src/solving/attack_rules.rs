@@ -0,0 +1,440 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::path::Path;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+#[cfg(feature = "std")]
+use std::{env, fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::solving::area_template::AreaShape;
+use crate::solving::{Attack, AvailableEquipment, Enemy, RequiredAttack, RingPosition, TargetArea};
+
+///The environment variable pointing to a file with the rules [`attack_rules`] returns, in the format
+/// of [`DEFAULT_ATTACK_RULES`]
+pub const ATTACK_RULES_VAR: &str = "TOK_ATTACK_RULES";
+///The rules of the game as JSON object:
+///
+///- `attacks` lists the attacks defeating enemies with a weakness, like
+///  `{ "weakness": "J", "attacks": ["jump", "iron-boots"] }`. Weaknesses are `H`, `J` and `P`, the
+///  attacks are `jump`, `hammer` and `iron-boots`.
+///- `rules` only allows attacking enemies with a weakness in `long` or `wide` areas if one of the
+///  equipment flags it `needs` is set, like `{ "weakness": "H", "shape": "long", "needs": ["hammer"] }`
+///  since the hammer only reaches long areas when thrown. The optional `rings` limit the rule to
+///  enemies on the `inner` or `outer` two rings, a rule which needs nothing never allows the attack.
+///  The first rule matching an enemy and an area decides, anything without a rule is allowed.
+pub const DEFAULT_ATTACK_RULES: &str = include_str!("../../data/attack_rules.json");
+
+const WEAKNESSES: [RequiredAttack; 3] = [
+  RequiredAttack::Hammer,
+  RequiredAttack::Jump,
+  RequiredAttack::IronBootsOrHammer,
+];
+const SHAPES: [(AreaShape, &str); 2] = [(AreaShape::Long, "long"), (AreaShape::Wide, "wide")];
+const RINGS: [(RingPosition, &str); 2] = [
+  (RingPosition::Inner, "inner"),
+  (RingPosition::Outer, "outer"),
+];
+
+///Restricts in which areas enemies with a weakness can be attacked
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct AttackRule {
+  pub weakness: RequiredAttack,
+  pub shape: AreaShape,
+  ///the rings the rule applies to, None for all rings
+  pub rings: Option<RingPosition>,
+  ///the attack is allowed if any of these flags is set in the available equipment, never if there
+  /// are none
+  pub needs_any_of: AvailableEquipment,
+}
+
+impl AttackRule {
+  fn applies_to(&self, area: &TargetArea, enemy: &Enemy) -> bool {
+    enemy.required_attack == Some(self.weakness)
+      && area.shape() == self.shape
+      && self
+        .rings
+        .is_none_or(|rings| rings == RingPosition::from(&enemy.position))
+  }
+
+  fn allows(&self, equipment: &AvailableEquipment) -> bool {
+    let needed = self.needs_any_of;
+    (needed.throwing_hammer && equipment.throwing_hammer)
+      || (needed.iron_boots && equipment.iron_boots)
+      || (needed.wide_attacks_anywhere && equipment.wide_attacks_anywhere)
+  }
+}
+
+///Which attacks defeat enemies with a weakness and in which areas they can be attacked, read from
+/// a file like [`DEFAULT_ATTACK_RULES`], so mechanics can be changed without touching the solver
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct AttackRules {
+  attacks: Vec<(RequiredAttack, Vec<Attack>)>,
+  rules: Vec<AttackRule>,
+}
+
+impl AttackRules {
+  ///The attacks which defeat enemies with the weakness
+  pub fn attacks(&self, weakness: RequiredAttack) -> &[Attack] {
+    self
+      .attacks
+      .iter()
+      .find(|(listed, _)| *listed == weakness)
+      .map_or(&[], |(_, attacks)| attacks)
+  }
+
+  pub fn rules(&self) -> &[AttackRule] {
+    &self.rules
+  }
+
+  ///Whether the enemy can be attacked within the area with the equipment, decided by the first rule
+  /// which applies
+  pub fn allows(&self, area: &TargetArea, enemy: &Enemy, equipment: &AvailableEquipment) -> bool {
+    self
+      .rules
+      .iter()
+      .find(|rule| rule.applies_to(area, enemy))
+      .is_none_or(|rule| rule.allows(equipment))
+  }
+
+  #[cfg(feature = "std")]
+  pub fn load<P>(path: P) -> Result<Self, AttackRulesError>
+  where
+    P: AsRef<Path>,
+  {
+    Self::try_from(fs::read_to_string(path)?.as_str())
+  }
+
+  ///The rules with names instead of the types of the solver, as they are written to the file
+  fn to_file(&self) -> RulesFile {
+    RulesFile {
+      attacks: self
+        .attacks
+        .iter()
+        .map(|(weakness, attacks)| AttacksEntry {
+          weakness: weakness.symbol().to_string(),
+          attacks: attacks
+            .iter()
+            .map(|attack| attack.name().to_string())
+            .collect(),
+        })
+        .collect(),
+      rules: self
+        .rules
+        .iter()
+        .map(|rule| {
+          let mut needed = rule.needs_any_of;
+          RuleEntry {
+            weakness: rule.weakness.symbol().to_string(),
+            shape: shape_name(rule.shape).to_string(),
+            rings: rule.rings.map(|rings| ring_name(rings).to_string()),
+            needs: AvailableEquipment::FLAGS
+              .into_iter()
+              .filter(|flag| *needed.flag_mut(flag).expect("flags are known"))
+              .map(ToString::to_string)
+              .collect(),
+          }
+        })
+        .collect(),
+    }
+  }
+
+  fn from_file(file: RulesFile) -> Result<Self, AttackRulesError> {
+    let attacks = file
+      .attacks
+      .into_iter()
+      .map(|entry| {
+        let attacks = entry
+          .attacks
+          .iter()
+          .map(|name| Attack::by_name(name).ok_or_else(|| AttackRulesError::unknown(name)))
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok((parse_weakness(&entry.weakness)?, attacks))
+      })
+      .collect::<Result<Vec<_>, AttackRulesError>>()?;
+    let rules = file
+      .rules
+      .into_iter()
+      .map(|entry| {
+        let mut needs_any_of = AvailableEquipment {
+          throwing_hammer: false,
+          iron_boots: false,
+          wide_attacks_anywhere: false,
+        };
+        for flag in &entry.needs {
+          *needs_any_of
+            .flag_mut(flag)
+            .ok_or_else(|| AttackRulesError::unknown(flag))? = true;
+        }
+        let rings = match entry.rings.as_deref() {
+          None => None,
+          Some(name) => Some(
+            RINGS
+              .iter()
+              .find(|(_, ring_name)| *ring_name == name)
+              .ok_or_else(|| AttackRulesError::unknown(name))?
+              .0,
+          ),
+        };
+        Ok(AttackRule {
+          weakness: parse_weakness(&entry.weakness)?,
+          shape: SHAPES
+            .iter()
+            .find(|(_, name)| *name == entry.shape)
+            .ok_or_else(|| AttackRulesError::unknown(&entry.shape))?
+            .0,
+          rings,
+          needs_any_of,
+        })
+      })
+      .collect::<Result<Vec<_>, AttackRulesError>>()?;
+    Ok(Self { attacks, rules })
+  }
+}
+
+///Parses rules in the format of [`DEFAULT_ATTACK_RULES`]
+impl TryFrom<&str> for AttackRules {
+  type Error = AttackRulesError;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    Self::from_file(serde_json::from_str(value)?)
+  }
+}
+
+impl Default for AttackRules {
+  fn default() -> Self {
+    Self::try_from(DEFAULT_ATTACK_RULES).expect("default attack rules are valid")
+  }
+}
+
+///Writes the rules in the format they are parsed from
+impl Display for AttackRules {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    let json = serde_json::to_string_pretty(&self.to_file()).map_err(|_| core::fmt::Error)?;
+    writeln!(f, "{}", json)
+  }
+}
+
+///The file format of [`AttackRules`]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+  attacks: Vec<AttacksEntry>,
+  #[serde(default)]
+  rules: Vec<RuleEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AttacksEntry {
+  weakness: String,
+  attacks: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleEntry {
+  weakness: String,
+  shape: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  rings: Option<String>,
+  needs: Vec<String>,
+}
+
+///The rules the solver uses. They are read on first use from the file [`ATTACK_RULES_VAR`] points
+/// to if it is set, and from [`DEFAULT_ATTACK_RULES`] otherwise. If the file can't be used, the
+/// default rules are used and [`attack_rules_error`] tells why.
+pub fn attack_rules() -> &'static AttackRules {
+  &loaded_rules().0
+}
+
+///Why the rules of the file [`ATTACK_RULES_VAR`] points to aren't used, if they can't be read, so
+/// front-ends can tell the user
+pub fn attack_rules_error() -> Option<&'static AttackRulesError> {
+  loaded_rules().1.as_ref()
+}
+
+//the coverage search asks for the rules for every enemy it places, which only costs an atomic load
+// once they are loaded
+#[cfg(feature = "std")]
+fn loaded_rules() -> &'static (AttackRules, Option<AttackRulesError>) {
+  static RULES: OnceLock<(AttackRules, Option<AttackRulesError>)> = OnceLock::new();
+  RULES.get_or_init(load_rules)
+}
+
+#[cfg(not(feature = "std"))]
+fn loaded_rules() -> &'static (AttackRules, Option<AttackRulesError>) {
+  static RULES: spin::Once<(AttackRules, Option<AttackRulesError>)> = spin::Once::new();
+  RULES.call_once(load_rules)
+}
+
+#[cfg(feature = "std")]
+fn load_rules() -> (AttackRules, Option<AttackRulesError>) {
+  let Some(path) = env::var_os(ATTACK_RULES_VAR).filter(|path| !path.is_empty()) else {
+    return (AttackRules::default(), None);
+  };
+  match AttackRules::load(&path) {
+    Ok(rules) => (rules, None),
+    Err(e) => (AttackRules::default(), Some(e)),
+  }
+}
+
+#[cfg(not(feature = "std"))]
+fn load_rules() -> (AttackRules, Option<AttackRulesError>) {
+  (AttackRules::default(), None)
+}
+
+fn shape_name(shape: AreaShape) -> &'static str {
+  SHAPES
+    .iter()
+    .find(|(known, _)| *known == shape)
+    .map(|(_, name)| *name)
+    .expect("all shapes have names")
+}
+
+fn ring_name(rings: RingPosition) -> &'static str {
+  RINGS
+    .iter()
+    .find(|(known, _)| *known == rings)
+    .map(|(_, name)| *name)
+    .expect("all ring positions have names")
+}
+
+fn parse_weakness(symbol: &str) -> Result<RequiredAttack, AttackRulesError> {
+  WEAKNESSES
+    .into_iter()
+    .find(|weakness| symbol.len() == 1 && symbol.starts_with(weakness.symbol()))
+    .ok_or_else(|| AttackRulesError::unknown(symbol))
+}
+
+#[derive(Debug)]
+pub enum AttackRulesError {
+  #[cfg(feature = "std")]
+  IO(io::Error),
+  ///the file isn't JSON in the format of [`DEFAULT_ATTACK_RULES`]
+  Format(serde_json::Error),
+  ///a weakness, attack, area shape, ring position or equipment flag which doesn't exist
+  UnknownName(String),
+}
+
+impl AttackRulesError {
+  fn unknown(name: &str) -> Self {
+    Self::UnknownName(name.to_string())
+  }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for AttackRulesError {
+  fn from(value: io::Error) -> Self {
+    Self::IO(value)
+  }
+}
+
+impl From<serde_json::Error> for AttackRulesError {
+  fn from(value: serde_json::Error) -> Self {
+    Self::Format(value)
+  }
+}
+
+impl Display for AttackRulesError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      #[cfg(feature = "std")]
+      AttackRulesError::IO(e) => write!(f, "IO error: {}", e),
+      AttackRulesError::Format(e) => write!(f, "Invalid attack rules: {}", e),
+      AttackRulesError::UnknownName(name) => write!(f, "Unknown name in attack rules: '{}'", name),
+    }
+  }
+}
+
+impl Error for AttackRulesError {}
+
+#[cfg(test)]
+mod test_attack_rules {
+  use crate::position::Position;
+  use crate::solving::attack_rules::{AttackRules, AttackRulesError};
+  use crate::solving::{Attack, AvailableEquipment, Enemy, RequiredAttack, TargetArea};
+
+  #[test]
+  fn test_default_rules() {
+    let rules = AttackRules::default();
+    assert_eq!(
+      &[Attack::Jump, Attack::IronBoots],
+      rules.attacks(RequiredAttack::Jump)
+    );
+    assert_eq!(
+      rules,
+      AttackRules::try_from(rules.to_string().as_str()).unwrap()
+    );
+
+    let hammer_enemy = Enemy {
+      position: Position::at(3, 0).unwrap(),
+      required_attack: Some(RequiredAttack::Hammer),
+      hp: None,
+    };
+    let long = TargetArea::long(&hammer_enemy);
+    let no_hammer = AvailableEquipment {
+      throwing_hammer: false,
+      ..Default::default()
+    };
+    assert!(rules.allows(&long, &hammer_enemy, &AvailableEquipment::default()));
+    assert!(!rules.allows(&long, &hammer_enemy, &no_hammer));
+  }
+
+  #[test]
+  fn test_custom_rules() {
+    //house rule: jumping on hammer enemies works as well
+    let rules = AttackRules::try_from(
+      r#"{
+        "attacks": [{ "weakness": "H", "attacks": ["hammer", "jump"] }],
+        "rules": [{ "weakness": "J", "shape": "long", "rings": "outer", "needs": ["iron-boots"] }]
+      }"#,
+    )
+    .unwrap();
+    assert_eq!(
+      &[Attack::Hammer, Attack::Jump],
+      rules.attacks(RequiredAttack::Hammer)
+    );
+    assert!(rules.attacks(RequiredAttack::IronBootsOrHammer).is_empty());
+
+    let jump_enemy = Enemy {
+      position: Position::at(3, 0).unwrap(),
+      required_attack: Some(RequiredAttack::Jump),
+      hp: None,
+    };
+    let no_boots = AvailableEquipment {
+      iron_boots: false,
+      ..Default::default()
+    };
+    assert!(!rules.allows(&TargetArea::long(&jump_enemy), &jump_enemy, &no_boots));
+    //wide areas have no rule anymore
+    assert!(rules.allows(
+      &TargetArea::wide(&jump_enemy, true, 2),
+      &jump_enemy,
+      &no_boots
+    ));
+
+    for invalid in [
+      r#"{ "attacks": [{ "weakness": "X", "attacks": ["hammer"] }] }"#,
+      r#"{ "attacks": [{ "weakness": "H", "attacks": ["kick"] }] }"#,
+      r#"{ "attacks": [], "rules": [{ "weakness": "J", "shape": "long" }] }"#,
+      r#"{ "attacks": [], "rules": [{ "weakness": "H", "shape": "round", "needs": [] }] }"#,
+      r#"{ "attacks": [], "rules": [{ "weakness": "H", "shape": "long", "needs": ["cape"] }] }"#,
+      "attacks H hammer",
+    ] {
+      assert!(
+        AttackRules::try_from(invalid).is_err(),
+        "{} should be invalid",
+        invalid
+      );
+    }
+    assert!(matches!(
+      AttackRules::load("missing_attack_rules.json"),
+      Err(AttackRulesError::IO(_))
+    ));
+  }
+}
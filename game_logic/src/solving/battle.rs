@@ -0,0 +1,121 @@
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::position::Move;
+use crate::solving::{Enemy, SolvableArena, SolveParams, solve_with};
+use crate::solving::solve_cache::SolveCache;
+
+///The solutions for all rounds of a fight against enemies which survive some attacks
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BattlePlan {
+  pub rounds: Vec<Round>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Round {
+  ///the arena at the start of the round
+  pub arena: SolvableArena,
+  pub solution: Vec<Move>,
+}
+
+impl Display for BattlePlan {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    for (index, round) in self.rounds.iter().enumerate() {
+      if index > 0 {
+        writeln!(f)?;
+      }
+      write!(
+        f,
+        "round {} ({} enemies): {}",
+        index + 1,
+        round.arena.enemies.len(),
+        if round.solution.is_empty() {
+          "already solved".to_string()
+        } else {
+          round
+            .solution
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+        }
+      )?;
+    }
+    Ok(())
+  }
+}
+
+///Plans a fight over multiple rounds: every round gets solved according to the [`SolveParams`], then
+/// all enemies get attacked and the survivors stay in place for the next round. Returns `None` if a
+/// round can't be solved.
+pub fn plan_battle(
+  arena: &SolvableArena,
+  params: &SolveParams,
+  cache: &mut SolveCache,
+) -> Option<BattlePlan> {
+  let mut rounds = vec![];
+  let mut current = arena.after_first_strike();
+  while !current.enemies.is_empty() {
+    let solution = solve_with(&current, params, cache, None, |_| true)?;
+    let mut next = current.clone();
+    for move_ in &solution {
+      next.apply_move(*move_);
+    }
+    next.enemies = next
+      .enemies
+      .iter()
+      .copied()
+      .filter_map(Enemy::attacked)
+      .collect();
+    //survivors are counted anew in the next round
+    next.num_groups = None;
+    //locked columns have been attacked and don't need to be kept
+    next.locked_columns.clear();
+
+    rounds.push(Round {
+      arena: current,
+      solution,
+    });
+    current = next;
+  }
+  Some(BattlePlan { rounds })
+}
+
+#[cfg(test)]
+mod test_battle {
+  use crate::command::parse_quietly;
+  use crate::solving::battle::plan_battle;
+  use crate::solving::solve_cache::SolveCache;
+  use crate::solving::{SolvableArena, SolveParams};
+
+  #[test]
+  fn test_plan_battle() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "hp c2 1 2", "hp c3 3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let plan =
+      plan_battle(&arena, &SolveParams::new(), &mut SolveCache::new()).expect("should be solvable");
+    let num_enemies: Vec<usize> = plan
+      .rounds
+      .iter()
+      .map(|round| round.arena.enemies.len())
+      .collect();
+    assert_eq!(vec![4, 2, 1], num_enemies);
+    assert_eq!(1, plan.rounds[0].solution.len());
+  }
+
+  #[test]
+  fn test_no_enemies() {
+    let plan = plan_battle(
+      &SolvableArena::default(),
+      &SolveParams::new(),
+      &mut SolveCache::new(),
+    )
+    .expect("nothing to solve");
+    assert!(plan.rounds.is_empty());
+  }
+}
@@ -0,0 +1,223 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+use crate::collections::Map;
+use crate::position::{Move, Num};
+use crate::solving::cancel::CancelToken;
+use crate::solving::move_filter::MoveFilter;
+use crate::solving::pattern_database::{cells_of, distances_from, normalize, solved_patterns};
+use crate::solving::solve_cache::SolveCache;
+use crate::solving::{Comparator, SolvableArena, allowed_moves, search};
+
+///Solves an arena by meeting in the middle. Forward, all distinct arenas reachable in ⌈n/2⌉ moves
+/// are enumerated. Backward, all enemy patterns which can be solved in ⌊n/2⌋ moves are found by
+/// moving away from the solved patterns, like the [pattern database](super::pattern_database::PatternDatabase) is generated.
+/// Both halves are joined on the [normalized](normalize) patterns, so only the few forward arenas
+/// meeting the backward half are searched any further. Since many move sequences lead to the same
+/// arena (`r1 2, r1 3` equals `r1 5`), this checks far fewer states than [`super::solve`] for arenas
+/// needing 4 or more turns. Returns an equally good solution as [`super::solve`].
+///
+///Patterns don't know about weaknesses, items or locked columns, which only makes them easier to
+/// solve. So the backward half only tells how many moves an arena needs at least, and the remaining
+/// moves of a meeting arena are searched with [`super::solve`].
+///
+///Only the best sequence of moves reaching an arena is kept, so comparators which don't just add up
+/// a value per move, like the direction switches, may miss the best solution where both halves meet.
+///Arenas with an objective don't have to be covered, so they are searched with [`super::solve`].
+pub fn solve_bidirectional(
+  arena: &SolvableArena,
+  in_turns: Num,
+  fast: bool,
+  filter: &MoveFilter,
+  comparator: Comparator,
+  cache: &mut SolveCache,
+  cancel: Option<&CancelToken>,
+) -> Option<Vec<Move>> {
+  let arena = arena.after_first_strike();
+  if arena.objective.is_some() {
+    return search(
+      &arena,
+      in_turns,
+      fast,
+      filter,
+      comparator,
+      cache,
+      cancel,
+      &mut (),
+    );
+  }
+
+  let forward_depth = in_turns.div_ceil(2);
+  let backward_depth = in_turns / 2;
+
+  let moves: Vec<Move> = allowed_moves(&arena, filter).collect();
+  let forward = explore(
+    arena.canonical(),
+    forward_depth,
+    &moves,
+    comparator,
+    |arena, move_| {
+      let mut next = arena.clone();
+      next.apply_move(move_);
+      next.canonical()
+    },
+  );
+  let num_enemies = arena.enemies.len() as u32;
+  let solved = solved_patterns(&arena, arena.num_groups() as usize, |count| {
+    count == num_enemies
+  });
+  let backward = distances_from(solved, backward_depth);
+
+  //check shorter combinations first, so the first total length with a solution is the optimal one
+  for total in 0..=in_turns {
+    let mut best_solution: Option<Vec<Move>> = None;
+    for forward_len in total.saturating_sub(backward_depth)..=total.min(forward_depth) {
+      let remaining = total - forward_len;
+      for (state, forward_moves) in &forward[forward_len as usize] {
+        if cancel.is_some_and(CancelToken::is_cancelled) {
+          return None;
+        }
+        //arenas not meeting the backward half need more than the remaining moves
+        let pattern = normalize(cells_of(state));
+        if backward
+          .get(&pattern)
+          .is_none_or(|&distance| distance > remaining)
+        {
+          continue;
+        }
+        //shorter solutions of this arena would have been found with a smaller total
+        let Some(backward_moves) = search(
+          state,
+          remaining,
+          fast,
+          filter,
+          comparator,
+          cache,
+          cancel,
+          &mut (),
+        ) else {
+          continue;
+        };
+
+        let solution: Vec<Move> = forward_moves
+          .iter()
+          .chain(backward_moves.iter())
+          .copied()
+          .collect();
+        if fast {
+          return Some(solution);
+        }
+        if best_solution
+          .as_ref()
+          .is_none_or(|current_best| comparator.is_better(&solution, current_best))
+        {
+          best_solution = Some(solution);
+        }
+      }
+    }
+
+    if best_solution.is_some() {
+      return best_solution;
+    }
+  }
+
+  None
+}
+
+///Breadth first search over distinct states using the given moves. Returns the states first reached
+/// with the given number of moves at the respective index, together with the best sequence of moves
+/// reaching them.
+pub(crate) fn explore<S, F>(
+  start: S,
+  depth: Num,
+  moves: &[Move],
+  comparator: Comparator,
+  apply: F,
+) -> Vec<Map<S, Vec<Move>>>
+where
+  S: Ord + Hash + Clone,
+  F: Fn(&S, Move) -> S,
+{
+  let mut levels = vec![Map::from([(start, vec![])])];
+  for _ in 0..depth {
+    let mut next_level: Map<S, Vec<Move>> = Map::new();
+    let previous_level = levels.last().expect("levels start with one element");
+    for (state, state_moves) in previous_level {
+      for &move_ in moves {
+        let next = apply(state, move_);
+        if levels.iter().any(|level| level.contains_key(&next)) {
+          continue;
+        }
+
+        let mut next_moves = state_moves.clone();
+        next_moves.push(move_);
+        match next_level.get(&next) {
+          Some(present) if !comparator.is_better(&next_moves, present) => {}
+          _ => {
+            next_level.insert(next, next_moves);
+          }
+        }
+      }
+    }
+    levels.push(next_level);
+  }
+  levels
+}
+
+#[cfg(test)]
+mod test_bidirectional {
+  use crate::command::parse_quietly;
+  use crate::position::{Move, Num};
+  use crate::solving::solve_cache::SolveCache;
+  use crate::solving::{Comparator, SolvableArena, solve};
+  use crate::solving::bidirectional::solve_bidirectional;
+  use crate::solving::move_filter::MoveFilter;
+
+  fn arena(cmds: &[&str]) -> SolvableArena {
+    let mut arena = SolvableArena::default();
+    for cmd in cmds {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    arena
+  }
+
+  #[test]
+  fn test_same_as_dfs() {
+    for cmds in [
+      &["c2 124", "c3 3"][..],
+      &["c2 124", "c3 3", "c4 2", "c5 123"],
+      &["c2 23", "c6 1234", "c8 14"],
+      //weaknesses aren't part of the patterns
+      &["c2 124 H", "c3 3", "c4 1 J"],
+    ] {
+      let arena = arena(cmds);
+      for in_turns in 0..=2 {
+        //solutions of equal quality may differ, so only compare their quality
+        let quality = |solution: Option<Vec<Move>>| {
+          solution.map(|moves| {
+            (
+              moves.len(),
+              moves.iter().map(|m| m.normalized().amount).sum::<Num>(),
+            )
+          })
+        };
+        assert_eq!(
+          quality(solve(&arena, in_turns, false, None, None)),
+          quality(solve_bidirectional(
+            &arena,
+            in_turns,
+            false,
+            &MoveFilter::new(),
+            Comparator::default(),
+            &mut SolveCache::new(),
+            None
+          )),
+          "different solutions for {:?} in {} turns",
+          cmds,
+          in_turns
+        );
+      }
+    }
+  }
+}
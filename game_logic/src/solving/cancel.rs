@@ -0,0 +1,50 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+///Aborts a running [`super::solve`] from another thread. Clones share the same state, so one clone
+/// is handed to the search while another one cancels it.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+  cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  ///Makes every search using this token return `None` as soon as it notices
+  pub fn cancel(&self) {
+    self.cancelled.store(true, Ordering::Release);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::Acquire)
+  }
+}
+
+#[cfg(test)]
+mod test_cancel {
+  use crate::command::parse_quietly;
+  use crate::solving::cancel::CancelToken;
+  use crate::solving::{SolvableArena, solve};
+
+  #[test]
+  fn test_cancel() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    let token = CancelToken::new();
+    assert!(solve(&arena, 2, false, None, &token).is_some());
+
+    token.clone().cancel();
+    assert!(token.is_cancelled());
+    assert_eq!(None, solve(&arena, 2, false, None, &token));
+    //once cancelled, not even a solved arena is reported as solved
+    assert_eq!(
+      None,
+      solve(&SolvableArena::default(), 0, false, None, &token)
+    );
+  }
+}
@@ -0,0 +1,66 @@
+use crate::position::{Move, Num, Position};
+use crate::position::Dimension::{Column, Row};
+use crate::solving::SolvableArena;
+
+pub const NUM_CELLS: usize = Row.size() as usize * Column.size() as usize;
+
+///Describes where every cell of the arena ends up after a sequence of moves. Unlike arenas,
+/// permutations don't depend on the enemies, so they can be shared between all arenas.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct CellPermutation([u8; NUM_CELLS]);
+
+impl CellPermutation {
+  pub fn identity() -> Self {
+    let mut cells = [0; NUM_CELLS];
+    for (index, cell) in cells.iter_mut().enumerate() {
+      *cell = index as u8;
+    }
+    Self(cells)
+  }
+
+  pub fn of(move_: Move) -> Self {
+    let mut cells = [0; NUM_CELLS];
+    for (index, cell) in cells.iter_mut().enumerate() {
+      let mut position = position_of(index);
+      position.apply_move(move_);
+      *cell = index_of(&position) as u8;
+    }
+    Self(cells)
+  }
+
+  ///Returns the permutation which first applies `self`, then `next`
+  pub fn then(&self, next: &Self) -> Self {
+    Self(self.0.map(|cell| next.0[cell as usize]))
+  }
+
+  pub fn apply(&self, arena: &SolvableArena) -> SolvableArena {
+    let mut result = arena.clone();
+    for enemy in &mut result.enemies {
+      enemy.position = position_of(self.0[index_of(&enemy.position)] as usize);
+    }
+    result.canonical()
+  }
+
+  ///Applies the permutation to a set of cells, where bit `n` denotes the cell with index `n`
+  pub fn apply_to_cells(&self, cells: u64) -> u64 {
+    let mut result = 0;
+    let mut remaining = cells;
+    while remaining != 0 {
+      let index = remaining.trailing_zeros() as usize;
+      result |= 1 << self.0[index];
+      remaining &= remaining - 1;
+    }
+    result
+  }
+}
+
+pub fn index_of(position: &Position) -> usize {
+  position.row as usize * Column.size() as usize + position.column as usize
+}
+
+pub fn position_of(index: usize) -> Position {
+  Position {
+    row: (index / Column.size() as usize) as Num,
+    column: (index % Column.size() as usize) as Num,
+  }
+}
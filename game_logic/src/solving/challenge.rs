@@ -0,0 +1,187 @@
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::position::Dimension::Row;
+use crate::position::{Num, Position};
+use crate::solving::area_template::{AreaShape, available_templates};
+use crate::solving::{Attack, AvailableEquipment, EnemyArea, RequiredAttack, SolvableArena};
+
+///The challenges runners play, by name, with the attacks they forbid. Iron boots are forbidden in
+/// no-jump runs as well, since stomping is a jump.
+pub const CHALLENGES: [(&str, &[Attack]); 3] = [
+  ("no-jump", &[Attack::Jump, Attack::IronBoots]),
+  ("no-hammer", &[Attack::Hammer]),
+  ("no-iron-boots", &[Attack::IronBoots]),
+];
+
+///The attacks an area of the shape can be attacked with, given the equipment
+pub fn shape_attacks(shape: AreaShape, equipment: &AvailableEquipment) -> Vec<Attack> {
+  match shape {
+    AreaShape::Long => Attack::ALL
+      .into_iter()
+      .filter(|attack| match attack {
+        Attack::Jump => true,
+        Attack::Hammer => equipment.throwing_hammer,
+        Attack::IronBoots => equipment.iron_boots,
+      })
+      .collect(),
+    AreaShape::Wide => vec![Attack::Hammer],
+  }
+}
+
+///Whether the area can still be attacked without the forbidden attacks: one of the attacks its shape
+/// allows has to defeat every enemy in it. Areas attacked with an item don't need an attack.
+pub fn allows_area(
+  area: &EnemyArea,
+  forbidden: &BTreeSet<Attack>,
+  equipment: &AvailableEquipment,
+) -> bool {
+  if forbidden.is_empty() || area.item.is_some() {
+    return true;
+  }
+  shape_attacks(area.shape(), equipment)
+    .iter()
+    .filter(|attack| !forbidden.contains(attack))
+    .any(|attack| {
+      area
+        .attack_whitelist
+        .as_ref()
+        .is_none_or(|whitelist| whitelist.contains(attack))
+    })
+}
+
+///Why an arena can't be solved without the forbidden attacks, regardless of the moves
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ChallengeConflict {
+  ///every attack defeating the enemy is forbidden
+  Undefeatable {
+    position: Position,
+    weakness: RequiredAttack,
+  },
+  ///no area which can still be attacked reaches the ring, and enemies can't leave their ring
+  UnreachableRing(Num),
+}
+
+impl Display for ChallengeConflict {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      ChallengeConflict::Undefeatable { position, weakness } => {
+        write!(f, "the enemy on {} can only be defeated with", position)?;
+        for (index, attack) in weakness.attacks().iter().enumerate() {
+          if index > 0 {
+            write!(f, " or")?;
+          }
+          write!(f, " {}", attack.name())?;
+        }
+        write!(f, ", which the challenge forbids")
+      }
+      ChallengeConflict::UnreachableRing(ring) => write!(
+        f,
+        "no allowed attack reaches the enemies on ring {}: 2x2 areas don't reach it and columns \
+         need a jump or a thrown hammer",
+        ring + 1
+      ),
+    }
+  }
+}
+
+///Finds the reasons why the arena can't be solved without its forbidden attacks which don't depend
+/// on the moves, so challenge runners learn why instead of just getting no solution
+pub fn conflicts(arena: &SolvableArena) -> Vec<ChallengeConflict> {
+  let forbidden = &arena.forbidden_attacks;
+  let mut conflicts = Vec::new();
+  if forbidden.is_empty() {
+    return conflicts;
+  }
+
+  conflicts.extend(arena.enemies.iter().filter_map(|enemy| {
+    let weakness = enemy.required_attack?;
+    weakness
+      .attacks()
+      .iter()
+      .all(|attack| forbidden.contains(attack))
+      .then_some(ChallengeConflict::Undefeatable {
+        position: enemy.position,
+        weakness,
+      })
+  }));
+
+  let reaches_ring = |ring: Num| {
+    available_templates(arena.available_equipment).any(|template| {
+      let reaches = match template.shape {
+        AreaShape::Long => true,
+        AreaShape::Wide => template
+          .inner_rings
+          .iter()
+          .any(|&inner_ring| (inner_ring..inner_ring + 2).contains(&ring)),
+      };
+      reaches
+        && shape_attacks(template.shape, &arena.available_equipment)
+          .iter()
+          .any(|attack| !forbidden.contains(attack))
+    })
+  };
+  conflicts.extend(
+    (0..Row.size())
+      .filter(|&ring| arena.enemies.iter().any(|enemy| enemy.row == ring))
+      .filter(|&ring| !reaches_ring(ring))
+      .map(ChallengeConflict::UnreachableRing),
+  );
+
+  conflicts
+}
+
+#[cfg(test)]
+mod test_challenge {
+  use crate::command::parse_quietly;
+  use crate::position::Position;
+  use crate::solving::challenge::{ChallengeConflict, conflicts};
+  use crate::solving::{Attack, Coverage, RequiredAttack, SolvableArena};
+
+  #[test]
+  fn test_only_hammer() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c1 1", "c2 1", "c1 2", "c2 2"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    arena
+      .forbidden_attacks
+      .extend([Attack::Jump, Attack::IronBoots]);
+    assert!(Coverage::find(&arena).is_some());
+    assert!(conflicts(&arena).is_empty());
+
+    //a whole column can only be attacked with a thrown hammer now
+    parse_quietly(&mut arena, "c6 1234").expect("parse error");
+    assert!(Coverage::find(&arena).is_some());
+    arena.available_equipment.throwing_hammer = false;
+    assert!(Coverage::find(&arena).is_none());
+    assert_eq!(
+      vec![
+        ChallengeConflict::UnreachableRing(2),
+        ChallengeConflict::UnreachableRing(3)
+      ],
+      conflicts(&arena)
+    );
+  }
+
+  #[test]
+  fn test_undefeatable() {
+    let mut arena = SolvableArena::default();
+    parse_quietly(&mut arena, "c3 1 J").expect("parse error");
+    assert!(Coverage::find(&arena).is_some());
+
+    arena
+      .forbidden_attacks
+      .extend([Attack::Jump, Attack::IronBoots]);
+    assert!(Coverage::find(&arena).is_none());
+    assert_eq!(
+      vec![ChallengeConflict::Undefeatable {
+        position: Position::at(0, 2).unwrap(),
+        weakness: RequiredAttack::Jump
+      }],
+      conflicts(&arena)
+    );
+  }
+}
@@ -0,0 +1,324 @@
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::time::Duration;
+
+use crate::position::Dimension::{Column, Row};
+use crate::position::{Move, Num};
+use crate::solving::compare_solutions;
+
+///Time to select a ring or column and confirm the move, roughly measured in game
+const MOVE_TIME: Duration = Duration::from_millis(700);
+///Time for every step a ring is rotated or a column slid
+const STEP_TIME: Duration = Duration::from_millis(150);
+///Extra time for switching between rotating rings and sliding columns, since the cursor changes
+const SWITCH_TIME: Duration = Duration::from_millis(300);
+
+///What makes a solution better than another one with the same number of moves. Implementations are
+/// listed in [`COMPARATORS`], so front-ends can choose them by name.
+pub trait SolutionComparator: Sync {
+  ///The name front-ends use to select the comparator
+  fn name(&self) -> &'static str;
+
+  ///A one line description for help texts
+  fn description(&self) -> &'static str;
+
+  ///Compares solutions with the same number of moves. Solutions which are equal here are ordered by
+  /// [`compare_solutions`].
+  fn compare(&self, solution: &[Move], other: &[Move]) -> Ordering;
+}
+
+///Every comparator which can be chosen by name, the first one is the default
+pub static COMPARATORS: &[&dyn SolutionComparator] = &[
+  &SmallestAmounts,
+  &FewestColumnMoves,
+  &FewestDirectionSwitches,
+  &LeastRotation,
+  &ShortestExecutionTime,
+];
+
+///Prefers the smallest sum of amounts, the order of [`compare_solutions`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct SmallestAmounts;
+
+impl SolutionComparator for SmallestAmounts {
+  fn name(&self) -> &'static str {
+    "amounts"
+  }
+
+  fn description(&self) -> &'static str {
+    "the smallest rotations and slides in total"
+  }
+
+  fn compare(&self, _solution: &[Move], _other: &[Move]) -> Ordering {
+    Ordering::Equal
+  }
+}
+
+///Prefers rotating rings over sliding columns
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct FewestColumnMoves;
+
+impl SolutionComparator for FewestColumnMoves {
+  fn name(&self) -> &'static str {
+    "column-moves"
+  }
+
+  fn description(&self) -> &'static str {
+    "as few slid columns as possible"
+  }
+
+  fn compare(&self, solution: &[Move], other: &[Move]) -> Ordering {
+    let column_moves = |moves: &[Move]| {
+      moves
+        .iter()
+        .filter(|move_| move_.dimension == Column)
+        .count()
+    };
+    column_moves(solution).cmp(&column_moves(other))
+  }
+}
+
+///Prefers solutions which keep moving in the same direction, see [`direction_switches`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct FewestDirectionSwitches;
+
+impl SolutionComparator for FewestDirectionSwitches {
+  fn name(&self) -> &'static str {
+    "switches"
+  }
+
+  fn description(&self) -> &'static str {
+    "as few changes between rotating, sliding and their directions as possible"
+  }
+
+  fn compare(&self, solution: &[Move], other: &[Move]) -> Ordering {
+    direction_switches(solution).cmp(&direction_switches(other))
+  }
+}
+
+///Prefers the smallest total rotation of rings
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct LeastRotation;
+
+impl SolutionComparator for LeastRotation {
+  fn name(&self) -> &'static str {
+    "rotation"
+  }
+
+  fn description(&self) -> &'static str {
+    "the smallest rotations of rings in total"
+  }
+
+  fn compare(&self, solution: &[Move], other: &[Move]) -> Ordering {
+    let rotation = |moves: &[Move]| -> Num {
+      moves
+        .iter()
+        .filter(|move_| move_.dimension == Row)
+        .map(|move_| move_.normalized().amount)
+        .sum()
+    };
+    rotation(solution).cmp(&rotation(other))
+  }
+}
+
+///Prefers the solution which takes the least time to enter, see [`estimated_execution_time`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ShortestExecutionTime;
+
+impl SolutionComparator for ShortestExecutionTime {
+  fn name(&self) -> &'static str {
+    "time"
+  }
+
+  fn description(&self) -> &'static str {
+    "the shortest estimated time to execute the moves in game"
+  }
+
+  fn compare(&self, solution: &[Move], other: &[Move]) -> Ordering {
+    estimated_execution_time(solution).cmp(&estimated_execution_time(other))
+  }
+}
+
+///How often a move rotates or slides differently than the one before: switching between rings and
+/// columns, or changing the direction
+pub fn direction_switches(moves: &[Move]) -> usize {
+  moves
+    .windows(2)
+    .filter(|pair| {
+      let (previous, next) = (pair[0].normalized(), pair[1].normalized());
+      previous.dimension != next.dimension
+        || previous.in_positive_direction != next.in_positive_direction
+    })
+    .count()
+}
+
+///A rough estimate how long it takes to enter the moves in game
+pub fn estimated_execution_time(moves: &[Move]) -> Duration {
+  let steps: u32 = moves
+    .iter()
+    .map(|move_| move_.normalized().amount as u32)
+    .sum();
+  let switches = moves
+    .windows(2)
+    .filter(|pair| pair[0].dimension != pair[1].dimension)
+    .count() as u32;
+  MOVE_TIME * moves.len() as u32 + STEP_TIME * steps + SWITCH_TIME * switches
+}
+
+///A handle to one of the [`COMPARATORS`], which can be copied into
+/// [`SolveParams`](super::SolveParams). Comparators are identified by name.
+#[derive(Copy, Clone)]
+pub struct Comparator(&'static dyn SolutionComparator);
+
+impl Comparator {
+  ///Wraps a comparator which isn't part of [`COMPARATORS`]
+  pub const fn new(comparator: &'static dyn SolutionComparator) -> Self {
+    Self(comparator)
+  }
+
+  ///All comparators in the registry
+  pub fn all() -> impl Iterator<Item = Comparator> {
+    COMPARATORS.iter().map(|&comparator| Comparator(comparator))
+  }
+
+  pub fn by_name(name: &str) -> Option<Self> {
+    Self::all().find(|comparator| comparator.name() == name)
+  }
+
+  pub fn name(&self) -> &'static str {
+    self.0.name()
+  }
+
+  pub fn description(&self) -> &'static str {
+    self.0.description()
+  }
+
+  ///Orders solutions by quality: shorter solutions always come first, since the solver looks for the
+  /// fewest turns, then the comparator decides and [`compare_solutions`] breaks ties
+  pub fn compare(&self, solution: &[Move], other: &[Move]) -> Ordering {
+    solution
+      .len()
+      .cmp(&other.len())
+      .then_with(|| self.0.compare(solution, other))
+      .then_with(|| compare_solutions(solution, other))
+  }
+
+  ///A solution is better if it comes first in the order of [`Comparator::compare`]
+  pub fn is_better(&self, solution: &[Move], current_best: &[Move]) -> bool {
+    self.compare(solution, current_best).is_lt()
+  }
+}
+
+impl Default for Comparator {
+  fn default() -> Self {
+    Comparator(COMPARATORS[0])
+  }
+}
+
+impl PartialEq for Comparator {
+  fn eq(&self, other: &Self) -> bool {
+    self.name() == other.name()
+  }
+}
+
+impl Eq for Comparator {}
+
+impl Hash for Comparator {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.name().hash(state)
+  }
+}
+
+impl Debug for Comparator {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.debug_tuple("Comparator").field(&self.name()).finish()
+  }
+}
+
+impl Display for Comparator {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+#[cfg(test)]
+mod test_comparator {
+  use core::time::Duration;
+
+  use crate::command::parse_quietly;
+  use crate::position::Move;
+  use crate::solving::comparator::{
+    COMPARATORS, Comparator, direction_switches, estimated_execution_time,
+  };
+  use crate::solving::solve_cache::SolveCache;
+  use crate::solving::{SolvableArena, SolveParams, Strategy, compare_solutions, solve_with};
+
+  fn moves(moves: &str) -> Vec<Move> {
+    moves
+      .split(", ")
+      .map(|move_| move_.parse().unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn test_registry() {
+    for comparator in Comparator::all() {
+      assert_eq!(Some(comparator), Comparator::by_name(comparator.name()));
+    }
+    assert_eq!(COMPARATORS.len(), Comparator::all().count());
+    assert_eq!(None, Comparator::by_name("luck"));
+  }
+
+  #[test]
+  fn test_criteria() {
+    let rotating = moves("r1 3, r2 3");
+    let sliding = moves("c1 1, c2 -1");
+    let by_name = |name| Comparator::by_name(name).unwrap();
+
+    assert_eq!(
+      compare_solutions(&sliding, &rotating),
+      Comparator::default().compare(&sliding, &rotating)
+    );
+    assert!(by_name("column-moves").is_better(&rotating, &sliding));
+    assert!(by_name("switches").is_better(&rotating, &sliding));
+    assert!(by_name("rotation").is_better(&sliding, &rotating));
+    assert!(by_name("time").is_better(&sliding, &rotating));
+    //fewer moves always win
+    assert!(by_name("column-moves").is_better(&moves("c1 1"), &rotating));
+
+    assert_eq!(1, direction_switches(&sliding));
+    assert_eq!(
+      Duration::from_millis(2 * 700 + 6 * 150),
+      estimated_execution_time(&rotating)
+    );
+  }
+
+  #[test]
+  fn test_solve_with_comparator() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    for comparator in Comparator::all() {
+      let params = SolveParams::new().in_turns(2).comparator(comparator);
+      let solution = solve_with(&arena, &params, &mut SolveCache::new(), None, |_| true)
+        .unwrap_or_else(|| panic!("{} found no solution", comparator));
+      let mut solved = arena.clone();
+      solution.iter().for_each(|&move_| solved.apply_move(move_));
+      assert!(solved.is_solved(), "{}", comparator);
+
+      //exhaustive strategies have to agree on the best solution
+      for strategy in ["iterative", "heuristic"] {
+        let params = params.strategy(Strategy::by_name(strategy).unwrap());
+        assert_eq!(
+          Some(&solution),
+          solve_with(&arena, &params, &mut SolveCache::new(), None, |_| true).as_ref(),
+          "{} with {}",
+          strategy,
+          comparator
+        );
+      }
+    }
+  }
+}
@@ -0,0 +1,167 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::position::Dimension::{Column, Row};
+use crate::position::{Move, Num, Position};
+use crate::solving::SolvableArena;
+
+///A move of a solution in words, together with what it achieves
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MoveExplanation {
+  pub move_: Move,
+  ///what the move achieves, None if it only prepares later moves
+  pub context: Option<String>,
+}
+
+impl MoveExplanation {
+  ///The move in words, like `Rotate ring 3 counter-clockwise by 1`
+  pub fn action(&self) -> String {
+    let move_ = self.move_.normalized();
+    match move_.dimension {
+      Row => format!(
+        "Rotate ring {} {} by {}",
+        move_.coordinate + 1,
+        if move_.in_positive_direction {
+          "clockwise"
+        } else {
+          "counter-clockwise"
+        },
+        move_.amount
+      ),
+      Column => format!(
+        "Slide column {} {} by {}",
+        move_.coordinate + 1,
+        if move_.in_positive_direction {
+          "outward"
+        } else {
+          "inward"
+        },
+        move_.amount
+      ),
+    }
+  }
+}
+
+impl Display for MoveExplanation {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{}", self.action())?;
+    if let Some(context) = &self.context {
+      write!(f, " - {}", context)?;
+    }
+    Ok(())
+  }
+}
+
+///Explains each move of a solution by what it does to the arena: which enemies it lines up in a
+/// column or groups on a ring, or that all enemies can be attacked afterwards
+pub fn explain(arena: &SolvableArena, solution: &[Move]) -> Vec<MoveExplanation> {
+  let mut arena = arena.after_first_strike();
+  solution
+    .iter()
+    .map(|&move_| {
+      let moved: Vec<Position> = arena
+        .enemies_affected_by(&move_)
+        .into_iter()
+        .map(|enemy| {
+          let mut position = enemy.position;
+          position.apply_move(move_);
+          position
+        })
+        .collect();
+      arena.apply_move(move_);
+      MoveExplanation {
+        move_,
+        context: context(&arena, move_, &moved),
+      }
+    })
+    .collect()
+}
+
+///What a move achieved, given the arena after it and where the moved enemies ended up
+fn context(arena: &SolvableArena, move_: Move, moved: &[Position]) -> Option<String> {
+  if arena.is_solved() {
+    return Some(
+      if arena.objective.is_some() {
+        "all target cells are occupied now"
+      } else {
+        "all enemies can be attacked now"
+      }
+      .to_string(),
+    );
+  }
+
+  let count = |predicate: &dyn Fn(&Position) -> bool| {
+    arena
+      .enemies
+      .iter()
+      .filter(|enemy| predicate(&enemy.position))
+      .count()
+  };
+  match move_.dimension {
+    //rotating a ring lines enemies up in the columns they end up in
+    Row => moved
+      .iter()
+      .map(|landed| {
+        let in_column = count(&|position| position.column == landed.column);
+        (in_column, landed.column)
+      })
+      .max()
+      .filter(|&(in_column, _)| in_column >= 2)
+      .map(|(in_column, column)| {
+        format!("lines up {} enemies in column {}", in_column, column + 1)
+      }),
+    //sliding a column groups enemies next to each other on the rings they end up on
+    Column => moved
+      .iter()
+      .map(|landed| {
+        let side_by_side = count(&|position| {
+          position.row == landed.row && column_distance(position.column, landed.column) <= 1
+        });
+        (side_by_side, landed.row)
+      })
+      .max()
+      .filter(|&(side_by_side, _)| side_by_side >= 2)
+      .map(|(side_by_side, row)| {
+        format!(
+          "groups {} enemies side by side on ring {}",
+          side_by_side,
+          row + 1
+        )
+      }),
+  }
+}
+
+///How many columns apart two columns are, going around the shorter way
+fn column_distance(a: Num, b: Num) -> Num {
+  let distance = a.abs_diff(b);
+  distance.min(Column.size() - distance)
+}
+
+#[cfg(test)]
+mod test_explain {
+  use crate::command::parse_quietly;
+  use crate::position::{Dimension, Move};
+  use crate::solving::explain::explain;
+  use crate::solving::SolvableArena;
+
+  #[test]
+  fn test_explain() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c1 1", "c4 1", "c7 2"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    let solution = [
+      Move::new(Dimension::Column, 6, 2, true).unwrap(),
+      Move::new(Dimension::Row, 0, 3, true).unwrap(),
+    ];
+    let explanations = explain(&arena, &solution);
+    assert_eq!(2, explanations.len());
+    assert_eq!("Slide column 7 outward by 2", explanations[0].to_string());
+    assert_eq!(
+      "Rotate ring 1 clockwise by 3 - all enemies can be attacked now",
+      explanations[1].to_string()
+    );
+  }
+}
@@ -0,0 +1,98 @@
+use alloc::vec::Vec;
+
+use crate::position::{Move, Num, Position};
+use crate::solving::{SolvableArena, SolveParams, solve_with};
+use crate::solving::solve_cache::SolveCache;
+
+///An enemy worth attacking before the fight, since it makes the arena easier to solve
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FirstStrike {
+  pub position: Position,
+  ///the solution after the first strike
+  pub solution: Vec<Move>,
+}
+
+///Tries a first strike on every enemy and returns the one leading to the best solution, if it is
+/// better than the solution without a first strike
+pub fn recommend_first_strike(
+  arena: &SolvableArena,
+  params: &SolveParams,
+  cache: &mut SolveCache,
+) -> Option<FirstStrike> {
+  let mut without_first_strike = arena.clone();
+  without_first_strike.first_strike = None;
+  let mut best_solution = solve_with(&without_first_strike, params, cache, None, |_| true);
+  let mut best_first_strike = None;
+
+  for enemy in &arena.enemies {
+    let mut candidate = without_first_strike.clone();
+    candidate.first_strike = Some(enemy.position);
+    //only solutions with at most as many turns as the best one so far can be better
+    let mut candidate_params = *params;
+    if let Some(best_solution) = &best_solution {
+      candidate_params.in_turns = Some(best_solution.len() as Num);
+    }
+    let Some(solution) = solve_with(&candidate, &candidate_params, cache, None, |_| true) else {
+      continue;
+    };
+    if best_solution
+      .as_ref()
+      .is_none_or(|current_best| params.comparator.is_better(&solution, current_best))
+    {
+      best_first_strike = Some(FirstStrike {
+        position: enemy.position,
+        solution: solution.clone(),
+      });
+      best_solution = Some(solution);
+    }
+  }
+
+  best_first_strike
+}
+
+#[cfg(test)]
+mod test_first_strike {
+  use crate::command::parse_quietly;
+  use crate::position::Position;
+  use crate::solving::first_strike::recommend_first_strike;
+  use crate::solving::solve_cache::SolveCache;
+  use crate::solving::{SolvableArena, SolveParams, solve};
+
+  #[test]
+  fn test_first_strike_removes_enemy() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    assert_eq!(None, solve(&arena, 0, false, None, None));
+
+    arena.first_strike = Some(Position::at(0, 1).unwrap());
+    assert_eq!(None, solve(&arena, 0, false, None, None));
+    arena.first_strike = Some(Position::at(2, 2).unwrap());
+    assert_eq!(Some(vec![]), solve(&arena, 0, false, None, None));
+  }
+
+  #[test]
+  fn test_recommend() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let first_strike = recommend_first_strike(&arena, &SolveParams::new(), &mut SolveCache::new())
+      .expect("should recommend a first strike");
+    assert_eq!(Position::at(2, 2).unwrap(), first_strike.position);
+    assert!(first_strike.solution.is_empty());
+  }
+
+  #[test]
+  fn test_no_recommendation_for_solved() {
+    let mut arena = SolvableArena::default();
+    parse_quietly(&mut arena, "c2 1234").expect("parse error");
+
+    assert_eq!(
+      None,
+      recommend_first_strike(&arena, &SolveParams::new(), &mut SolveCache::new())
+    );
+  }
+}
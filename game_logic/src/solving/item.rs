@@ -0,0 +1,79 @@
+use core::fmt::{Display, Formatter};
+
+use crate::position::Num;
+
+///A consumable item attacking a whole column, regardless of the weaknesses of the enemies in it
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Item {
+  FireFlower,
+  IceFlower,
+}
+
+impl Item {
+  pub const ALL: [Item; 2] = [Item::FireFlower, Item::IceFlower];
+
+  ///The name used by commands, like `fire-flower`
+  pub fn name(&self) -> &'static str {
+    match self {
+      Item::FireFlower => "fire-flower",
+      Item::IceFlower => "ice-flower",
+    }
+  }
+
+  pub fn by_name(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|item| item.name() == name)
+  }
+
+  fn index(&self) -> usize {
+    match self {
+      Item::FireFlower => 0,
+      Item::IceFlower => 1,
+    }
+  }
+}
+
+///Like `Fire Flower`
+impl Display for Item {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Item::FireFlower => write!(f, "Fire Flower"),
+      Item::IceFlower => write!(f, "Ice Flower"),
+    }
+  }
+}
+
+///How many of each [`Item`] may be used in a fight
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub struct ItemStock([Num; Item::ALL.len()]);
+
+impl ItemStock {
+  pub fn get(&self, item: Item) -> Num {
+    self.0[item.index()]
+  }
+
+  pub fn set(&mut self, item: Item, count: Num) {
+    self.0[item.index()] = count;
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.iter().all(|&count| count == 0)
+  }
+}
+
+///Lists the items in stock like `2 Fire Flower, 1 Ice Flower`
+impl Display for ItemStock {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    let mut first = true;
+    for item in Item::ALL.into_iter().filter(|&item| self.get(item) > 0) {
+      if !first {
+        write!(f, ", ")?;
+      }
+      write!(f, "{} {}", self.get(item), item)?;
+      first = false;
+    }
+    if first {
+      write!(f, "no items")?;
+    }
+    Ok(())
+  }
+}
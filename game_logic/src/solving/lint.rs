@@ -0,0 +1,172 @@
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::position::{Num, Position};
+use crate::solving::SolvableArena;
+
+///The most enemies a single attack can hit: a whole column or a 2x2 area
+const MAX_ENEMIES_PER_GROUP: usize = 4;
+
+///Something about an arena which can't happen in the game, so the arena was most likely entered
+/// wrong and solving it would give a useless result
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LintWarning {
+  ///more than one enemy was entered on the same cell
+  StackedEnemies(Position),
+  ///there are more enemies than the given number of groups can hold
+  TooManyEnemies { enemies: usize, groups: Num },
+  ///there are more groups than enemies
+  TooManyGroups { enemies: usize, groups: Num },
+  ///an enemy needs to be attacked zero times
+  ZeroHp(Position),
+  ///the enemy attacked before the fight doesn't exist
+  FirstStrikeOnEmptyCell(Position),
+  ///a column without enemies is locked as grouped
+  LockedEmptyColumn(Num),
+  ///the target cells can't all be occupied since there are fewer enemies
+  TooManyTargetCells { cells: usize, enemies: usize },
+}
+
+impl Display for LintWarning {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      LintWarning::StackedEnemies(position) => write!(
+        f,
+        "there is more than one enemy on {}, but every cell holds at most one",
+        position
+      ),
+      LintWarning::TooManyEnemies { enemies, groups } => write!(
+        f,
+        "{} enemies don't fit into {} groups, a group has at most {} enemies",
+        enemies, groups, MAX_ENEMIES_PER_GROUP
+      ),
+      LintWarning::TooManyGroups { enemies, groups } => write!(
+        f,
+        "{} groups can't be made of {} enemies, every group needs at least one",
+        groups, enemies
+      ),
+      LintWarning::ZeroHp(position) => write!(
+        f,
+        "the enemy on {} survives 0 attacks, but every enemy needs at least one",
+        position
+      ),
+      LintWarning::FirstStrikeOnEmptyCell(position) => write!(
+        f,
+        "the first strike hits {}, but there is no enemy",
+        position
+      ),
+      LintWarning::LockedEmptyColumn(column) => write!(
+        f,
+        "column {} is locked as grouped, but has no enemies",
+        column + 1
+      ),
+      LintWarning::TooManyTargetCells { cells, enemies } => write!(
+        f,
+        "{} target cells can't be occupied by {} enemies",
+        cells, enemies
+      ),
+    }
+  }
+}
+
+///Checks the arena for inputs which are impossible in the game, like two enemies on one cell or more
+/// enemies than the groups can hold
+pub fn lint(arena: &SolvableArena) -> Vec<LintWarning> {
+  let mut warnings = Vec::new();
+  let enemies = arena.enemies.len();
+
+  let mut positions: Vec<Position> = arena.enemies.iter().map(|enemy| enemy.position).collect();
+  positions.sort_unstable();
+  let mut stacked: Vec<Position> = positions
+    .windows(2)
+    .filter(|pair| pair[0] == pair[1])
+    .map(|pair| pair[0])
+    .collect();
+  stacked.dedup();
+  warnings.extend(stacked.into_iter().map(LintWarning::StackedEnemies));
+
+  if let Some(groups) = arena.num_groups {
+    if enemies > groups as usize * MAX_ENEMIES_PER_GROUP {
+      warnings.push(LintWarning::TooManyEnemies { enemies, groups });
+    }
+    if groups as usize > enemies {
+      warnings.push(LintWarning::TooManyGroups { enemies, groups });
+    }
+  }
+
+  warnings.extend(
+    arena
+      .enemies
+      .iter()
+      .filter(|enemy| enemy.hp == Some(0))
+      .map(|enemy| LintWarning::ZeroHp(enemy.position)),
+  );
+
+  if let Some(first_strike) = arena.first_strike {
+    if !positions.contains(&first_strike) {
+      warnings.push(LintWarning::FirstStrikeOnEmptyCell(first_strike));
+    }
+  }
+
+  warnings.extend(
+    arena
+      .locked_columns
+      .iter()
+      .filter(|&&column| !positions.iter().any(|position| position.column == column))
+      .map(|&column| LintWarning::LockedEmptyColumn(column)),
+  );
+
+  if let Some(objective) = &arena.objective {
+    let cells = objective.cells().len();
+    if cells > enemies {
+      warnings.push(LintWarning::TooManyTargetCells { cells, enemies });
+    }
+  }
+
+  warnings
+}
+
+#[cfg(test)]
+mod test_lint {
+  use crate::position::Position;
+  use crate::solving::lint::{LintWarning, lint};
+  use crate::solving::{Enemy, SolvableArena};
+
+  #[test]
+  fn test_lint() {
+    let mut arena = SolvableArena::default();
+    arena.add_column(0, 0..4, |position| Enemy {
+      position,
+      required_attack: None,
+      hp: None,
+    });
+    assert_eq!(Vec::<LintWarning>::new(), lint(&arena));
+
+    let position = Position::at(0, 0).unwrap();
+    arena.enemies.push(Enemy {
+      position,
+      required_attack: None,
+      hp: Some(0),
+    });
+    arena.num_groups = Some(1);
+    arena.locked_columns.insert(5);
+    assert_eq!(
+      vec![
+        LintWarning::StackedEnemies(position),
+        LintWarning::TooManyEnemies {
+          enemies: 5,
+          groups: 1
+        },
+        LintWarning::ZeroHp(position),
+        LintWarning::LockedEmptyColumn(5),
+      ],
+      lint(&arena)
+    );
+
+    arena.num_groups = Some(6);
+    assert!(lint(&arena).contains(&LintWarning::TooManyGroups {
+      enemies: 5,
+      groups: 6
+    }));
+  }
+}
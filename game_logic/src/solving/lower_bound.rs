@@ -0,0 +1,105 @@
+use crate::position::Dimension::Row;
+use crate::position::Num;
+use crate::solving::SolvableArena;
+use crate::solving::area_template::{AreaShape, available_templates};
+#[cfg(feature = "std")]
+use crate::solving::pattern_database::PatternDatabase;
+
+///The best known lower bound of the moves needed to solve the arena: the larger of
+/// [`coverage_lower_bound`] and the [pattern database](PatternDatabase::global) lookup, if it covers
+/// the arena. The database is only used with the `std` feature, and by default only with the
+/// `pattern_db` feature.
+pub fn lower_bound(arena: &SolvableArena) -> Num {
+  let lower_bound = coverage_lower_bound(arena);
+  #[cfg(feature = "std")]
+  let lower_bound = PatternDatabase::global()
+    .and_then(|database| database.lower_bound(arena))
+    .map_or(lower_bound, |database_bound| {
+      database_bound.max(lower_bound)
+    });
+  lower_bound
+}
+
+///A cheap lower bound of the moves needed to solve the arena, which never overestimates.
+///
+///Every area covers at most one column of a ring, or two if it's a wide area reaching that ring, so
+/// a ring can only hold enemies in as many columns as the groups can cover. Rotating a ring doesn't
+/// change how many columns it has enemies in, and moving a column only changes the two columns on
+/// its line, so every excess column takes at least half a move to get rid of.
+///
+///Returns 0 for arenas with an objective, since they don't have to be covered.
+pub fn coverage_lower_bound(arena: &SolvableArena) -> Num {
+  if arena.objective.is_some() {
+    return 0;
+  }
+  let num_groups = arena.num_groups() as u32;
+  (0..Row.size())
+    .map(|ring| {
+      let reachable_by_wide_area = available_templates(arena.available_equipment).any(|template| {
+        template.shape == AreaShape::Wide
+          && template
+            .inner_rings
+            .iter()
+            .any(|&inner_ring| (inner_ring..inner_ring + 2).contains(&ring))
+      });
+      let columns_per_group = if reachable_by_wide_area { 2 } else { 1 };
+
+      let occupied_columns = arena
+        .enemies
+        .iter()
+        .filter(|enemy| enemy.row == ring)
+        .fold(0u32, |columns, enemy| columns | 1 << enemy.column);
+      let excess_columns = occupied_columns
+        .count_ones()
+        .saturating_sub(columns_per_group * num_groups);
+      excess_columns.div_ceil(2)
+    })
+    .max()
+    .unwrap_or(0) as Num
+}
+
+#[cfg(test)]
+mod test_lower_bound {
+  use crate::command::parse_quietly;
+  use crate::solving::lower_bound::coverage_lower_bound;
+  use crate::solving::search_tree::{NodeOutcome, trace_solve};
+  use crate::solving::{SolvableArena, SolveParams, solve_with};
+  use crate::solving::solve_cache::SolveCache;
+
+  #[test]
+  fn test_admissible() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    let solution = solve_with(
+      &arena,
+      &SolveParams::new(),
+      &mut SolveCache::new(),
+      None,
+      |_| true,
+    )
+    .expect("is solvable");
+    assert!(coverage_lower_bound(&arena) as usize <= solution.len());
+
+    let mut solved = SolvableArena::default();
+    parse_quietly(&mut solved, "c2 1234").expect("parse error");
+    assert_eq!(0, coverage_lower_bound(&solved));
+  }
+
+  #[test]
+  fn test_prunes_scattered_outer_ring() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c1 4", "c3 4", "c5 4", "c7 4", "c9 4", "c11 4"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    arena.num_groups = Some(1);
+    //one long area can only cover one of the six columns
+    assert_eq!(3, coverage_lower_bound(&arena));
+
+    let (solution, tree) = trace_solve(&arena, 2, false);
+    assert_eq!(None, solution);
+    assert_eq!(1, tree.nodes().len());
+    assert_eq!(NodeOutcome::PrunedByLowerBound(3), tree.nodes()[0].outcome);
+  }
+}
@@ -0,0 +1,176 @@
+use alloc::string::{String, ToString};
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+use crate::position::Dimension::{Column, Row};
+use crate::position::{Move, Num};
+
+///Moves the solver must not use, e.g. because the player finds them too error-prone under the timer
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub struct MoveFilter {
+  ///a set bit forbids rotating the ring with that index
+  forbidden_rings: u8,
+  ///a set bit forbids moving the column with that index, together with the opposite one
+  forbidden_columns: u16,
+}
+
+impl MoveFilter {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  ///Forbids rotating the given ring
+  pub fn forbid_ring(mut self, ring: Num) -> Self {
+    self.forbidden_rings |= 1 << ring;
+    self
+  }
+
+  ///Forbids moving the given column. Since moving a column also moves the opposite one, `c6` and
+  /// `c12` forbid the same moves.
+  pub fn forbid_column(mut self, column: Num) -> Self {
+    self.forbidden_columns |= 1 << column;
+    self
+  }
+
+  ///Forbids rotating any ring
+  pub fn forbid_rows(self) -> Self {
+    (0..Row.size()).fold(self, Self::forbid_ring)
+  }
+
+  ///Forbids moving any column
+  pub fn forbid_columns(self) -> Self {
+    (0..Column.size()).fold(self, Self::forbid_column)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.forbidden_rings == 0 && self.forbidden_columns == 0
+  }
+
+  pub fn allows(&self, move_: Move) -> bool {
+    match move_.dimension {
+      Row => self.forbidden_rings & 1 << move_.coordinate == 0,
+      Column => {
+        let opposite_column = (move_.coordinate + Column.size() / 2) % Column.size();
+        self.forbidden_columns & (1 << move_.coordinate | 1 << opposite_column) == 0
+      }
+    }
+  }
+}
+
+///Lists the forbidden moves like the options of `solve`, e.g. `forbid rows forbid c2`
+impl Display for MoveFilter {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    let mut separator = "";
+    let mut forbid = |f: &mut Formatter<'_>, prefix: &str, index: Option<Num>| {
+      let result = match index {
+        Some(index) => write!(f, "{}forbid {}{}", separator, prefix, index + 1),
+        None => write!(f, "{}forbid {}", separator, prefix),
+      };
+      separator = " ";
+      result
+    };
+    if self.forbidden_rings == Self::new().forbid_rows().forbidden_rings {
+      forbid(f, "rows", None)?;
+    } else {
+      for ring in (0..Row.size()).filter(|ring| self.forbidden_rings & 1 << ring != 0) {
+        forbid(f, "r", Some(ring))?;
+      }
+    }
+    if self.forbidden_columns == Self::new().forbid_columns().forbidden_columns {
+      forbid(f, "columns", None)?;
+    } else {
+      for column in (0..Column.size()).filter(|column| self.forbidden_columns & 1 << column != 0) {
+        forbid(f, "c", Some(column))?;
+      }
+    }
+    Ok(())
+  }
+}
+
+///Parses the format of [`Display`], like `forbid rows forbid c2`
+impl FromStr for MoveFilter {
+  type Err = MoveFilterParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let error = || MoveFilterParseError(s.to_string());
+    let mut filter = Self::new();
+    let mut args = s.split_whitespace();
+    while let Some(arg) = args.next() {
+      if arg != "forbid" {
+        return Err(error());
+      }
+      filter = match args.next().ok_or_else(error)? {
+        "rows" => filter.forbid_rows(),
+        "columns" => filter.forbid_columns(),
+        moved => {
+          let (dimension, number) = match moved.split_at_checked(1) {
+            Some(("r", number)) => (Row, number),
+            Some(("c", number)) => (Column, number),
+            _ => return Err(error()),
+          };
+          let index = number
+            .parse::<Num>()
+            .ok()
+            .and_then(|number| number.checked_sub(1))
+            .filter(|&index| index < dimension.size())
+            .ok_or_else(error)?;
+          match dimension {
+            Row => filter.forbid_ring(index),
+            Column => filter.forbid_column(index),
+          }
+        }
+      };
+    }
+    Ok(filter)
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MoveFilterParseError(pub String);
+
+impl Display for MoveFilterParseError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    write!(
+      f,
+      "Invalid move filter '{}', needs to be 'forbid <rows|columns|r<ring>|c<column>> ...'",
+      self.0
+    )
+  }
+}
+
+impl Error for MoveFilterParseError {}
+
+#[cfg(test)]
+mod test_move_filter {
+  use crate::position::Move;
+  use crate::solving::move_filter::MoveFilter;
+
+  fn allows(filter: MoveFilter, move_: &str) -> bool {
+    filter.allows(move_.parse::<Move>().unwrap())
+  }
+
+  #[test]
+  fn test_allows() {
+    assert!(MoveFilter::new().is_empty());
+    assert!(allows(MoveFilter::new(), "r2 3"));
+
+    let filter = MoveFilter::new().forbid_column(5).forbid_ring(1);
+    assert!(!allows(filter, "c6 1"));
+    assert!(!allows(filter, "c12 -2"));
+    assert!(allows(filter, "c5 1"));
+    assert!(!allows(filter, "r2 3"));
+    assert!(allows(filter, "r1 3"));
+
+    let filter = MoveFilter::new().forbid_rows();
+    assert!(!allows(filter, "r4 1"));
+    assert!(allows(filter, "c1 1"));
+    let filter = filter.forbid_column(2);
+    assert_eq!("forbid rows forbid c3", filter.to_string());
+    assert_eq!(Ok(filter), filter.to_string().parse());
+    assert_eq!(Ok(MoveFilter::new()), "".parse());
+    for invalid in ["rows", "forbid", "forbid c13", "forbid r0", "allow c1"] {
+      assert!(invalid.parse::<MoveFilter>().is_err(), "{}", invalid);
+    }
+  }
+}
@@ -0,0 +1,89 @@
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::arena::Arena;
+use crate::position::Position;
+
+///Cells which all have to be occupied to solve an arena, like the magic circles in vellumental fights.
+/// Replaces the usual requirement of all enemies being attackable.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub struct ObjectivePattern {
+  cells: Vec<Position>,
+}
+
+impl ObjectivePattern {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn add(&mut self, position: Position) {
+    //keep cells sorted, so equal patterns are equal regardless of insertion order
+    if let Err(index) = self.cells.binary_search(&position) {
+      self.cells.insert(index, position);
+    }
+  }
+
+  pub fn remove(&mut self, position: &Position) {
+    self.cells.retain(|cell| cell != position);
+  }
+
+  pub fn cells(&self) -> &[Position] {
+    &self.cells
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.cells.is_empty()
+  }
+
+  pub fn is_fulfilled<E>(&self, arena: &Arena<E>) -> bool
+  where
+    E: Clone + Deref<Target = Position>,
+  {
+    self.cells.iter().all(|cell| arena.get_at(cell).is_some())
+  }
+}
+
+impl FromIterator<Position> for ObjectivePattern {
+  fn from_iter<T: IntoIterator<Item = Position>>(iter: T) -> Self {
+    let mut pattern = Self::new();
+    for position in iter {
+      pattern.add(position);
+    }
+    pattern
+  }
+}
+
+#[cfg(test)]
+mod test_objective {
+  use crate::command::parse_quietly;
+  use crate::position::Position;
+  use crate::solving::objective::ObjectivePattern;
+  use crate::solving::{SolvableArena, solve};
+
+  #[test]
+  fn test_fulfilled() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 12", "c5 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let pattern: ObjectivePattern = [Position::at(0, 1).unwrap(), Position::at(2, 4).unwrap()]
+      .into_iter()
+      .collect();
+    assert!(pattern.is_fulfilled(&arena));
+    let mut pattern = pattern;
+    pattern.add(Position::at(3, 4).unwrap());
+    assert!(!pattern.is_fulfilled(&arena));
+  }
+
+  #[test]
+  fn test_solve_objective() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 1", "target c3 1"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let solution = solve(&arena, 1, false, None, None).expect("is solvable");
+    assert_eq!("r1 1", solution[0].to_string());
+  }
+}
@@ -0,0 +1,182 @@
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use rayon::prelude::*;
+
+use crate::position::{Move, Num};
+use crate::solving::cancel::CancelToken;
+use crate::solving::lower_bound::lower_bound;
+use crate::solving::move_filter::MoveFilter;
+use crate::solving::solve_cache::SolveCache;
+use crate::solving::{Comparator, SolvableArena, allowed_moves, search};
+
+///Like the depth first search of [`super::solve`], but searches the branches of the first moves on
+/// all cpu cores. Every thread searches with a [fork](SolveCache::fork) of the cache, the forks are
+/// merged into it afterwards. Finds the same solution as the single threaded search, unless `fast`
+/// is true: then the first solution of any branch wins.
+pub fn solve_parallel(
+  arena: &SolvableArena,
+  in_turns: Num,
+  fast: bool,
+  filter: &MoveFilter,
+  comparator: Comparator,
+  cache: &mut SolveCache,
+  cancel: Option<&CancelToken>,
+) -> Option<Vec<Move>> {
+  let arena = arena.after_first_strike();
+  //without branching there is nothing to parallelize
+  if in_turns == 0 || arena.is_solved() || lower_bound(&arena) > in_turns {
+    return search(
+      &arena,
+      in_turns,
+      fast,
+      filter,
+      comparator,
+      cache,
+      cancel,
+      &mut (),
+    );
+  }
+
+  let moves: Vec<Move> = allowed_moves(&arena, filter).collect();
+  let template = cache.fork();
+  let found = AtomicBool::new(false);
+  let (forks, solution) = moves
+    .par_iter()
+    .enumerate()
+    .fold(
+      || (Vec::new(), template.fork(), None),
+      |(forks, mut fork, best), (index, &move_)| {
+        if fast && found.load(Ordering::Relaxed) {
+          return (forks, fork, best);
+        }
+        let mut next = arena.clone();
+        next.apply_move(move_);
+        let solution = search(
+          &next,
+          in_turns - 1,
+          fast,
+          filter,
+          comparator,
+          &mut fork,
+          cancel,
+          &mut (),
+        )
+        .map(|mut solution| {
+          solution.insert(0, move_);
+          found.store(true, Ordering::Relaxed);
+          (index, solution)
+        });
+        (forks, fork, pick_better(comparator, best, solution))
+      },
+    )
+    .map(|(mut forks, fork, best)| {
+      forks.push(fork);
+      (forks, best)
+    })
+    .reduce(
+      || (Vec::new(), None),
+      |(mut forks, best), (other_forks, other_best)| {
+        forks.extend(other_forks);
+        (forks, pick_better(comparator, best, other_best))
+      },
+    );
+
+  for fork in forks {
+    cache.merge(fork);
+  }
+  solution.map(|(_, solution)| solution)
+}
+
+///The better of two solutions found after the first moves with the indices. Like in the single
+/// threaded search, the solution after the earlier first move wins if both are equally good.
+fn pick_better(
+  comparator: Comparator,
+  solution: Option<(usize, Vec<Move>)>,
+  other: Option<(usize, Vec<Move>)>,
+) -> Option<(usize, Vec<Move>)> {
+  match (solution, other) {
+    (Some(solution), Some(other)) => {
+      let other_wins = comparator.is_better(&other.1, &solution.1)
+        || (!comparator.is_better(&solution.1, &other.1) && other.0 < solution.0);
+      Some(if other_wins { other } else { solution })
+    }
+    (solution, other) => solution.or(other),
+  }
+}
+
+#[cfg(test)]
+mod test_parallel {
+  use crate::command::parse_quietly;
+  use crate::solving::cancel::CancelToken;
+  use crate::solving::move_filter::MoveFilter;
+  use crate::solving::parallel::solve_parallel;
+  use crate::solving::solve_cache::SolveCache;
+  use crate::solving::{Comparator, SolvableArena, solve};
+
+  fn arena_with(cmds: &[&str]) -> SolvableArena {
+    let mut arena = SolvableArena::default();
+    for cmd in cmds {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    arena
+  }
+
+  #[test]
+  fn test_same_solution() {
+    let filter = MoveFilter::new();
+    for arena in [
+      arena_with(&["c2 124", "c3 3", "c4 2", "c5 123"]),
+      arena_with(&["c1 1234", "c7 1234"]),
+      arena_with(&["c2 1234"]),
+      SolvableArena::default(),
+    ] {
+      let mut cache = SolveCache::new();
+      let solution = solve_parallel(
+        &arena,
+        3,
+        false,
+        &filter,
+        Comparator::default(),
+        &mut cache,
+        None,
+      );
+      assert_eq!(solve(&arena, 3, false, None, None), solution);
+      assert!(!cache.is_empty());
+
+      let fast = solve_parallel(
+        &arena,
+        3,
+        true,
+        &filter,
+        Comparator::default(),
+        &mut cache,
+        None,
+      );
+      assert_eq!(solution.is_some(), fast.is_some());
+      if let Some(fast) = fast {
+        let mut solved = arena.clone();
+        fast.iter().for_each(|&move_| solved.apply_move(move_));
+        assert!(solved.is_solved());
+      }
+    }
+  }
+
+  #[test]
+  fn test_cancel() {
+    let arena = arena_with(&["c2 124", "c3 3", "c4 2", "c5 123"]);
+    let token = CancelToken::new();
+    token.cancel();
+    let mut cache = SolveCache::new();
+    let solution = solve_parallel(
+      &arena,
+      2,
+      false,
+      &MoveFilter::new(),
+      Comparator::default(),
+      &mut cache,
+      Some(&token),
+    );
+    assert_eq!(None, solution);
+  }
+}
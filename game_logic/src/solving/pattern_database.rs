@@ -0,0 +1,449 @@
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::path::Path;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+#[cfg(feature = "std")]
+use std::{env, fs, io};
+
+use crate::collections::{Map, Set};
+use crate::position::Num;
+use crate::position::Dimension::{Column, Row};
+use crate::solving::area_template::available_templates;
+use crate::solving::cell_permutation::{CellPermutation, NUM_CELLS, index_of, position_of};
+use crate::solving::{AvailableEquipment, Enemy, SolvableArena, TargetArea, all_moves};
+
+///The environment variable pointing to a file with the database [`PatternDatabase::global`] returns,
+/// as written by [`PatternDatabase::save`]
+pub const PATTERN_DATABASE_VAR: &str = "TOK_PATTERN_DATABASE";
+///The database for up to 8 enemies and 2 moves, generated with the `generate_pattern_database` binary
+#[cfg(feature = "pattern_db")]
+pub const DEFAULT_PATTERN_DATABASE: &[u8] = include_bytes!("../../data/pattern_database.bin");
+
+const MAGIC: &[u8; 4] = b"TPDB";
+const VERSION: u8 = 1;
+///the number of bytes needed to store a pattern, since the arena only has 48 cells
+const PATTERN_BYTES: usize = 6;
+
+///Knows the minimal number of moves needed to solve every arena with few enemies. Weaknesses are
+/// ignored, which makes arenas only easier to solve, so the stored distances are lower bounds for
+/// arenas with weaknesses as well. Patterns are stored as occupied cells, normalized over turning all
+/// rings at once, since that doesn't change how many moves are needed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PatternDatabase {
+  max_enemies: u8,
+  max_moves: Num,
+  distances: Map<u64, Num>,
+}
+
+impl PatternDatabase {
+  ///Computes the database for all arenas with up to `max_enemies` enemies which are solvable in up to
+  /// `max_moves` moves by searching backwards from all solved arenas
+  pub fn generate(max_enemies: u8, max_moves: Num) -> Self {
+    let max_groups = (max_enemies as usize).div_ceil(Row.size() as usize);
+    let solved = solved_patterns(&SolvableArena::default(), max_groups, |num_enemies| {
+      num_enemies <= max_enemies as u32
+    });
+
+    Self {
+      max_enemies,
+      max_moves,
+      distances: distances_from(solved, max_moves),
+    }
+  }
+
+  ///Returns a lower bound for the number of moves needed to solve the arena, or `None` if the arena
+  /// isn't covered by this database
+  pub fn lower_bound(&self, arena: &SolvableArena) -> Option<Num> {
+    let num_enemies = arena.enemies.len();
+    if num_enemies > self.max_enemies as usize || arena.objective.is_some() {
+      return None;
+    }
+    //the database was generated with the areas available with the default equipment
+    if !uses_default_areas(&arena.available_equipment) {
+      return None;
+    }
+    //the database was generated with the default number of groups
+    if arena
+      .num_groups
+      .is_some_and(|num_groups| num_groups as usize != num_enemies.div_ceil(Row.size() as usize))
+    {
+      return None;
+    }
+
+    let pattern = normalize(cells_of(arena));
+    Some(
+      self
+        .distances
+        .get(&pattern)
+        .copied()
+        .unwrap_or(self.max_moves + 1),
+    )
+  }
+
+  pub fn max_enemies(&self) -> u8 {
+    self.max_enemies
+  }
+
+  pub fn max_moves(&self) -> Num {
+    self.max_moves
+  }
+
+  pub fn len(&self) -> usize {
+    self.distances.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.distances.is_empty()
+  }
+
+  ///Encodes the database as a header followed by the number of patterns and the sorted patterns for
+  /// every distance
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(16 + self.len() * PATTERN_BYTES);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&[VERSION, self.max_enemies, self.max_moves]);
+    for distance in 0..=self.max_moves {
+      let mut patterns: Vec<u64> = self
+        .distances
+        .iter()
+        .filter(|(_, &d)| d == distance)
+        .map(|(&pattern, _)| pattern)
+        .collect();
+      patterns.sort_unstable();
+      bytes.extend_from_slice(&(patterns.len() as u32).to_le_bytes());
+      for pattern in patterns {
+        bytes.extend_from_slice(&pattern.to_le_bytes()[..PATTERN_BYTES]);
+      }
+    }
+    bytes
+  }
+
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, PatternDatabaseError> {
+    let mut reader = ByteReader(bytes);
+    if reader.take(MAGIC.len())? != MAGIC {
+      return Err(PatternDatabaseError::InvalidFormat("missing magic bytes"));
+    }
+    let [version, max_enemies, max_moves] = reader.take(3)? else {
+      unreachable!("took 3 bytes");
+    };
+    if *version != VERSION {
+      return Err(PatternDatabaseError::InvalidFormat("unsupported version"));
+    }
+
+    let mut distances = Map::new();
+    for distance in 0..=*max_moves {
+      let len = u32::from_le_bytes(reader.take(4)?.try_into().expect("took 4 bytes"));
+      for _ in 0..len {
+        let mut pattern = [0; 8];
+        pattern[..PATTERN_BYTES].copy_from_slice(reader.take(PATTERN_BYTES)?);
+        distances.insert(u64::from_le_bytes(pattern), distance);
+      }
+    }
+    if !reader.0.is_empty() {
+      return Err(PatternDatabaseError::InvalidFormat("trailing bytes"));
+    }
+
+    Ok(Self {
+      max_enemies: *max_enemies,
+      max_moves: *max_moves,
+      distances,
+    })
+  }
+
+  #[cfg(feature = "std")]
+  pub fn load<P>(path: P) -> Result<Self, PatternDatabaseError>
+  where
+    P: AsRef<Path>,
+  {
+    Self::from_bytes(&fs::read(path)?)
+  }
+
+  #[cfg(feature = "std")]
+  pub fn save<P>(&self, path: P) -> io::Result<()>
+  where
+    P: AsRef<Path>,
+  {
+    fs::write(path, self.to_bytes())
+  }
+
+  ///The database used by the solver. It is read on first use from the file
+  /// [`PATTERN_DATABASE_VAR`] points to if it is set, and from [`DEFAULT_PATTERN_DATABASE`]
+  /// otherwise. Without the `pattern_db` feature, there only is a database if the variable is set.
+  #[cfg(feature = "std")]
+  pub fn global() -> Option<&'static PatternDatabase> {
+    static GLOBAL: OnceLock<Option<PatternDatabase>> = OnceLock::new();
+    GLOBAL
+      .get_or_init(|| {
+        let Some(path) = env::var_os(PATTERN_DATABASE_VAR).filter(|path| !path.is_empty()) else {
+          return built_in();
+        };
+        Self::load(&path).map(Some).unwrap_or_else(|e| {
+          eprintln!(
+            "failed to load pattern database {:?}, using the built-in one: {}",
+            path, e
+          );
+          built_in()
+        })
+      })
+      .as_ref()
+  }
+}
+
+///Decodes [`DEFAULT_PATTERN_DATABASE`]
+#[cfg(feature = "pattern_db")]
+impl Default for PatternDatabase {
+  fn default() -> Self {
+    Self::from_bytes(DEFAULT_PATTERN_DATABASE).expect("default pattern database is valid")
+  }
+}
+
+#[cfg(feature = "pattern_db")]
+fn built_in() -> Option<PatternDatabase> {
+  Some(PatternDatabase::default())
+}
+
+#[cfg(all(feature = "std", not(feature = "pattern_db")))]
+fn built_in() -> Option<PatternDatabase> {
+  None
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+  fn take(&mut self, len: usize) -> Result<&'a [u8], PatternDatabaseError> {
+    if self.0.len() < len {
+      return Err(PatternDatabaseError::InvalidFormat(
+        "unexpected end of data",
+      ));
+    }
+    let (taken, rest) = self.0.split_at(len);
+    self.0 = rest;
+    Ok(taken)
+  }
+}
+
+pub(crate) fn cells_of(arena: &SolvableArena) -> u64 {
+  arena
+    .enemies
+    .iter()
+    .fold(0, |cells, enemy| cells | 1 << index_of(&enemy.position))
+}
+
+///Turns all rings at once so that the pattern becomes as small as possible
+pub(crate) fn normalize(cells: u64) -> u64 {
+  let ring_size = Column.size() as u32;
+  let ring_mask = (1 << ring_size) - 1;
+  (0..ring_size)
+    .map(|amount| {
+      (0..Row.size() as u32).fold(0, |rotated, row| {
+        let ring = (cells >> (row * ring_size)) & ring_mask;
+        let ring = ((ring << amount) | (ring >> ((ring_size - amount) % ring_size))) & ring_mask;
+        rotated | ring << (row * ring_size)
+      })
+    })
+    .min()
+    .expect("rings have columns")
+}
+
+fn uses_default_areas(equipment: &AvailableEquipment) -> bool {
+  let default_equipment = AvailableEquipment::default();
+  available_templates(*equipment).all(|template| (template.is_available)(&default_equipment))
+}
+
+///The minimal number of moves needed to solve every pattern which can be solved in up to
+/// `max_moves` moves, found by searching backwards from the solved patterns
+pub(crate) fn distances_from(solved: Set<u64>, max_moves: Num) -> Map<u64, Num> {
+  let mut distances = Map::new();
+  let mut level = solved;
+  let moves: Vec<CellPermutation> = all_moves().map(CellPermutation::of).collect();
+
+  for distance in 0..=max_moves {
+    for &pattern in &level {
+      distances.insert(pattern, distance);
+    }
+    if distance == max_moves {
+      break;
+    }
+
+    //moves can always be undone by another move, so moving away from solved arenas finds all
+    // arenas solvable in one more move
+    let mut next_level = Set::new();
+    for &pattern in &level {
+      for move_ in &moves {
+        let next = normalize(move_.apply_to_cells(pattern));
+        if !distances.contains_key(&next) {
+          next_level.insert(next);
+        }
+      }
+    }
+    level = next_level;
+  }
+  distances
+}
+
+///Finds all solved patterns with an accepted number of enemies by checking every subset of cells
+/// which can be covered by `max_groups` areas. Only the equipment and number of groups of `rules`
+/// are used.
+pub(crate) fn solved_patterns<F>(rules: &SolvableArena, max_groups: usize, accepts: F) -> Set<u64>
+where
+  F: Fn(u32) -> bool,
+{
+  let areas: Set<TargetArea> = (0..NUM_CELLS)
+    .flat_map(|index| {
+      let enemy = Enemy {
+        position: position_of(index),
+        required_attack: None,
+        hp: None,
+      };
+      available_templates(rules.available_equipment)
+        .flat_map(move |template| template.areas_covering(&enemy))
+    })
+    .collect();
+  let areas: Vec<u64> = areas
+    .into_iter()
+    .map(|area| {
+      (0..NUM_CELLS)
+        .filter(|&index| area.covers(&position_of(index)))
+        .fold(0, |cells, index| cells | 1 << index)
+    })
+    .collect();
+
+  let mut unions = Set::new();
+  collect_unions(&areas, max_groups, 0, &mut unions);
+
+  let mut solved = Set::new();
+  for union in unions {
+    let mut cells = union;
+    while cells != 0 {
+      if accepts(cells.count_ones()) {
+        let pattern = normalize(cells);
+        if !solved.contains(&pattern) && arena_of(cells, rules).is_solved() {
+          solved.insert(pattern);
+        }
+      }
+      cells = (cells - 1) & union;
+    }
+  }
+  //an empty arena is solved as well
+  if accepts(0) {
+    solved.insert(0);
+  }
+  solved
+}
+
+fn collect_unions(areas: &[u64], remaining_groups: usize, cells: u64, unions: &mut Set<u64>) {
+  if cells != 0 {
+    unions.insert(cells);
+  }
+  if remaining_groups == 0 {
+    return;
+  }
+  for (index, area) in areas.iter().enumerate() {
+    collect_unions(
+      &areas[index + 1..],
+      remaining_groups - 1,
+      cells | area,
+      unions,
+    );
+  }
+}
+
+fn arena_of(cells: u64, rules: &SolvableArena) -> SolvableArena {
+  let mut arena = SolvableArena {
+    num_groups: rules.num_groups,
+    available_equipment: rules.available_equipment,
+    ..SolvableArena::default()
+  };
+  let mut remaining = cells;
+  while remaining != 0 {
+    arena.add(Enemy {
+      position: position_of(remaining.trailing_zeros() as usize),
+      required_attack: None,
+      hp: None,
+    });
+    remaining &= remaining - 1;
+  }
+  arena
+}
+
+#[derive(Debug)]
+pub enum PatternDatabaseError {
+  #[cfg(feature = "std")]
+  IO(io::Error),
+  InvalidFormat(&'static str),
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for PatternDatabaseError {
+  fn from(value: io::Error) -> Self {
+    Self::IO(value)
+  }
+}
+
+impl Display for PatternDatabaseError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      #[cfg(feature = "std")]
+      PatternDatabaseError::IO(e) => write!(f, "IO error: {}", e),
+      PatternDatabaseError::InvalidFormat(reason) => write!(f, "Invalid format: {}", reason),
+    }
+  }
+}
+
+impl Error for PatternDatabaseError {}
+
+#[cfg(test)]
+mod test_pattern_database {
+  use crate::command::parse_quietly;
+  use crate::solving::SolvableArena;
+  use crate::solving::pattern_database::PatternDatabase;
+
+  fn arena(cmds: &[&str]) -> SolvableArena {
+    let mut arena = SolvableArena::default();
+    for cmd in cmds {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    arena
+  }
+
+  #[test]
+  fn test_lower_bound() {
+    let database = PatternDatabase::generate(4, 1);
+
+    assert_eq!(Some(0), database.lower_bound(&arena(&["c2 1234"])));
+    assert_eq!(Some(1), database.lower_bound(&arena(&["c2 124", "c3 3"])));
+    //weaknesses are ignored
+    assert_eq!(Some(1), database.lower_bound(&arena(&["c2 124 J", "c3 3"])));
+    assert_eq!(
+      Some(2),
+      database.lower_bound(&arena(&["c1 1", "c4 2", "c7 3", "c10 4"]))
+    );
+    assert_eq!(None, database.lower_bound(&arena(&["c2 1234", "c3 1"])));
+  }
+
+  #[test]
+  fn test_bytes() {
+    let database = PatternDatabase::generate(4, 1);
+    assert_eq!(
+      database,
+      PatternDatabase::from_bytes(&database.to_bytes()).expect("failed to decode")
+    );
+  }
+
+  #[cfg(feature = "pattern_db")]
+  #[test]
+  fn test_default() {
+    let database = PatternDatabase::default();
+    assert_eq!(8, database.max_enemies());
+    assert_eq!(2, database.max_moves());
+    assert_eq!(Some(1), database.lower_bound(&arena(&["c2 124", "c3 3"])));
+    //unknown patterns need more moves than the database knows
+    assert_eq!(
+      Some(3),
+      database.lower_bound(&arena(&["c1 1", "c4 2", "c7 3", "c10 4"]))
+    );
+  }
+}
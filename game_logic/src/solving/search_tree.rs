@@ -0,0 +1,222 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::position::{Move, Num};
+use crate::solving::move_filter::MoveFilter;
+use crate::solving::solve_cache::SolveCache;
+use crate::solving::{Comparator, SolvableArena, search};
+
+///Gets told about every arena [`search`] visits
+pub(crate) trait SearchTracer {
+  ///Called before an arena gets searched, with the move which lead to it
+  fn node(&mut self, _move_: Option<Move>, _remaining_turns: Num) {}
+
+  ///Called when the search stops at the current arena without trying further moves
+  fn outcome(&mut self, _outcome: NodeOutcome) {}
+
+  ///Called after the current arena has been searched
+  fn finish(&mut self, _solved: bool) {}
+}
+
+impl SearchTracer for () {}
+
+///Why the search did or didn't try further moves at an arena
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NodeOutcome {
+  ///all moves have been tried
+  Expanded,
+  Solved,
+  ///the arena isn't solved and there are no turns left
+  OutOfTurns,
+  ///the arena is known to need more moves than there are turns left, see
+  /// [`super::lower_bound::lower_bound`]
+  PrunedByLowerBound(Num),
+}
+
+impl NodeOutcome {
+  pub fn name(&self) -> &'static str {
+    match self {
+      NodeOutcome::Expanded => "expanded",
+      NodeOutcome::Solved => "solved",
+      NodeOutcome::OutOfTurns => "out of turns",
+      NodeOutcome::PrunedByLowerBound(_) => "pruned by lower bound",
+    }
+  }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SearchNode {
+  ///the index of the parent node, `None` for the root
+  pub parent: Option<usize>,
+  ///the move leading from the parent to this node
+  pub move_: Option<Move>,
+  pub remaining_turns: Num,
+  pub outcome: NodeOutcome,
+  ///whether a solution has been found from this node
+  pub solved: bool,
+}
+
+///Every arena the depth first search visited while solving, see [`trace_solve`]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct SearchTree {
+  nodes: Vec<SearchNode>,
+  path: Vec<usize>,
+}
+
+impl SearchTree {
+  ///The visited nodes in the order they were visited, so the root comes first
+  pub fn nodes(&self) -> &[SearchNode] {
+    &self.nodes
+  }
+
+  ///Describes the tree in the graphviz dot language
+  pub fn to_dot(&self) -> String {
+    let mut dot = String::from("digraph search_tree {\n");
+    for (index, node) in self.nodes.iter().enumerate() {
+      let color = match (node.outcome, node.solved) {
+        (_, true) => "green",
+        (NodeOutcome::PrunedByLowerBound(_), _) => "red",
+        _ => "gray",
+      };
+      let _ = writeln!(
+        dot,
+        "  n{} [label=\"{}\\n{}\", color={}];",
+        index,
+        node.move_.map_or("start".to_string(), |m| m.to_string()),
+        describe(node),
+        color
+      );
+      if let Some(parent) = node.parent {
+        let _ = writeln!(dot, "  n{} -> n{};", parent, index);
+      }
+    }
+    dot.push_str("}\n");
+    dot
+  }
+
+  ///Describes the tree as a json object with a list of nodes, which refer to their parent by index
+  pub fn to_json(&self) -> String {
+    let nodes: Vec<String> = self
+      .nodes
+      .iter()
+      .map(|node| {
+        format!(
+          "{{\"parent\":{},\"move\":{},\"remaining_turns\":{},\"outcome\":\"{}\",\"lower_bound\":{},\"solved\":{}}}",
+          node.parent.map_or("null".to_string(), |parent| parent.to_string()),
+          node
+            .move_
+            .map_or("null".to_string(), |m| format!("\"{}\"", m)),
+          node.remaining_turns,
+          node.outcome.name(),
+          match node.outcome {
+            NodeOutcome::PrunedByLowerBound(lower_bound) => lower_bound.to_string(),
+            _ => "null".to_string(),
+          },
+          node.solved
+        )
+      })
+      .collect();
+    format!("{{\"nodes\":[{}]}}", nodes.join(","))
+  }
+}
+
+fn describe(node: &SearchNode) -> String {
+  match node.outcome {
+    NodeOutcome::PrunedByLowerBound(lower_bound) => {
+      format!("needs {} > {} turns", lower_bound, node.remaining_turns)
+    }
+    outcome => format!("{} ({} turns left)", outcome.name(), node.remaining_turns),
+  }
+}
+
+impl SearchTracer for SearchTree {
+  fn node(&mut self, move_: Option<Move>, remaining_turns: Num) {
+    self.path.push(self.nodes.len());
+    self.nodes.push(SearchNode {
+      parent: self.path.iter().rev().nth(1).copied(),
+      move_,
+      remaining_turns,
+      outcome: NodeOutcome::Expanded,
+      solved: false,
+    });
+  }
+
+  fn outcome(&mut self, outcome: NodeOutcome) {
+    if let Some(&current) = self.path.last() {
+      self.nodes[current].outcome = outcome;
+    }
+  }
+
+  fn finish(&mut self, solved: bool) {
+    if let Some(current) = self.path.pop() {
+      self.nodes[current].solved = solved;
+    }
+  }
+}
+
+///Solves like [`super::solve`] without a shared cache and records every visited arena. Meant to
+/// understand and tune the search, since the tree grows by ~100 nodes per level.
+pub fn trace_solve(
+  arena: &SolvableArena,
+  in_turns: Num,
+  fast: bool,
+) -> (Option<Vec<Move>>, SearchTree) {
+  let mut tree = SearchTree::default();
+  let arena = arena.after_first_strike();
+  tree.node(None, in_turns);
+  let solution = search(
+    &arena,
+    in_turns,
+    fast,
+    &MoveFilter::new(),
+    Comparator::default(),
+    &mut SolveCache::new(),
+    None,
+    &mut tree,
+  );
+  tree.finish(solution.is_some());
+  (solution, tree)
+}
+
+#[cfg(test)]
+mod test_search_tree {
+  use crate::command::parse_quietly;
+  use crate::solving::search_tree::{NodeOutcome, trace_solve};
+  use crate::solving::{SolvableArena, all_moves, solve};
+
+  #[test]
+  fn test_trace() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let (solution, tree) = trace_solve(&arena, 1, false);
+    assert_eq!(solve(&arena, 1, false, None, None), solution);
+    let nodes = tree.nodes();
+    assert_eq!(1 + all_moves().count(), nodes.len());
+    assert_eq!(None, nodes[0].parent);
+    assert!(nodes[0].solved);
+    assert!(nodes[1..].iter().all(|node| node.parent == Some(0)));
+    assert!(nodes[1..]
+      .iter()
+      .all(|node| node.solved == (node.outcome == NodeOutcome::Solved)));
+  }
+
+  #[test]
+  fn test_export() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+
+    let (_, tree) = trace_solve(&arena, 1, true);
+    let dot = tree.to_dot();
+    assert!(dot.starts_with("digraph"));
+    assert!(dot.contains("n0 -> n1;"));
+    let json = tree.to_json();
+    assert!(json.starts_with("{\"nodes\":[{\"parent\":null,\"move\":null,"));
+  }
+}
@@ -0,0 +1,203 @@
+use core::mem;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::collections::Map;
+use crate::solving::SolvableArena;
+
+///Remembers whether arenas are solved. Holds at most a configurable amount of entries by aging them in
+/// two generations: once the young generation is full it replaces the old one, so everything which
+/// hasn't been looked up since gets evicted.
+#[derive(Debug, Clone)]
+pub struct SolveCache {
+  entry_limit: usize,
+  young: Map<SolvableArena, bool>,
+  old: Map<SolvableArena, bool>,
+  stats: SolveStats,
+  ///counts every lookup so other threads can watch the progress of a solve
+  #[cfg(feature = "std")]
+  lookup_counter: Option<Arc<AtomicU64>>,
+}
+
+impl SolveCache {
+  ///The entry limit used by [`SolveCache::default`]
+  pub const DEFAULT_ENTRY_LIMIT: usize = 1 << 19;
+
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  ///Creates a cache holding at most `entry_limit` entries (at least 2)
+  pub fn with_entry_limit(entry_limit: usize) -> Self {
+    Self {
+      entry_limit: entry_limit.max(2),
+      young: Map::new(),
+      old: Map::new(),
+      stats: SolveStats::default(),
+      #[cfg(feature = "std")]
+      lookup_counter: None,
+    }
+  }
+
+  pub fn entry_limit(&self) -> usize {
+    self.entry_limit
+  }
+
+  ///Makes every following lookup increment the counter, or stops counting when given `None`. Every
+  /// arena the solver looks at is looked up, so the counter tells how many arenas have been searched.
+  #[cfg(feature = "std")]
+  pub fn count_lookups_in(&mut self, counter: Option<Arc<AtomicU64>>) {
+    self.lookup_counter = counter;
+  }
+
+  pub fn get(&mut self, arena: &SolvableArena) -> Option<bool> {
+    #[cfg(feature = "std")]
+    if let Some(counter) = &self.lookup_counter {
+      counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    if let Some(&solved) = self.young.get(arena) {
+      self.stats.cache_hits += 1;
+      return Some(solved);
+    }
+
+    if let Some((arena, solved)) = self.old.remove_entry(arena) {
+      //promote to young generation since it is still in use
+      self.stats.cache_hits += 1;
+      self.insert_young(arena, solved);
+      return Some(solved);
+    }
+
+    self.stats.cache_misses += 1;
+    None
+  }
+
+  pub fn insert(&mut self, arena: SolvableArena, solved: bool) {
+    self.old.remove(&arena);
+    self.insert_young(arena, solved);
+  }
+
+  fn insert_young(&mut self, arena: SolvableArena, solved: bool) {
+    if self.young.len() >= self.entry_limit / 2 {
+      let old = mem::replace(&mut self.old, mem::take(&mut self.young));
+      self.stats.cache_evictions += old.len() as u64;
+    }
+    self.young.insert(arena, solved);
+  }
+
+  pub fn len(&self) -> usize {
+    self.young.len() + self.old.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  pub fn clear(&mut self) {
+    self.young.clear();
+    self.old.clear();
+  }
+
+  ///An empty cache with the same entry limit, counting its lookups in the same counter. Lets
+  /// threads search with caches of their own, which are [merged](SolveCache::merge) afterwards.
+  pub fn fork(&self) -> Self {
+    Self {
+      #[cfg(feature = "std")]
+      lookup_counter: self.lookup_counter.clone(),
+      ..Self::with_entry_limit(self.entry_limit)
+    }
+  }
+
+  ///Takes over the entries and statistics of another cache, e.g. a [forked](SolveCache::fork) one
+  pub fn merge(&mut self, other: SolveCache) {
+    self.stats.cache_hits += other.stats.cache_hits;
+    self.stats.cache_misses += other.stats.cache_misses;
+    self.stats.cache_evictions += other.stats.cache_evictions;
+    //the young entries of the other cache are inserted last, so they are the ones most likely kept
+    for (arena, solved) in other.old.into_iter().chain(other.young) {
+      self.insert(arena, solved);
+    }
+  }
+
+  pub fn stats(&self) -> SolveStats {
+    SolveStats {
+      cache_entries: self.len(),
+      ..self.stats
+    }
+  }
+}
+
+impl Default for SolveCache {
+  fn default() -> Self {
+    Self::with_entry_limit(Self::DEFAULT_ENTRY_LIMIT)
+  }
+}
+
+///Statistics about a solve
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct SolveStats {
+  pub cache_hits: u64,
+  pub cache_misses: u64,
+  pub cache_evictions: u64,
+  ///the number of entries currently held by the cache
+  pub cache_entries: usize,
+}
+
+#[cfg(test)]
+mod test_solve_cache {
+  use crate::command::parse_quietly;
+  use crate::solving::SolvableArena;
+  use crate::solving::solve_cache::SolveCache;
+
+  fn arena_with(cmd: &str) -> SolvableArena {
+    let mut arena = SolvableArena::default();
+    parse_quietly(&mut arena, cmd).expect("parse error");
+    arena
+  }
+
+  #[test]
+  fn test_bounded() {
+    let mut cache = SolveCache::with_entry_limit(4);
+    for column in 1..=12 {
+      cache.insert(arena_with(&format!("c{} 1", column)), false);
+      assert!(cache.len() <= 4);
+    }
+
+    let stats = cache.stats();
+    assert_eq!(cache.len(), stats.cache_entries);
+    assert_eq!(12, stats.cache_entries as u64 + stats.cache_evictions);
+  }
+
+  #[test]
+  fn test_used_entries_survive() {
+    let mut cache = SolveCache::with_entry_limit(4);
+    let kept = arena_with("c1 1");
+    cache.insert(kept.clone(), true);
+    for column in 2..=12 {
+      assert_eq!(Some(true), cache.get(&kept));
+      cache.insert(arena_with(&format!("c{} 1", column)), false);
+    }
+
+    assert_eq!(Some(true), cache.get(&kept));
+    assert_eq!(None, cache.get(&arena_with("c2 1")));
+    assert_eq!(1, cache.stats().cache_misses);
+  }
+
+  #[test]
+  fn test_merge() {
+    let mut cache = SolveCache::with_entry_limit(8);
+    cache.insert(arena_with("c1 1"), false);
+    let mut fork = cache.fork();
+    assert!(fork.is_empty());
+    assert_eq!(8, fork.entry_limit());
+
+    fork.insert(arena_with("c2 1"), true);
+    assert_eq!(None, fork.get(&arena_with("c1 1")));
+    cache.merge(fork);
+    assert_eq!(2, cache.len());
+    assert_eq!(Some(true), cache.get(&arena_with("c2 1")));
+    assert_eq!(1, cache.stats().cache_misses);
+  }
+}
@@ -0,0 +1,439 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+
+use crate::position::{Move, Num};
+use crate::solving::bidirectional::solve_bidirectional;
+use crate::solving::cancel::CancelToken;
+use crate::solving::lower_bound::lower_bound;
+use crate::solving::move_filter::MoveFilter;
+#[cfg(feature = "parallel")]
+use crate::solving::parallel::solve_parallel;
+use crate::solving::solve_cache::SolveCache;
+use crate::solving::{Comparator, SolvableArena, SolveParams, allowed_moves, search};
+
+///An algorithm which solves an arena within a fixed number of turns. Implementations are listed in
+/// [`STRATEGIES`], so front-ends can choose them by name.
+pub trait SolverStrategy: Sync {
+  ///The name front-ends use to select the strategy
+  fn name(&self) -> &'static str;
+
+  ///A one line description for help texts
+  fn description(&self) -> &'static str;
+
+  ///Solves the arena in at most `in_turns` turns. Unless [`SolveParams::stop_at_first`] is true, the
+  /// solution has to be the best one in the order of [`SolveParams::comparator`]. Once the
+  /// [`CancelToken`] is cancelled, the search has to give up with `None`.
+  fn solve(
+    &self,
+    arena: &SolvableArena,
+    in_turns: Num,
+    params: &SolveParams,
+    cache: &mut SolveCache,
+    cancel: Option<&CancelToken>,
+  ) -> Option<Vec<Move>>;
+}
+
+///Every strategy which can be chosen by name, the first one is the default
+pub static STRATEGIES: &[&dyn SolverStrategy] = &[
+  &DepthFirst,
+  &FastDepthFirst,
+  &IterativeDeepening,
+  &Bidirectional,
+  &HeuristicOrdered,
+  #[cfg(feature = "parallel")]
+  &ParallelDepthFirst,
+];
+
+///Tries every sequence of moves, see [`super::solve`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct DepthFirst;
+
+impl SolverStrategy for DepthFirst {
+  fn name(&self) -> &'static str {
+    "dfs"
+  }
+
+  fn description(&self) -> &'static str {
+    "tries every sequence of moves"
+  }
+
+  fn solve(
+    &self,
+    arena: &SolvableArena,
+    in_turns: Num,
+    params: &SolveParams,
+    cache: &mut SolveCache,
+    cancel: Option<&CancelToken>,
+  ) -> Option<Vec<Move>> {
+    search(
+      arena,
+      in_turns,
+      params.stop_at_first(),
+      &params.move_filter,
+      params.comparator,
+      cache,
+      cancel,
+      &mut (),
+    )
+  }
+}
+
+///Like [`DepthFirst`], but always stops at the first solution unless the solve is deterministic
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct FastDepthFirst;
+
+impl SolverStrategy for FastDepthFirst {
+  fn name(&self) -> &'static str {
+    "fast-dfs"
+  }
+
+  fn description(&self) -> &'static str {
+    "stops at the first solution, which may not be optimal"
+  }
+
+  fn solve(
+    &self,
+    arena: &SolvableArena,
+    in_turns: Num,
+    params: &SolveParams,
+    cache: &mut SolveCache,
+    cancel: Option<&CancelToken>,
+  ) -> Option<Vec<Move>> {
+    search(
+      arena,
+      in_turns,
+      !params.deterministic,
+      &params.move_filter,
+      params.comparator,
+      cache,
+      cancel,
+      &mut (),
+    )
+  }
+}
+
+///Searches depth first with an increasing number of turns, so even a fast solve returns a solution
+/// with as few moves as possible
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct IterativeDeepening;
+
+impl SolverStrategy for IterativeDeepening {
+  fn name(&self) -> &'static str {
+    "iterative"
+  }
+
+  fn description(&self) -> &'static str {
+    "finds the shortest solution first, also when solving fast"
+  }
+
+  fn solve(
+    &self,
+    arena: &SolvableArena,
+    in_turns: Num,
+    params: &SolveParams,
+    cache: &mut SolveCache,
+    cancel: Option<&CancelToken>,
+  ) -> Option<Vec<Move>> {
+    (0..=in_turns).find_map(|depth| {
+      search(
+        arena,
+        depth,
+        params.stop_at_first(),
+        &params.move_filter,
+        params.comparator,
+        &mut *cache,
+        cancel,
+        &mut (),
+      )
+    })
+  }
+}
+
+///Meets in the middle, see [`solve_bidirectional`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Bidirectional;
+
+impl SolverStrategy for Bidirectional {
+  fn name(&self) -> &'static str {
+    "bidirectional"
+  }
+
+  fn description(&self) -> &'static str {
+    "meets in the middle, faster for arenas needing 4 or more turns"
+  }
+
+  fn solve(
+    &self,
+    arena: &SolvableArena,
+    in_turns: Num,
+    params: &SolveParams,
+    cache: &mut SolveCache,
+    cancel: Option<&CancelToken>,
+  ) -> Option<Vec<Move>> {
+    solve_bidirectional(
+      arena,
+      in_turns,
+      params.stop_at_first(),
+      &params.move_filter,
+      params.comparator,
+      cache,
+      cancel,
+    )
+  }
+}
+
+///Searches depth first, but tries the moves which look closest to a solution first. Only pays off
+/// when solving fast, since an exhaustive search visits every move anyway.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct HeuristicOrdered;
+
+impl SolverStrategy for HeuristicOrdered {
+  fn name(&self) -> &'static str {
+    "heuristic"
+  }
+
+  fn description(&self) -> &'static str {
+    "tries promising moves first, finds a solution sooner when solving fast"
+  }
+
+  fn solve(
+    &self,
+    arena: &SolvableArena,
+    in_turns: Num,
+    params: &SolveParams,
+    cache: &mut SolveCache,
+    cancel: Option<&CancelToken>,
+  ) -> Option<Vec<Move>> {
+    search_ordered(
+      arena,
+      in_turns,
+      params.stop_at_first(),
+      &params.move_filter,
+      params.comparator,
+      cache,
+      cancel,
+    )
+  }
+}
+
+///Like [`DepthFirst`], but searches the branches of the first moves on all cpu cores, see
+/// [`solve_parallel`]
+#[cfg(feature = "parallel")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ParallelDepthFirst;
+
+#[cfg(feature = "parallel")]
+impl SolverStrategy for ParallelDepthFirst {
+  fn name(&self) -> &'static str {
+    "parallel"
+  }
+
+  fn description(&self) -> &'static str {
+    "tries every sequence of moves on all cpu cores"
+  }
+
+  fn solve(
+    &self,
+    arena: &SolvableArena,
+    in_turns: Num,
+    params: &SolveParams,
+    cache: &mut SolveCache,
+    cancel: Option<&CancelToken>,
+  ) -> Option<Vec<Move>> {
+    solve_parallel(
+      arena,
+      in_turns,
+      params.stop_at_first(),
+      &params.move_filter,
+      params.comparator,
+      cache,
+      cancel,
+    )
+  }
+}
+
+fn search_ordered(
+  arena: &SolvableArena,
+  in_turns: Num,
+  fast: bool,
+  filter: &MoveFilter,
+  comparator: Comparator,
+  cache: &mut SolveCache,
+  cancel: Option<&CancelToken>,
+) -> Option<Vec<Move>> {
+  if cancel.is_some_and(CancelToken::is_cancelled) {
+    return None;
+  }
+  let solved = match cache.get(arena) {
+    Some(solved) => solved,
+    None => {
+      let solved = arena.is_solved();
+      cache.insert(arena.clone(), solved);
+      solved
+    }
+  };
+  if solved {
+    return Some(vec![]);
+  }
+  if in_turns == 0 {
+    return None;
+  }
+
+  let mut children: Vec<((Num, usize), Move, SolvableArena)> = allowed_moves(arena, filter)
+    .map(|move_| {
+      let mut child = arena.clone();
+      child.apply_move(move_);
+      (estimate(&child), move_, child)
+    })
+    .collect();
+  //stable, so equally promising moves keep the order of `all_moves`
+  children.sort_by_key(|&(estimate, _, _)| estimate);
+
+  let mut best_solution: Option<Vec<Move>> = None;
+  for ((lower_bound, _), move_, child) in children {
+    if lower_bound >= in_turns {
+      continue;
+    }
+    if let Some(mut solution) = search_ordered(
+      &child,
+      in_turns - 1,
+      fast,
+      filter,
+      comparator,
+      &mut *cache,
+      cancel,
+    ) {
+      solution.insert(0, move_);
+      if fast {
+        return Some(solution);
+      }
+      if best_solution
+        .as_ref()
+        .is_none_or(|current_best| comparator.is_better(&solution, current_best))
+      {
+        best_solution = Some(solution);
+      }
+    }
+  }
+  best_solution
+}
+
+///Lower is more promising: the lower bound of moves still needed, then the number of columns holding
+/// enemies, since enemies have to be lined up to be attacked together
+fn estimate(arena: &SolvableArena) -> (Num, usize) {
+  let mut columns: Vec<Num> = arena.enemies.iter().map(|enemy| enemy.column).collect();
+  columns.sort_unstable();
+  columns.dedup();
+  (lower_bound(arena), columns.len())
+}
+
+///A handle to one of the [`STRATEGIES`], which can be copied into [`SolveParams`]. Strategies are
+/// identified by name.
+#[derive(Copy, Clone)]
+pub struct Strategy(&'static dyn SolverStrategy);
+
+impl Strategy {
+  pub const DEPTH_FIRST: Strategy = Strategy(&DepthFirst);
+  pub const BIDIRECTIONAL: Strategy = Strategy(&Bidirectional);
+
+  ///Wraps a strategy which isn't part of [`STRATEGIES`]
+  pub const fn new(strategy: &'static dyn SolverStrategy) -> Self {
+    Self(strategy)
+  }
+
+  ///All strategies in the registry
+  pub fn all() -> impl Iterator<Item = Strategy> {
+    STRATEGIES.iter().map(|&strategy| Strategy(strategy))
+  }
+
+  pub fn by_name(name: &str) -> Option<Self> {
+    Self::all().find(|strategy| strategy.name() == name)
+  }
+
+  pub fn name(&self) -> &'static str {
+    self.0.name()
+  }
+
+  pub fn description(&self) -> &'static str {
+    self.0.description()
+  }
+
+  pub fn solver(&self) -> &'static dyn SolverStrategy {
+    self.0
+  }
+}
+
+impl Default for Strategy {
+  fn default() -> Self {
+    Strategy(STRATEGIES[0])
+  }
+}
+
+impl PartialEq for Strategy {
+  fn eq(&self, other: &Self) -> bool {
+    self.name() == other.name()
+  }
+}
+
+impl Eq for Strategy {}
+
+impl Hash for Strategy {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.name().hash(state)
+  }
+}
+
+impl Debug for Strategy {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.debug_tuple("Strategy").field(&self.name()).finish()
+  }
+}
+
+impl Display for Strategy {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+#[cfg(test)]
+mod test_strategy {
+  use crate::command::parse_quietly;
+  use crate::solving::solve_cache::SolveCache;
+  use crate::solving::strategy::{STRATEGIES, Strategy};
+  use crate::solving::{SolvableArena, SolveParams, solve, solve_with};
+
+  #[test]
+  fn test_registry() {
+    for strategy in Strategy::all() {
+      assert_eq!(Some(strategy), Strategy::by_name(strategy.name()));
+    }
+    assert_eq!(STRATEGIES.len(), Strategy::all().count());
+    assert_eq!(Strategy::DEPTH_FIRST, Strategy::default());
+    assert_eq!(None, Strategy::by_name("bogo"));
+  }
+
+  #[test]
+  fn test_all_strategies_solve() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3", "c4 2", "c5 123"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    let optimal = solve(&arena, 2, false, None, None).expect("is solvable");
+
+    for strategy in Strategy::all() {
+      let exhaustive = SolveParams::new().in_turns(2).strategy(strategy);
+      let solution = solve_with(&arena, &exhaustive, &mut SolveCache::new(), None, |_| true);
+      assert_eq!(Some(&optimal), solution.as_ref(), "{}", strategy);
+
+      let fast = exhaustive.fast(true);
+      let solution = solve_with(&arena, &fast, &mut SolveCache::new(), None, |_| true)
+        .unwrap_or_else(|| panic!("{} found no solution", strategy));
+      assert!(solution.len() <= 2);
+      let mut solved = arena.clone();
+      solution.iter().for_each(|&move_| solved.apply_move(move_));
+      assert!(solved.is_solved(), "{}", strategy);
+    }
+  }
+}
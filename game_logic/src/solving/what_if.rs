@@ -0,0 +1,141 @@
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::position::{Move, Position};
+use crate::solving::solve_cache::SolveCache;
+use crate::solving::{RequiredAttack, SolvableArena, SolveParams, solve_with};
+
+///A hypothetical change of an enemy, which [`what_if`] compares the arena with
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Edit {
+  ///the enemy is gone, like after a first strike defeating it
+  Remove(Position),
+  ///the enemy needs another attack, or none
+  Convert(Position, Option<RequiredAttack>),
+}
+
+impl Edit {
+  pub fn position(&self) -> Position {
+    match self {
+      Edit::Remove(position) | Edit::Convert(position, _) => *position,
+    }
+  }
+
+  ///Changes the enemy in the arena. Returns false if there is no enemy at the position.
+  pub fn apply(&self, arena: &mut SolvableArena) -> bool {
+    match self {
+      Edit::Remove(position) if arena.get_at(position).is_some() => arena.remove(position),
+      Edit::Convert(position, required_attack) => match arena.get_at_mut(position) {
+        Some(enemy) => enemy.required_attack = *required_attack,
+        None => return false,
+      },
+      Edit::Remove(_) => return false,
+    }
+    true
+  }
+}
+
+///Writes the edit like `remove c3r2` or `convert c3r2 to J`
+impl Display for Edit {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Edit::Remove(position) => write!(f, "remove {}", position),
+      Edit::Convert(position, Some(required_attack)) => {
+        write!(f, "convert {} to {}", position, required_attack.symbol())
+      }
+      Edit::Convert(position, None) => write!(f, "convert {} to a regular enemy", position),
+    }
+  }
+}
+
+///The best solutions of an arena with and without an [`Edit`]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct WhatIf {
+  pub before: Option<Vec<Move>>,
+  pub after: Option<Vec<Move>>,
+}
+
+impl WhatIf {
+  ///How many turns fewer the arena takes with the edit, negative if it takes more. None unless
+  /// both arenas are solvable.
+  pub fn turns_saved(&self) -> Option<isize> {
+    Some(self.before.as_ref()?.len() as isize - self.after.as_ref()?.len() as isize)
+  }
+}
+
+///Solves the arena as it is and with the edit, leaving the arena as it is. Returns None if there is
+/// no enemy to edit.
+pub fn what_if(
+  arena: &SolvableArena,
+  edit: Edit,
+  params: &SolveParams,
+  cache: &mut SolveCache,
+) -> Option<WhatIf> {
+  let mut edited = arena.clone();
+  if !edit.apply(&mut edited) {
+    return None;
+  }
+  //an enemy attacked before the fight can't be attacked anymore once it is removed
+  if edited.first_strike == Some(edit.position()) && matches!(edit, Edit::Remove(_)) {
+    edited.first_strike = None;
+  }
+  Some(WhatIf {
+    before: solve_with(arena, params, cache, None, |_| true),
+    after: solve_with(&edited, params, cache, None, |_| true),
+  })
+}
+
+#[cfg(test)]
+mod test_what_if {
+  use crate::command::parse_quietly;
+  use crate::position::Position;
+  use crate::solving::solve_cache::SolveCache;
+  use crate::solving::what_if::{Edit, what_if};
+  use crate::solving::{RequiredAttack, SolvableArena, SolveParams};
+
+  #[test]
+  fn test_remove() {
+    let mut arena = SolvableArena::default();
+    for cmd in ["c2 124", "c3 3"] {
+      parse_quietly(&mut arena, cmd).expect("parse error");
+    }
+    let before = arena.clone();
+
+    let edit = Edit::Remove(Position::at(2, 2).unwrap());
+    let removed = what_if(&arena, edit, &SolveParams::new(), &mut SolveCache::new()).unwrap();
+    assert_eq!(Some(0), removed.after.as_ref().map(Vec::len));
+    assert!(removed.turns_saved().unwrap() > 0);
+    assert_eq!(before, arena);
+    assert_eq!("remove c3r3", edit.to_string());
+
+    let empty = Edit::Remove(Position::at(0, 0).unwrap());
+    assert_eq!(
+      None,
+      what_if(&arena, empty, &SolveParams::new(), &mut SolveCache::new())
+    );
+  }
+
+  #[test]
+  fn test_convert() {
+    let mut arena = SolvableArena::default();
+    parse_quietly(&mut arena, "c2 1234").expect("parse error");
+
+    let position = Position::at(0, 1).unwrap();
+    let edit = Edit::Convert(position, Some(RequiredAttack::Jump));
+    let mut edited = arena.clone();
+    assert!(edit.apply(&mut edited));
+    assert_eq!(
+      Some(RequiredAttack::Jump),
+      edited.get_at(&position).unwrap().required_attack
+    );
+    assert_eq!(4, edited.enemies.len());
+    assert_eq!("convert c2r1 to J", edit.to_string());
+
+    let converted = what_if(&arena, edit, &SolveParams::new(), &mut SolveCache::new()).unwrap();
+    assert_eq!(Some(vec![]), converted.before);
+    assert_eq!(
+      converted.turns_saved(),
+      converted.after.map(|after| -(after.len() as isize))
+    );
+  }
+}
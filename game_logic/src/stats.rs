@@ -0,0 +1,266 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::fs::OpenOptions;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::data_dir::{create_parent, data_file};
+use crate::solving::SolvableArena;
+
+///The environment variable pointing to the file statistics are stored in
+pub const STATS_VAR: &str = "TOK_STATS";
+///The file in the [data directory](crate::data_dir::data_dir) statistics are stored in if
+/// [`STATS_VAR`] isn't set
+pub const DEFAULT_STATS_FILE: &str = "stats.txt";
+
+///One solved arena, either by the solver or by the user in assist mode
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SolveRecord {
+  ///seconds since the unix epoch
+  pub timestamp: u64,
+  ///identifies the arena, see [`arena_hash`]
+  pub arena_hash: u64,
+  ///the number of turns of the solution, `None` if there wasn't one
+  pub turns: Option<usize>,
+  ///how long the solver took, `None` if the arena was solved by the user
+  pub solve_time: Option<Duration>,
+  ///whether the user solved the arena in as few turns as possible, `None` if not solved by the user
+  pub followed_optimal: Option<bool>,
+}
+
+impl SolveRecord {
+  ///Creates a record with the current time
+  pub fn now(arena: &SolvableArena) -> Self {
+    Self {
+      timestamp: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs()),
+      arena_hash: arena_hash(arena),
+      turns: None,
+      solve_time: None,
+      followed_optimal: None,
+    }
+  }
+}
+
+///Records are stored as one line of `<timestamp> <arena hash> <turns> <solve time ms> <followed>`, with
+/// `-` for missing values
+impl Display for SolveRecord {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{} {:016x} {} {} {}",
+      self.timestamp,
+      self.arena_hash,
+      self
+        .turns
+        .map_or("-".to_string(), |turns| turns.to_string()),
+      self
+        .solve_time
+        .map_or("-".to_string(), |time| time.as_millis().to_string()),
+      self
+        .followed_optimal
+        .map_or("-", |followed| if followed { "y" } else { "n" })
+    )
+  }
+}
+
+impl TryFrom<&str> for SolveRecord {
+  type Error = StatsError;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    let invalid = || StatsError::InvalidFormat(value.to_string());
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [timestamp, arena_hash, turns, solve_time, followed_optimal] = parts[..] else {
+      return Err(invalid());
+    };
+    fn optional(part: &str) -> Option<&str> {
+      (part != "-").then_some(part)
+    }
+    Ok(Self {
+      timestamp: timestamp.parse().map_err(|_| invalid())?,
+      arena_hash: u64::from_str_radix(arena_hash, 16).map_err(|_| invalid())?,
+      turns: optional(turns)
+        .map(str::parse)
+        .transpose()
+        .map_err(|_| invalid())?,
+      solve_time: optional(solve_time)
+        .map(str::parse)
+        .transpose()
+        .map_err(|_| invalid())?
+        .map(Duration::from_millis),
+      followed_optimal: match followed_optimal {
+        "y" => Some(true),
+        "n" => Some(false),
+        "-" => None,
+        _ => return Err(invalid()),
+      },
+    })
+  }
+}
+
+///Identifies arenas with the same enemies and settings. Only stable between runs of the same build.
+pub fn arena_hash(arena: &SolvableArena) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  arena.canonical().hash(&mut hasher);
+  hasher.finish()
+}
+
+///Solve records stored in a file, so users can track their improvement
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct StatsStore {
+  path: PathBuf,
+}
+
+impl StatsStore {
+  pub fn new<P>(path: P) -> Self
+  where
+    P: AsRef<Path>,
+  {
+    Self {
+      path: path.as_ref().to_path_buf(),
+    }
+  }
+
+  ///The store in the file [`STATS_VAR`] points to, or in [`DEFAULT_STATS_FILE`]
+  pub fn from_env() -> Self {
+    Self::new(data_file(STATS_VAR, DEFAULT_STATS_FILE))
+  }
+
+  pub fn record(&self, record: SolveRecord) -> io::Result<()> {
+    create_parent(&self.path)?;
+    let mut file = OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(&self.path)?;
+    writeln!(file, "{}", record)
+  }
+
+  ///All records, oldest first
+  pub fn history(&self) -> Result<Vec<SolveRecord>, StatsError> {
+    let content = match fs::read_to_string(&self.path) {
+      Ok(content) => content,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+      Err(e) => return Err(e.into()),
+    };
+    content
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(SolveRecord::try_from)
+      .collect()
+  }
+}
+
+///A summary over multiple records
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct StatsSummary {
+  pub num_records: usize,
+  pub average_solve_time: Option<Duration>,
+  ///how often the user followed the optimal line out of all arenas solved by the user
+  pub followed_optimal: (usize, usize),
+}
+
+impl StatsSummary {
+  pub fn of(records: &[SolveRecord]) -> Self {
+    let solve_times: Vec<Duration> = records
+      .iter()
+      .filter_map(|record| record.solve_time)
+      .collect();
+    let followed: Vec<bool> = records
+      .iter()
+      .filter_map(|record| record.followed_optimal)
+      .collect();
+    Self {
+      num_records: records.len(),
+      average_solve_time: (!solve_times.is_empty())
+        .then(|| solve_times.iter().sum::<Duration>() / solve_times.len() as u32),
+      followed_optimal: (
+        followed.iter().filter(|&&followed| followed).count(),
+        followed.len(),
+      ),
+    }
+  }
+}
+
+impl Display for StatsSummary {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} solves", self.num_records)?;
+    if let Some(average_solve_time) = self.average_solve_time {
+      write!(
+        f,
+        ", {} ms average solver time",
+        average_solve_time.as_millis()
+      )?;
+    }
+    let (followed, total) = self.followed_optimal;
+    if total > 0 {
+      write!(f, ", optimal line followed {}/{} times", followed, total)?;
+    }
+    Ok(())
+  }
+}
+
+#[derive(Debug)]
+pub enum StatsError {
+  IO(io::Error),
+  InvalidFormat(String),
+}
+
+impl From<io::Error> for StatsError {
+  fn from(value: io::Error) -> Self {
+    Self::IO(value)
+  }
+}
+
+impl Display for StatsError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      StatsError::IO(e) => write!(f, "IO error: {}", e),
+      StatsError::InvalidFormat(line) => write!(f, "Invalid stats record: {}", line),
+    }
+  }
+}
+
+impl Error for StatsError {}
+
+#[cfg(test)]
+mod test_stats {
+  use std::env;
+  use std::fs;
+  use std::time::Duration;
+
+  use crate::solving::SolvableArena;
+  use crate::stats::{SolveRecord, StatsStore, StatsSummary};
+
+  #[test]
+  fn test_record_and_history() {
+    let path = env::temp_dir().join(format!("tok_stats_{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let store = StatsStore::new(&path);
+    assert!(store.history().expect("missing file is empty").is_empty());
+
+    let solved = SolveRecord {
+      turns: Some(2),
+      solve_time: Some(Duration::from_millis(40)),
+      ..SolveRecord::now(&SolvableArena::default())
+    };
+    let assisted = SolveRecord {
+      turns: Some(3),
+      followed_optimal: Some(false),
+      ..SolveRecord::now(&SolvableArena::default())
+    };
+    store.record(solved).expect("failed to record");
+    store.record(assisted).expect("failed to record");
+
+    let history = store.history().expect("failed to read history");
+    assert_eq!(vec![solved, assisted], history);
+    let summary = StatsSummary::of(&history);
+    assert_eq!(Some(Duration::from_millis(40)), summary.average_solve_time);
+    assert_eq!((0, 1), summary.followed_optimal);
+    let _ = fs::remove_file(&path);
+  }
+}
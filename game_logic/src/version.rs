@@ -0,0 +1,28 @@
+use alloc::format;
+use alloc::string::String;
+
+///The version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+///The abbreviated hash of the commit this was built from, ending with `-dirty` if there were
+/// uncommitted changes, or `unknown` if it wasn't built from a git checkout
+pub const GIT_HASH: &str = env!("TOK_GIT_HASH");
+///The day this was built, like `2024-07-21`
+pub const BUILD_DATE: &str = env!("TOK_BUILD_DATE");
+///The enabled cargo features, separated by commas
+pub const FEATURES: &str = env!("TOK_FEATURES");
+
+///Identifies the build, to be included in bug reports, like
+/// `game_logic 0.1.0 (3f2a9c1, built 2024-07-21, features: cli,std)`
+pub fn about() -> String {
+  format!(
+    "game_logic {} ({}, built {}, features: {})",
+    VERSION,
+    GIT_HASH,
+    BUILD_DATE,
+    if FEATURES.is_empty() {
+      "none"
+    } else {
+      FEATURES
+    }
+  )
+}
@@ -1,31 +1,85 @@
+use std::env;
 use std::fs;
 use std::path::Path;
-use std::process::Command;
 
 use wgsl_to_wgpu::{create_shader_module, MatrixVectorTypes, WriteOptions};
 
+use game_logic::position::Dimension;
+use shader_pre_processor::bind_groups::generate_bind_group_helpers;
 use shader_pre_processor::environment::PreProcessingEnvironment;
+use shader_pre_processor::output::emit_module;
 use shader_pre_processor::pre_processing_cache::PreProcessingCache;
+use shader_pre_processor::statement_handler::{StatementHandler, StatementHandlerError};
 use shader_pre_processor::type_analysis::primitive_type::PrimitiveType;
-use shader_pre_processor::{pre_process_shader, ProcessContext};
+use shader_pre_processor::{pre_process_shader, ProcessContext, StatementUsage, STMT_PREFIX};
 
 const INCLUDE_HOOK_POINT: &str = "INCLUDE_HOOK_POINT";
 
+///`#ifdef_platform <target_os>` - keeps the following lines up to `#endif_platform` only when
+/// building for the given target os, like `android`
+#[derive(Debug)]
+struct IfdefPlatform {
+  target_os: String,
+}
+
+impl IfdefPlatform {
+  const END: &'static str = "endif_platform";
+}
+
+impl StatementHandler for IfdefPlatform {
+  fn name(&self) -> &str {
+    "ifdef_platform"
+  }
+
+  fn handle(
+    &self,
+    usages: &[StatementUsage],
+    shader_source: &str,
+  ) -> Result<String, StatementHandlerError> {
+    let end_line = format!("{STMT_PREFIX}{}", Self::END);
+    let mut lines: Vec<&str> = shader_source.lines().collect();
+    for usage in usages {
+      let start = usage.line_nr.get() - 1;
+      let end = lines[start..]
+        .iter()
+        .position(|line| line.trim() == end_line)
+        .map(|offset| start + offset)
+        .ok_or_else(|| StatementHandlerError::new(usage, format!("missing {end_line}")))?;
+      let keep = usage.arg_str == self.target_os;
+      //blank lines instead of removing them, so line numbers stay valid
+      for (index, line) in lines[start..=end].iter_mut().enumerate() {
+        if !keep || index == 0 || start + index == end {
+          *line = "";
+        }
+      }
+    }
+    Ok(lines.join("\n"))
+  }
+}
+
 fn main() {
   println!("cargo::rerun-if-changed=resources/shader/**");
 
   let environment = PreProcessingEnvironment::new()
     .with(PrimitiveType::new("f32", 4, "f32"))
-    .with(PrimitiveType::new("u32", 4, "u32"));
+    .with(PrimitiveType::new("u32", 4, "u32"))
+    .with_const("NUM_ROWS", Dimension::Row.size() as u32)
+    .with_const("NUM_COLUMNS", Dimension::Column.size() as u32)
+    .with_statement_handler(IfdefPlatform {
+      target_os: env::var("CARGO_CFG_TARGET_OS").expect("cargo sets the target os"),
+    });
 
   let shader_directory = Path::new("resources/shader");
   let mut shader_rs_source = String::new();
 
-  for entry in fs::read_dir(shader_directory)
+  let mut entries = fs::read_dir(shader_directory)
     .expect("failed to open shader directory")
     .collect::<Result<Vec<_>, _>>()
-    .expect("failed to access file in shader directory")
-  {
+    .expect("failed to access file in shader directory");
+  //directory iteration order differs between platforms, keep shader.rs stable
+  entries.sort_by_key(|entry| entry.file_name());
+
+  for entry in entries {
     let path = entry.path();
     let file_name = entry
       .file_name()
@@ -36,10 +90,11 @@ fn main() {
     if let Some(shader_name) = file_name.strip_suffix(".wgsl").map(|s| s.to_string()) {
       println!("Processing shader {}...", shader_name);
 
+      let mut pre_processing_cache = PreProcessingCache::default();
       if let Some(source_code) = pre_process_shader(
         &path,
         ProcessContext::Standalone,
-        &mut PreProcessingCache::default(),
+        &mut pre_processing_cache,
         &environment,
       )
       .expect("failed to pre-process shader")
@@ -60,7 +115,16 @@ fn main() {
           &format!("r#\"\n{source_code}\"#"),
         );
 
-        shader_rs_source += &format!("pub mod {shader_name} {{\n{shader_module_source}\n}}\n");
+        let bind_group_helpers =
+          generate_bind_group_helpers(&source_code).expect("invalid shader bindings");
+        let module_source = [shader_module_source]
+          .into_iter()
+          .chain(pre_processing_cache.generated_code)
+          .chain([bind_group_helpers])
+          .collect::<Vec<_>>()
+          .join("\n");
+
+        shader_rs_source += &format!("pub mod {shader_name} {{\n{module_source}}}\n");
 
         println!("Ok!");
       } else {
@@ -69,10 +133,6 @@ fn main() {
     }
   }
 
-  let shader_rs_path = Path::new("src").join("shader.rs");
-  fs::write(&shader_rs_path, shader_rs_source).expect("failed to create shader.rs");
-  //try running rust fmt on the file
-  if let Ok(mut process) = Command::new("rustfmt").arg(shader_rs_path).spawn() {
-    let _ = process.wait();
-  }
+  emit_module(Path::new("src").join("shader.rs"), &shader_rs_source)
+    .expect("failed to create shader.rs");
 }
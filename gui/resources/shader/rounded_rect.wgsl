@@ -0,0 +1,66 @@
+#vertex
+struct RoundedRectVertex {
+  @location(0) position: vec3<f32>,
+  @location(1) radius: f32, //pixels
+  @location(2) fill: vec4<f32>,
+  @location(3) border: vec4<f32>,
+  @location(4) local: vec2<f32>, //pixels from the center of the rectangle
+  @location(5) half_size: vec2<f32>, //pixels
+  @location(6) border_width: f32, //pixels
+  @location(7) glow: f32, //pixels the glow reaches beyond the rectangle
+  @location(8) _padding: vec2<f32>,
+}
+
+struct VertexOutput {
+  @builtin(position) clip_position: vec4<f32>,
+  @location(0) local: vec2<f32>,
+  @location(1) half_size: vec2<f32>,
+  @location(2) radius: f32,
+  @location(3) border_width: f32,
+  @location(4) fill: vec4<f32>,
+  @location(5) border: vec4<f32>,
+  @location(6) glow: f32,
+}
+
+@vertex
+fn vs_main(in: RoundedRectVertex) -> VertexOutput {
+  var out: VertexOutput;
+  out.clip_position = vec4<f32>(in.position, 1.0);
+  out.local = in.local;
+  out.half_size = in.half_size;
+  out.radius = in.radius;
+  out.border_width = in.border_width;
+  out.fill = in.fill;
+  out.border = in.border;
+  out.glow = in.glow;
+  return out;
+}
+
+//signed distance to the edge of the rounded rectangle, negative inside
+fn rounded_rect_distance(local: vec2<f32>, half_size: vec2<f32>, radius: f32) -> f32 {
+  let corner = abs(local) - half_size + vec2<f32>(radius);
+  return length(max(corner, vec2<f32>(0.0))) + min(max(corner.x, corner.y), 0.0) - radius;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+  let distance = rounded_rect_distance(in.local, in.half_size, in.radius);
+  //edges fade over about a pixel, which keeps them smooth at any scale
+  let edge = max(fwidth(distance), 0.0001);
+  let coverage = clamp(0.5 - distance / edge, 0.0, 1.0);
+  let inside_border = clamp(0.5 - (distance + in.border_width) / edge, 0.0, 1.0);
+  let color = mix(in.border, in.fill, inside_border);
+  //premultiplied alpha
+  let alpha = color.a * coverage;
+  return vec4<f32>(color.rgb * alpha, alpha);
+}
+
+//a soft halo fading out around the rectangle, drawn with additive blending
+@fragment
+fn fs_glow(in: VertexOutput) -> @location(0) vec4<f32> {
+  let distance = rounded_rect_distance(in.local, in.half_size, in.radius);
+  let falloff = 1.0 - clamp(distance / max(in.glow, 0.0001), 0.0, 1.0);
+  //the inside is only tinted slightly, so enemies stay readable
+  let intensity = select(falloff * falloff, 0.3, distance < 0.0);
+  return vec4<f32>(in.fill.rgb * in.fill.a * intensity, 0.0);
+}
@@ -1,3 +1,4 @@
+#vertex
 struct VertexInput {
   @location(0) position: vec3<f32>,
   @location(1) _padding: f32,
@@ -1,5 +1,6 @@
 #include util/coordinates.wgsl
 
+#vertex
 struct Vertex {
   @location(0) position: vec3<f32>,
   @location(1) tex_coords: vec2<f32>,
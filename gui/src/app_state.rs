@@ -1,16 +1,102 @@
+use std::mem;
 use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-use game_logic::arena::Arena;
+use game_logic::arena::{Arena, Layer, LayeredArena};
+use game_logic::arena::observed::Observed;
+use game_logic::capabilities::{CAPABILITIES, Capability};
+use game_logic::data_dir::create_parent;
+use game_logic::deep_link::{arena_link, solution_link};
+use game_logic::fixture::{FixtureError, load_arena};
 use game_logic::position::{Dimension, Move, Num, Position};
-use game_logic::solving::SolvableArena;
+use game_logic::session::SessionServer;
+use game_logic::solver_service::{AnytimeSolve, JobProgress, solve_anytime};
+use game_logic::solving::{Coverage, Enemy, SolvableArena, SolveParams, TargetArea};
+use game_logic::stats::{SolveRecord, StatsStore, StatsSummary};
+use game_logic::{CommandContext, parse};
+use image::{ImageError, RgbaImage};
+
+use crate::hud::{
+  hud, hud_area, QR_BUTTON, RECENT_BUTTON, SETTINGS_BUTTON, STATS_BUTTON, TUTORIAL_BUTTON,
+};
+use crate::metrics::{Feature, MetricsStore};
+use crate::puzzle_timer::PuzzleTimer;
+use crate::qr_code::{arena_qr_code, qr_buttons, qr_buttons_area, qr_code_text, QR_CODE_FILE};
+use crate::recent_arenas::{row_at, RecentArenas};
+use crate::renderer::{ElementId, Feedback, FeedbackTarget, PTexCoords, WidgetList};
+use crate::settings::{Settings, SettingsStore};
+use crate::settings_screen::SettingsScreen;
+
+use screen::{Screen, ScreenEvent};
+
+pub mod screen;
+
+///The optional subsystems of the gui, see [`AppState::capabilities`]
+const GUI_CAPABILITIES: [Capability; 4] = [
+  Capability::new(
+    "global_hotkey",
+    "bring the window to the front with a hotkey while the game is focused",
+    cfg!(all(feature = "global_hotkey", not(target_os = "android"))),
+  ),
+  Capability::new(
+    "tray",
+    "tray icon with quick actions",
+    cfg!(all(feature = "tray", any(windows, target_os = "macos"))),
+  ),
+  Capability::new(
+    "clipboard",
+    "copy share links of arenas and solutions",
+    cfg!(any(feature = "clipboard", target_os = "android")),
+  ),
+  Capability::new(
+    "audio",
+    "beeps for the moves of the solution",
+    cfg!(feature = "audio"),
+  ),
+];
 
 ///Holds the current data of the app which should outlive different render and animation states
 #[derive(Debug)]
 pub struct AppState {
-  pub arena: SolvableArena,
-  pub arena_ground: Arena<Tile>,
+  ///the enemies and the ground tiles they stand on
+  pub arena: Observed<LayeredArena<(SolvableArena, Arena<Tile>)>>,
+  ///what `undo` goes back through: the enemies before each time they were replaced or cleared, see
+  /// [`Self::undo`]
+  pub commands: CommandContext,
   pub number_of_turns: Num,
   pub current_solution: Option<Solution>,
+  ///the running search for solutions of the arena, which improves the current solution as it goes
+  pub solver: Option<AnytimeSolve>,
+  ///how far the last search got
+  pub solver_progress: Option<JobProgress>,
+  ///whether a file is dragged over the window, which is then highlighted as drop target
+  pub hovered_file: bool,
+  pub settings: SettingsStore,
+  ///the arena files loaded last, saved next to the settings
+  pub recent: RecentArenas,
+  ///the text and picture of the qr code shown last, see [`Screen::QrCode`]
+  pub qr_code: Option<(String, RgbaImage)>,
+  ///what is shown, which decides how input is handled
+  pub screen: Screen,
+  ///shakes and flashes showing that an action was invalid
+  pub feedback: Feedback,
+  ///counts down the time for the puzzle of the loaded arena, if enabled in the settings
+  pub puzzle_timer: Option<PuzzleTimer>,
+  ///whether the window is a compact overlay showing nothing but the board and the next move
+  pub overlay: bool,
+  ///how the app is used, only stored locally
+  pub metrics: MetricsStore,
+  ///where the searches of the app are recorded, shared with the `stats` command of the CLI
+  pub stats: StatsStore,
+  ///the recorded solves the chart shows, loaded when it is opened, see [`Screen::Stats`]
+  pub solves: Vec<SolveRecord>,
+  ///which optional subsystems of the solver and the gui this build supports
+  pub capabilities: Vec<Capability>,
+  ///shares the arena with attached terminals, and whether it changed since it was last shared
+  pub session: Option<(SessionServer, Arc<AtomicBool>)>,
   pub height: i32, //temporary used while developing this app
 }
 
@@ -29,20 +115,484 @@ impl Default for AppState {
         })
       }
     }
+    let settings = SettingsStore::load_default_or_reset();
     Self {
-      arena: Default::default(),
-      arena_ground,
+      arena: Observed::new(LayeredArena::new((
+        SolvableArena::default(),
+        arena_ground,
+      ))),
+      commands: CommandContext::new(),
       number_of_turns: 2,
       current_solution: None,
+      solver: None,
+      solver_progress: None,
+      hovered_file: false,
+      recent: RecentArenas::load_beside_or_reset(&settings),
+      qr_code: None,
+      settings,
+      screen: Screen::default(),
+      feedback: Feedback::default(),
+      puzzle_timer: None,
+      overlay: false,
+      metrics: MetricsStore::load_default_or_reset(),
+      stats: StatsStore::from_env(),
+      solves: vec![],
+      capabilities: CAPABILITIES.into_iter().chain(GUI_CAPABILITIES).collect(),
+      session: None,
       height: 0,
     }
   }
 }
 
 impl AppState {
-  pub fn apply_move(&mut self, move_: Move) {
+  ///Applies the move, unless it doesn't move anything or moves a locked column, which shakes the
+  /// arena instead. Returns whether the move was applied.
+  pub fn apply_move(&mut self, move_: Move) -> bool {
+    if move_.normalized().amount == 0 || !self.arena.layers.0.allows_move(move_) {
+      self.feedback.reject(FeedbackTarget::Arena);
+      return false;
+    }
     self.arena.apply_move(move_);
-    self.arena_ground.apply_move(move_);
+    true
+  }
+
+  ///Replaces the enemies with the ones of an arena file, see [`load_arena`], and remembers it as
+  /// recent arena. Entering the arena starts the [`PuzzleTimer`] if it is enabled.
+  pub fn load_arena_file(&mut self, path: &Path) -> Result<(), FixtureError> {
+    let loaded = load_arena(path)?;
+    if let Err(e) = self.recent.add(path) {
+      eprintln!("failed to save the recent arenas: {}", e);
+    }
+    self.record_change(&loaded);
+    self.arena.modify(|arena| arena.layers.0 = loaded);
+    self.current_solution = None;
+    self.transition(ScreenEvent::ArenaReplaced);
+    self.puzzle_timer = self
+      .settings
+      .settings()
+      .puzzle_timer
+      .map(|secs| PuzzleTimer::start(Duration::from_secs(secs as u64), Instant::now()));
+    self.metrics.record_usage(Feature::ArenaFile);
+    if self.puzzle_timer.is_some() {
+      self.metrics.record_usage(Feature::PuzzleTimer);
+    }
+    Ok(())
+  }
+
+  ///Shares the arena with terminals attaching to the server from now on
+  pub fn share_arena(&mut self, server: SessionServer) {
+    let outdated = Arc::new(AtomicBool::new(true));
+    let changed = outdated.clone();
+    self.arena.subscribe(move |_| changed.store(true, Ordering::Relaxed));
+    self.session = Some((server, outdated));
+  }
+
+  ///Shares the arena with the session if it changed since it was last shared
+  pub fn publish_arena(&self) {
+    if let Some((server, outdated)) = &self.session {
+      if outdated.swap(false, Ordering::Relaxed) {
+        server.publish(&self.arena.layers.0);
+      }
+    }
+  }
+
+  ///Replaces the enemies with the ones entered in an attached terminal
+  pub fn replace_arena(&mut self, arena: SolvableArena) {
+    self.record_change(&arena);
+    self.show_arena(arena);
+  }
+
+  ///Goes back to the enemies before they were last replaced or cleared, every terminal command or
+  /// loaded file is one step. Shakes the arena if there is nothing to undo. Returns whether anything
+  /// was undone.
+  pub fn undo(&mut self) -> bool {
+    let mut arena = self.arena.layers.0.clone();
+    if parse(&mut self.commands, &mut arena, "undo").is_err() {
+      self.feedback.reject(FeedbackTarget::Arena);
+      return false;
+    }
+    self.show_arena(arena);
+    true
+  }
+
+  ///Remembers the enemies for [`Self::undo`] before they are replaced, unless they stay the same
+  fn record_change(&mut self, replacement: &SolvableArena) {
+    if self.arena.layers.0 != *replacement {
+      self.commands.record_change(self.arena.layers.0.clone());
+    }
+  }
+
+  ///Shows the arena instead of the current one, which stops everything belonging to it
+  fn show_arena(&mut self, arena: SolvableArena) {
+    self.arena.modify(|shown| shown.layers.0 = arena);
+    self.current_solution = None;
+    self.solver = None;
+    self.solver_progress = None;
+    self.transition(ScreenEvent::ArenaReplaced);
+  }
+
+  ///The areas to attack once every move of the current solution is executed, nothing while moves
+  /// are left
+  pub fn target_areas(&self) -> &[TargetArea] {
+    match &self.current_solution {
+      Some(solution) if solution.executed_moves >= solution.moves.len() => &solution.target_areas,
+      _ => &[],
+    }
+  }
+
+  ///The move of the current solution which is to be executed next
+  pub fn next_move(&self) -> Option<Move> {
+    let solution = self.current_solution.as_ref()?;
+    solution.moves.get(solution.executed_moves).copied()
+  }
+
+  ///Applies the next move of the current solution, like the player just did in the game. Returns
+  /// whether there was a move left.
+  pub fn execute_next_move(&mut self) -> bool {
+    let Some(move_) = self.next_move() else {
+      self.feedback.reject(FeedbackTarget::Arena);
+      return false;
+    };
+    if let Some(solution) = &mut self.current_solution {
+      solution.executed_moves += 1;
+    }
+    self.apply_move(move_)
+  }
+
+  ///Replaces the enemies with scanned ones, keeping the equipment and other options
+  pub fn replace_enemies(&mut self, enemies: Vec<Enemy>) {
+    let mut arena = self.arena.layers.0.clone();
+    arena.enemies.clear();
+    for enemy in enemies {
+      arena.add(enemy);
+    }
+    self.replace_arena(arena);
+  }
+
+  ///Replaces the enemies with shared ones and plays back the shared solution of them
+  pub fn play_shared_solution(&mut self, enemies: Vec<Enemy>, moves: Vec<Move>) {
+    self.replace_enemies(enemies);
+    self.current_solution = Some(Solution::new(moves, &self.arena.layers.0));
+    self.transition(ScreenEvent::SolutionShared);
+  }
+
+  ///A link to the arena with the current solution, which opens the arena and plays the solution
+  /// back for whoever follows it. Without a solution only the arena is shared.
+  pub fn share_link(&self) -> String {
+    let enemies = &self.arena.layers.0.enemies;
+    match &self.current_solution {
+      Some(solution) => solution_link(enemies, &solution.moves),
+      None => arena_link(enemies),
+    }
+  }
+
+  ///Removes all enemies and stops everything belonging to the previous arena, like the search for
+  /// solutions and the puzzle timer
+  pub fn clear_arena(&mut self) {
+    self.record_change(&SolvableArena::default());
+    self
+      .arena
+      .modify(|arena| arena.layers.0 = SolvableArena::default());
+    self.current_solution = None;
+    self.solver = None;
+    self.solver_progress = None;
+    self.puzzle_timer = None;
+    self.transition(ScreenEvent::ArenaReplaced);
+  }
+
+  ///Starts searching for solutions of the current arena in the configured number of turns, replacing
+  /// any running search. An arena without enemies shakes instead. Returns whether the search was
+  /// started.
+  pub fn start_solving(&mut self) -> bool {
+    if self.arena.layers.0.enemies.is_empty() {
+      self.feedback.reject(FeedbackTarget::Arena);
+      return false;
+    }
+    let arena = self.arena.layers.0.clone();
+    self.solver = Some(solve_anytime(
+      arena,
+      SolveParams::new()
+        .in_turns(self.number_of_turns)
+        .strategy(self.settings.settings().solver_strategy)
+        .comparator(self.settings.settings().solution_order)
+        .move_filter(self.settings.settings().move_filter),
+    ));
+    self.solver_progress = None;
+    self.current_solution = None;
+    self.transition(ScreenEvent::SolvingStarted);
+    true
+  }
+
+  ///Changes the screen as the event demands
+  pub fn transition(&mut self, event: ScreenEvent) {
+    self.screen = mem::take(&mut self.screen).handle(event);
+  }
+
+  pub fn toggle_settings(&mut self) {
+    if self.screen.settings().is_some() {
+      self.transition(ScreenEvent::Close);
+    } else {
+      self.metrics.record_usage(Feature::Settings);
+      let settings = SettingsScreen::new(self.settings.settings());
+      self.transition(ScreenEvent::OpenSettings(settings));
+    }
+  }
+
+  pub fn toggle_recent(&mut self) {
+    if self.screen.recent_focus().is_some() {
+      self.transition(ScreenEvent::Close);
+    } else {
+      self.metrics.record_usage(Feature::RecentArenas);
+      self.transition(ScreenEvent::OpenRecent);
+    }
+  }
+
+  ///Shows the qr code of the arena for others to scan and saves it next to the settings as
+  /// [`QR_CODE_FILE`], or closes it if it is shown
+  pub fn toggle_qr_code(&mut self) {
+    if self.screen.is_qr_code() {
+      self.transition(ScreenEvent::Close);
+      return;
+    }
+    self.metrics.record_usage(Feature::QrCode);
+    let enemies = &self.arena.layers.0.enemies;
+    let code = arena_qr_code(enemies);
+    let path = self.settings.beside(QR_CODE_FILE);
+    let saved = create_parent(&path)
+      .map_err(ImageError::from)
+      .and_then(|_| code.save(&path));
+    if let Err(e) = saved {
+      eprintln!("failed to save the qr code to {}: {}", path.display(), e);
+    }
+    self.qr_code = Some((qr_code_text(enemies), code));
+    self.transition(ScreenEvent::OpenQrCode);
+  }
+
+  ///Shows the chart of the latest solves, or closes it if it is shown
+  pub fn toggle_stats(&mut self) {
+    if self.screen.is_stats() {
+      self.transition(ScreenEvent::Close);
+      return;
+    }
+    self.solves = self.stats.history().unwrap_or_else(|e| {
+      eprintln!("failed to read the stats: {}", e);
+      vec![]
+    });
+    self.transition(ScreenEvent::OpenStats);
+  }
+
+  ///A summary of all recorded solves while the chart is shown, like `stats: 12 solves, ...`
+  pub fn stats_status(&self) -> Option<String> {
+    self
+      .screen
+      .is_stats()
+      .then(|| format!("stats: {}", StatsSummary::of(&self.solves)))
+  }
+
+  ///The button below the qr code at the point, while the qr code is shown. Scanning and sharing
+  /// are up to the app.
+  pub fn qr_button_at(&self, point: PTexCoords) -> Option<ElementId> {
+    if !self.screen.is_qr_code() {
+      return None;
+    }
+    qr_buttons().tap(qr_buttons_area(self.settings.settings().ui_scale), point)
+  }
+
+  ///Moves the focus through the recent arenas, the focused one shakes if it is the first or last.
+  /// Returns whether the recent arenas changed or shake, which does nothing while they aren't shown.
+  pub fn move_recent_focus(&mut self, steps: i32) -> bool {
+    let Some(focused) = self.screen.recent_focus() else {
+      return false;
+    };
+    let last = self.recent.arenas().len().saturating_sub(1);
+    let target = focused.saturating_add_signed(steps as isize).min(last);
+    if target == focused {
+      self.feedback.reject(FeedbackTarget::RecentRow(focused));
+    } else {
+      self.transition(ScreenEvent::Focus(target));
+    }
+    true
+  }
+
+  ///The file of the focused recent arena, if the recent arenas are shown and there is one
+  pub fn focused_recent_arena(&self) -> Option<PathBuf> {
+    let focused = self.screen.recent_focus()?;
+    Some(self.recent.arenas().get(focused)?.path.clone())
+  }
+
+  ///The file of the recent arena at the point, if the recent arenas are shown and there is one
+  pub fn recent_arena_at(&self, point: PTexCoords) -> Option<PathBuf> {
+    self.screen.recent_focus()?;
+    Some(self.recent.arenas().get(row_at(point)?)?.path.clone())
+  }
+
+  ///The focused recent arena with its number, like `recent 1/3: arena.txt`
+  pub fn recent_status(&self) -> Option<String> {
+    let focused = self.screen.recent_focus()?;
+    let arenas = self.recent.arenas();
+    Some(match arenas.get(focused) {
+      Some(arena) => format!("recent {}/{}: {}", focused + 1, arenas.len(), arena.name()),
+      None => "no recent arenas yet".to_string(),
+    })
+  }
+
+  ///Lets the widgets of the settings screen be edited and saves the settings if they changed.
+  /// Returns whether the settings screen changed, which does nothing while it isn't shown.
+  pub fn edit_settings<F>(&mut self, edit: F) -> bool
+  where
+    F: FnOnce(&mut WidgetList),
+  {
+    let Some(screen) = self.screen.settings_mut() else {
+      return false;
+    };
+    let before = screen.clone();
+    if let Some(changed) = screen.edit(self.settings.settings(), edit) {
+      if let Err(e) = self.settings.set(changed) {
+        eprintln!("failed to save settings: {}", e);
+      }
+    }
+    before != *screen
+  }
+
+  ///Changes the focused setting by a number of steps. If it can't change any further its row shakes.
+  /// Returns whether the settings screen changed or shakes, which does nothing while it isn't shown.
+  pub fn step_setting(&mut self, steps: i32) -> bool {
+    let Some(screen) = self.screen.settings() else {
+      return false;
+    };
+    let focused = screen.widgets().focused();
+    if !self.edit_settings(|widgets| widgets.focused_widget_mut().step(steps)) {
+      self.feedback.reject(FeedbackTarget::SettingsRow(focused));
+    }
+    true
+  }
+
+  ///Changes the settings from outside the settings screen, like from the tray menu, and saves them
+  pub fn change_settings<F>(&mut self, change: F)
+  where
+    F: FnOnce(&mut Settings),
+  {
+    let mut settings = *self.settings.settings();
+    change(&mut settings);
+    if let Err(e) = self.settings.set(settings) {
+      eprintln!("failed to save settings: {}", e);
+    }
+    if let Some(screen) = self.screen.settings_mut() {
+      screen.refresh(&settings);
+    }
+  }
+
+  ///Handles a tap or click on the window. The settings screen closes when tapping beside its
+  /// widgets, the tutorial shows its next page, the recent arenas, the qr code and the stats close
+  /// when tapping beside them and the buttons of the [`hud`] open what they show. Opening a tapped recent arena
+  /// and the qr code buttons are up to the app, see [`Self::recent_arena_at`] and
+  /// [`Self::qr_button_at`]. Returns whether anything changed.
+  pub fn tap(&mut self, point: PTexCoords) -> bool {
+    match &self.screen {
+      Screen::Settings { settings, .. } if settings.widgets().row_at(point).is_none() => {
+        self.transition(ScreenEvent::Close);
+        true
+      }
+      Screen::Settings { .. } => self.edit_settings(|widgets| {
+        widgets.tap(point);
+      }),
+      Screen::Tutorial { .. } => {
+        self.transition(ScreenEvent::NextPage);
+        true
+      }
+      Screen::Recent { .. } | Screen::QrCode { .. } | Screen::Stats { .. } => {
+        self.transition(ScreenEvent::Close);
+        true
+      }
+      _ => match hud().tap(hud_area(self.settings.settings().ui_scale), point) {
+        Some(SETTINGS_BUTTON) => {
+          self.toggle_settings();
+          true
+        }
+        Some(TUTORIAL_BUTTON) => {
+          self.transition(ScreenEvent::OpenTutorial);
+          true
+        }
+        Some(RECENT_BUTTON) => {
+          self.toggle_recent();
+          true
+        }
+        Some(QR_BUTTON) => {
+          self.toggle_qr_code();
+          true
+        }
+        Some(STATS_BUTTON) => {
+          self.toggle_stats();
+          true
+        }
+        _ => false,
+      },
+    }
+  }
+
+  ///Takes the best solution found so far and the progress of the running search. Returns whether
+  /// anything changed.
+  pub fn poll_solver(&mut self) -> bool {
+    let Some(solver) = &mut self.solver else {
+      return false;
+    };
+    let mut changed = false;
+    while let Some(moves) = solver.try_next() {
+      self.current_solution = Some(Solution::new(moves, &self.arena.layers.0));
+      changed = true;
+    }
+
+    let progress = solver.progress();
+    changed |= self.solver_progress != Some(progress);
+    self.solver_progress = Some(progress);
+    if progress.finished {
+      self.solver = None;
+      let found_solution = self.current_solution.is_some();
+      self
+        .metrics
+        .record(|metrics| metrics.record_solve(progress.elapsed, found_solution));
+      let record = SolveRecord {
+        turns: self
+          .current_solution
+          .as_ref()
+          .map(|solution| solution.moves.len()),
+        solve_time: Some(progress.elapsed),
+        ..SolveRecord::now(&self.arena.layers.0)
+      };
+      if let Err(e) = self.stats.record(record) {
+        eprintln!("failed to record stats: {}", e);
+      }
+      self.transition(ScreenEvent::SolvingFinished { found_solution });
+    }
+    changed
+  }
+
+  ///Describes the state of the search: a gauge of the searched depth, the search speed and the best
+  /// solution found so far
+  pub fn solver_status(&self) -> Option<String> {
+    let progress = self.solver_progress?;
+    let searched_turns = progress.searched_turns.min(self.number_of_turns);
+    let gauge = "\u{25AE}".repeat(searched_turns as usize)
+      + &"\u{25AF}".repeat((self.number_of_turns - searched_turns) as usize);
+    let best = match &self.current_solution {
+      Some(solution) => solution
+        .moves
+        .iter()
+        .map(|move_| move_.to_string())
+        .collect::<Vec<_>>()
+        .join(", "),
+      None if progress.finished => "no solution".to_string(),
+      None => "-".to_string(),
+    };
+    Some(format!(
+      "{} depth {}/{} {} | {:.1}k nodes/s | best: {}",
+      if progress.finished { "done" } else { "solving" },
+      searched_turns,
+      self.number_of_turns,
+      gauge,
+      progress.nodes_per_second() / 1000.0,
+      best
+    ))
   }
 }
 
@@ -50,6 +600,30 @@ impl AppState {
 pub struct Solution {
   pub moves: Vec<Move>,
   pub executed_moves: usize,
+  ///the areas covering every enemy after the moves, empty if the moves don't lead to a coverage
+  pub target_areas: Vec<TargetArea>,
+}
+
+impl Solution {
+  ///A solution for the arena, none of its moves executed yet
+  pub fn new(moves: Vec<Move>, arena: &SolvableArena) -> Self {
+    let mut solved = arena.clone();
+    for &move_ in &moves {
+      solved.apply_move(move_);
+    }
+    let target_areas = Coverage::find(&solved).map_or(vec![], |coverage| {
+      coverage
+        .areas()
+        .iter()
+        .map(|area| area.target_area)
+        .collect()
+    });
+    Self {
+      moves,
+      executed_moves: 0,
+      target_areas,
+    }
+  }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
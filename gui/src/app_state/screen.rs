@@ -0,0 +1,328 @@
+use crate::settings::Language;
+use crate::settings_screen::SettingsScreen;
+
+///The pages of the tutorial in english and german, see [`Screen::Tutorial`]
+const TUTORIAL_PAGES: [(&str, &str); 4] = [
+  (
+    "drop an arena file onto the window to load and solve it",
+    "zieh eine Arena-Datei auf das Fenster, um sie zu laden und zu lösen",
+  ),
+  (
+    "press O for the overlay, enter shows the next move after doing it in the game",
+    "drück O für das Overlay, Enter zeigt den nächsten Zug, nachdem du ihn im Spiel gemacht hast",
+  ),
+  (
+    "press escape for the settings, the arrow keys choose and change them",
+    "drück Escape für die Einstellungen, die Pfeiltasten wählen und ändern sie",
+  ),
+  (
+    "start the app with TOK_SESSION set to enter enemies in a terminal with attach",
+    "starte die App mit gesetztem TOK_SESSION, um Gegner im Terminal mit attach einzugeben",
+  ),
+];
+
+///What the app shows. Rendering and input depend on the screen instead of a flag per feature, so
+/// new screens are a variant here and their transitions in [`Screen::handle`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Screen {
+  ///enemies are entered and nothing is solved yet
+  #[default]
+  Editor,
+  ///solutions of the arena are searched, the best one so far is shown
+  Solving,
+  ///the moves of the solution are executed one after the other
+  Playback,
+  ///the settings, shown above the screen they were opened from
+  Settings {
+    settings: SettingsScreen,
+    below: Box<Screen>,
+  },
+  ///explains the app page by page, above the screen it was opened from
+  Tutorial { page: usize, below: Box<Screen> },
+  ///the recently loaded arena files to open one of them again, above the screen it was opened from
+  Recent { focused: usize, below: Box<Screen> },
+  ///the qr code of the arena for others to scan, above the screen it was opened from
+  QrCode { below: Box<Screen> },
+  ///a chart of the latest solves, above the screen it was opened from
+  Stats { below: Box<Screen> },
+}
+
+///What makes the app change its [`Screen`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScreenEvent {
+  SolvingStarted,
+  SolvingFinished {
+    found_solution: bool,
+  },
+  ///the arena was cleared or replaced, so there is nothing to solve or play back anymore
+  ArenaReplaced,
+  OpenSettings(SettingsScreen),
+  OpenTutorial,
+  ///shows the next page of the tutorial, closing it after the last one
+  NextPage,
+  OpenRecent,
+  ///focuses the recent arena with the index
+  Focus(usize),
+  OpenQrCode,
+  OpenStats,
+  ///a shared solution replaced the arena, which is played back right away
+  SolutionShared,
+  ///closes the settings, the tutorial, the recent arenas, the qr code or the stats
+  Close,
+}
+
+impl Screen {
+  ///The screen after the event. The settings, the tutorial, the recent arenas, the qr code and the
+  /// stats don't stack, while one of them is shown the screen below it handles the events instead.
+  pub fn handle(self, event: ScreenEvent) -> Screen {
+    match (self, event) {
+      (
+        Screen::Settings { below, .. }
+        | Screen::Tutorial { below, .. }
+        | Screen::Recent { below, .. }
+        | Screen::QrCode { below }
+        | Screen::Stats { below },
+        ScreenEvent::Close,
+      ) => *below,
+      (Screen::Tutorial { page, below }, ScreenEvent::NextPage) => {
+        if page + 1 < TUTORIAL_PAGES.len() {
+          Screen::Tutorial {
+            page: page + 1,
+            below,
+          }
+        } else {
+          *below
+        }
+      }
+      (Screen::Recent { below, .. }, ScreenEvent::Focus(focused)) => {
+        Screen::Recent { focused, below }
+      }
+      (
+        screen @ (Screen::Settings { .. }
+        | Screen::Tutorial { .. }
+        | Screen::Recent { .. }
+        | Screen::QrCode { .. }
+        | Screen::Stats { .. }),
+        ScreenEvent::OpenSettings(_)
+        | ScreenEvent::OpenTutorial
+        | ScreenEvent::OpenRecent
+        | ScreenEvent::OpenQrCode
+        | ScreenEvent::OpenStats,
+      ) => screen,
+      (Screen::Settings { settings, below }, event) => Screen::Settings {
+        settings,
+        below: Box::new(below.handle(event)),
+      },
+      (Screen::Tutorial { page, below }, event) => Screen::Tutorial {
+        page,
+        below: Box::new(below.handle(event)),
+      },
+      (Screen::Recent { focused, below }, event) => Screen::Recent {
+        focused,
+        below: Box::new(below.handle(event)),
+      },
+      (Screen::QrCode { below }, event) => Screen::QrCode {
+        below: Box::new(below.handle(event)),
+      },
+      (Screen::Stats { below }, event) => Screen::Stats {
+        below: Box::new(below.handle(event)),
+      },
+      (screen, ScreenEvent::OpenSettings(settings)) => Screen::Settings {
+        settings,
+        below: Box::new(screen),
+      },
+      (screen, ScreenEvent::OpenTutorial) => Screen::Tutorial {
+        page: 0,
+        below: Box::new(screen),
+      },
+      (screen, ScreenEvent::OpenRecent) => Screen::Recent {
+        focused: 0,
+        below: Box::new(screen),
+      },
+      (screen, ScreenEvent::OpenQrCode) => Screen::QrCode {
+        below: Box::new(screen),
+      },
+      (screen, ScreenEvent::OpenStats) => Screen::Stats {
+        below: Box::new(screen),
+      },
+      (_, ScreenEvent::SolvingStarted) => Screen::Solving,
+      (Screen::Solving, ScreenEvent::SolvingFinished { found_solution }) => {
+        if found_solution {
+          Screen::Playback
+        } else {
+          Screen::Editor
+        }
+      }
+      (_, ScreenEvent::ArenaReplaced) => Screen::Editor,
+      (_, ScreenEvent::SolutionShared) => Screen::Playback,
+      (screen, _) => screen,
+    }
+  }
+
+  ///The settings screen, if the settings are shown
+  pub fn settings(&self) -> Option<&SettingsScreen> {
+    match self {
+      Screen::Settings { settings, .. } => Some(settings),
+      _ => None,
+    }
+  }
+
+  pub fn settings_mut(&mut self) -> Option<&mut SettingsScreen> {
+    match self {
+      Screen::Settings { settings, .. } => Some(settings),
+      _ => None,
+    }
+  }
+
+  ///The index of the focused recent arena, if the recent arenas are shown
+  pub fn recent_focus(&self) -> Option<usize> {
+    match self {
+      Screen::Recent { focused, .. } => Some(*focused),
+      _ => None,
+    }
+  }
+
+  pub fn is_qr_code(&self) -> bool {
+    matches!(self, Screen::QrCode { .. })
+  }
+
+  pub fn is_stats(&self) -> bool {
+    matches!(self, Screen::Stats { .. })
+  }
+
+  ///The text of the shown tutorial page with its number, like `tutorial 1/4: ...`
+  pub fn tutorial_status(&self, language: Language) -> Option<String> {
+    let Screen::Tutorial { page, .. } = self else {
+      return None;
+    };
+    let (english, german) = TUTORIAL_PAGES[*page];
+    let text = match language {
+      Language::English => english,
+      Language::German => german,
+    };
+    Some(format!(
+      "tutorial {}/{}: {}",
+      page + 1,
+      TUTORIAL_PAGES.len(),
+      text
+    ))
+  }
+}
+
+#[cfg(test)]
+mod test_screen {
+  use crate::app_state::screen::{Screen, ScreenEvent, TUTORIAL_PAGES};
+  use crate::settings::{Language, Settings};
+  use crate::settings_screen::SettingsScreen;
+
+  #[test]
+  fn test_solving() {
+    let screen = Screen::default().handle(ScreenEvent::SolvingStarted);
+    assert_eq!(Screen::Solving, screen);
+    assert_eq!(
+      Screen::Playback,
+      screen.clone().handle(ScreenEvent::SolvingFinished {
+        found_solution: true
+      })
+    );
+    assert_eq!(
+      Screen::Editor,
+      screen.handle(ScreenEvent::SolvingFinished {
+        found_solution: false
+      })
+    );
+    assert_eq!(
+      Screen::Editor,
+      Screen::Playback.handle(ScreenEvent::ArenaReplaced)
+    );
+    //only a running search can finish
+    assert_eq!(
+      Screen::Editor,
+      Screen::Editor.handle(ScreenEvent::SolvingFinished {
+        found_solution: true
+      })
+    );
+  }
+
+  #[test]
+  fn test_settings() {
+    let settings = SettingsScreen::new(&Settings::default());
+    let screen = Screen::Solving.handle(ScreenEvent::OpenSettings(settings.clone()));
+    assert_eq!(Some(&settings), screen.settings());
+
+    //the search goes on below the settings
+    let screen = screen
+      .handle(ScreenEvent::OpenTutorial)
+      .handle(ScreenEvent::SolvingFinished {
+        found_solution: true,
+      });
+    assert_eq!(Some(&settings), screen.settings());
+    assert_eq!(Screen::Playback, screen.handle(ScreenEvent::Close));
+  }
+
+  #[test]
+  fn test_recent() {
+    let screen = Screen::Playback
+      .handle(ScreenEvent::OpenRecent)
+      .handle(ScreenEvent::Focus(2));
+    assert_eq!(Some(2), screen.recent_focus());
+    //nothing stacks on the recent arenas
+    let screen = screen.handle(ScreenEvent::OpenTutorial);
+    assert_eq!(Some(2), screen.recent_focus());
+    assert_eq!(Screen::Playback, screen.handle(ScreenEvent::Close));
+    assert_eq!(None, Screen::Editor.handle(ScreenEvent::Focus(1)).recent_focus());
+  }
+
+  #[test]
+  fn test_qr_code() {
+    let screen = Screen::Solving.handle(ScreenEvent::OpenQrCode);
+    assert!(screen.is_qr_code());
+    let screen = screen
+      .handle(ScreenEvent::OpenRecent)
+      .handle(ScreenEvent::SolvingFinished {
+        found_solution: false,
+      });
+    assert!(screen.is_qr_code());
+    assert_eq!(Screen::Editor, screen.handle(ScreenEvent::Close));
+  }
+
+  #[test]
+  fn test_stats() {
+    let screen = Screen::Playback.handle(ScreenEvent::OpenStats);
+    assert!(screen.is_stats());
+    //the search goes on below the stats, nothing stacks on them
+    let screen = screen
+      .handle(ScreenEvent::OpenQrCode)
+      .handle(ScreenEvent::SolvingStarted);
+    assert!(screen.is_stats());
+    assert_eq!(Screen::Solving, screen.handle(ScreenEvent::Close));
+  }
+
+  #[test]
+  fn test_solution_shared() {
+    assert_eq!(
+      Screen::Playback,
+      Screen::Solving.handle(ScreenEvent::SolutionShared)
+    );
+    //a link opened while the tutorial is shown plays back below it
+    let screen = Screen::Editor
+      .handle(ScreenEvent::OpenTutorial)
+      .handle(ScreenEvent::SolutionShared);
+    assert!(screen.tutorial_status(Language::English).is_some());
+    assert_eq!(Screen::Playback, screen.handle(ScreenEvent::Close));
+  }
+
+  #[test]
+  fn test_tutorial() {
+    let mut screen = Screen::Editor.handle(ScreenEvent::OpenTutorial);
+    assert!(screen
+      .tutorial_status(Language::English)
+      .is_some_and(|status| status.starts_with("tutorial 1/")));
+    for _ in 0..TUTORIAL_PAGES.len() {
+      assert!(screen.tutorial_status(Language::German).is_some());
+      screen = screen.handle(ScreenEvent::NextPage);
+    }
+    assert_eq!(Screen::Editor, screen);
+    assert_eq!(None, screen.tutorial_status(Language::English));
+  }
+}
@@ -0,0 +1,201 @@
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::time::Duration;
+
+use game_logic::position::Dimension::{Column, Row};
+use game_logic::position::{Dimension, Move};
+
+///The samples per second cues are synthesized with
+#[cfg(feature = "audio")]
+const SAMPLE_RATE: u32 = 44_100;
+///How long a beep counting the steps of a move lasts
+const BEEP: Duration = Duration::from_millis(120);
+///The silence between two beeps
+const GAP: Duration = Duration::from_millis(100);
+///How long the tone telling the direction of a move lasts
+const DIRECTION_TONE: Duration = Duration::from_millis(300);
+///Tones fade in and out over this time, so they don't click
+#[cfg(feature = "audio")]
+const FADE: Duration = Duration::from_millis(5);
+///The ratio between the frequencies of a fifth
+const FIFTH: f32 = 1.5;
+
+///A sine tone or, without a frequency, silence
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tone {
+  ///in hertz
+  pub frequency: Option<f32>,
+  pub duration: Duration,
+}
+
+impl Tone {
+  pub fn beep(frequency: f32, duration: Duration) -> Self {
+    Self {
+      frequency: Some(frequency),
+      duration,
+    }
+  }
+
+  pub fn pause(duration: Duration) -> Self {
+    Self {
+      frequency: None,
+      duration,
+    }
+  }
+}
+
+///The pitch of the beeps of moves in the dimension: ring rotations beep high, column shifts low
+pub fn dimension_frequency(dimension: Dimension) -> f32 {
+  match dimension {
+    Row => 880.0,
+    Column => 523.25,
+  }
+}
+
+///The cue of a move: a beep per step in the pitch of its [dimension](dimension_frequency),
+/// followed by a longer tone a fifth higher for clockwise or outward moves and a fifth lower for
+/// the others
+pub fn move_cue(move_: Move) -> Vec<Tone> {
+  let move_ = move_.normalized();
+  let frequency = dimension_frequency(move_.dimension);
+  let mut cue = vec![];
+  for _ in 0..move_.amount {
+    cue.push(Tone::beep(frequency, BEEP));
+    cue.push(Tone::pause(GAP));
+  }
+  let direction = if move_.in_positive_direction {
+    frequency * FIFTH
+  } else {
+    frequency / FIFTH
+  };
+  cue.push(Tone::beep(direction, DIRECTION_TONE));
+  cue
+}
+
+///The cue once every move of the solution is executed: a rising major chord, one note after the
+/// other
+pub fn finished_cue() -> Vec<Tone> {
+  [523.25, 659.25, 783.99]
+    .into_iter()
+    .map(|frequency| Tone::beep(frequency, BEEP))
+    .collect()
+}
+
+///Synthesizes the tones as mono samples from -1.0 to 1.0
+#[cfg(feature = "audio")]
+fn synthesize(tones: &[Tone], sample_rate: u32) -> Vec<f32> {
+  use std::f32::consts::TAU;
+
+  let fade = (FADE.as_secs_f32() * sample_rate as f32).max(1.0);
+  let mut samples = vec![];
+  for tone in tones {
+    let count = (tone.duration.as_secs_f32() * sample_rate as f32) as usize;
+    samples.extend((0..count).map(|index| {
+      let Some(frequency) = tone.frequency else {
+        return 0.0;
+      };
+      let envelope = (index as f32 / fade)
+        .min((count - index) as f32 / fade)
+        .min(1.0);
+      //half the volume leaves room for the sound of the game
+      0.5 * envelope * (TAU * frequency * index as f32 / sample_rate as f32).sin()
+    }));
+  }
+  samples
+}
+
+///Plays cues on the default audio output while it lives
+#[cfg(feature = "audio")]
+pub struct CuePlayer {
+  _stream: rodio::OutputStream,
+  sink: rodio::Sink,
+}
+
+#[cfg(feature = "audio")]
+impl CuePlayer {
+  pub fn open() -> Result<Self, Box<dyn Error>> {
+    let (stream, handle) = rodio::OutputStream::try_default()?;
+    let sink = rodio::Sink::try_new(&handle)?;
+    Ok(Self {
+      _stream: stream,
+      sink,
+    })
+  }
+
+  ///Plays the cue instead of the one playing, so cues don't pile up when moves are executed quickly
+  pub fn play(&self, cue: &[Tone]) {
+    self.sink.clear();
+    self.sink.append(rodio::buffer::SamplesBuffer::new(
+      1,
+      SAMPLE_RATE,
+      synthesize(cue, SAMPLE_RATE),
+    ));
+    self.sink.play();
+  }
+}
+
+///Playing cues needs the `audio` feature
+#[cfg(not(feature = "audio"))]
+pub struct CuePlayer;
+
+#[cfg(not(feature = "audio"))]
+impl CuePlayer {
+  pub fn open() -> Result<Self, Box<dyn Error>> {
+    Err("the gui has been built without the audio feature".into())
+  }
+
+  pub fn play(&self, _cue: &[Tone]) {}
+}
+
+impl Debug for CuePlayer {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("CuePlayer").finish_non_exhaustive()
+  }
+}
+
+#[cfg(test)]
+mod test_audio {
+  use game_logic::position::Dimension::{Column, Row};
+
+  use crate::audio::{dimension_frequency, finished_cue, move_cue, Tone};
+
+  fn beeps(cue: &[Tone]) -> Vec<f32> {
+    cue.iter().filter_map(|tone| tone.frequency).collect()
+  }
+
+  #[test]
+  fn test_move_cue() {
+    let ring = dimension_frequency(Row);
+    let cue = move_cue("r2 3".parse().unwrap());
+    assert_eq!(vec![ring, ring, ring, ring * 1.5], beeps(&cue));
+
+    //columns beep lower and inward moves end a fifth below
+    let column = dimension_frequency(Column);
+    assert!(column < ring);
+    let cue = move_cue("c4 -1".parse().unwrap());
+    assert_eq!(vec![column, column / 1.5], beeps(&cue));
+    //the direction tone stands out by its length
+    assert!(cue[0].duration < cue[2].duration);
+
+    assert_eq!(3, beeps(&finished_cue()).len());
+  }
+
+  #[cfg(feature = "audio")]
+  #[test]
+  fn test_synthesize() {
+    use std::time::Duration;
+
+    use crate::audio::synthesize;
+
+    let tones = [
+      Tone::beep(440.0, Duration::from_millis(100)),
+      Tone::pause(Duration::from_millis(50)),
+    ];
+    let samples = synthesize(&tones, 1000);
+    assert_eq!(150, samples.len());
+    assert!(samples.iter().all(|sample| sample.abs() <= 0.5));
+    //faded in and silent in the pause
+    assert_eq!(0.0, samples[0]);
+    assert!(samples[100..].iter().all(|&sample| sample == 0.0));
+  }
+}
@@ -0,0 +1,148 @@
+use jni::errors::Result;
+use jni::objects::{JObject, JString, JValue};
+use jni::JNIEnv;
+use winit::platform::android::activity::AndroidApp;
+
+use crate::notification::with_activity;
+
+///`MediaStore.ACTION_IMAGE_CAPTURE`, which opens the camera app
+const IMAGE_CAPTURE_ACTION: &str = "android.media.action.IMAGE_CAPTURE";
+///`MediaStore.EXTRA_OUTPUT`, where the camera app saves the photo
+const OUTPUT_EXTRA: &str = "output";
+///`Intent.FLAG_GRANT_WRITE_URI_PERMISSION`, which lets the camera app write the photo
+const GRANT_WRITE_PERMISSION: i32 = 2;
+///The name of the photo in the gallery until it was read
+const PHOTO_NAME: &str = "arena_qr_scan.jpg";
+///How many bytes of the photo are copied from java at once
+const CHUNK_SIZE: i32 = 1 << 16;
+
+///Opens the camera app to take a photo of a qr code. The photo is saved as new picture of the
+/// gallery, whose uri is returned to [read](read_photo) it once the app is resumed.
+pub fn take_photo(app: &AndroidApp) -> Result<String> {
+  with_activity(app, |env, activity| {
+    let resolver = content_resolver(env, activity)?;
+    let values = env.new_object("android/content/ContentValues", "()V", &[])?;
+    for (key, value) in [("_display_name", PHOTO_NAME), ("mime_type", "image/jpeg")] {
+      let key = env.new_string(key)?;
+      let value = env.new_string(value)?;
+      env.call_method(
+        &values,
+        "put",
+        "(Ljava/lang/String;Ljava/lang/String;)V",
+        &[JValue::from(&key), JValue::from(&value)],
+      )?;
+    }
+    let pictures = env
+      .get_static_field(
+        "android/provider/MediaStore$Images$Media",
+        "EXTERNAL_CONTENT_URI",
+        "Landroid/net/Uri;",
+      )?
+      .l()?;
+    let uri = env
+      .call_method(
+        &resolver,
+        "insert",
+        "(Landroid/net/Uri;Landroid/content/ContentValues;)Landroid/net/Uri;",
+        &[JValue::from(&pictures), JValue::from(&values)],
+      )?
+      .l()?;
+
+    let action = env.new_string(IMAGE_CAPTURE_ACTION)?;
+    let intent = env.new_object(
+      "android/content/Intent",
+      "(Ljava/lang/String;)V",
+      &[JValue::from(&action)],
+    )?;
+    let extra = env.new_string(OUTPUT_EXTRA)?;
+    env.call_method(
+      &intent,
+      "putExtra",
+      "(Ljava/lang/String;Landroid/os/Parcelable;)Landroid/content/Intent;",
+      &[JValue::from(&extra), JValue::from(&uri)],
+    )?;
+    env.call_method(
+      &intent,
+      "addFlags",
+      "(I)Landroid/content/Intent;",
+      &[JValue::Int(GRANT_WRITE_PERMISSION)],
+    )?;
+    env.call_method(
+      activity,
+      "startActivity",
+      "(Landroid/content/Intent;)V",
+      &[JValue::from(&intent)],
+    )?;
+
+    let uri = env
+      .call_method(&uri, "toString", "()Ljava/lang/String;", &[])?
+      .l()?;
+    Ok(env.get_string(&JString::from(uri))?.into())
+  })
+}
+
+///Reads the photo [taken](take_photo) for the uri and removes it from the gallery, it was only
+/// needed to scan the qr code. The photo is empty if the camera app was closed without taking one.
+pub fn read_photo(app: &AndroidApp, uri: &str) -> Result<Vec<u8>> {
+  with_activity(app, |env, activity| {
+    let resolver = content_resolver(env, activity)?;
+    let uri = env.new_string(uri)?;
+    let uri = env
+      .call_static_method(
+        "android/net/Uri",
+        "parse",
+        "(Ljava/lang/String;)Landroid/net/Uri;",
+        &[JValue::from(&uri)],
+      )?
+      .l()?;
+    let stream = env
+      .call_method(
+        &resolver,
+        "openInputStream",
+        "(Landroid/net/Uri;)Ljava/io/InputStream;",
+        &[JValue::from(&uri)],
+      )?
+      .l()?;
+    let chunk = env.new_byte_array(CHUNK_SIZE)?;
+    let mut photo = vec![];
+    loop {
+      let read = env
+        .call_method(&stream, "read", "([B)I", &[JValue::from(&chunk)])?
+        .i()?;
+      if read < 0 {
+        break;
+      }
+      let mut bytes = vec![0; read as usize];
+      env.get_byte_array_region(&chunk, 0, &mut bytes)?;
+      photo.extend(bytes.into_iter().map(|byte| byte as u8));
+    }
+    env.call_method(&stream, "close", "()V", &[])?;
+
+    let no_selection = JObject::null();
+    env.call_method(
+      &resolver,
+      "delete",
+      "(Landroid/net/Uri;Ljava/lang/String;[Ljava/lang/String;)I",
+      &[
+        JValue::from(&uri),
+        JValue::from(&no_selection),
+        JValue::from(&no_selection),
+      ],
+    )?;
+    Ok(photo)
+  })
+}
+
+fn content_resolver<'local>(
+  env: &mut JNIEnv<'local>,
+  activity: &JObject,
+) -> Result<JObject<'local>> {
+  env
+    .call_method(
+      activity,
+      "getContentResolver",
+      "()Landroid/content/ContentResolver;",
+      &[],
+    )?
+    .l()
+}
@@ -0,0 +1,61 @@
+use std::error::Error;
+
+///Copies the text to the system clipboard
+#[cfg(all(feature = "clipboard", not(target_os = "android")))]
+pub fn copy(text: &str) -> Result<(), Box<dyn Error>> {
+  use std::sync::Mutex;
+
+  //on some platforms the text is only available as long as the clipboard lives
+  static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+  let mut clipboard = CLIPBOARD.lock().expect("clipboard is poisoned");
+  let clipboard = match &mut *clipboard {
+    Some(clipboard) => clipboard,
+    None => clipboard.insert(arboard::Clipboard::new()?),
+  };
+  Ok(clipboard.set_text(text)?)
+}
+
+///Copying needs the `clipboard` feature on desktops
+#[cfg(all(not(feature = "clipboard"), not(target_os = "android")))]
+pub fn copy(_text: &str) -> Result<(), Box<dyn Error>> {
+  Err("the gui has been built without the clipboard feature".into())
+}
+
+///Copies the text to the clipboard of the device
+#[cfg(target_os = "android")]
+pub fn copy(
+  app: &winit::platform::android::activity::AndroidApp,
+  text: &str,
+) -> Result<(), Box<dyn Error>> {
+  use jni::objects::JValue;
+
+  crate::notification::with_activity(app, |env, activity| {
+    let service = env.new_string("clipboard")?;
+    let manager = env
+      .call_method(
+        activity,
+        "getSystemService",
+        "(Ljava/lang/String;)Ljava/lang/Object;",
+        &[JValue::from(&service)],
+      )?
+      .l()?;
+    let label = env.new_string("arena")?;
+    let text = env.new_string(text)?;
+    let clip = env
+      .call_static_method(
+        "android/content/ClipData",
+        "newPlainText",
+        "(Ljava/lang/CharSequence;Ljava/lang/CharSequence;)Landroid/content/ClipData;",
+        &[JValue::from(&label), JValue::from(&text)],
+      )?
+      .l()?;
+    env.call_method(
+      &manager,
+      "setPrimaryClip",
+      "(Landroid/content/ClipData;)V",
+      &[JValue::from(&clip)],
+    )?;
+    Ok(())
+  })
+  .map_err(Into::into)
+}
@@ -0,0 +1,97 @@
+use game_logic::solving::SolvableArena;
+use winit::event_loop::EventLoopProxy;
+
+use crate::resources::{DecodedTexture, TextureLoadError};
+
+///Everything subsystems tell the event loop, grouped by where it comes from. Each group is handled by
+/// its own handler of the app, see [`EventSender`] for how to send them.
+#[derive(Debug)]
+pub enum AppEvent {
+  Input(InputEvent),
+  Timer(TimerEvent),
+  Resource(ResourceEvent),
+  Solver(SolverEvent),
+  Session(SessionEvent),
+}
+
+///Input which doesn't come through the window
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum InputEvent {
+  ///A global hotkey was pressed to bring the window to the front, see the `global_hotkey` feature
+  Summon { clear_arena: bool },
+  ///Chosen in the menu of the tray icon, see the `tray` feature
+  QuickAction(QuickAction),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TimerEvent {
+  ///Sent by the [`Ticker`](crate::ticker::Ticker), 20 times per second unless
+  /// [`TICK_RATE_VAR`](crate::ticker::TICK_RATE_VAR) says otherwise
+  AnimationTick,
+}
+
+#[derive(Debug)]
+pub enum ResourceEvent {
+  ///A texture was decoded in the background and can be uploaded now
+  TextureLoaded(Result<DecodedTexture, TextureLoadError>),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SolverEvent {
+  ///The search found a better solution or got further, which changes what is shown of it
+  Progressed,
+}
+
+#[derive(Debug)]
+pub enum SessionEvent {
+  ///An attached terminal changed the shared arena, see [`SESSION_VAR`](game_logic::session::SESSION_VAR)
+  ArenaReceived(SolvableArena),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum QuickAction {
+  ToggleWindow,
+  ClearArena,
+  ToggleAlwaysOnTop,
+  ToggleOverlay,
+}
+
+macro_rules! impl_from_event {
+  ($($variant:ident($event:ty)),*) => {
+    $(
+      impl From<$event> for AppEvent {
+        fn from(event: $event) -> Self {
+          AppEvent::$variant(event)
+        }
+      }
+    )*
+  };
+}
+
+impl_from_event!(
+  Input(InputEvent),
+  Timer(TimerEvent),
+  Resource(ResourceEvent),
+  Solver(SolverEvent),
+  Session(SessionEvent)
+);
+
+///Sends events of any group to the event loop, from any thread
+#[derive(Debug, Clone)]
+pub struct EventSender {
+  proxy: EventLoopProxy<AppEvent>,
+}
+
+impl EventSender {
+  pub fn new(proxy: EventLoopProxy<AppEvent>) -> Self {
+    Self { proxy }
+  }
+
+  ///Returns false if the event loop is gone, then nobody handles events anymore
+  pub fn send<E>(&self, event: E) -> bool
+  where
+    E: Into<AppEvent>,
+  {
+    self.proxy.send_event(event.into()).is_ok()
+  }
+}
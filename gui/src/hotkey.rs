@@ -0,0 +1,48 @@
+use std::sync::Mutex;
+
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+use crate::events::{EventSender, InputEvent};
+
+///Brings the window to the front, even while another app like the game is focused
+const SUMMON_KEYS: (Modifiers, Code) = (Modifiers::CONTROL.union(Modifiers::ALT), Code::KeyS);
+///Brings the window to the front and clears the arena, to enter the next fight right away
+const SUMMON_AND_CLEAR_KEYS: (Modifiers, Code) = (
+  Modifiers::CONTROL
+    .union(Modifiers::ALT)
+    .union(Modifiers::SHIFT),
+  Code::KeyS,
+);
+
+///The registered global hotkeys, which stay registered as long as this exists
+pub struct SummonHotkeys {
+  _manager: GlobalHotKeyManager,
+}
+
+impl SummonHotkeys {
+  ///Registers the hotkeys, which send [`InputEvent::Summon`] when pressed. Needs to be called on the
+  /// thread the event loop runs on.
+  pub fn register(events: EventSender) -> Result<Self, global_hotkey::Error> {
+    let manager = GlobalHotKeyManager::new()?;
+    let summon = HotKey::new(Some(SUMMON_KEYS.0), SUMMON_KEYS.1);
+    let summon_and_clear = HotKey::new(Some(SUMMON_AND_CLEAR_KEYS.0), SUMMON_AND_CLEAR_KEYS.1);
+    manager.register_all(&[summon, summon_and_clear])?;
+
+    let events = Mutex::new(events);
+    GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+      if event.state != HotKeyState::Pressed {
+        return;
+      }
+      let clear_arena = event.id == summon_and_clear.id();
+      if clear_arena || event.id == summon.id() {
+        //fails only if the event loop is gone, then there is nothing to summon anymore
+        events
+          .lock()
+          .expect("event sender is poisoned")
+          .send(InputEvent::Summon { clear_arena });
+      }
+    }));
+    Ok(Self { _manager: manager })
+  }
+}
@@ -0,0 +1,77 @@
+use crate::renderer::{Element, ElementId, PTexCoords, Panel, TexRect};
+
+///Opens the settings, see [`Screen::Settings`](crate::app_state::screen::Screen::Settings)
+pub const SETTINGS_BUTTON: ElementId = "settings";
+///Opens the tutorial, see [`Screen::Tutorial`](crate::app_state::screen::Screen::Tutorial)
+pub const TUTORIAL_BUTTON: ElementId = "tutorial";
+///Opens the recent arenas, see [`Screen::Recent`](crate::app_state::screen::Screen::Recent)
+pub const RECENT_BUTTON: ElementId = "recent";
+///Shows the qr code of the arena, see [`Screen::QrCode`](crate::app_state::screen::Screen::QrCode)
+pub const QR_BUTTON: ElementId = "qr";
+///Shows the chart of the latest solves, see [`Screen::Stats`](crate::app_state::screen::Screen::Stats)
+pub const STATS_BUTTON: ElementId = "stats";
+
+///The buttons shown above the arena while neither the settings nor the overlay are shown
+pub fn hud() -> Panel {
+  Panel::horizontal(vec![
+    Element::button(STATS_BUTTON, "stats"),
+    Element::button(TUTORIAL_BUTTON, "?"),
+    Element::button(RECENT_BUTTON, "recent"),
+    Element::button(QR_BUTTON, "qr"),
+    Element::button(SETTINGS_BUTTON, "settings"),
+  ])
+}
+
+///Where the [`hud`] is drawn, in the top right corner. It grows to the left and down with the ui
+/// scale, see [`Settings::UI_SCALES`](crate::settings::Settings::UI_SCALES).
+pub fn hud_area(ui_scale: f32) -> TexRect {
+  TexRect::new(PTexCoords::new(0.63, 0.02), PTexCoords::new(0.98, 0.08))
+    .scaled(PTexCoords::new(0.98, 0.02), ui_scale)
+}
+
+#[cfg(test)]
+mod test_hud {
+  use crate::hud::{
+    hud, hud_area, QR_BUTTON, RECENT_BUTTON, SETTINGS_BUTTON, STATS_BUTTON, TUTORIAL_BUTTON,
+  };
+  use crate::renderer::PTexCoords;
+
+  #[test]
+  fn test_buttons() {
+    let mut hud = hud();
+    assert_eq!(
+      Some(SETTINGS_BUTTON),
+      hud.tap(hud_area(1.0), PTexCoords::new(0.95, 0.05))
+    );
+    assert_eq!(
+      Some(TUTORIAL_BUTTON),
+      hud.tap(hud_area(1.0), PTexCoords::new(0.72, 0.05))
+    );
+    assert_eq!(
+      Some(RECENT_BUTTON),
+      hud.tap(hud_area(1.0), PTexCoords::new(0.8, 0.05))
+    );
+    assert_eq!(
+      Some(QR_BUTTON),
+      hud.tap(hud_area(1.0), PTexCoords::new(0.87, 0.05))
+    );
+    assert_eq!(
+      Some(STATS_BUTTON),
+      hud.tap(hud_area(1.0), PTexCoords::new(0.66, 0.05))
+    );
+    assert_eq!(None, hud.tap(hud_area(1.0), PTexCoords::new(0.5, 0.05)));
+  }
+
+  #[test]
+  fn test_ui_scale() {
+    let mut hud = hud();
+    //the buttons grow from the top right corner
+    let point = PTexCoords::new(0.45, 0.12);
+    assert_eq!(None, hud.tap(hud_area(1.0), point));
+    assert_eq!(Some(TUTORIAL_BUTTON), hud.tap(hud_area(2.0), point));
+    assert_eq!(
+      Some(SETTINGS_BUTTON),
+      hud.tap(hud_area(2.0), PTexCoords::new(0.95, 0.05))
+    );
+  }
+}
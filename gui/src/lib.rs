@@ -1,81 +1,864 @@
 use std::default::Default;
-use std::thread;
-use std::time::Duration;
+use std::env;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
 
+use game_logic::data_dir::data_dir;
+use game_logic::deep_link::{is_link, parse_arena_link, parse_solution};
+use game_logic::session::{SessionServer, SESSION_VAR};
+use game_logic::solving::attack_rules::attack_rules_error;
+use game_logic::solving::{Enemy, SolvableArena};
 use pollster::FutureExt;
 use wgpu::SurfaceError;
 use winit::application::ApplicationHandler;
-use winit::dpi::PhysicalSize;
-use winit::event::WindowEvent;
+use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
+use winit::event::{ElementState, KeyEvent, MouseButton, StartCause, Touch, TouchPhase, WindowEvent};
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
-use winit::window::{Icon, WindowAttributes, WindowId};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+use winit::window::{Icon, WindowAttributes, WindowId, WindowLevel};
 
+use crate::app_state::screen::{Screen, ScreenEvent};
 use crate::app_state::AppState;
-use crate::renderer::Renderer;
-use crate::resources::load_icon;
+use crate::events::{EventSender, InputEvent, ResourceEvent, SessionEvent, SolverEvent, TimerEvent};
+use crate::metrics::{Feature, METRICS_REPORT_FILE};
+use crate::puzzle_timer::TimerAlert;
+use crate::qr_code::{read_arena_qr_code, SHARE_BUTTON};
+use crate::renderer::{CaptureMode, FeedbackTarget, Renderer};
+use crate::resources::{decode_in_background, load_scaled_icon};
+use crate::ticker::Ticker;
 
 mod app_state;
+mod audio;
+#[cfg(target_os = "android")]
+mod camera;
+mod clipboard;
+mod events;
+#[cfg(all(feature = "global_hotkey", not(target_os = "android")))]
+mod hotkey;
+mod hud;
+mod link_handler;
+mod metrics;
+#[cfg(target_os = "android")]
+mod notification;
+mod puzzle_timer;
+mod qr_code;
+mod recent_arenas;
 mod renderer;
 pub mod resources;
+mod settings;
+mod settings_screen;
 mod shader; //generated by build.rs
+mod stats_chart;
+mod ticker;
+#[cfg(all(feature = "tray", any(windows, target_os = "macos")))]
+mod tray;
+
+pub use events::{AppEvent, QuickAction};
 
 pub fn run(event_loop: EventLoop<AppEvent>) {
   env_logger::init();
-  let proxy = event_loop.create_proxy();
+  if let Some(e) = attack_rules_error() {
+    eprintln!(
+      "failed to load the attack rules, using the default ones: {}",
+      e
+    );
+  }
+  let events = EventSender::new(event_loop.create_proxy());
   event_loop.set_control_flow(ControlFlow::Wait);
 
-  let app_icon =
-    load_icon(include_resource_bytes!(icon / app_icon.png)).expect("failed to load app icon");
+  #[cfg(all(feature = "global_hotkey", not(target_os = "android")))]
+  let _hotkeys = hotkey::SummonHotkeys::register(events.clone())
+    .map_err(|e| eprintln!("failed to register global hotkeys: {}", e))
+    .ok();
 
-  event_loop
-    .run_app(&mut App::new(app_icon))
-    .expect("failed to run app");
+  //the ticker lives in the app, so it stops together with the event loop
+  let ticker = Ticker::start(events.clone(), Ticker::configured_rate());
+  let mut app = App::new(events.clone(), ticker);
+  if let Some(server) = start_session(events) {
+    app.state.share_arena(server);
+  }
+  //the system passes clicked links as argument
+  if let Some(link) = env::args().skip(1).find(|arg| is_link(arg)) {
+    app.open_link(&link);
+  }
+  event_loop.run_app(&mut app).expect("failed to run app");
+}
 
-  //send animation tick every 50ms (20tps)
-  //TODO stop sending when app gets suspended -> maybe a Ticker struct
-  thread::spawn(move || {
-    while proxy.send_event(AppEvent::AnimationTick).is_ok() {
-      thread::sleep(Duration::from_millis(50));
-    }
+///Makes the system open arena links with this app, see [`game_logic::deep_link::arena_link`]
+pub fn register_arena_links() -> io::Result<()> {
+  link_handler::register(TITLE)
+}
+
+///Shares the arena on the address of [`SESSION_VAR`], if it is set
+fn start_session(events: EventSender) -> Option<SessionServer> {
+  let address = env::var(SESSION_VAR).ok().filter(|address| !address.is_empty())?;
+  let server = SessionServer::start(&address, SolvableArena::default(), move |arena| {
+    //fails only if the event loop is gone, then nobody shows the arena anymore
+    events.send(SessionEvent::ArenaReceived(arena));
   });
+  match server {
+    Ok(server) => {
+      eprintln!("sharing the arena on {}", server.address());
+      Some(server)
+    }
+    Err(e) => {
+      eprintln!("failed to share the arena on {}: {}", address, e);
+      None
+    }
+  }
 }
 
+const TITLE: &str = "Paper Mario: The Origami King AI";
 const GENERAL_NAME: &str = "paper_mario_origami_king_ai";
 const INSTANCE_NAME: &str = "main";
+///The app icon in every available resolution
+const APP_ICON_VARIANTS: &[&[u8]] = &[include_resource_bytes!(icon / app_icon.png)];
+///Captures the draw list of the next frame, together with shift also an image of each render pass
+const CAPTURE_KEY: NamedKey = NamedKey::F12;
+///Exports the usage metrics to [`METRICS_REPORT_FILE`]
+const EXPORT_METRICS_KEY: NamedKey = NamedKey::F9;
+///Opens the tutorial, see [`Screen::Tutorial`]
+const TUTORIAL_KEY: NamedKey = NamedKey::F1;
+///Switches between the normal window and the compact overlay
+const OVERLAY_KEY: &str = "o";
+///Shows the recent arenas, see [`Screen::Recent`]
+const RECENT_KEY: &str = "r";
+///Shows the qr code of the arena, see [`Screen::QrCode`]
+const QR_KEY: &str = "q";
+///Shows the chart of the latest solves, see [`Screen::Stats`]
+const STATS_KEY: &str = "s";
+///Together with control, or command on macOS, undoes the last change of the enemies
+const UNDO_KEY: &str = "z";
+///Big enough for the board, which is three times as wide as high, at a ui scale of 1
+const OVERLAY_SIZE: LogicalSize<u32> = LogicalSize::new(360, 130);
 
 #[derive(Debug)]
 struct App {
   state: AppState,
   render_state: Option<Renderer>,
-  app_icon: Option<Icon>,
+  events: EventSender,
+  ///sends the animation ticks
+  ticker: Ticker,
+  modifiers: ModifiersState,
+  ///where the mouse is, to know what is clicked
+  cursor_position: PhysicalPosition<f64>,
+  ///whether the window is fullscreen, None if it wasn't set since the window was created
+  fullscreen: Option<bool>,
+  ///whether the window is always on top, None if it wasn't set since the window was created
+  always_on_top: Option<bool>,
+  ///the size and position of the window before it became an overlay
+  windowed: Option<(PhysicalSize<u32>, Option<PhysicalPosition<i32>>)>,
+  ///the photo of a qr code the camera app takes, read when the app is resumed
+  #[cfg(target_os = "android")]
+  pending_scan: Option<String>,
+  ///the link the app was opened with, which is only opened once although it is read on every resume
+  #[cfg(target_os = "android")]
+  opened_link: Option<String>,
+  #[cfg(all(feature = "tray", any(windows, target_os = "macos")))]
+  tray: Option<tray::Tray>,
+  ///plays the audio cues, opened with the first cue. None inside if there is no audio output.
+  cue_player: Option<Option<audio::CuePlayer>>,
 }
 
 impl App {
-  pub fn new<I>(app_icon: I) -> Self
-  where
-    I: Into<Option<Icon>>,
-  {
+  pub fn new(events: EventSender, ticker: Ticker) -> Self {
     Self {
       state: AppState::default(),
       render_state: None,
-      app_icon: app_icon.into(),
+      events,
+      ticker,
+      modifiers: ModifiersState::default(),
+      cursor_position: PhysicalPosition::default(),
+      fullscreen: None,
+      always_on_top: None,
+      windowed: None,
+      #[cfg(target_os = "android")]
+      pending_scan: None,
+      #[cfg(target_os = "android")]
+      opened_link: None,
+      #[cfg(all(feature = "tray", any(windows, target_os = "macos")))]
+      tray: None,
+      cue_player: None,
+    }
+  }
+
+  ///Makes the window fullscreen or not, as configured in the settings. On android this hides the
+  /// status and navigation bar (immersive mode).
+  fn apply_fullscreen(&mut self, event_loop: &ActiveEventLoop) {
+    let fullscreen = self.state.settings.settings().fullscreen;
+    let Some(render_state) = &self.render_state else {
+      return;
+    };
+    if self.fullscreen == Some(fullscreen) {
+      return;
+    }
+    self.fullscreen = Some(fullscreen);
+
+    #[cfg(target_os = "android")]
+    {
+      use winit::platform::android::activity::WindowManagerFlags;
+      use winit::platform::android::ActiveEventLoopExtAndroid;
+
+      let _ = render_state;
+      let flags = WindowManagerFlags::FULLSCREEN | WindowManagerFlags::LAYOUT_IN_SCREEN;
+      if fullscreen {
+        event_loop
+          .android_app()
+          .set_window_flags(flags, WindowManagerFlags::empty());
+      } else {
+        event_loop
+          .android_app()
+          .set_window_flags(WindowManagerFlags::empty(), flags);
+      }
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+      use winit::window::Fullscreen;
+
+      let _ = event_loop;
+      render_state
+        .window()
+        .set_fullscreen(fullscreen.then_some(Fullscreen::Borderless(None)));
+    }
+  }
+
+  ///Keeps the window above all other windows or not, as configured in the settings. The overlay is
+  /// always on top.
+  fn apply_always_on_top(&mut self) {
+    let configured = self.state.settings.settings().always_on_top;
+    let always_on_top = configured || self.state.overlay;
+    let Some(render_state) = &self.render_state else {
+      return;
+    };
+    if self.always_on_top == Some(always_on_top) {
+      return;
+    }
+    self.always_on_top = Some(always_on_top);
+    render_state.window().set_window_level(if always_on_top {
+      WindowLevel::AlwaysOnTop
+    } else {
+      WindowLevel::Normal
+    });
+    #[cfg(all(feature = "tray", any(windows, target_os = "macos")))]
+    if let Some(tray) = &self.tray {
+      tray.set_always_on_top(configured);
+    }
+  }
+
+  ///Switches between the normal window and the compact overlay, which is small, borderless and stays
+  /// on top of other windows like the capture software. The window gets its size and position back
+  /// afterwards.
+  fn toggle_overlay(&mut self) {
+    let Some(render_state) = &self.render_state else {
+      return;
+    };
+    let window = render_state.window();
+    self.state.overlay ^= true;
+    if self.state.overlay {
+      self.state.metrics.record_usage(Feature::Overlay);
+      self.state.transition(ScreenEvent::Close);
+      self.windowed = Some((window.inner_size(), window.outer_position().ok()));
+      window.set_decorations(false);
+      //a larger ui scale enlarges the board of the overlay along with the window
+      let ui_scale = self.state.settings.settings().ui_scale;
+      let _ = window.request_inner_size(LogicalSize::new(
+        OVERLAY_SIZE.width as f32 * ui_scale,
+        OVERLAY_SIZE.height as f32 * ui_scale,
+      ));
+    } else {
+      window.set_decorations(true);
+      if let Some((size, position)) = self.windowed.take() {
+        let _ = window.request_inner_size(size);
+        if let Some(position) = position {
+          window.set_outer_position(position);
+        }
+      }
+    }
+    self.update_title();
+    self.request_redraw();
+  }
+
+  ///Toggles the overlay. While it is shown, escape leaves it and enter, space or the right arrow key
+  /// execute the next move. Returns whether the key was used.
+  fn handle_overlay_key(&mut self, key: &Key) -> bool {
+    match key {
+      Key::Character(character) if character.as_str() == OVERLAY_KEY => {
+        self.toggle_overlay();
+        true
+      }
+      Key::Named(NamedKey::Escape) if self.state.overlay => {
+        self.toggle_overlay();
+        true
+      }
+      Key::Named(NamedKey::Enter | NamedKey::Space | NamedKey::ArrowRight)
+        if self.state.overlay =>
+      {
+        if self.state.execute_next_move() {
+          self.cue_next_move();
+        }
+        self.request_redraw();
+        true
+      }
+      //the settings can't be used in the overlay
+      _ => self.state.overlay,
+    }
+  }
+
+  ///Undoes the last change of the enemies with control + Z, or command + Z on macOS. Returns whether
+  /// the key was used.
+  fn handle_undo_key(&mut self, key: &Key) -> bool {
+    let modified = self.modifiers.control_key() || self.modifiers.super_key();
+    match key {
+      Key::Character(character) if modified && character.as_str() == UNDO_KEY => {
+        self.state.undo();
+        self.update_title();
+        self.request_redraw();
+        true
+      }
+      _ => false,
+    }
+  }
+
+  ///Wakes the event loop up whenever the shown time of the puzzle timer changes and alerts the player
+  /// when the time gets low, also while the app is in the background
+  fn poll_puzzle_timer(&mut self, event_loop: &ActiveEventLoop) {
+    let now = Instant::now();
+    let Some(timer) = &mut self.state.puzzle_timer else {
+      event_loop.set_control_flow(ControlFlow::Wait);
+      return;
+    };
+    let alert = timer.poll(now);
+    event_loop.set_control_flow(match timer.next_update(now) {
+      Some(next_update) => ControlFlow::WaitUntil(next_update),
+      None => ControlFlow::Wait,
+    });
+    if let Some(alert) = alert {
+      self.alert(event_loop, alert);
+    }
+    self.update_title();
+  }
+
+  ///Shakes the arena and tells the player about the puzzle timer with a notification on android,
+  /// or by requesting attention for the window on desktop
+  fn alert(&mut self, event_loop: &ActiveEventLoop, alert: TimerAlert) {
+    self.state.feedback.reject(FeedbackTarget::Arena);
+    let message = alert.message(self.state.settings.settings().language);
+
+    #[cfg(target_os = "android")]
+    {
+      use winit::platform::android::ActiveEventLoopExtAndroid;
+
+      if let Err(e) = notification::notify(event_loop.android_app(), TITLE, message) {
+        eprintln!("failed to show notification: {}", e);
+      }
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+      use winit::window::UserAttentionType;
+
+      let _ = event_loop;
+      eprintln!("{}", message);
+      if let Some(render_state) = &self.render_state {
+        render_state
+          .window()
+          .request_user_attention(Some(UserAttentionType::Critical));
+      }
+    }
+  }
+
+  ///Keeps the arena and the widgets clear of notches and system bars. Only android reports which
+  /// part of the window is covered, but not when that changes, so this is checked before each frame.
+  fn update_safe_area(&mut self) {
+    #[cfg(target_os = "android")]
+    if let Some(render_state) = &mut self.render_state {
+      use winit::platform::android::WindowExtAndroid;
+
+      use crate::renderer::SafeArea;
+
+      let content = render_state.window().content_rect();
+      let size = render_state.window().inner_size();
+      render_state.set_safe_area(SafeArea::from_content_rect(
+        size,
+        content.left,
+        content.top,
+        content.right,
+        content.bottom,
+      ));
+    }
+  }
+
+  ///Opens the tutorial, while it is shown escape and the back button of android close it and enter,
+  /// space or the right arrow key show the next page. Returns whether the key was used.
+  fn handle_tutorial_key(&mut self, key: &Key) -> bool {
+    let event = match key {
+      Key::Named(TUTORIAL_KEY) => ScreenEvent::OpenTutorial,
+      _ if !matches!(self.state.screen, Screen::Tutorial { .. }) => return false,
+      Key::Named(NamedKey::Escape | NamedKey::BrowserBack) => ScreenEvent::Close,
+      Key::Named(NamedKey::Enter | NamedKey::Space | NamedKey::ArrowRight) => ScreenEvent::NextPage,
+      _ => return false,
+    };
+    self.state.transition(event);
+    self.update_title();
+    self.request_redraw();
+    true
+  }
+
+  ///Opens and closes the recent arenas. While they are shown escape and the back button of android
+  /// close them, the arrow keys move the focus and enter or space open the focused arena. Returns
+  /// whether the key was used.
+  fn handle_recent_key(&mut self, event_loop: &ActiveEventLoop, key: &Key) -> bool {
+    match key {
+      Key::Character(character) if character.as_str() == RECENT_KEY => self.state.toggle_recent(),
+      _ if self.state.screen.recent_focus().is_none() => return false,
+      Key::Named(NamedKey::Escape | NamedKey::BrowserBack) => {
+        self.state.transition(ScreenEvent::Close)
+      }
+      Key::Named(NamedKey::ArrowUp) => {
+        self.state.move_recent_focus(-1);
+      }
+      Key::Named(NamedKey::ArrowDown) => {
+        self.state.move_recent_focus(1);
+      }
+      Key::Named(NamedKey::Enter | NamedKey::Space) => {
+        if let Some(path) = self.state.focused_recent_arena() {
+          self.open_arena_file(event_loop, &path);
+        }
+      }
+      _ => return false,
+    }
+    self.update_title();
+    self.request_redraw();
+    true
+  }
+
+  ///Shows and closes the qr code of the arena. While it is shown escape and the back button of
+  /// android close it. Returns whether the key was used.
+  fn handle_qr_key(&mut self, key: &Key) -> bool {
+    match key {
+      Key::Character(character) if character.as_str() == QR_KEY => self.state.toggle_qr_code(),
+      Key::Named(NamedKey::Escape | NamedKey::BrowserBack) if self.state.screen.is_qr_code() => {
+        self.state.transition(ScreenEvent::Close)
+      }
+      _ => return false,
+    }
+    self.update_title();
+    self.request_redraw();
+    true
+  }
+
+  ///Shows and closes the chart of the latest solves. While it is shown escape and the back button
+  /// of android close it. Returns whether the key was used.
+  fn handle_stats_key(&mut self, key: &Key) -> bool {
+    match key {
+      Key::Character(character) if character.as_str() == STATS_KEY => self.state.toggle_stats(),
+      Key::Named(NamedKey::Escape | NamedKey::BrowserBack) if self.state.screen.is_stats() => {
+        self.state.transition(ScreenEvent::Close)
+      }
+      _ => return false,
+    }
+    self.update_title();
+    self.request_redraw();
+    true
+  }
+
+  ///Loads the arena file and starts solving it, or shakes the arena if it can't be loaded. Pictures
+  /// are loaded if they show the qr code of an arena. The recent arenas are closed if they are
+  /// shown.
+  fn open_arena_file(&mut self, event_loop: &ActiveEventLoop, path: &Path) {
+    if self.state.screen.recent_focus().is_some() {
+      self.state.transition(ScreenEvent::Close);
+    }
+    if is_image(path) {
+      let scanned = image::open(path)
+        .map_err(|e| e.to_string())
+        .and_then(|picture| read_arena_qr_code(&picture).map_err(|e| e.to_string()));
+      match scanned {
+        Ok(enemies) => self.import_enemies(enemies),
+        Err(e) => {
+          eprintln!(
+            "can't load {}: {}, recognizing enemies in screenshots without a qr code isn't supported yet",
+            path.display(),
+            e
+          );
+          self.state.feedback.reject(FeedbackTarget::Arena);
+        }
+      }
+    } else if let Err(e) = self.state.load_arena_file(path) {
+      eprintln!("failed to load {}: {}", path.display(), e);
+      self.state.feedback.reject(FeedbackTarget::Arena);
+    } else {
+      self.save_thumbnail();
+      self.state.start_solving();
+      #[cfg(target_os = "android")]
+      if self.state.puzzle_timer.is_some() {
+        use winit::platform::android::ActiveEventLoopExtAndroid;
+
+        if let Err(e) = notification::request_permission(event_loop.android_app()) {
+          eprintln!("failed to request the permission for notifications: {}", e);
+        }
+      }
+      self.poll_puzzle_timer(event_loop);
+    }
+    self.update_title();
+    self.request_redraw();
+  }
+
+  ///Replaces the enemies with shared ones, like from a qr code or a link, and starts solving them
+  fn import_enemies(&mut self, enemies: Vec<Enemy>) {
+    if self.state.screen.is_qr_code() {
+      self.state.transition(ScreenEvent::Close);
+    }
+    self.state.replace_enemies(enemies);
+    self.state.start_solving();
+  }
+
+  ///Replaces the enemies with the ones of an arena link and starts solving them, or shakes the arena
+  /// if the link is invalid
+  fn open_link(&mut self, link: &str) {
+    let shared =
+      parse_arena_link(link).and_then(|enemies| parse_solution(link).map(|moves| (enemies, moves)));
+    match shared {
+      Ok((enemies, moves)) if moves.is_empty() => self.import_enemies(enemies),
+      Ok((enemies, moves)) => {
+        if self.state.screen.is_qr_code() {
+          self.state.transition(ScreenEvent::Close);
+        }
+        self.state.play_shared_solution(enemies, moves);
+        self.cue_next_move();
+      }
+      Err(e) => {
+        eprintln!("can't open {}: {}", link, e);
+        self.state.feedback.reject(FeedbackTarget::Arena);
+      }
+    }
+  }
+
+  ///Opens the link the app was opened with, unless it was opened already
+  #[cfg(target_os = "android")]
+  fn open_launch_link(&mut self, event_loop: &ActiveEventLoop) {
+    use winit::platform::android::ActiveEventLoopExtAndroid;
+
+    match link_handler::launch_link(event_loop.android_app()) {
+      Ok(Some(link)) if self.opened_link.as_ref() != Some(&link) => {
+        self.open_link(&link);
+        self.opened_link = Some(link);
+        self.update_title();
+        self.request_redraw();
+      }
+      Ok(_) => {}
+      Err(e) => eprintln!("failed to read the link the app was opened with: {}", e),
+    }
+  }
+
+  ///Beeps the next move of the current solution if audio cues are enabled, or a chord once every
+  /// move is executed
+  fn cue_next_move(&mut self) {
+    if !self.state.settings.settings().audio_cues {
+      return;
+    }
+    let cue = match self.state.next_move() {
+      Some(next_move) => audio::move_cue(next_move),
+      None => audio::finished_cue(),
+    };
+    let player = self.cue_player.get_or_insert_with(|| {
+      audio::CuePlayer::open()
+        .map_err(|e| eprintln!("can't play audio cues: {}", e))
+        .ok()
+    });
+    if let Some(player) = player {
+      player.play(&cue);
+    }
+  }
+
+  ///Copies a link to the arena and its current solution to the clipboard. The link is printed as
+  /// well, so it can be shared from the console if there is no clipboard.
+  fn copy_share_link(&mut self, _event_loop: &ActiveEventLoop) {
+    let link = self.state.share_link();
+    println!("{}", link);
+    #[cfg(target_os = "android")]
+    let copied = {
+      use winit::platform::android::ActiveEventLoopExtAndroid;
+
+      clipboard::copy(_event_loop.android_app(), &link)
+    };
+    #[cfg(not(target_os = "android"))]
+    let copied = clipboard::copy(&link);
+    if let Err(e) = copied {
+      eprintln!("failed to copy the link: {}", e);
+      self.state.feedback.reject(FeedbackTarget::Arena);
+      self.request_redraw();
+    }
+  }
+
+  ///Opens the camera app to scan a qr code, which is imported once the app is resumed
+  #[cfg(target_os = "android")]
+  fn take_qr_photo(&mut self, event_loop: &ActiveEventLoop) {
+    use winit::platform::android::ActiveEventLoopExtAndroid;
+
+    match camera::take_photo(event_loop.android_app()) {
+      Ok(uri) => self.pending_scan = Some(uri),
+      Err(e) => {
+        eprintln!("failed to open the camera: {}", e);
+        self.state.feedback.reject(FeedbackTarget::Arena);
+      }
+    }
+  }
+
+  ///Imports the arena of the qr code the camera app took a photo of, if any
+  #[cfg(target_os = "android")]
+  fn import_qr_photo(&mut self, event_loop: &ActiveEventLoop) {
+    use winit::platform::android::ActiveEventLoopExtAndroid;
+
+    let Some(uri) = self.pending_scan.take() else {
+      return;
+    };
+    let scanned = camera::read_photo(event_loop.android_app(), &uri)
+      .map_err(|e| e.to_string())
+      .and_then(|photo| image::load_from_memory(&photo).map_err(|e| e.to_string()))
+      .and_then(|photo| read_arena_qr_code(&photo).map_err(|e| e.to_string()));
+    match scanned {
+      Ok(enemies) => self.import_enemies(enemies),
+      Err(e) => {
+        eprintln!("failed to scan the qr code: {}", e);
+        self.state.feedback.reject(FeedbackTarget::Arena);
+      }
+    }
+    self.update_title();
+    self.request_redraw();
+  }
+
+  ///Saves a thumbnail of the arena loaded last next to its file and shows it in the recent arenas
+  fn save_thumbnail(&mut self) {
+    let (Some(render_state), Some(latest)) =
+      (&mut self.render_state, self.state.recent.arenas().first())
+    else {
+      return;
+    };
+    let path = latest.path.clone();
+    let settings = self.state.settings.settings();
+    match render_state.save_thumbnail(&self.state.arena.layers.0, &path, settings) {
+      Ok(thumbnail) => self.state.recent.set_latest_thumbnail(thumbnail),
+      Err(e) => eprintln!("failed to save the thumbnail of {}: {}", path.display(), e),
+    }
+  }
+
+  ///Navigates the settings screen: escape opens and closes it, the back button of android closes
+  /// it, the arrow keys move the focus and change the focused setting, enter and space activate it.
+  /// Returns whether the key was used.
+  fn handle_settings_key(&mut self, key: &Key) -> bool {
+    let changed = match key {
+      Key::Named(NamedKey::Escape) => {
+        self.state.toggle_settings();
+        true
+      }
+      Key::Named(NamedKey::BrowserBack) if self.state.screen.settings().is_some() => {
+        self.state.toggle_settings();
+        true
+      }
+      Key::Named(NamedKey::ArrowUp) => self.state.edit_settings(|widgets| widgets.move_focus(-1)),
+      Key::Named(NamedKey::ArrowDown) => self.state.edit_settings(|widgets| widgets.move_focus(1)),
+      Key::Named(NamedKey::ArrowLeft) => self.state.step_setting(-1),
+      Key::Named(NamedKey::ArrowRight) => self.state.step_setting(1),
+      Key::Named(NamedKey::Enter | NamedKey::Space) => self
+        .state
+        .edit_settings(|widgets| widgets.focused_widget_mut().activate()),
+      _ => false,
+    };
+    if changed {
+      self.update_title();
+      self.request_redraw();
+    }
+    changed
+  }
+
+  fn tap(&mut self, event_loop: &ActiveEventLoop, position: PhysicalPosition<f64>) {
+    let Some(render_state) = &self.render_state else {
+      return;
+    };
+    let point = render_state.relative_position(position);
+    if let Some(path) = self.state.recent_arena_at(point) {
+      self.open_arena_file(event_loop, &path);
+    } else if let Some(button) = self.state.qr_button_at(point) {
+      match button {
+        SHARE_BUTTON => self.copy_share_link(event_loop),
+        #[cfg(target_os = "android")]
+        qr_code::SCAN_BUTTON => self.take_qr_photo(event_loop),
+        _ => {}
+      }
+    } else if self.state.tap(point) {
+      self.update_title();
+      self.request_redraw();
+    }
+  }
+
+  fn request_redraw(&self) {
+    if let Some(render_state) = &self.render_state {
+      render_state.window().request_redraw();
+    }
+  }
+
+  ///Shows the remaining time of the puzzle timer and the solver status, or the focused setting,
+  /// recent arena or stats summary while they are shown, in the title bar, which serves as status bar until the app can render
+  /// text
+  fn update_title(&self) {
+    if let Some(render_state) = &self.render_state {
+      let language = self.state.settings.settings().language;
+      let status = match &self.state.screen {
+        Screen::Settings { settings, .. } => Some(settings.status()),
+        screen @ Screen::Tutorial { .. } => screen.tutorial_status(language),
+        Screen::Recent { .. } => self.state.recent_status(),
+        Screen::QrCode { .. } => self.state.qr_code.as_ref().map(|(text, _)| text.clone()),
+        Screen::Stats { .. } => self.state.stats_status(),
+        _ => self.state.solver_status(),
+      };
+      let timer = self
+        .state
+        .puzzle_timer
+        .map(|timer| timer.format_remaining(Instant::now()));
+      let title = [Some(TITLE.to_string()), timer, status]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" - ");
+      render_state.window().set_title(&title);
+    }
+  }
+}
+
+///Handlers of the [`AppEvent`]s, one per group
+impl App {
+  fn dispatch(&mut self, event: AppEvent) {
+    match event {
+      AppEvent::Input(event) => self.handle_input(event),
+      AppEvent::Timer(event) => self.handle_timer(event),
+      AppEvent::Resource(event) => self.handle_resource(event),
+      AppEvent::Solver(event) => self.handle_solver(event),
+      AppEvent::Session(event) => self.handle_session(event),
+    }
+  }
+
+  fn handle_input(&mut self, event: InputEvent) {
+    if let InputEvent::QuickAction(_) = event {
+      self.state.metrics.record_usage(Feature::QuickAction);
+    }
+    let Some(render_state) = &self.render_state else {
+      return;
+    };
+    let window = render_state.window();
+    match event {
+      InputEvent::Summon { clear_arena } => {
+        self.state.metrics.record_usage(Feature::Summon);
+        window.set_visible(true);
+        window.set_minimized(false);
+        window.focus_window();
+        if clear_arena {
+          self.state.clear_arena();
+          self.update_title();
+        }
+      }
+      InputEvent::QuickAction(QuickAction::ToggleWindow) => {
+        let visible = window.is_visible().unwrap_or(true);
+        window.set_visible(!visible);
+        if !visible {
+          window.set_minimized(false);
+          window.focus_window();
+        }
+      }
+      InputEvent::QuickAction(QuickAction::ClearArena) => {
+        self.state.clear_arena();
+        self.update_title();
+      }
+      InputEvent::QuickAction(QuickAction::ToggleAlwaysOnTop) => {
+        self
+          .state
+          .change_settings(|settings| settings.always_on_top ^= true);
+      }
+      InputEvent::QuickAction(QuickAction::ToggleOverlay) => {
+        self.toggle_overlay();
+      }
+    }
+    self.request_redraw();
+  }
+
+  fn handle_timer(&mut self, event: TimerEvent) {
+    match event {
+      TimerEvent::AnimationTick => {
+        if self.render_state.is_none() {
+          return;
+        }
+        self.state.height -= 1;
+        let animation_speed = self.state.settings.settings().animation_speed;
+        self
+          .state
+          .feedback
+          .tick(animation_speed * self.ticker.tick_scale());
+        self.request_redraw();
+      }
+    }
+  }
+
+  fn handle_resource(&mut self, event: ResourceEvent) {
+    match event {
+      ResourceEvent::TextureLoaded(decoded) => {
+        if let Some(render_state) = &mut self.render_state {
+          render_state.add_texture(decoded);
+          render_state.window().request_redraw();
+        }
+      }
+    }
+  }
+
+  fn handle_solver(&mut self, event: SolverEvent) {
+    match event {
+      SolverEvent::Progressed => {
+        if self.state.solver.is_none() && self.state.current_solution.is_some() {
+          self.cue_next_move();
+        }
+        self.update_title();
+        self.request_redraw();
+      }
+    }
+  }
+
+  fn handle_session(&mut self, event: SessionEvent) {
+    match event {
+      SessionEvent::ArenaReceived(arena) => {
+        self.state.replace_arena(arena);
+        self.update_title();
+        self.request_redraw();
+      }
     }
   }
 }
 
 impl ApplicationHandler<AppEvent> for App {
+  fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+    if let StartCause::ResumeTimeReached { .. } = cause {
+      self.poll_puzzle_timer(event_loop);
+    }
+  }
+
   fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+    self.ticker.resume();
+    let scale_factor = event_loop
+      .primary_monitor()
+      .map_or(1.0, |monitor| monitor.scale_factor());
+    let app_icon: Option<Icon> = load_scaled_icon(APP_ICON_VARIANTS, scale_factor)
+      .map_err(|e| eprintln!("failed to load app icon: {}", e))
+      .ok();
+
     #[allow(unused_mut)]
     let mut window_attributes = WindowAttributes::default()
-      .with_title("Paper Mario: The Origami King AI")
+      .with_title(TITLE)
       .with_inner_size(PhysicalSize::new(600, 800))
-      .with_window_icon(self.app_icon.clone());
+      .with_window_icon(app_icon.clone());
     #[cfg(windows)]
     {
       use winit::platform::windows::WindowAttributesExtWindows;
-      window_attributes = window_attributes.with_taskbar_icon(self.app_icon.clone());
+      window_attributes = window_attributes.with_taskbar_icon(app_icon);
     }
-    #[cfg(unix)]
+    #[cfg(all(unix, not(target_os = "android")))]
     {
       use winit::platform::x11::ActiveEventLoopExtX11;
       use winit::platform::wayland::ActiveEventLoopExtWayland;
@@ -94,18 +877,37 @@ impl ApplicationHandler<AppEvent> for App {
     let window = event_loop
       .create_window(window_attributes)
       .expect("failed to create window");
-    self.render_state = Some(Renderer::new(window).block_on());
+    #[cfg(all(feature = "tray", any(windows, target_os = "macos")))]
+    if self.tray.is_none() {
+      self.tray = tray::Tray::new(
+        TITLE,
+        APP_ICON_VARIANTS,
+        scale_factor,
+        self.state.settings.settings().always_on_top,
+        self.events.clone(),
+      )
+      .map_err(|e| eprintln!("failed to create tray icon: {}", e))
+      .ok();
+    }
+    let render_state = Renderer::new(window).block_on();
+    let events = self.events.clone();
+    decode_in_background(render_state.texture_requests(), move |decoded| {
+      //fails only if the event loop is gone, then nobody needs the texture anymore
+      events.send(ResourceEvent::TextureLoaded(decoded));
+    });
+    self.render_state = Some(render_state);
+    #[cfg(target_os = "android")]
+    {
+      self.open_launch_link(event_loop);
+      self.import_qr_photo(event_loop);
+    }
   }
 
   fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: AppEvent) {
-    if let Some(render_state) = &self.render_state {
-      match event {
-        AppEvent::AnimationTick => {
-          self.state.height -= 1;
-        }
-      }
-
-      render_state.window().request_redraw();
+    self.dispatch(event);
+    //the search runs on its own thread, any event is a chance to look how far it got
+    if self.state.poll_solver() {
+      self.dispatch(SolverEvent::Progressed.into());
     }
   }
 
@@ -122,16 +924,112 @@ impl ApplicationHandler<AppEvent> for App {
         }
       }
       WindowEvent::RedrawRequested => {
+        self.update_safe_area();
         if let Some(render_state) = &mut self.render_state {
+          if render_state.is_device_lost() {
+            render_state.recreate(true);
+          }
           if let Err(e) = render_state.render(&self.state) {
             match e {
-              SurfaceError::Lost => render_state.resize(render_state.size()),
+              SurfaceError::Lost | SurfaceError::Outdated => render_state.recreate(false),
               SurfaceError::OutOfMemory => event_loop.exit(),
               _ => eprintln!("render error: {}", e),
             }
           }
         }
       }
+      WindowEvent::ModifiersChanged(modifiers) => {
+        self.modifiers = modifiers.state();
+      }
+      WindowEvent::KeyboardInput {
+        event:
+          KeyEvent {
+            logical_key: Key::Named(CAPTURE_KEY),
+            state: ElementState::Pressed,
+            repeat: false,
+            ..
+          },
+        ..
+      } => {
+        self.state.metrics.record_usage(Feature::FrameCapture);
+        if let Some(render_state) = &mut self.render_state {
+          render_state.capture_next_frame(if self.modifiers.shift_key() {
+            CaptureMode::WithImages
+          } else {
+            CaptureMode::DrawList
+          });
+        }
+        self.request_redraw();
+      }
+      WindowEvent::KeyboardInput {
+        event:
+          KeyEvent {
+            logical_key: Key::Named(EXPORT_METRICS_KEY),
+            state: ElementState::Pressed,
+            repeat: false,
+            ..
+          },
+        ..
+      } => {
+        let path = data_dir().join(METRICS_REPORT_FILE);
+        match self.state.metrics.export(&path, &self.state.capabilities) {
+          Ok(()) => eprintln!("exported usage metrics to {}", path.display()),
+          Err(e) => eprintln!("failed to export usage metrics: {}", e),
+        }
+      }
+      WindowEvent::KeyboardInput {
+        event:
+          KeyEvent {
+            logical_key,
+            state: ElementState::Pressed,
+            ..
+          },
+        ..
+      } if !self.handle_overlay_key(&logical_key)
+        && !self.handle_undo_key(&logical_key)
+        && !self.handle_tutorial_key(&logical_key)
+        && !self.handle_recent_key(event_loop, &logical_key)
+        && !self.handle_qr_key(&logical_key)
+        && !self.handle_stats_key(&logical_key) =>
+      {
+        self.handle_settings_key(&logical_key);
+      }
+      WindowEvent::CursorMoved { position, .. } => {
+        self.cursor_position = position;
+      }
+      WindowEvent::MouseInput {
+        state: ElementState::Pressed,
+        button: MouseButton::Left,
+        ..
+      } => {
+        if self.state.overlay {
+          //the overlay has no title bar to move it with
+          if let Some(render_state) = &self.render_state {
+            let _ = render_state.window().drag_window();
+          }
+        } else {
+          self.tap(event_loop, self.cursor_position);
+        }
+      }
+      WindowEvent::Touch(Touch {
+        phase: TouchPhase::Started,
+        location,
+        ..
+      }) => {
+        self.tap(event_loop, location);
+      }
+      WindowEvent::HoveredFile(_) => {
+        self.state.hovered_file = true;
+        self.request_redraw();
+      }
+      WindowEvent::HoveredFileCancelled => {
+        self.state.hovered_file = false;
+        self.request_redraw();
+      }
+      WindowEvent::DroppedFile(path) => {
+        self.state.hovered_file = false;
+        self.open_arena_file(event_loop, &path);
+      }
       WindowEvent::CloseRequested => {
         event_loop.exit();
       }
@@ -139,13 +1037,26 @@ impl ApplicationHandler<AppEvent> for App {
     }
   }
 
+  fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+    self.state.publish_arena();
+    self.apply_fullscreen(event_loop);
+    self.apply_always_on_top();
+  }
+
   fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+    //nothing is drawn in the background, so animations wait for the app to return
+    self.ticker.pause();
     self.render_state = None;
+    self.fullscreen = None;
+    self.always_on_top = None;
   }
 }
 
-#[derive(Debug)]
-pub enum AppEvent {
-  ///Will be sent every 50ms (20 tps)
-  AnimationTick,
+fn is_image(path: &Path) -> bool {
+  path
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .is_some_and(|extension| {
+      ["png", "jpg", "jpeg", "bmp", "webp"].contains(&extension.to_ascii_lowercase().as_str())
+    })
 }
@@ -0,0 +1,90 @@
+use std::io;
+#[cfg(any(windows, target_os = "linux"))]
+use std::process::Command;
+
+use game_logic::deep_link::SCHEME;
+
+///The desktop entry opening links on linux, in the applications folder of the user
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = "tok-solver.desktop";
+
+///Makes the system open [`SCHEME`] links with this executable, which gets the link as argument. The
+/// app is registered for the current user only.
+#[cfg(windows)]
+pub fn register(app_name: &str) -> io::Result<()> {
+  let executable = std::env::current_exe()?;
+  let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+  let command_key = format!(r"{}\shell\open\command", key);
+  let command = format!("\"{}\" \"%1\"", executable.display());
+  let description = format!("URL:{}", app_name);
+  run("reg", &["add", &key, "/ve", "/d", &description, "/f"])?;
+  run("reg", &["add", &key, "/v", "URL Protocol", "/d", "", "/f"])?;
+  run("reg", &["add", &command_key, "/ve", "/d", &command, "/f"])
+}
+
+///Makes the system open [`SCHEME`] links with this executable, which gets the link as argument. The
+/// app is registered for the current user only.
+#[cfg(target_os = "linux")]
+pub fn register(app_name: &str) -> io::Result<()> {
+  use std::env;
+  use std::fs;
+  use std::path::PathBuf;
+
+  let executable = env::current_exe()?;
+  let applications = env::var_os("XDG_DATA_HOME")
+    .map(PathBuf::from)
+    .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    .ok_or_else(|| io::Error::other("HOME isn't set"))?
+    .join("applications");
+  fs::create_dir_all(&applications)?;
+  let entry = format!(
+    "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\" %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+    app_name,
+    executable.display(),
+    SCHEME
+  );
+  fs::write(applications.join(DESKTOP_FILE), entry)?;
+  let mime_type = format!("x-scheme-handler/{}", SCHEME);
+  run("xdg-mime", &["default", DESKTOP_FILE, &mime_type])
+}
+
+///Links can only be registered on windows and linux, macos reads them from the bundle of an app
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn register(_app_name: &str) -> io::Result<()> {
+  Err(io::Error::new(
+    io::ErrorKind::Unsupported,
+    format!("{} links can't be registered on this system", SCHEME),
+  ))
+}
+
+#[cfg(any(windows, target_os = "linux"))]
+fn run(program: &str, args: &[&str]) -> io::Result<()> {
+  let status = Command::new(program).args(args).status()?;
+  if status.success() {
+    Ok(())
+  } else {
+    let message = format!("{} failed with {}", program, status);
+    Err(io::Error::other(message))
+  }
+}
+
+///The link the app was opened with, if it was opened by tapping one
+#[cfg(target_os = "android")]
+pub fn launch_link(
+  app: &winit::platform::android::activity::AndroidApp,
+) -> jni::errors::Result<Option<String>> {
+  use jni::objects::JString;
+
+  crate::notification::with_activity(app, |env, activity| {
+    let intent = env
+      .call_method(activity, "getIntent", "()Landroid/content/Intent;", &[])?
+      .l()?;
+    let data = env
+      .call_method(&intent, "getDataString", "()Ljava/lang/String;", &[])?
+      .l()?;
+    if data.is_null() {
+      return Ok(None);
+    }
+    Ok(Some(env.get_string(&JString::from(data))?.into()))
+  })
+}
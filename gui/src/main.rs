@@ -1,8 +1,24 @@
+use std::env;
+use std::process;
+
 use winit::event_loop::EventLoop;
 
-use gui::run;
+use gui::{register_arena_links, run};
+
+///Registers the app for arena links instead of starting it
+const REGISTER_LINKS_ARG: &str = "--register-links";
 
 fn main() {
+  if env::args().skip(1).any(|arg| arg == REGISTER_LINKS_ARG) {
+    match register_arena_links() {
+      Ok(()) => println!("arena links open this app from now on"),
+      Err(e) => {
+        eprintln!("failed to register arena links: {}", e);
+        process::exit(1);
+      }
+    }
+    return;
+  }
   let event_loop = EventLoop::with_user_event()
     .build()
     .expect("Failed to create event loop");
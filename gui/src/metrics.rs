@@ -0,0 +1,332 @@
+use std::env;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use game_logic::capabilities::Capability;
+use game_logic::data_dir::{create_parent, data_file};
+
+///The environment variable pointing to the file the metrics are stored in
+pub const METRICS_VAR: &str = "TOK_METRICS";
+///The file in the [data directory](game_logic::data_dir::data_dir) the metrics are stored in if
+/// [`METRICS_VAR`] isn't set
+pub const DEFAULT_METRICS_FILE: &str = "metrics.txt";
+///The file in the [data directory](game_logic::data_dir::data_dir) the metrics are exported to, to
+/// be attached to an issue
+pub const METRICS_REPORT_FILE: &str = "metrics_report.txt";
+
+///The features whose usage is counted
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Feature {
+  ArenaFile,
+  Settings,
+  Overlay,
+  PuzzleTimer,
+  ///bringing the window to the front with a global hotkey
+  Summon,
+  ///an action from the tray menu
+  QuickAction,
+  FrameCapture,
+  ///showing the list of recent arenas
+  RecentArenas,
+  ///showing the qr code of the arena
+  QrCode,
+}
+
+impl Feature {
+  pub const ALL: [Feature; 9] = [
+    Feature::ArenaFile,
+    Feature::Settings,
+    Feature::Overlay,
+    Feature::PuzzleTimer,
+    Feature::Summon,
+    Feature::QuickAction,
+    Feature::FrameCapture,
+    Feature::RecentArenas,
+    Feature::QrCode,
+  ];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Feature::ArenaFile => "arena_file",
+      Feature::Settings => "settings",
+      Feature::Overlay => "overlay",
+      Feature::PuzzleTimer => "puzzle_timer",
+      Feature::Summon => "summon",
+      Feature::QuickAction => "quick_action",
+      Feature::FrameCapture => "frame_capture",
+      Feature::RecentArenas => "recent_arenas",
+      Feature::QrCode => "qr_code",
+    }
+  }
+
+  pub fn by_name(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|feature| feature.name() == name)
+  }
+
+  fn index(&self) -> usize {
+    Self::ALL
+      .iter()
+      .position(|feature| feature == self)
+      .expect("all features are listed")
+  }
+}
+
+///Counts how the app is used, without anything identifying the user. They are only stored locally,
+/// see [`MetricsStore`], and only leave the device if the user attaches an export to an issue.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Metrics {
+  ///searches which found a solution
+  pub solves: u32,
+  ///searches which finished without finding a solution
+  pub failed_solves: u32,
+  ///the time all searches which found a solution took together
+  pub solve_time: Duration,
+  ///how often each feature was used, in the order of [`Feature::ALL`]
+  pub feature_usage: [u32; Feature::ALL.len()],
+}
+
+impl Metrics {
+  ///Counts a finished search, which took the given time
+  pub fn record_solve(&mut self, elapsed: Duration, found_solution: bool) {
+    if found_solution {
+      self.solves += 1;
+      self.solve_time += elapsed;
+    } else {
+      self.failed_solves += 1;
+    }
+  }
+
+  pub fn record_usage(&mut self, feature: Feature) {
+    self.feature_usage[feature.index()] += 1;
+  }
+
+  pub fn usage(&self, feature: Feature) -> u32 {
+    self.feature_usage[feature.index()]
+  }
+
+  ///The average time of the searches which found a solution
+  pub fn average_solve_time(&self) -> Option<Duration> {
+    (self.solves > 0).then(|| self.solve_time / self.solves)
+  }
+
+  ///The metrics in the format of the metrics file, headed by the app version, the operating system
+  /// and the capabilities of the build, to be attached to issues
+  pub fn report(&self, capabilities: &[Capability]) -> String {
+    let mut report = format!(
+      "# usage metrics of version {} on {}\n",
+      env!("CARGO_PKG_VERSION"),
+      env::consts::OS
+    );
+    for capability in capabilities {
+      report += &format!("# {}\n", capability);
+    }
+    if let Some(average) = self.average_solve_time() {
+      report += &format!("# average solve time: {:.3}s\n", average.as_secs_f64());
+    }
+    report + &self.to_string()
+  }
+
+  ///Sets a metric from its representation in the metrics file
+  fn set(&mut self, key: &str, value: &str) -> Option<()> {
+    match key.split_once('.') {
+      Some(("uses", feature)) => {
+        self.feature_usage[Feature::by_name(feature)?.index()] = value.parse().ok()?
+      }
+      Some(_) => return None,
+      None => match key {
+        "solves" => self.solves = value.parse().ok()?,
+        "failed_solves" => self.failed_solves = value.parse().ok()?,
+        "solve_time_ms" => self.solve_time = Duration::from_millis(value.parse().ok()?),
+        _ => return None,
+      },
+    }
+    Some(())
+  }
+}
+
+///Parses the metrics file, which has the format of the settings file. Metrics which aren't listed
+/// are zero.
+impl TryFrom<&str> for Metrics {
+  type Error = MetricsError;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    let mut metrics = Metrics::default();
+    for line in value
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+      line
+        .split_once('=')
+        .and_then(|(key, value)| metrics.set(key.trim(), value.trim()))
+        .ok_or(MetricsError::InvalidLine(line.to_string()))?;
+    }
+    Ok(metrics)
+  }
+}
+
+impl Display for Metrics {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    writeln!(f, "solves = {}", self.solves)?;
+    writeln!(f, "failed_solves = {}", self.failed_solves)?;
+    writeln!(f, "solve_time_ms = {}", self.solve_time.as_millis())?;
+    for feature in Feature::ALL {
+      writeln!(f, "uses.{} = {}", feature.name(), self.usage(feature))?;
+    }
+    Ok(())
+  }
+}
+
+///The metrics saved in a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsStore {
+  path: PathBuf,
+  metrics: Metrics,
+}
+
+impl MetricsStore {
+  ///Loads the metrics from the file [`METRICS_VAR`] points to, or from [`DEFAULT_METRICS_FILE`]
+  pub fn load_default() -> Result<Self, MetricsError> {
+    Self::load(Self::default_path())
+  }
+
+  ///Loads the metrics from the given file, which doesn't have to exist yet
+  pub fn load<P>(path: P) -> Result<Self, MetricsError>
+  where
+    P: AsRef<Path>,
+  {
+    let path = path.as_ref().to_path_buf();
+    let metrics = match fs::read_to_string(&path) {
+      Ok(content) => Metrics::try_from(content.as_str())?,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => Metrics::default(),
+      Err(e) => return Err(e.into()),
+    };
+    Ok(Self { path, metrics })
+  }
+
+  ///Starts counting from zero if the metrics can't be loaded. Recording overwrites the file then.
+  pub fn load_default_or_reset() -> Self {
+    Self::load_default().unwrap_or_else(|e| {
+      eprintln!("failed to load metrics, starting from zero: {}", e);
+      Self {
+        path: Self::default_path(),
+        metrics: Metrics::default(),
+      }
+    })
+  }
+
+  fn default_path() -> PathBuf {
+    data_file(METRICS_VAR, DEFAULT_METRICS_FILE)
+  }
+
+  ///Changes the metrics and writes them to the file. Failing to save is only logged, since the
+  /// metrics aren't worth interrupting the user for.
+  pub fn record<F>(&mut self, record: F)
+  where
+    F: FnOnce(&mut Metrics),
+  {
+    record(&mut self.metrics);
+    let saved =
+      create_parent(&self.path).and_then(|_| fs::write(&self.path, self.metrics.to_string()));
+    if let Err(e) = saved {
+      eprintln!("failed to save metrics: {}", e);
+    }
+  }
+
+  pub fn record_usage(&mut self, feature: Feature) {
+    self.record(|metrics| metrics.record_usage(feature));
+  }
+
+  ///Writes the [report](Metrics::report) to the given file
+  pub fn export<P>(&self, path: P, capabilities: &[Capability]) -> io::Result<()>
+  where
+    P: AsRef<Path>,
+  {
+    create_parent(path.as_ref())?;
+    fs::write(path, self.metrics.report(capabilities))
+  }
+}
+
+#[derive(Debug)]
+pub enum MetricsError {
+  IO(io::Error),
+  InvalidLine(String),
+}
+
+impl From<io::Error> for MetricsError {
+  fn from(value: io::Error) -> Self {
+    Self::IO(value)
+  }
+}
+
+impl Display for MetricsError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      MetricsError::IO(e) => write!(f, "IO error: {}", e),
+      MetricsError::InvalidLine(line) => {
+        write!(
+          f,
+          "Invalid metric '{}', needs to be '<key> = <value>'",
+          line
+        )
+      }
+    }
+  }
+}
+
+impl Error for MetricsError {}
+
+#[cfg(test)]
+mod test_metrics {
+  use std::env;
+  use std::fs;
+  use std::time::Duration;
+
+  use game_logic::capabilities::CAPABILITIES;
+
+  use crate::metrics::{Feature, Metrics, MetricsStore};
+
+  #[test]
+  fn test_parse() {
+    let mut metrics = Metrics::default();
+    assert_eq!(None, metrics.average_solve_time());
+    metrics.record_solve(Duration::from_secs(1), true);
+    metrics.record_solve(Duration::from_secs(3), true);
+    metrics.record_solve(Duration::from_secs(60), false);
+    metrics.record_usage(Feature::Overlay);
+    assert_eq!(Some(Duration::from_secs(2)), metrics.average_solve_time());
+    assert_eq!(1, metrics.failed_solves);
+    assert_eq!(1, metrics.usage(Feature::Overlay));
+
+    assert_eq!(
+      metrics,
+      Metrics::try_from(metrics.to_string().as_str()).unwrap()
+    );
+    //the report can be read back in as well
+    assert_eq!(
+      metrics,
+      Metrics::try_from(metrics.report(&CAPABILITIES).as_str()).unwrap()
+    );
+
+    assert!(Metrics::try_from("uses.teleport = 1").is_err());
+    assert!(Metrics::try_from("solves = many").is_err());
+  }
+
+  #[test]
+  fn test_save_and_load() {
+    let path = env::temp_dir().join(format!("tok_metrics_{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let mut store = MetricsStore::load(&path).expect("missing file has no metrics");
+    assert_eq!(Metrics::default(), store.metrics);
+    store.record_usage(Feature::ArenaFile);
+
+    let store = MetricsStore::load(&path).expect("failed to load");
+    assert_eq!(1, store.metrics.usage(Feature::ArenaFile));
+    let _ = fs::remove_file(&path);
+  }
+}
@@ -0,0 +1,128 @@
+use jni::errors::Result;
+use jni::objects::{JObject, JValue};
+use jni::{JNIEnv, JavaVM};
+use winit::platform::android::activity::AndroidApp;
+
+///The notification channel the player can mute in the android settings
+const CHANNEL_ID: &str = "puzzle_timer";
+const CHANNEL_NAME: &str = "Puzzle timer";
+///`NotificationManager.IMPORTANCE_HIGH`, which makes the notification pop up
+const IMPORTANCE_HIGH: i32 = 4;
+///Showing a notification again with the same id replaces the old one
+const NOTIFICATION_ID: i32 = 1;
+///Needed to show notifications since android 13, unknown and ignored before
+const NOTIFICATION_PERMISSION: &str = "android.permission.POST_NOTIFICATIONS";
+
+///Runs a function with the java environment of the app and its activity
+pub(crate) fn with_activity<F, T>(app: &AndroidApp, f: F) -> Result<T>
+where
+  F: FnOnce(&mut JNIEnv, &JObject) -> Result<T>,
+{
+  //the pointers stay valid as long as the app runs
+  let vm = unsafe { JavaVM::from_raw(app.vm_as_ptr().cast()) }?;
+  let activity = unsafe { JObject::from_raw(app.activity_as_ptr().cast()) };
+  let mut env = vm.attach_current_thread()?;
+  f(&mut env, &activity)
+}
+
+///Asks the player for the permission to show notifications, if it wasn't granted yet
+pub fn request_permission(app: &AndroidApp) -> Result<()> {
+  with_activity(app, |env, activity| {
+    let permission = env.new_string(NOTIFICATION_PERMISSION)?;
+    let granted = env
+      .call_method(
+        activity,
+        "checkSelfPermission",
+        "(Ljava/lang/String;)I",
+        &[JValue::from(&permission)],
+      )?
+      .i()?;
+    if granted == 0 {
+      return Ok(());
+    }
+    let permissions = env.new_object_array(1, "java/lang/String", &permission)?;
+    env.call_method(
+      activity,
+      "requestPermissions",
+      "([Ljava/lang/String;I)V",
+      &[JValue::from(&permissions), JValue::Int(0)],
+    )?;
+    Ok(())
+  })
+}
+
+///Shows a notification, which pops up even if the app is in the background
+pub fn notify(app: &AndroidApp, title: &str, text: &str) -> Result<()> {
+  with_activity(app, |env, activity| {
+    let service = env.new_string("notification")?;
+    let manager = env
+      .call_method(
+        activity,
+        "getSystemService",
+        "(Ljava/lang/String;)Ljava/lang/Object;",
+        &[JValue::from(&service)],
+      )?
+      .l()?;
+
+    //creating an existing channel does nothing
+    let channel_id = env.new_string(CHANNEL_ID)?;
+    let channel_name = env.new_string(CHANNEL_NAME)?;
+    let channel = env.new_object(
+      "android/app/NotificationChannel",
+      "(Ljava/lang/String;Ljava/lang/CharSequence;I)V",
+      &[
+        JValue::from(&channel_id),
+        JValue::from(&channel_name),
+        JValue::Int(IMPORTANCE_HIGH),
+      ],
+    )?;
+    env.call_method(
+      &manager,
+      "createNotificationChannel",
+      "(Landroid/app/NotificationChannel;)V",
+      &[JValue::from(&channel)],
+    )?;
+
+    let icon = env
+      .get_static_field("android/R$drawable", "ic_dialog_alert", "I")?
+      .i()?;
+    let title = env.new_string(title)?;
+    let text = env.new_string(text)?;
+    let builder = env.new_object(
+      "android/app/Notification$Builder",
+      "(Landroid/content/Context;Ljava/lang/String;)V",
+      &[JValue::from(activity), JValue::from(&channel_id)],
+    )?;
+    for (method, signature, argument) in [
+      ("setSmallIcon", "(I)", JValue::Int(icon)),
+      (
+        "setContentTitle",
+        "(Ljava/lang/CharSequence;)",
+        JValue::from(&title),
+      ),
+      (
+        "setContentText",
+        "(Ljava/lang/CharSequence;)",
+        JValue::from(&text),
+      ),
+    ] {
+      env.call_method(
+        &builder,
+        method,
+        format!("{}Landroid/app/Notification$Builder;", signature),
+        &[argument],
+      )?;
+    }
+    let notification = env
+      .call_method(&builder, "build", "()Landroid/app/Notification;", &[])?
+      .l()?;
+
+    env.call_method(
+      &manager,
+      "notify",
+      "(ILandroid/app/Notification;)V",
+      &[JValue::Int(NOTIFICATION_ID), JValue::from(&notification)],
+    )?;
+    Ok(())
+  })
+}
@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use crate::settings::Language;
+
+///Something the player should be told about, even if the app isn't in the foreground
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TimerAlert {
+  ///[`PuzzleTimer::LOW_TIME`] or less is left
+  LowOnTime,
+  Expired,
+}
+
+impl TimerAlert {
+  pub fn message(&self, language: Language) -> &'static str {
+    match (self, language) {
+      (TimerAlert::LowOnTime, Language::English) => "The puzzle timer is running out!",
+      (TimerAlert::LowOnTime, Language::German) => "Die Zeit für das Rätsel läuft ab!",
+      (TimerAlert::Expired, Language::English) => "The time for the puzzle is up.",
+      (TimerAlert::Expired, Language::German) => "Die Zeit für das Rätsel ist abgelaufen.",
+    }
+  }
+}
+
+///Counts down the time the game gives for solving the puzzle of an arena, started when the arena
+/// is entered. Works with instants instead of animation ticks, so it stays exact while the app
+/// sleeps in the background.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PuzzleTimer {
+  deadline: Instant,
+  ///the last alert which was given
+  alerted: Option<TimerAlert>,
+}
+
+impl PuzzleTimer {
+  ///How much time is left when the player is alerted the first time
+  pub const LOW_TIME: Duration = Duration::from_secs(10);
+
+  pub fn start(duration: Duration, now: Instant) -> Self {
+    Self {
+      deadline: now + duration,
+      alerted: None,
+    }
+  }
+
+  pub fn remaining(&self, now: Instant) -> Duration {
+    self.deadline.saturating_duration_since(now)
+  }
+
+  ///The remaining time as shown to the player, in whole seconds rounded up
+  pub fn remaining_secs(&self, now: Instant) -> u64 {
+    self.remaining(now).as_secs_f64().ceil() as u64
+  }
+
+  ///The remaining time like the game shows it, e.g. `1:05`
+  pub fn format_remaining(&self, now: Instant) -> String {
+    let secs = self.remaining_secs(now);
+    format!("{}:{:02}", secs / 60, secs % 60)
+  }
+
+  ///Returns each alert once, as soon as it is due
+  pub fn poll(&mut self, now: Instant) -> Option<TimerAlert> {
+    let due = if self.remaining(now).is_zero() {
+      TimerAlert::Expired
+    } else if self.remaining(now) <= Self::LOW_TIME {
+      TimerAlert::LowOnTime
+    } else {
+      return None;
+    };
+    if self.alerted == Some(due) {
+      return None;
+    }
+    self.alerted = Some(due);
+    Some(due)
+  }
+
+  ///When the shown remaining time changes next, which is also when alerts become due. None once the
+  /// time is up.
+  pub fn next_update(&self, now: Instant) -> Option<Instant> {
+    let secs = self.remaining_secs(now);
+    (secs > 0).then(|| self.deadline - Duration::from_secs(secs - 1))
+  }
+}
+
+#[cfg(test)]
+mod test_puzzle_timer {
+  use std::time::{Duration, Instant};
+
+  use crate::puzzle_timer::{PuzzleTimer, TimerAlert};
+
+  #[test]
+  fn test_countdown() {
+    let start = Instant::now();
+    let mut timer = PuzzleTimer::start(Duration::from_secs(65), start);
+    assert_eq!("1:05", timer.format_remaining(start));
+    assert_eq!(None, timer.poll(start));
+
+    let now = start + Duration::from_millis(54_500);
+    assert_eq!("0:11", timer.format_remaining(now));
+    assert_eq!(
+      Some(start + Duration::from_secs(55)),
+      timer.next_update(now)
+    );
+    assert_eq!(None, timer.poll(now));
+
+    let now = start + Duration::from_secs(55);
+    assert_eq!(Some(TimerAlert::LowOnTime), timer.poll(now));
+    assert_eq!(None, timer.poll(now + Duration::from_secs(1)));
+
+    let now = start + Duration::from_secs(70);
+    assert_eq!("0:00", timer.format_remaining(now));
+    assert_eq!(Some(TimerAlert::Expired), timer.poll(now));
+    assert_eq!(None, timer.poll(now));
+    assert_eq!(None, timer.next_update(now));
+  }
+}
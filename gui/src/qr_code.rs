@@ -0,0 +1,168 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use game_logic::import::{format_by_name, ArenaFormat, ImportError, RingString};
+use game_logic::solving::Enemy;
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage, Rgba, RgbaImage};
+use qrcode::{Color, EcLevel, QrCode};
+
+use crate::renderer::{Element, ElementId, PTexCoords, Panel, TexRect};
+
+///The file the qr code of the arena is saved to when it is shown, next to the settings
+pub const QR_CODE_FILE: &str = "arena_qr.png";
+///Takes a photo of a qr code to import the arena, only available on android, see [`qr_buttons`]
+pub const SCAN_BUTTON: ElementId = "scan";
+///Copies a link to the arena and its current solution, see [`qr_buttons`]
+pub const SHARE_BUTTON: ElementId = "share";
+///The pixels per module of the qr code
+const MODULE_SIZE: u32 = 8;
+///The empty modules around the qr code which scanners need to find it
+const QUIET_ZONE: u32 = 4;
+///Photos are scaled down to this width and height before looking for qr codes, which keeps reading
+/// them fast while codes filling a good part of the photo stay readable
+const SCAN_SIZE: u32 = 1024;
+
+///What the qr code of the enemies contains: the name of the format followed by the enemies in it,
+/// so the text can be pasted into the `import` command of the CLI as well
+pub fn qr_code_text(enemies: &[Enemy]) -> String {
+  format!("{} {}", RingString.name(), RingString::format(enemies))
+}
+
+///The qr code of the enemies as black and white picture with a quiet zone around it
+pub fn arena_qr_code(enemies: &[Enemy]) -> RgbaImage {
+  let code = QrCode::with_error_correction_level(qr_code_text(enemies), EcLevel::M)
+    .expect("the compact notation fits into a qr code");
+  let modules = code.width() as u32;
+  let colors = code.to_colors();
+  let size = (modules + QUIET_ZONE * 2) * MODULE_SIZE;
+  RgbaImage::from_fn(size, size, |x, y| {
+    let (column, row) = (x / MODULE_SIZE, y / MODULE_SIZE);
+    let dark = (QUIET_ZONE..QUIET_ZONE + modules).contains(&column)
+      && (QUIET_ZONE..QUIET_ZONE + modules).contains(&row)
+      && colors[((row - QUIET_ZONE) * modules + column - QUIET_ZONE) as usize] == Color::Dark;
+    if dark {
+      Rgba([0, 0, 0, 255])
+    } else {
+      Rgba([255, 255, 255, 255])
+    }
+  })
+}
+
+///Reads the enemies from the first qr code of an arena in the picture, like a photo of a screen
+/// showing one
+pub fn read_arena_qr_code(picture: &DynamicImage) -> Result<Vec<Enemy>, QrCodeError> {
+  let picture = if picture.width() > SCAN_SIZE || picture.height() > SCAN_SIZE {
+    picture.resize(SCAN_SIZE, SCAN_SIZE, FilterType::Triangle)
+  } else {
+    picture.clone()
+  };
+  let gray: GrayImage = picture.to_luma8();
+  let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+    gray.width() as usize,
+    gray.height() as usize,
+    |x, y| gray.get_pixel(x as u32, y as u32).0[0],
+  );
+  //the error of the last code which isn't an arena, if there are codes at all
+  let mut error = QrCodeError::NotFound;
+  for grid in prepared.detect_grids() {
+    let text = match grid.decode() {
+      Ok((_, text)) => text,
+      Err(e) => {
+        error = QrCodeError::Unreadable(e.to_string());
+        continue;
+      }
+    };
+    let (format_name, enemies) = text.split_once(' ').unwrap_or((text.as_str(), ""));
+    let Some(format) = format_by_name(format_name) else {
+      error = QrCodeError::NotAnArena(text);
+      continue;
+    };
+    return format.parse(enemies).map_err(QrCodeError::Import);
+  }
+  Err(error)
+}
+
+///Where the qr code is shown, a square in the upper part of the window. The aspect ratio is the
+/// width of the window divided by its height.
+pub fn qr_code_rect(aspect_ratio: f32) -> TexRect {
+  let height = 0.6;
+  let width = (height / aspect_ratio).min(0.9);
+  TexRect::new(
+    PTexCoords::new(0.5 - width / 2.0, 0.1),
+    PTexCoords::new(0.5 + width / 2.0, 0.1 + height),
+  )
+}
+
+///The buttons below the qr code. Only android can take photos of other qr codes, elsewhere they
+/// are imported by dropping a picture of them onto the window.
+pub fn qr_buttons() -> Panel {
+  let mut buttons = vec![Element::button(SHARE_BUTTON, "share")];
+  if cfg!(target_os = "android") {
+    buttons.push(Element::button(SCAN_BUTTON, "scan"));
+  }
+  Panel::horizontal(buttons)
+}
+
+///Where the [`qr_buttons`] are drawn, below the qr code. They grow to the sides and down with the ui
+/// scale.
+pub fn qr_buttons_area(ui_scale: f32) -> TexRect {
+  TexRect::new(PTexCoords::new(0.3, 0.78), PTexCoords::new(0.7, 0.88))
+    .scaled(PTexCoords::new(0.5, 0.78), ui_scale)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum QrCodeError {
+  ///there is no qr code in the picture
+  NotFound,
+  ///the qr code is damaged or blurry, and why it can't be read
+  Unreadable(String),
+  ///the qr code contains something else, like a link
+  NotAnArena(String),
+  Import(ImportError),
+}
+
+impl Display for QrCodeError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      QrCodeError::NotFound => write!(f, "no qr code found"),
+      QrCodeError::Unreadable(reason) => write!(f, "can't read the qr code: {}", reason),
+      QrCodeError::NotAnArena(text) => write!(f, "the qr code isn't an arena: {}", text),
+      QrCodeError::Import(e) => write!(f, "the qr code contains an invalid arena: {}", e),
+    }
+  }
+}
+
+impl Error for QrCodeError {}
+
+#[cfg(test)]
+mod test_qr_code {
+  use game_logic::import::{ArenaFormat, RingString};
+  use image::{DynamicImage, RgbaImage};
+
+  use crate::qr_code::{arena_qr_code, qr_code_text, read_arena_qr_code, QrCodeError};
+
+  #[test]
+  fn test_round_trip() {
+    let mut enemies = RingString.parse(RingString.example()).unwrap();
+    assert!(qr_code_text(&enemies).starts_with("rings .E."));
+    let code = arena_qr_code(&enemies);
+    assert_eq!(code.width(), code.height());
+    //the quiet zone is white
+    assert_eq!([255, 255, 255, 255], code.get_pixel(1, 1).0);
+
+    let mut read = read_arena_qr_code(&DynamicImage::ImageRgba8(code)).expect("readable");
+    read.sort();
+    enemies.sort();
+    assert_eq!(enemies, read);
+  }
+
+  #[test]
+  fn test_no_qr_code() {
+    let blank = RgbaImage::from_pixel(64, 64, [255, 255, 255, 255].into());
+    assert_eq!(
+      Err(QrCodeError::NotFound),
+      read_arena_qr_code(&DynamicImage::ImageRgba8(blank))
+    );
+  }
+}
@@ -0,0 +1,227 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use game_logic::data_dir::create_parent;
+use game_logic::fixture::load_arena;
+use game_logic::solving::SolvableArena;
+use image::RgbaImage;
+
+use crate::renderer::{PTexCoords, TexRect};
+use crate::settings::SettingsStore;
+
+///The file the recent arenas are stored in, next to the settings file
+pub const RECENT_ARENAS_FILE: &str = "recent_arenas.txt";
+
+///An arena file which was loaded recently
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentArena {
+  pub path: PathBuf,
+  ///the enemies of the file when the list was loaded, to preview them. None if the file can't be
+  /// read anymore.
+  pub preview: Option<SolvableArena>,
+  ///the picture of the arena saved next to the file, see [`thumbnail_path`]
+  pub thumbnail: Option<RgbaImage>,
+}
+
+impl RecentArena {
+  fn load(path: PathBuf) -> Self {
+    let preview = load_arena(&path).ok();
+    let thumbnail = image::open(thumbnail_path(&path))
+      .ok()
+      .map(|image| image.to_rgba8());
+    Self {
+      path,
+      preview,
+      thumbnail,
+    }
+  }
+
+  ///The name of the file, to tell the entries apart in the title bar
+  pub fn name(&self) -> String {
+    self
+      .path
+      .file_name()
+      .unwrap_or(self.path.as_os_str())
+      .to_string_lossy()
+      .into_owned()
+  }
+}
+
+///The last loaded arena files, the latest first, saved with one path per line
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentArenas {
+  path: PathBuf,
+  arenas: Vec<RecentArena>,
+}
+
+impl RecentArenas {
+  ///How many arenas are remembered
+  pub const LIMIT: usize = 6;
+
+  ///Loads the list from the file, which doesn't have to exist yet
+  pub fn load<P>(path: P) -> io::Result<Self>
+  where
+    P: AsRef<Path>,
+  {
+    let path = path.as_ref().to_path_buf();
+    let arenas = match fs::read_to_string(&path) {
+      Ok(content) => content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(Self::LIMIT)
+        .map(|line| RecentArena::load(PathBuf::from(line)))
+        .collect(),
+      Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+      Err(e) => return Err(e),
+    };
+    Ok(Self { path, arenas })
+  }
+
+  ///Loads the list stored next to the settings, starting with an empty one if it can't be read
+  pub fn load_beside_or_reset(settings: &SettingsStore) -> Self {
+    let path = settings.beside(RECENT_ARENAS_FILE);
+    Self::load(&path).unwrap_or_else(|e| {
+      eprintln!("failed to load the recent arenas: {}", e);
+      Self {
+        path,
+        arenas: vec![],
+      }
+    })
+  }
+
+  pub fn arenas(&self) -> &[RecentArena] {
+    &self.arenas
+  }
+
+  ///Puts the arena file in front of the list and saves it. Paths are stored absolute, so they stay
+  /// valid if the app is started from somewhere else.
+  pub fn add(&mut self, path: &Path) -> io::Result<()> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    self.arenas.retain(|arena| arena.path != path);
+    self.arenas.insert(0, RecentArena::load(path));
+    self.arenas.truncate(Self::LIMIT);
+    self.save()
+  }
+
+  ///Replaces the thumbnail of the arena added last, like after a new one was rendered
+  pub fn set_latest_thumbnail(&mut self, thumbnail: RgbaImage) {
+    if let Some(latest) = self.arenas.first_mut() {
+      latest.thumbnail = Some(thumbnail);
+    }
+  }
+
+  fn save(&self) -> io::Result<()> {
+    let content: String = self
+      .arenas
+      .iter()
+      .map(|arena| format!("{}\n", arena.path.display()))
+      .collect();
+    create_parent(&self.path)?;
+    fs::write(&self.path, content)
+  }
+}
+
+///Where the thumbnail of an arena file is stored, next to it: `arena.txt` gets
+/// `arena.thumbnail.png`
+pub fn thumbnail_path(arena_file: &Path) -> PathBuf {
+  arena_file.with_extension("thumbnail.png")
+}
+
+///The top and bottom edge of the row with the index
+fn row_edges(index: usize) -> (f32, f32) {
+  let height = 0.8 / RecentArenas::LIMIT as f32;
+  let top = 0.1 + index as f32 * height;
+  (top + height * 0.05, top + height * 0.95)
+}
+
+///Where the entry of the recent arena with the index is drawn, one row below the other
+pub fn row_rect(index: usize) -> TexRect {
+  let (top, bottom) = row_edges(index);
+  TexRect::new(PTexCoords::new(0.05, top), PTexCoords::new(0.95, bottom))
+}
+
+///Where the enemies of the recent arena with the index are previewed, at the right end of its row
+pub fn preview_rect(index: usize) -> TexRect {
+  let (top, bottom) = row_edges(index);
+  let inset = (bottom - top) * 0.1;
+  TexRect::new(
+    PTexCoords::new(0.6, top + inset),
+    PTexCoords::new(0.93, bottom - inset),
+  )
+}
+
+///Where the thumbnail of the recent arena with the index is drawn, a square at the right end of its
+/// row. The aspect ratio is the width of the window divided by its height.
+pub fn thumbnail_rect(index: usize, aspect_ratio: f32) -> TexRect {
+  let (top, bottom) = row_edges(index);
+  let inset = (bottom - top) * 0.05;
+  let width = (bottom - top - inset * 2.0) / aspect_ratio;
+  TexRect::new(
+    PTexCoords::new(0.93 - width, top + inset),
+    PTexCoords::new(0.93, bottom - inset),
+  )
+}
+
+///The index of the row at the point, if there is one
+pub fn row_at(point: PTexCoords) -> Option<usize> {
+  (0..RecentArenas::LIMIT).find(|&index| row_rect(index).contains(point))
+}
+
+#[cfg(test)]
+mod test_recent_arenas {
+  use std::env;
+  use std::fs;
+  use std::path::Path;
+
+  use image::RgbaImage;
+
+  use crate::recent_arenas::{row_at, thumbnail_path, RecentArenas};
+  use crate::renderer::PTexCoords;
+
+  #[test]
+  fn test_add() {
+    let directory = env::temp_dir().join(format!("tok_recent_{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let list = directory.join("recent_arenas.txt");
+    let _ = fs::remove_file(&list);
+    let arena = directory.join("arena.txt");
+    fs::write(&arena, "c3 12\n").unwrap();
+
+    let mut recent = RecentArenas::load(&list).expect("missing file is an empty list");
+    assert!(recent.arenas().is_empty());
+    for index in 0..RecentArenas::LIMIT + 2 {
+      recent.add(&directory.join(format!("{}.txt", index))).unwrap();
+    }
+    recent.add(&arena).unwrap();
+    recent.add(&arena).unwrap();
+    assert_eq!(RecentArenas::LIMIT, recent.arenas().len());
+    assert_eq!("arena.txt", recent.arenas()[0].name());
+    assert!(recent.arenas()[0].preview.is_some());
+    assert!(recent.arenas()[1].preview.is_none());
+    assert!(recent.arenas()[0].thumbnail.is_none());
+
+    let thumbnail = RgbaImage::from_pixel(4, 4, [10, 20, 30, 255].into());
+    thumbnail.save(thumbnail_path(&arena)).unwrap();
+    recent.set_latest_thumbnail(thumbnail);
+    let loaded = RecentArenas::load(&list).expect("failed to load");
+    assert_eq!(recent, loaded);
+    let _ = fs::remove_dir_all(&directory);
+  }
+
+  #[test]
+  fn test_rows() {
+    assert_eq!(Some(0), row_at(PTexCoords::new(0.5, 0.15)));
+    assert_eq!(
+      Some(RecentArenas::LIMIT - 1),
+      row_at(PTexCoords::new(0.5, 0.85))
+    );
+    assert_eq!(None, row_at(PTexCoords::new(0.5, 0.05)));
+    assert_eq!(None, row_at(PTexCoords::new(0.01, 0.5)));
+    assert_eq!(
+      Path::new("saves/arena.thumbnail.png"),
+      thumbnail_path(Path::new("saves/arena.txt"))
+    );
+  }
+}
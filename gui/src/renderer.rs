@@ -1,27 +1,68 @@
 use std::iter::once;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+use game_logic::render::{EnemyMarking, EnemyShape};
+use game_logic::solving::SolvableArena;
 use glam::{Vec3, Vec4};
+use image::RgbaImage;
+use pollster::FutureExt;
 use wgpu::{
-  BlendState, Buffer, BufferUsages, Color, ColorTargetState, ColorWrites, CommandEncoderDescriptor,
-  CompositeAlphaMode, Device, DeviceDescriptor, Extent3d, Face, FilterMode, IndexFormat, Instance,
-  LoadOp, Operations, PresentMode, PrimitiveState, PrimitiveTopology, Queue,
-  RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor,
-  RequestAdapterOptions, SamplerDescriptor, StoreOp, Surface, SurfaceConfiguration, SurfaceError,
-  TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureViewDescriptor,
-  VertexStepMode,
+  Adapter, BindGroup, BindGroupLayout, BlendComponent, BlendFactor, BlendOperation, BlendState,
+  Buffer, BufferUsages, Color, ColorTargetState, ColorWrites, CommandEncoderDescriptor,
+  CompositeAlphaMode, Device, DeviceDescriptor, DeviceLostReason, Extent3d, Face, FilterMode,
+  IndexFormat, Instance, MultisampleState, PresentMode, PrimitiveState, PrimitiveTopology, Queue,
+  RenderPipeline, RenderPipelineDescriptor, RequestAdapterOptions, Sampler, SamplerDescriptor,
+  Surface, SurfaceConfiguration, SurfaceError, TextureDescriptor, TextureDimension, TextureFormat,
+  TextureUsages, TextureView, TextureViewDescriptor, VertexState, VertexStepMode,
 };
-use wgpu::util::{BufferInitDescriptor, DeviceExt, TextureDataOrder};
-use winit::dpi::PhysicalSize;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::window::Window;
 
 use crate::app_state::AppState;
+use crate::hud::{hud, hud_area};
 use crate::include_resource_bytes;
-use crate::shader::{shader, texture_shader};
-use crate::shader::shader::VertexInput;
+use crate::qr_code::{qr_buttons, qr_buttons_area, qr_code_rect};
+use crate::recent_arenas::{preview_rect, row_rect, thumbnail_path, thumbnail_rect};
+use crate::renderer::pipelines::{BufferDescriptor, BufferInfo, BufferWrapper};
+use crate::resources::{
+  generate_mipmaps, DecodedTexture, ResourceManager, TextureLoadError, TextureRequest,
+};
+use crate::settings::{Settings, Theme};
+use crate::shader::{rounded_rect, shader, texture_shader};
+use crate::shader::rounded_rect::RoundedRectVertexRepr;
+use crate::shader::shader::VertexInputRepr;
+use crate::stats_chart::{chart_bars, chart_rect};
+
+use board::{BoardGrid, BoardPart, Glow};
+use rect_style::{glow_vertices, rounded_rect_vertices, RectStyle};
+pub use coordinates::{PTexCoords, TexRect};
+pub use frame_capture::CaptureMode;
+pub use feedback::{Feedback, FeedbackTarget};
+use frame_capture::{BufferSummary, FrameCapture, PassReadback, CAPTURE_DIRECTORY};
+use render_graph::{Attachment, Draw, Pass, RenderGraph, Viewport};
+pub use safe_area::SafeArea;
+use thumbnail::{render_thumbnail, textured_vertices, thumbnail_texture, ThumbnailError};
 
+mod board;
 mod coordinates;
+mod feedback;
+mod frame_capture;
+mod layout;
 mod pipelines;
+mod readback;
+mod rect_style;
+mod render_graph;
+mod safe_area;
+mod thumbnail;
+#[allow(dead_code)] //the hud doesn't need every element yet
+mod toolkit;
+mod widgets;
+
+pub use toolkit::{Element, ElementId, Panel};
+pub use widgets::{LabeledWidget, Widget, WidgetList, WidgetPart};
 
 const BACKGROUND_COLOR: Color = Color {
   r: 0.0,
@@ -30,34 +71,88 @@ const BACKGROUND_COLOR: Color = Color {
   a: 1.0,
 };
 
+const LIGHT_BACKGROUND_COLOR: Color = Color {
+  r: 0.85,
+  g: 0.9,
+  b: 0.8,
+  a: 1.0,
+};
+
+///The background while a file is dragged over the window
+const DROP_TARGET_COLOR: Color = Color {
+  r: 0.1,
+  g: 0.35,
+  b: 0.15,
+  a: 1.0,
+};
+
+///The background while required textures are still loading
+const LOADING_COLOR: Color = Color {
+  r: 0.05,
+  g: 0.05,
+  b: 0.05,
+  a: 1.0,
+};
+
+///Tints what [flashes](Feedback::flash) after an invalid action
+const ERROR_COLOR: Vec4 = Vec4::new(0.85, 0.1, 0.1, 1.0);
+///How far glows reach beyond the cells they surround, in pixels
+const GLOW_WIDTH: f32 = 12.0;
+///Adds the color onto the target, glows only ever brighten
+const ADDITIVE_BLENDING: BlendState = BlendState {
+  color: BlendComponent {
+    src_factor: BlendFactor::One,
+    dst_factor: BlendFactor::One,
+    operation: BlendOperation::Add,
+  },
+  alpha: BlendComponent::OVER,
+};
+
+///The attachment every pass of a frame draws into
+const CANVAS: &str = "Canvas";
+///Clears the canvas and draws the tutorial triangles
+const SCENE_PASS: &str = "Scene Pass";
+const WIDGET_PASS: &str = "Widget Pass";
+///Draws the thumbnails of the recent arenas onto their rows, or the qr code of the arena
+const THUMBNAIL_PASS: &str = "Thumbnail Pass";
+const GLOW_PASS: &str = "Glow Pass";
+
+///The number of samples per pixel if msaa is enabled
+const MSAA_SAMPLES: u32 = 4;
+
+const LOGO_TEXTURE: &str = "StepTechLogo";
+
+///Textures without which nothing is rendered but the loading screen
+const REQUIRED_TEXTURES: &[&str] = &[LOGO_TEXTURE];
+
 //vertices in counter-clockwise order: top, bottom left, bottom right
-const VERTICES: &[VertexInput] = &[
+const VERTICES: &[VertexInputRepr] = &[
   //top
-  VertexInput {
+  VertexInputRepr {
     position: Vec3::new(0.0, 0.5, 0.0),
     color: Vec4::new(0.0, 1.0, 0.0, 1.0),
     _padding: 0.0,
   },
   //bottom left
-  VertexInput {
+  VertexInputRepr {
     position: Vec3::new(-0.5, -0.5, 0.0),
     color: Vec4::new(1.0, 0.0, 0.0, 1.0),
     _padding: 0.0,
   },
   //bottom right
-  VertexInput {
+  VertexInputRepr {
     position: Vec3::new(0.5, -0.5, 0.0),
     color: Vec4::new(0.0, 0.0, 1.0, 1.0),
     _padding: 0.0,
   },
   //left
-  VertexInput {
+  VertexInputRepr {
     position: Vec3::new(-0.75, 0.15, 0.0),
     color: Vec4::new(1.0, 1.0, 1.0, 1.0),
     _padding: 0.0,
   },
   //right
-  VertexInput {
+  VertexInputRepr {
     position: Vec3::new(0.75, 0.15, 0.0),
     color: Vec4::new(0.0, 0.0, 0.0, 1.0),
     _padding: 0.0,
@@ -73,121 +168,85 @@ const INDICES: &[u16] = &[
 
 #[derive(Debug)]
 pub struct Renderer {
+  instance: Instance,
   surface: Surface<'static>,
   device: Device,
   queue: Queue,
   config: SurfaceConfiguration,
   window: Arc<Window>,
   size: PhysicalSize<u32>,
-  texture_bind_group: texture_shader::bind_groups::BindGroup0,
+  ///set by wgpu once the device is lost, like after a driver reset
+  device_lost: Arc<AtomicBool>,
+  ///textures stay loaded as long as the renderer exists
+  resources: ResourceManager,
+  gpu: GpuResources,
+  ///None until the texture is loaded
+  texture_bind_group: Option<BindGroup>,
+  ///what to capture of the next frame
+  capture: Option<CaptureMode>,
+  captured_frames: u32,
+  ///the multisampled texture which is rendered to before it is resolved to the surface, None while
+  /// msaa is disabled
+  msaa_view: Option<TextureView>,
+  ///what isn't covered by notches or system bars
+  safe_area: SafeArea,
+}
+
+///What is uploaded for a frame before its passes are recorded
+#[derive(Debug)]
+struct FrameData {
+  loading: bool,
+  ///whether the draws need buffer summaries for a frame capture
+  capturing: bool,
+  background: Color,
+  ///the tutorial triangles, if they are shown
+  tutorial: Option<Vec<VertexInputRepr>>,
+  ///the textures of the shown thumbnails or qr code, in the order of their quads
+  thumbnails: Vec<BindGroup>,
+  ///the safe area, where everything but the background is drawn
+  viewport: Viewport,
+}
+
+///Everything which belongs to a device and needs to be created again when it is lost
+#[derive(Debug)]
+struct GpuResources {
+  sampler: Sampler,
+  texture_bind_group_layout: BindGroupLayout,
   texture_pipeline: RenderPipeline,
   tutorial_pipeline: RenderPipeline,
+  ///draws the flat rectangles widgets are made of
+  widget_pipeline: RenderPipeline,
+  ///adds soft glows around highlighted parts of the board
+  glow_pipeline: RenderPipeline,
   vertex_buffer: Buffer,
   index_buffer: Buffer,
+  widget_vertices: BufferWrapper,
+  glow_vertices: BufferWrapper,
+  ///a quad for each shown thumbnail or the qr code, see [`textured_vertices`]
+  thumbnail_vertices: BufferWrapper,
+  sample_count: u32,
 }
 
-/*
-TODO
- pipelines + shader:
-  circle
-  ring
-  line
-  texture
-  pixel (for text rendering)
-*/
-
-impl Renderer {
-  pub async fn new(window: Window) -> Self {
-    let window = Arc::new(window);
-    let size = window.inner_size();
-
-    let instance = Instance::default();
-
-    let surface = instance
-      .create_surface(window.clone())
-      .expect("failed to create surface");
-
-    let adapter = instance
-      .request_adapter(&RequestAdapterOptions {
-        power_preference: Default::default(),
-        force_fallback_adapter: false,
-        compatible_surface: Some(&surface),
-      })
-      .await
-      .expect("can't find appropriate adapter");
-
-    let (device, queue) = adapter
-      .request_device(&DeviceDescriptor::default(), None)
-      .await
-      .expect("failed to create logical device");
-
-    let surface_caps = surface.get_capabilities(&adapter);
-    let surface_format = surface_caps
-      .formats
-      .iter()
-      .find(|f| f.is_srgb())
-      .copied()
-      .unwrap_or(surface_caps.formats[0]);
-
-    let config = SurfaceConfiguration {
-      usage: TextureUsages::RENDER_ATTACHMENT,
-      format: surface_format,
-      width: size.width,
-      height: size.height,
-      present_mode: PresentMode::default(),
-      desired_maximum_frame_latency: 2,
-      alpha_mode: CompositeAlphaMode::Auto,
-      view_formats: vec![],
-    };
-
-    surface.configure(&device, &config);
-
-    //load texture
-    let sprite = image::load_from_memory(include_resource_bytes!(texture / steptech_logo.png))
-      .expect("failed to load sprite")
-      .to_rgba8();
-    let (width, height) = sprite.dimensions();
-    let texture = device.create_texture_with_data(
-      &queue,
-      &TextureDescriptor {
-        label: Some("StepTechLogo"),
-        size: Extent3d {
-          width,
-          height,
-          depth_or_array_layers: 1,
-        },
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: TextureDimension::D2,
-        format: TextureFormat::Rgba8UnormSrgb,
-        usage: TextureUsages::TEXTURE_BINDING,
-        view_formats: &[],
-      },
-      TextureDataOrder::default(),
-      &sprite,
-    );
-    let texture_view = texture.create_view(&TextureViewDescriptor::default());
+impl GpuResources {
+  fn new(device: &Device, format: TextureFormat, sample_count: u32) -> Self {
     let sampler = device.create_sampler(&SamplerDescriptor {
       label: Some("Sampler"),
       mag_filter: FilterMode::Linear,
+      min_filter: FilterMode::Linear,
+      mipmap_filter: FilterMode::Linear,
       ..Default::default()
     });
-    let texture_bind_group = texture_shader::bind_groups::BindGroup0::from_bindings(
-      &device,
-      texture_shader::bind_groups::BindGroupLayout0 {
-        texture: &texture_view,
-        t_sampler: &sampler,
-      },
-    );
+    let texture_bind_group_layout =
+      texture_shader::bind_group_builders::BindGroup0::create_layout(device);
 
     //texture pipeline
-    let texture_shader = texture_shader::create_shader_module(&device);
+    let texture_shader = texture_shader::create_shader_module(device);
     let color_target_state = [Some(ColorTargetState {
-      format: config.format,
+      format,
       blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
       write_mask: ColorWrites::ALL,
     })];
-    let texture_pipeline_layout = texture_shader::create_pipeline_layout(&device);
+    let texture_pipeline_layout = texture_shader::create_pipeline_layout(device);
     let texture_vertex_entry = texture_shader::vs_main_entry(VertexStepMode::Vertex);
     let texture_fragment_entry = texture_shader::fs_main_entry(color_target_state.clone());
     let texture_pipeline_descriptor = RenderPipelineDescriptor {
@@ -204,28 +263,81 @@ impl Renderer {
       },
       label: Some("Render Pipeline"),
       depth_stencil: None,
-      multisample: Default::default(),
+      multisample: MultisampleState {
+        count: sample_count,
+        ..Default::default()
+      },
       multiview: None,
       cache: None, //TODO might be interesting to improve performance on android
     };
     let texture_pipeline = device.create_render_pipeline(&texture_pipeline_descriptor);
 
     //tutorial render pipeline
-    let shader = shader::create_shader_module(&device);
-    let tutorial_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-      layout: Some(&shader::create_pipeline_layout(&device)),
-      vertex: shader::vertex_state(&shader, &shader::vs_main_entry(VertexStepMode::Vertex)),
-      fragment: Some(shader::fragment_state(
-        &shader,
-        &shader::fs_main_entry(color_target_state.clone()),
-      )),
+    let shader = shader::create_shader_module(device);
+    let shader_pipeline_layout = shader::create_pipeline_layout(device);
+    let shader_fragment_entry = shader::fs_main_entry(color_target_state.clone());
+    let tutorial_pipeline_descriptor = RenderPipelineDescriptor {
+      layout: Some(&shader_pipeline_layout),
+      vertex: VertexState {
+        module: &shader,
+        entry_point: "vs_main",
+        compilation_options: Default::default(),
+        buffers: &[VertexInputRepr::layout(VertexStepMode::Vertex)],
+      },
+      fragment: Some(shader::fragment_state(&shader, &shader_fragment_entry)),
       ..texture_pipeline_descriptor.clone()
+    };
+    let tutorial_pipeline = device.create_render_pipeline(&tutorial_pipeline_descriptor);
+
+    //widget pipeline: rounded rectangles of triangles in any orientation
+    let rounded_rect_shader = rounded_rect::create_shader_module(device);
+    let rounded_rect_pipeline_layout = rounded_rect::create_pipeline_layout(device);
+    let rounded_rect_fragment_entry = rounded_rect::fs_main_entry(color_target_state.clone());
+    let widget_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+      label: Some("Widget Pipeline"),
+      layout: Some(&rounded_rect_pipeline_layout),
+      vertex: VertexState {
+        module: &rounded_rect_shader,
+        entry_point: "vs_main",
+        compilation_options: Default::default(),
+        buffers: &[RoundedRectVertexRepr::layout(VertexStepMode::Vertex)],
+      },
+      fragment: Some(rounded_rect::fragment_state(
+        &rounded_rect_shader,
+        &rounded_rect_fragment_entry,
+      )),
+      primitive: PrimitiveState::default(),
+      ..tutorial_pipeline_descriptor.clone()
+    });
+
+    //glow pipeline: the same rectangles, added onto what is drawn below them
+    let glow_target_state = [Some(ColorTargetState {
+      format,
+      blend: Some(ADDITIVE_BLENDING),
+      write_mask: ColorWrites::ALL,
+    })];
+    let glow_fragment_entry = rounded_rect::fs_glow_entry(glow_target_state);
+    let glow_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+      label: Some("Glow Pipeline"),
+      layout: Some(&rounded_rect_pipeline_layout),
+      vertex: VertexState {
+        module: &rounded_rect_shader,
+        entry_point: "vs_main",
+        compilation_options: Default::default(),
+        buffers: &[RoundedRectVertexRepr::layout(VertexStepMode::Vertex)],
+      },
+      fragment: Some(rounded_rect::fragment_state(
+        &rounded_rect_shader,
+        &glow_fragment_entry,
+      )),
+      primitive: PrimitiveState::default(),
+      ..tutorial_pipeline_descriptor.clone()
     });
 
     let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
       label: Some("Vertex Buffer"),
       contents: bytemuck::cast_slice(VERTICES),
-      usage: BufferUsages::VERTEX,
+      usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
     });
 
     let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
@@ -234,23 +346,365 @@ impl Renderer {
       usage: BufferUsages::INDEX,
     });
 
+    let widget_vertices = BufferWrapper::new(BufferDescriptor::from_info(
+      BufferInfo::new().with_label("Widget Vertices"),
+      BufferUsages::VERTEX | BufferUsages::COPY_DST,
+    ));
+    let glow_vertices = BufferWrapper::new(BufferDescriptor::from_info(
+      BufferInfo::new().with_label("Glow Vertices"),
+      BufferUsages::VERTEX | BufferUsages::COPY_DST,
+    ));
+    let thumbnail_vertices = BufferWrapper::new(BufferDescriptor::from_info(
+      BufferInfo::new().with_label("Thumbnail Vertices"),
+      BufferUsages::VERTEX | BufferUsages::COPY_DST,
+    ));
+
     Self {
+      sampler,
+      texture_bind_group_layout,
+      texture_pipeline,
+      tutorial_pipeline,
+      widget_pipeline,
+      glow_pipeline,
+      vertex_buffer,
+      index_buffer,
+      widget_vertices,
+      glow_vertices,
+      thumbnail_vertices,
+      sample_count,
+    }
+  }
+}
+
+///Requests an adapter which can present to the surface and a device of it
+async fn request_device(instance: &Instance, surface: &Surface<'_>) -> (Adapter, Device, Queue) {
+  let adapter = instance
+    .request_adapter(&RequestAdapterOptions {
+      power_preference: Default::default(),
+      force_fallback_adapter: false,
+      compatible_surface: Some(surface),
+    })
+    .await
+    .expect("can't find appropriate adapter");
+
+  let (device, queue) = adapter
+    .request_device(&DeviceDescriptor::default(), None)
+    .await
+    .expect("failed to create logical device");
+
+  (adapter, device, queue)
+}
+
+///Raises the flag once the device is lost for real - not when it is dropped on purpose
+fn watch_device_loss(device: &Device, device_lost: &Arc<AtomicBool>) {
+  let device_lost = device_lost.clone();
+  device.set_device_lost_callback(move |reason, message| {
+    if !matches!(
+      reason,
+      DeviceLostReason::Dropped | DeviceLostReason::ReplacedCallback
+    ) {
+      eprintln!("device lost ({:?}): {}", reason, message);
+      device_lost.store(true, Ordering::Release);
+    }
+  });
+}
+
+///Frames can only be captured as images where the surface supports being copied from
+fn surface_usage(surface: &Surface, adapter: &Adapter) -> TextureUsages {
+  let supported = surface.get_capabilities(adapter).usages;
+  TextureUsages::RENDER_ATTACHMENT | (supported & TextureUsages::COPY_SRC)
+}
+
+///The [`VERTICES`] moved to the right by an offset relative to the window width
+fn shifted_vertices(offset: f32) -> Vec<VertexInputRepr> {
+  VERTICES
+    .iter()
+    .map(|vertex| VertexInputRepr {
+      //clip space is 2.0 wide
+      position: vertex.position + Vec3::X * offset * 2.0,
+      ..*vertex
+    })
+    .collect()
+}
+
+///Mixes a color with the [`ERROR_COLOR`], where a flash of 1.0 gives the error color
+fn tinted(color: Color, flash: f32) -> Color {
+  let tinted = Vec4::new(
+    color.r as f32,
+    color.g as f32,
+    color.b as f32,
+    color.a as f32,
+  )
+  .lerp(ERROR_COLOR, flash);
+  Color {
+    r: tinted.x as f64,
+    g: tinted.y as f64,
+    b: tinted.z as f64,
+    a: tinted.w as f64,
+  }
+}
+
+fn background_color(theme: Theme) -> Color {
+  match theme {
+    Theme::Dark => BACKGROUND_COLOR,
+    Theme::Light => LIGHT_BACKGROUND_COLOR,
+  }
+}
+
+fn widget_color(theme: Theme, part: WidgetPart) -> Vec4 {
+  match (theme, part) {
+    (Theme::Dark, WidgetPart::Row { focused: false }) => Vec4::new(0.1, 0.3, 0.1, 1.0),
+    (Theme::Dark, WidgetPart::Row { focused: true }) => Vec4::new(0.2, 0.45, 0.2, 1.0),
+    (Theme::Dark, WidgetPart::Bar) => Vec4::new(0.9, 0.75, 0.2, 1.0),
+    (Theme::Light, WidgetPart::Row { focused: false }) => Vec4::new(0.7, 0.78, 0.65, 1.0),
+    (Theme::Light, WidgetPart::Row { focused: true }) => Vec4::new(0.55, 0.68, 0.5, 1.0),
+    (Theme::Light, WidgetPart::Bar) => Vec4::new(0.25, 0.4, 0.8, 1.0),
+  }
+}
+
+///Rows and buttons are rounded with a darker border, slider bars are round at their ends
+fn widget_style(theme: Theme, part: WidgetPart) -> RectStyle {
+  let color = widget_color(theme, part);
+  match part {
+    WidgetPart::Row { .. } => RectStyle::new(color)
+      .rounded(0.25)
+      .bordered(border_color(color), 2.0),
+    WidgetPart::Bar => RectStyle::new(color).rounded(0.5),
+  }
+}
+
+///Cells are rounded, enemies have the shape of the marking. Hollow shapes are only a thick border.
+fn board_style(theme: Theme, marking: EnemyMarking, part: BoardPart) -> RectStyle {
+  let color = board_color(theme, marking, part);
+  match part {
+    BoardPart::Cell | BoardPart::NextMove => RectStyle::new(color)
+      .rounded(0.15)
+      .bordered(border_color(color), 1.0),
+    BoardPart::Enemy(attack) => match marking.shape(attack) {
+      EnemyShape::Disc => RectStyle::new(color).rounded(0.5),
+      EnemyShape::Ring => RectStyle::new(Vec4::ZERO).rounded(0.5).bordered(color, 3.0),
+      EnemyShape::Square => RectStyle::new(color).rounded(0.1),
+      EnemyShape::Frame => RectStyle::new(Vec4::ZERO).rounded(0.1).bordered(color, 3.0),
+    },
+  }
+}
+
+///A darker shade of the color for the border around it
+fn border_color(color: Vec4) -> Vec4 {
+  (color.truncate() * 0.6).extend(color.w)
+}
+
+fn glow_color(theme: Theme, glow: Glow) -> Vec4 {
+  match (theme, glow) {
+    (Theme::Dark, Glow::NextMove) => Vec4::new(0.3, 0.65, 0.9, 0.8),
+    (Theme::Dark, Glow::Target) => Vec4::new(0.9, 0.45, 0.15, 0.8),
+    (Theme::Light, Glow::NextMove) => Vec4::new(0.1, 0.3, 0.6, 0.5),
+    (Theme::Light, Glow::Target) => Vec4::new(0.6, 0.25, 0.05, 0.5),
+  }
+}
+
+///The color of cells by theme, the color of enemies by marking, which is the same for every theme
+fn board_color(theme: Theme, marking: EnemyMarking, part: BoardPart) -> Vec4 {
+  match (theme, part) {
+    (_, BoardPart::Enemy(attack)) => {
+      let [red, green, blue] = marking.color(attack).map(|channel| channel as f32 / 255.0);
+      Vec4::new(red, green, blue, 1.0)
+    }
+    (Theme::Dark, BoardPart::Cell) => Vec4::new(0.1, 0.3, 0.1, 1.0),
+    (Theme::Dark, BoardPart::NextMove) => Vec4::new(0.2, 0.45, 0.6, 1.0),
+    (Theme::Light, BoardPart::Cell) => Vec4::new(0.7, 0.78, 0.65, 1.0),
+    (Theme::Light, BoardPart::NextMove) => Vec4::new(0.55, 0.7, 0.9, 1.0),
+  }
+}
+
+fn preferred_format(surface: &Surface, adapter: &Adapter) -> TextureFormat {
+  let surface_caps = surface.get_capabilities(adapter);
+  surface_caps
+    .formats
+    .iter()
+    .find(|f| f.is_srgb())
+    .copied()
+    .unwrap_or(surface_caps.formats[0])
+}
+
+/*
+TODO
+ pipelines + shader:
+  circle
+  ring
+  line
+  texture
+  pixel (for text rendering)
+*/
+
+impl Renderer {
+  pub async fn new(window: Window) -> Self {
+    let window = Arc::new(window);
+    let size = window.inner_size();
+
+    let instance = Instance::default();
+
+    let surface = instance
+      .create_surface(window.clone())
+      .expect("failed to create surface");
+
+    let (adapter, device, queue) = request_device(&instance, &surface).await;
+    let device_lost = Arc::new(AtomicBool::new(false));
+    watch_device_loss(&device, &device_lost);
+
+    let config = SurfaceConfiguration {
+      usage: surface_usage(&surface, &adapter),
+      format: preferred_format(&surface, &adapter),
+      width: size.width,
+      height: size.height,
+      present_mode: PresentMode::default(),
+      desired_maximum_frame_latency: 2,
+      alpha_mode: CompositeAlphaMode::Auto,
+      view_formats: vec![],
+    };
+
+    surface.configure(&device, &config);
+
+    //textures are decoded in the background and added once they are ready
+    let gpu = GpuResources::new(&device, config.format, 1);
+
+    Self {
+      instance,
       surface,
       device,
       queue,
       config,
       window,
       size,
-      texture_bind_group,
-      texture_pipeline,
-      tutorial_pipeline,
-      vertex_buffer,
-      index_buffer,
+      device_lost,
+      resources: ResourceManager::new(),
+      gpu,
+      texture_bind_group: None,
+      capture: None,
+      captured_frames: 0,
+      msaa_view: None,
+      safe_area: SafeArea::default(),
+    }
+  }
+
+  ///Whether the device was lost and everything needs to be [recreated](Self::recreate)
+  pub fn is_device_lost(&self) -> bool {
+    self.device_lost.load(Ordering::Acquire)
+  }
+
+  ///Gets the renderer working again after the surface became outdated or was lost, like after
+  /// alt-tabbing. If the device was lost as well, a new one is requested and all pipelines, buffers
+  /// and textures are created again from what is kept on the cpu.
+  pub fn recreate(&mut self, device_lost: bool) {
+    if device_lost {
+      let (adapter, device, queue) = request_device(&self.instance, &self.surface).block_on();
+      watch_device_loss(&device, &self.device_lost);
+      self.config.usage = surface_usage(&self.surface, &adapter);
+      self.config.format = preferred_format(&self.surface, &adapter);
+      self.gpu = GpuResources::new(&device, self.config.format, self.gpu.sample_count);
+      self.resources.reupload(&device, &queue);
+      self.device = device;
+      self.queue = queue;
+      self.texture_bind_group = None;
+      self.bind_logo_texture();
+      self.device_lost.store(false, Ordering::Release);
     }
+
+    let size = self.window.inner_size();
+    if size.width > 0 && size.height > 0 {
+      self.size = size;
+      self.config.width = size.width;
+      self.config.height = size.height;
+    }
+    self.surface.configure(&self.device, &self.config);
+    self.msaa_view = self.create_msaa_view();
+  }
+
+  ///Switches multisampling on or off, which needs new pipelines
+  pub fn set_msaa(&mut self, enabled: bool) {
+    let sample_count = if enabled { MSAA_SAMPLES } else { 1 };
+    if sample_count != self.gpu.sample_count {
+      self.gpu = GpuResources::new(&self.device, self.config.format, sample_count);
+      self.bind_logo_texture();
+      self.msaa_view = self.create_msaa_view();
+    }
+  }
+
+  fn create_msaa_view(&self) -> Option<TextureView> {
+    if self.gpu.sample_count == 1 {
+      return None;
+    }
+    let texture = self.device.create_texture(&TextureDescriptor {
+      label: Some("Msaa Texture"),
+      size: Extent3d {
+        width: self.config.width,
+        height: self.config.height,
+        depth_or_array_layers: 1,
+      },
+      mip_level_count: 1,
+      sample_count: self.gpu.sample_count,
+      dimension: TextureDimension::D2,
+      format: self.config.format,
+      usage: TextureUsages::RENDER_ATTACHMENT,
+      view_formats: &[],
+    });
+    Some(texture.create_view(&TextureViewDescriptor::default()))
+  }
+
+  #[cfg_attr(not(target_os = "android"), allow(dead_code))] //only android reports a safe area
+  pub fn set_safe_area(&mut self, safe_area: SafeArea) {
+    if safe_area != self.safe_area {
+      self.safe_area = safe_area;
+      self.window.request_redraw();
+    }
+  }
+
+  ///Where a position in the window lies, relative to the [safe area](SafeArea) everything but the
+  /// background is drawn in
+  pub fn relative_position(&self, position: PhysicalPosition<f64>) -> PTexCoords {
+    self.safe_area.locate(self.size, position)
   }
 
-  pub fn size(&self) -> PhysicalSize<u32> {
-    self.size
+  ///The textures to decode in the background, in the resolution fitting the window
+  pub fn texture_requests(&self) -> Vec<TextureRequest> {
+    vec![TextureRequest {
+      name: LOGO_TEXTURE,
+      variants: &[include_resource_bytes!(texture / steptech_logo.png)],
+      wanted_size: self.size.width.min(self.size.height),
+    }]
+  }
+
+  ///Uploads a texture which was decoded in the background
+  pub fn add_texture(&mut self, decoded: Result<DecodedTexture, TextureLoadError>) {
+    let name = match &decoded {
+      Ok(decoded) => decoded.name.clone(),
+      Err(e) => e.name.clone(),
+    };
+    self
+      .resources
+      .insert_decoded(&self.device, &self.queue, decoded);
+    if name == LOGO_TEXTURE {
+      self.bind_logo_texture();
+    }
+  }
+
+  fn bind_logo_texture(&mut self) {
+    if let Some(texture) = self.resources.texture(LOGO_TEXTURE) {
+      self.texture_bind_group = Some(
+        texture_shader::bind_group_builders::BindGroup0 {
+          texture: &texture.view,
+          t_sampler: &self.gpu.sampler,
+        }
+        .build(&self.device, &self.gpu.texture_bind_group_layout),
+      );
+    }
+  }
+
+  ///Whether the loading screen is shown because required textures are missing
+  pub fn is_loading(&self) -> bool {
+    !REQUIRED_TEXTURES
+      .iter()
+      .all(|name| self.resources.is_loaded(name))
   }
 
   pub fn resize(&mut self, size: PhysicalSize<u32>) {
@@ -259,10 +713,18 @@ impl Renderer {
       self.config.width = size.width;
       self.config.height = size.height;
       self.surface.configure(&self.device, &self.config);
+      self.msaa_view = self.create_msaa_view();
     }
   }
 
-  pub fn render(&self, _app_state: &AppState) -> Result<(), SurfaceError> {
+  ///Dumps what is drawn during the next frame to the [capture directory](CAPTURE_DIRECTORY)
+  pub fn capture_next_frame(&mut self, mode: CaptureMode) {
+    self.capture = Some(mode);
+  }
+
+  pub fn render(&mut self, app_state: &AppState) -> Result<(), SurfaceError> {
+    let settings = app_state.settings.settings();
+    self.set_msaa(settings.msaa);
     let canvas = self.surface.get_current_texture()?;
     let view = canvas
       .texture
@@ -272,32 +734,413 @@ impl Renderer {
       .create_command_encoder(&CommandEncoderDescriptor {
         label: Some("Render Encoder"),
       });
-    let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
-      label: Some("Render Pass"),
-      color_attachments: &[Some(RenderPassColorAttachment {
-        view: &view,
-        resolve_target: None,
-        ops: Operations {
-          load: LoadOp::Clear(BACKGROUND_COLOR),
-          store: StoreOp::Store,
-        },
-      })],
-      ..Default::default()
-    });
-
-    render_pass.set_pipeline(&self.tutorial_pipeline);
-    render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-    render_pass.set_index_buffer(self.index_buffer.slice(..), IndexFormat::Uint16);
-    render_pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+    let capture_mode = self.capture.take();
+    let mut capture = capture_mode.map(|_| FrameCapture::default());
+    let copyable = canvas.texture.usage().contains(TextureUsages::COPY_SRC);
+    let readback = capture_mode == Some(CaptureMode::WithImages);
+    if readback && !copyable {
+      eprintln!("can't capture images: the surface doesn't support being copied from");
+    }
 
-    drop(render_pass); //must be dropped before the encoder can be finished
+    let frame = self.prepare_frame(app_state, capture.is_some());
+    let mut graph = self.frame_graph(&frame);
+    graph.attach(
+      CANVAS,
+      Attachment {
+        view: self.msaa_view.as_ref().unwrap_or(&view),
+        resolve_target: self.msaa_view.as_ref().map(|_| &view),
+        readback: (readback && copyable).then_some(&canvas.texture),
+      },
+    );
+    let readbacks = graph
+      .encode(&self.device, &mut encoder, capture.as_mut())
+      .expect("the frame graph is valid");
 
     self.queue.submit(once(encoder.finish()));
+    if let Some(capture) = capture {
+      self.finish_capture(capture, readbacks);
+    }
     canvas.present();
 
     Ok(())
   }
 
+  ///Fills the vertex buffers for the next frame
+  fn prepare_frame(&mut self, app_state: &AppState, capturing: bool) -> FrameData {
+    let settings = app_state.settings.settings();
+    let loading = self.is_loading();
+    let feedback = &app_state.feedback;
+
+    let board = BoardGrid::new(TexRect::new(
+      PTexCoords::new(0.02, 0.05),
+      PTexCoords::new(0.98, 0.95),
+    ));
+    //the overlay shows nothing but the board, while the settings, the recent arenas, the qr code or
+    // the stats are shown they cover everything else
+    let quads: Vec<(TexRect, RectStyle, Option<FeedbackTarget>)> = if app_state.overlay {
+      let enemies = app_state.arena.layers.0.enemies.iter().copied();
+      board
+        .parts(enemies, app_state.next_move())
+        .into_iter()
+        .map(|(rect, part)| {
+          (
+            rect,
+            board_style(settings.theme, settings.enemy_marking, part),
+            Some(FeedbackTarget::Arena),
+          )
+        })
+        .collect()
+    } else if let Some(screen) = app_state.screen.settings() {
+      (0..screen.widgets().rows().len())
+        .flat_map(|row| {
+          let target = Some(FeedbackTarget::SettingsRow(row));
+          screen
+            .widgets()
+            .row_parts(row)
+            .map(|(rect, part)| (rect, widget_style(settings.theme, part), target))
+        })
+        .collect()
+    } else if let Some(focused) = app_state.screen.recent_focus() {
+      app_state
+        .recent
+        .arenas()
+        .iter()
+        .enumerate()
+        .flat_map(|(index, arena)| {
+          let target = Some(FeedbackTarget::RecentRow(index));
+          let row = widget_style(settings.theme, WidgetPart::Row { focused: index == focused });
+          //arenas without a thumbnail yet are previewed by their enemies
+          let enemies = arena
+            .preview
+            .iter()
+            .filter(|_| arena.thumbnail.is_none())
+            .flat_map(|preview| preview.enemies.iter().copied());
+          let preview = BoardGrid::new(preview_rect(index))
+            .parts(enemies, None)
+            .into_iter()
+            .map(move |(rect, part)| {
+              let style = board_style(settings.theme, settings.enemy_marking, part);
+              (rect, style, target)
+            });
+          once((row_rect(index), row, target)).chain(preview)
+        })
+        .collect()
+    } else if app_state.screen.is_stats() {
+      //missed solves are drawn like a focused row, so they stand out from the others
+      let background = widget_style(settings.theme, WidgetPart::Row { focused: false });
+      let bars = chart_bars(&app_state.solves)
+        .into_iter()
+        .map(|(rect, missed)| {
+          let part = if missed {
+            WidgetPart::Row { focused: true }
+          } else {
+            WidgetPart::Bar
+          };
+          (rect, widget_style(settings.theme, part), None)
+        });
+      once((chart_rect(), background, None)).chain(bars).collect()
+    } else if app_state.screen.is_qr_code() {
+      qr_buttons()
+        .parts(qr_buttons_area(settings.ui_scale))
+        .into_iter()
+        .map(|(rect, part)| (rect, widget_style(settings.theme, part), None))
+        .collect()
+    } else {
+      hud()
+        .parts(hud_area(settings.ui_scale))
+        .into_iter()
+        .map(|(rect, part)| (rect, widget_style(settings.theme, part), None))
+        .collect()
+    };
+    let (top_left, safe_size) = self.safe_area.inner_rect(self.size);
+    self.gpu.widget_vertices.clear();
+    for (rect, style, target) in quads {
+      let (offset, flash) = target.map_or((0.0, 0.0), |target| {
+        (feedback.shake_offset(target), feedback.flash(target))
+      });
+      self.gpu.widget_vertices.add(&rounded_rect_vertices(
+        rect.translated(PTexCoords::new(offset, 0.0)),
+        style.tinted(ERROR_COLOR, flash),
+        safe_size,
+      ));
+    }
+    //the board shakes as a whole, so its glows move along
+    self.gpu.glow_vertices.clear();
+    if app_state.overlay {
+      let offset = feedback.shake_offset(FeedbackTarget::Arena);
+      for (rect, glow) in board.glows(app_state.next_move(), app_state.target_areas()) {
+        self.gpu.glow_vertices.add(&glow_vertices(
+          rect.translated(PTexCoords::new(offset, 0.0)),
+          RectStyle::new(glow_color(settings.theme, glow)).rounded(0.15),
+          GLOW_WIDTH,
+          safe_size,
+        ));
+      }
+    }
+    let thumbnails = self.prepare_thumbnails(app_state, safe_size);
+    self.gpu.widget_vertices.get_buffer(&self.device, &self.queue);
+    if self.gpu.glow_vertices.len() > 0 {
+      self.gpu.glow_vertices.get_buffer(&self.device, &self.queue);
+    }
+    if self.gpu.thumbnail_vertices.len() > 0 {
+      self.gpu.thumbnail_vertices.get_buffer(&self.device, &self.queue);
+    }
+
+    let covered = app_state.overlay
+      || app_state.screen.settings().is_some()
+      || app_state.screen.recent_focus().is_some()
+      || app_state.screen.is_qr_code()
+      || app_state.screen.is_stats();
+    let tutorial = if !loading && !covered {
+      let vertices = shifted_vertices(feedback.shake_offset(FeedbackTarget::Arena));
+      self
+        .queue
+        .write_buffer(&self.gpu.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+      Some(vertices)
+    } else {
+      None
+    };
+
+    FrameData {
+      loading,
+      capturing,
+      background: if loading {
+        LOADING_COLOR
+      } else if app_state.hovered_file {
+        DROP_TARGET_COLOR
+      } else {
+        tinted(
+          background_color(settings.theme),
+          feedback.flash(FeedbackTarget::Arena),
+        )
+      },
+      tutorial,
+      thumbnails,
+      viewport: Viewport {
+        x: top_left.x as f32,
+        y: top_left.y as f32,
+        width: safe_size.width as f32,
+        height: safe_size.height as f32,
+      },
+    }
+  }
+
+  ///Uploads the thumbnails of the recent arenas or the qr code of the arena while they are shown
+  /// and fills their quads. Returns the textures to draw the quads with.
+  fn prepare_thumbnails(
+    &mut self,
+    app_state: &AppState,
+    safe_size: PhysicalSize<u32>,
+  ) -> Vec<BindGroup> {
+    self.gpu.thumbnail_vertices.clear();
+    let aspect_ratio = safe_size.width as f32 / safe_size.height as f32;
+    if app_state.screen.is_qr_code() {
+      let Some((text, code)) = &app_state.qr_code else {
+        return vec![];
+      };
+      //the text tells the codes of different arenas apart
+      let bind_group = self.picture_bind_group(&format!("QR Code {}", text), code);
+      self
+        .gpu
+        .thumbnail_vertices
+        .add(&textured_vertices(qr_code_rect(aspect_ratio)));
+      return vec![bind_group];
+    }
+    if app_state.screen.recent_focus().is_none() {
+      return vec![];
+    }
+    let mut bind_groups = vec![];
+    for (index, arena) in app_state.recent.arenas().iter().enumerate() {
+      let Some(thumbnail) = &arena.thumbnail else {
+        continue;
+      };
+      bind_groups.push(self.picture_bind_group(&thumbnail_texture(&arena.path), thumbnail));
+      let offset = app_state
+        .feedback
+        .shake_offset(FeedbackTarget::RecentRow(index));
+      self.gpu.thumbnail_vertices.add(&textured_vertices(
+        thumbnail_rect(index, aspect_ratio).translated(PTexCoords::new(offset, 0.0)),
+      ));
+    }
+    bind_groups
+  }
+
+  ///Binds the picture for the texture pipeline, uploading it under the name unless a texture with
+  /// that name is loaded already
+  fn picture_bind_group(&mut self, name: &str, picture: &RgbaImage) -> BindGroup {
+    if !self.resources.is_loaded(name) {
+      let decoded = DecodedTexture {
+        name: name.to_string(),
+        mip_levels: generate_mipmaps(picture),
+      };
+      self
+        .resources
+        .insert_decoded(&self.device, &self.queue, Ok(decoded));
+    }
+    let texture = self.resources.texture(name).expect("uploaded above");
+    texture_shader::bind_group_builders::BindGroup0 {
+      texture: &texture.view,
+      t_sampler: &self.gpu.sampler,
+    }
+    .build(&self.device, &self.gpu.texture_bind_group_layout)
+  }
+
+  ///Renders a thumbnail of the arena offscreen and saves it next to the arena file, see
+  /// [`thumbnail_path`]. The thumbnail shown for the file so far is replaced.
+  pub fn save_thumbnail(
+    &mut self,
+    arena: &SolvableArena,
+    arena_file: &Path,
+    settings: &Settings,
+  ) -> Result<RgbaImage, ThumbnailError> {
+    let thumbnail = render_thumbnail(
+      &self.device,
+      &self.queue,
+      arena,
+      settings.theme,
+      settings.enemy_marking,
+    )?;
+    thumbnail.save(thumbnail_path(arena_file))?;
+    self.resources.unload(&thumbnail_texture(arena_file));
+    Ok(thumbnail)
+  }
+
+  ///The passes drawing a [prepared](Self::prepare_frame) frame into the [`CANVAS`], which is
+  /// attached by the caller
+  fn frame_graph<'a>(&'a self, frame: &'a FrameData) -> RenderGraph<'a> {
+    let mut graph = RenderGraph::new();
+    let mut scene = Pass::new(SCENE_PASS, CANVAS)
+      .clearing(frame.background)
+      .with_viewport(frame.viewport);
+    if let Some(vertices) = &frame.tutorial {
+      scene.draw(Draw {
+        pipeline_name: "tutorial",
+        pipeline: &self.gpu.tutorial_pipeline,
+        bind_group: None,
+        vertex_buffer: self.gpu.vertex_buffer.slice(..),
+        index_buffer: Some((self.gpu.index_buffer.slice(..), IndexFormat::Uint16)),
+        vertices: 0..INDICES.len() as u32,
+        buffers: if frame.capturing {
+          vec![
+            BufferSummary::of(
+              "Vertex Buffer",
+              vertices.len() as u32,
+              bytemuck::cast_slice(vertices),
+            ),
+            BufferSummary::of(
+              "Index Buffer",
+              INDICES.len() as u32,
+              bytemuck::cast_slice(INDICES),
+            ),
+          ]
+        } else {
+          vec![]
+        },
+      });
+    }
+    graph.add_pass(scene);
+    //the loading screen is just the background
+    if frame.loading {
+      return graph;
+    }
+
+    let layers = [
+      (
+        WIDGET_PASS,
+        SCENE_PASS,
+        "widget",
+        &self.gpu.widget_pipeline,
+        &self.gpu.widget_vertices,
+        "Widget Vertices",
+      ),
+      (
+        GLOW_PASS,
+        WIDGET_PASS,
+        "glow",
+        &self.gpu.glow_pipeline,
+        &self.gpu.glow_vertices,
+        "Glow Vertices",
+      ),
+    ];
+    for (name, after, pipeline_name, pipeline, vertices, label) in layers {
+      let Some(buffer) = vertices.buffer().filter(|_| vertices.len() > 0) else {
+        continue;
+      };
+      let mut pass = Pass::new(name, CANVAS)
+        .after(after)
+        .with_viewport(frame.viewport);
+      pass.draw(Draw {
+        pipeline_name,
+        pipeline,
+        bind_group: None,
+        vertex_buffer: buffer.slice(..),
+        index_buffer: None,
+        vertices: 0..vertices.len(),
+        buffers: if frame.capturing {
+          vec![BufferSummary::of(label, vertices.len(), vertices.data())]
+        } else {
+          vec![]
+        },
+      });
+      graph.add_pass(pass);
+    }
+
+    let thumbnail_buffer = self
+      .gpu
+      .thumbnail_vertices
+      .buffer()
+      .filter(|_| !frame.thumbnails.is_empty());
+    if let Some(buffer) = thumbnail_buffer {
+      let mut pass = Pass::new(THUMBNAIL_PASS, CANVAS)
+        .after(WIDGET_PASS)
+        .with_viewport(frame.viewport);
+      for (index, bind_group) in frame.thumbnails.iter().enumerate() {
+        let first = index as u32 * 4;
+        pass.draw(Draw {
+          pipeline_name: "texture",
+          pipeline: &self.gpu.texture_pipeline,
+          bind_group: Some(bind_group),
+          vertex_buffer: buffer.slice(..),
+          index_buffer: None,
+          vertices: first..first + 4,
+          buffers: if frame.capturing {
+            let vertices = &self.gpu.thumbnail_vertices;
+            vec![BufferSummary::of(
+              "Thumbnail Vertices",
+              vertices.len(),
+              vertices.data(),
+            )]
+          } else {
+            vec![]
+          },
+        });
+      }
+      graph.add_pass(pass);
+    }
+    graph
+  }
+
+  ///Saves the images of the render passes and writes the draw list next to them
+  fn finish_capture(&mut self, mut capture: FrameCapture, readbacks: Vec<PassReadback>) {
+    let directory = Path::new(CAPTURE_DIRECTORY).join(format!("frame_{}", self.captured_frames));
+    self.captured_frames += 1;
+    for readback in readbacks {
+      let path = directory.join(format!(
+        "{}.png",
+        readback.pass.to_lowercase().replace(' ', "_")
+      ));
+      match readback.save(&self.device, &path) {
+        Ok(()) => capture.pass_images.push(path),
+        Err(e) => eprintln!("failed to capture {}: {}", path.display(), e),
+      }
+    }
+    eprintln!("{}", capture);
+    match capture.write_draw_list(&directory) {
+      Ok(path) => eprintln!("frame captured to {}", path.display()),
+      Err(e) => eprintln!("failed to write draw list: {}", e),
+    }
+  }
+
   pub fn window(&self) -> &Window {
     &self.window
   }
@@ -0,0 +1,189 @@
+use game_logic::position::Dimension::{Column, Row};
+use game_logic::position::{Move, Position};
+use game_logic::solving::{Enemy, RequiredAttack, TargetArea};
+
+use crate::renderer::coordinates::{PTexCoords, TexRect};
+
+///The parts the compact board of the overlay is drawn of
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BoardPart {
+  Cell,
+  ///a cell which the next move of the solution moves
+  NextMove,
+  ///an enemy, drawn by the attack it needs
+  Enemy(Option<RequiredAttack>),
+}
+
+///The glows drawn around cells of the board, so the ring to rotate next stands out
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Glow {
+  ///a cell which the next move of the solution moves
+  NextMove,
+  ///a cell attacked once every move of the solution is done
+  Target,
+}
+
+///Lays the arena out as a compact grid with a column of the grid for each column of the arena and
+/// the outer ring as top row, small enough to be shown over other windows
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoardGrid {
+  ///where the grid is drawn
+  area: TexRect,
+}
+
+impl BoardGrid {
+  ///The share of a cell between two cells
+  const SPACING: f32 = 0.1;
+  ///The share of a cell around an enemy
+  const ENEMY_MARGIN: f32 = 0.25;
+
+  pub fn new<R>(area: R) -> Self
+  where
+    R: Into<TexRect>,
+  {
+    Self { area: area.into() }
+  }
+
+  pub fn cell_rect(&self, position: Position) -> TexRect {
+    self.inset_cell_rect(position, Self::SPACING / 2.0)
+  }
+
+  ///The cell of a position, shrunk by a share of the cell size on each side
+  fn inset_cell_rect(&self, position: Position, inset: f32) -> TexRect {
+    let [top_left, bottom_right] = self.area.as_array().map(|tex| tex.as_p_tex_coords());
+    let width = (bottom_right.x - top_left.x) / Column.size() as f32;
+    let height = (bottom_right.y - top_left.y) / Row.size() as f32;
+    let left = top_left.x + position.column as f32 * width;
+    let top = top_left.y + (Row.size() - 1 - position.row) as f32 * height;
+    TexRect::new(
+      PTexCoords::new(left + width * inset, top + height * inset),
+      PTexCoords::new(left + width * (1.0 - inset), top + height * (1.0 - inset)),
+    )
+  }
+
+  ///The rectangles to draw, back to front: every cell, highlighted if the next move moves it, and
+  /// the enemies
+  pub fn parts<I>(&self, enemies: I, next_move: Option<Move>) -> Vec<(TexRect, BoardPart)>
+  where
+    I: IntoIterator<Item = Enemy>,
+  {
+    let moved = next_move.map_or(vec![], |move_| move_.affected_positions());
+    let cells = (0..Row.size())
+      .flat_map(|row| (0..Column.size()).map(move |column| Position { row, column }));
+    cells
+      .map(|position| {
+        let part = if moved.contains(&position) {
+          BoardPart::NextMove
+        } else {
+          BoardPart::Cell
+        };
+        (self.cell_rect(position), part)
+      })
+      .chain(enemies.into_iter().map(|enemy| {
+        (
+          self.inset_cell_rect(enemy.position, Self::ENEMY_MARGIN),
+          BoardPart::Enemy(enemy.required_attack),
+        )
+      }))
+      .collect()
+  }
+
+  ///The cells to draw a glow around: the ones moved by the next move and the ones covered by the
+  /// target areas
+  pub fn glows(&self, next_move: Option<Move>, targets: &[TargetArea]) -> Vec<(TexRect, Glow)> {
+    let moved = next_move.map_or(vec![], |move_| move_.affected_positions());
+    let cells = (0..Row.size())
+      .flat_map(|row| (0..Column.size()).map(move |column| Position { row, column }));
+    cells
+      .filter_map(|position| {
+        let glow = if moved.contains(&position) {
+          Glow::NextMove
+        } else if targets.iter().any(|target| target.covers(&position)) {
+          Glow::Target
+        } else {
+          return None;
+        };
+        Some((self.cell_rect(position), glow))
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod test_board {
+  use game_logic::position::Dimension::Column;
+  use game_logic::position::{Move, Position};
+  use game_logic::solving::{Enemy, RequiredAttack, TargetArea};
+
+  use crate::renderer::board::{BoardGrid, BoardPart, Glow};
+  use crate::renderer::coordinates::{PTexCoords, TexRect};
+
+  #[test]
+  fn test_parts() {
+    let grid = BoardGrid::new(TexRect::new(
+      PTexCoords::new(0.0, 0.0),
+      PTexCoords::new(1.2, 0.4),
+    ));
+    //the outer ring is on top
+    let outer = Position { row: 3, column: 0 };
+    assert!(grid.cell_rect(outer).contains(PTexCoords::new(0.05, 0.05)));
+    assert!(grid
+      .cell_rect(Position { row: 0, column: 11 })
+      .contains(PTexCoords::new(1.15, 0.35)));
+
+    let next_move = Move::new(Column, 2, 1, true).unwrap();
+    let enemy = Enemy {
+      position: outer,
+      required_attack: Some(RequiredAttack::Jump),
+      hp: None,
+    };
+    let parts = grid.parts([enemy], Some(next_move));
+    assert_eq!(4 * 12 + 1, parts.len());
+    assert_eq!(
+      next_move.affected_positions().len(),
+      parts
+        .iter()
+        .filter(|(_, part)| *part == BoardPart::NextMove)
+        .count()
+    );
+    assert_eq!(
+      Some(&BoardPart::Enemy(Some(RequiredAttack::Jump))),
+      parts.last().map(|(_, part)| part)
+    );
+  }
+
+  #[test]
+  fn test_glows() {
+    let grid = BoardGrid::new(TexRect::new(
+      PTexCoords::new(0.0, 0.0),
+      PTexCoords::new(1.2, 0.4),
+    ));
+    assert!(grid.glows(None, &[]).is_empty());
+
+    let targets = [TargetArea::Wide {
+      left_column: 11,
+      inner_ring: 0,
+    }];
+    let glows = grid.glows(None, &targets);
+    assert_eq!(4, glows.len());
+    assert!(glows.iter().all(|(_, glow)| *glow == Glow::Target));
+
+    //the next move takes precedence where both apply
+    let next_move = Move::new(Column, 0, 1, true).unwrap();
+    let glows = grid.glows(Some(next_move), &targets);
+    assert_eq!(
+      next_move.affected_positions().len(),
+      glows
+        .iter()
+        .filter(|(_, glow)| *glow == Glow::NextMove)
+        .count()
+    );
+    assert_eq!(
+      2,
+      glows
+        .iter()
+        .filter(|(_, glow)| *glow == Glow::Target)
+        .count()
+    );
+  }
+}
@@ -15,9 +15,14 @@
 // TexRect:2xTexCoords|Size+Rect
 // Clip:PClip|TexCoords
 // Square:3xClip|TexRect
+// Polar:f32+Rotation
+// RingSector:2xf32+2xRotation
+//every type is written to buffers via WgslRepr
 
 //CPU conversions:
 // Square / TexCoords... => Clip...
+// Square / Polar => Clip
+// Square * Rotation => Square
 // Square / TexRect => Square
 // Size / Pixel... => TexCoords...
 // Size / Rect => TexRect
@@ -26,16 +31,20 @@ use std::fmt::{Debug, Display, Formatter};
 use std::ops::{Add, Div};
 
 use p_clip::PClip;
-use p_tex_coords::PTexCoords;
+pub use p_tex_coords::PTexCoords;
 use pixel::Pixel;
+pub use polar::{Polar, Rotation};
 use rect::Rect;
+pub use ring_sector::RingSector;
 use size::Size;
 
 mod circle_center;
 mod p_clip;
 mod p_tex_coords;
 mod pixel;
+mod polar;
 mod rect;
+mod ring_sector;
 mod size;
 
 impl Div<TexCoords> for Square {
@@ -212,6 +221,41 @@ impl TexRect {
       TexRect::Absolute { size, rect } => (size / rect).into(),
     }
   }
+
+  ///Moves the rectangle by an offset relative to the size of the texture or canvas
+  pub fn translated(&self, offset: PTexCoords) -> Self {
+    let [top_left, bottom_right] = self
+      .as_array()
+      .map(|tex_coords| tex_coords.as_p_tex_coords());
+    Self::new(
+      PTexCoords::new(top_left.x + offset.x, top_left.y + offset.y),
+      PTexCoords::new(bottom_right.x + offset.x, bottom_right.y + offset.y),
+    )
+  }
+
+  ///Grows or shrinks the rectangle by the factor, keeping the anchor where it is on the texture or
+  /// canvas
+  pub fn scaled(&self, anchor: PTexCoords, factor: f32) -> Self {
+    let [top_left, bottom_right] = self
+      .as_array()
+      .map(|tex_coords| tex_coords.as_p_tex_coords());
+    let scale = |corner: PTexCoords| {
+      PTexCoords::new(
+        anchor.x + (corner.x - anchor.x) * factor,
+        anchor.y + (corner.y - anchor.y) * factor,
+      )
+    };
+    Self::new(scale(top_left), scale(bottom_right))
+  }
+
+  ///Whether a point on the same texture or canvas lies within the rectangle, including its edges
+  pub fn contains(&self, point: PTexCoords) -> bool {
+    let [top_left, bottom_right] = self
+      .as_array()
+      .map(|tex_coords| tex_coords.as_p_tex_coords());
+    (top_left.x..=bottom_right.x).contains(&point.x)
+      && (top_left.y..=bottom_right.y).contains(&point.y)
+  }
 }
 
 impl<R> Add<R> for Size
@@ -370,6 +414,36 @@ impl Square {
       Square::Screen(tex_rect) => (Square::default() / tex_rect).into(),
     }
   }
+
+  ///The inverse of `square / tex_coords`: converts a point on the screen into coordinates relative
+  /// to the square, ignoring depth. Returns None if the square has no area.
+  pub fn relative(&self, point: PTexCoords) -> Option<PTexCoords> {
+    let to_xy = |clip: Clip| {
+      let p_clip = clip.as_p_clip();
+      p_clip.xyz().truncate() / p_clip.w
+    };
+    let [top_left, bottom_left, bottom_right] = self.as_array().map(to_xy);
+    let point = to_xy(Square::default() / TexCoords::from(point));
+
+    let x_direction = bottom_right - bottom_left;
+    let y_direction = bottom_left - top_left;
+    let determinant = x_direction.perp_dot(y_direction);
+    if determinant.abs() < f32::EPSILON {
+      return None;
+    }
+    let offset = point - top_left;
+    Some(PTexCoords::new(
+      offset.perp_dot(y_direction) / determinant,
+      x_direction.perp_dot(offset) / determinant,
+    ))
+  }
+
+  ///Whether a point on the screen lies within the square, including its edges
+  pub fn contains(&self, point: PTexCoords) -> bool {
+    self.relative(point).is_some_and(|relative| {
+      TexRect::new(PTexCoords::new(0.0, 0.0), PTexCoords::new(1.0, 1.0)).contains(relative)
+    })
+  }
 }
 
 impl From<Square> for [Clip; 3] {
@@ -391,7 +465,118 @@ impl Display for Square {
   }
 }
 
-#[deprecated]
-pub trait FloatArrayRepr {
-  fn to_float_array(self) -> Vec<f32>;
+impl WgslRepr for TexCoords {
+  const SIZE: usize = PTexCoords::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    self.as_p_tex_coords().write_wgsl_repr(data);
+  }
+}
+
+impl WgslRepr for TexRect {
+  const SIZE: usize = <[TexCoords; 2]>::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    self.as_array().write_wgsl_repr(data);
+  }
+}
+
+impl WgslRepr for Clip {
+  const SIZE: usize = PClip::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    self.as_p_clip().write_wgsl_repr(data);
+  }
+}
+
+impl WgslRepr for Square {
+  const SIZE: usize = <[Clip; 3]>::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    self.as_array().write_wgsl_repr(data);
+  }
+}
+
+///A value which can be written into a gpu buffer as its wgsl counterpart. Enums resolve their
+/// variant on the cpu where possible, so the shaders only see the plain representation.
+pub trait WgslRepr {
+  ///The size of the representation in bytes
+  const SIZE: usize;
+
+  ///Appends the representation to buffer data
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>);
+
+  fn to_wgsl_repr(&self) -> Vec<u8> {
+    let mut data = Vec::with_capacity(Self::SIZE);
+    self.write_wgsl_repr(&mut data);
+    data
+  }
+}
+
+impl WgslRepr for f32 {
+  const SIZE: usize = size_of::<f32>();
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    data.extend_from_slice(bytemuck::bytes_of(self));
+  }
+}
+
+impl WgslRepr for u32 {
+  const SIZE: usize = size_of::<u32>();
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    data.extend_from_slice(bytemuck::bytes_of(self));
+  }
+}
+
+impl<T, const N: usize> WgslRepr for [T; N]
+where
+  T: WgslRepr,
+{
+  const SIZE: usize = T::SIZE * N;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    for element in self {
+      element.write_wgsl_repr(data);
+    }
+  }
+}
+
+macro_rules! impl_wgsl_repr_for_tuple {
+  ($($name: ident: $index: tt),+) => {
+    impl<$($name),+> WgslRepr for ($($name,)+)
+    where
+      $($name: WgslRepr),+
+    {
+      const SIZE: usize = 0 $(+ $name::SIZE)+;
+
+      fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+        $(self.$index.write_wgsl_repr(data);)+
+      }
+    }
+  };
+}
+
+impl_wgsl_repr_for_tuple!(A: 0, B: 1);
+impl_wgsl_repr_for_tuple!(A: 0, B: 1, C: 2);
+impl_wgsl_repr_for_tuple!(A: 0, B: 1, C: 2, D: 3);
+
+#[cfg(test)]
+mod test_wgsl_repr {
+  use crate::renderer::coordinates::{Square, TexCoords, TexRect, WgslRepr};
+
+  #[test]
+  fn test_wgsl_repr() {
+    assert_eq!(12 * 4, Square::SIZE);
+    assert_eq!(Square::SIZE, Square::default().to_wgsl_repr().len());
+
+    let instance = (
+      TexRect::new(TexCoords::new(0.0, 0.5), TexCoords::new(1.0, 1.0)),
+      Square::default(),
+    );
+    let data = instance.to_wgsl_repr();
+    assert_eq!(<(TexRect, Square)>::SIZE, data.len());
+    let floats: &[f32] = bytemuck::cast_slice(&data);
+    assert_eq!([0.0, 0.5, 1.0, 1.0, -1.0, 1.0, 0.0, 1.0], floats[..8]);
+  }
 }
@@ -3,7 +3,7 @@ use std::fmt::{Display, Formatter};
 use crate::renderer::coordinates::p_tex_coords::PTexCoords;
 use crate::renderer::coordinates::pixel::Pixel;
 use crate::renderer::coordinates::size::Size;
-use crate::renderer::coordinates::TexCoords;
+use crate::renderer::coordinates::{TexCoords, WgslRepr};
 
 ///Describes where the center of a circle is located
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -50,3 +50,16 @@ impl Display for CircleCenter {
     }
   }
 }
+
+///Written as a variant tag, 0 for [`CircleCenter::PTexCoords`] and 1 for [`CircleCenter::Pixel`],
+/// followed by the coordinates
+impl WgslRepr for CircleCenter {
+  const SIZE: usize = u32::SIZE + <[u32; 2]>::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    match self {
+      CircleCenter::PTexCoords(p_tex_coords) => (0u32, *p_tex_coords).write_wgsl_repr(data),
+      CircleCenter::Pixel(pixel) => (1u32, *pixel).write_wgsl_repr(data),
+    }
+  }
+}
@@ -3,6 +3,8 @@ use std::ops::{Add, Div, Mul, Sub};
 
 use glam::{Vec3, Vec4};
 
+use crate::renderer::coordinates::WgslRepr;
+
 /// A point in clip coordinate space.
 #[derive(Debug, Copy, Clone, PartialEq, Default)]
 pub struct PClip {
@@ -86,3 +88,11 @@ impl Display for PClip {
     write!(f, "({}, {}, {}, {})", self.x, self.y, self.z, self.w)
   }
 }
+
+impl WgslRepr for PClip {
+  const SIZE: usize = <[f32; 4]>::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    [self.x, self.y, self.z, self.w].write_wgsl_repr(data);
+  }
+}
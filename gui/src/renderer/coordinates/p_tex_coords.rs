@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter};
 
+use crate::renderer::coordinates::WgslRepr;
+
 ///Describes a point on a texture, canvas or square area relative to its size, where a value of 0.0
 /// means top/left corner and 1.0 means bottom/right
 #[derive(Debug, Copy, Clone, PartialEq, Default)]
@@ -19,3 +21,11 @@ impl Display for PTexCoords {
     write!(f, "[{}% {}%]", self.x * 100.0, self.y * 100.0)
   }
 }
+
+impl WgslRepr for PTexCoords {
+  const SIZE: usize = <[f32; 2]>::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    [self.x, self.y].write_wgsl_repr(data);
+  }
+}
@@ -1,6 +1,6 @@
 use std::fmt::{Display, Formatter};
 
-use crate::renderer::coordinates::FloatArrayRepr;
+use crate::renderer::coordinates::WgslRepr;
 
 ///Denotes a pixel on a canvas or texture
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
@@ -21,8 +21,10 @@ impl Display for Pixel {
   }
 }
 
-impl FloatArrayRepr for Pixel {
-  fn to_float_array(self) -> Vec<f32> {
-    vec![self.x as f32, self.y as f32]
+impl WgslRepr for Pixel {
+  const SIZE: usize = <[u32; 2]>::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    [self.x, self.y].write_wgsl_repr(data);
   }
 }
@@ -0,0 +1,218 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg};
+
+use glam::Vec2;
+
+use game_logic::position::Dimension::{Column, Row};
+use game_logic::position::Position;
+
+use crate::renderer::coordinates::p_clip::PClip;
+use crate::renderer::coordinates::p_tex_coords::PTexCoords;
+use crate::renderer::coordinates::{Clip, Square, TexCoords, WgslRepr};
+
+///A counter-clockwise rotation in radians
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Rotation(pub f32);
+
+impl Rotation {
+  pub fn degrees(degrees: f32) -> Self {
+    Self(degrees.to_radians())
+  }
+
+  pub fn radians(&self) -> f32 {
+    self.0
+  }
+}
+
+impl Add for Rotation {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    Self(self.0 + rhs.0)
+  }
+}
+
+impl Neg for Rotation {
+  type Output = Self;
+
+  fn neg(self) -> Self::Output {
+    Self(-self.0)
+  }
+}
+
+impl Display for Rotation {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}°", self.0.to_degrees())
+  }
+}
+
+impl WgslRepr for Rotation {
+  const SIZE: usize = f32::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    self.0.write_wgsl_repr(data);
+  }
+}
+
+///Describes a point in a square area by its distance to the center and its angle, where a radius of
+/// 1.0 touches the edges of the area and an angle of 0 points to the right
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Polar {
+  pub radius: f32,
+  pub angle: Rotation,
+}
+
+impl Polar {
+  pub fn new(radius: f32, angle: Rotation) -> Self {
+    Self { radius, angle }
+  }
+
+  ///The center of the field at a position of the arena. The rings are spread evenly between the
+  /// inner radius and the edge, the columns start at the top and go clockwise like in the game.
+  pub fn of_position(position: Position, inner_radius: f32) -> Self {
+    let ring_width = (1.0 - inner_radius) / Row.size() as f32;
+    let column_angle = TAU / Column.size() as f32;
+    Self::new(
+      inner_radius + (position.row as f32 + 0.5) * ring_width,
+      Rotation(FRAC_PI_2 - (position.column as f32 + 0.5) * column_angle),
+    )
+  }
+
+  ///The rotation which turns something upright, like an enemy sprite, to face away from the center
+  pub fn outward(&self) -> Rotation {
+    self.angle + -Rotation(FRAC_PI_2)
+  }
+
+  pub fn rotated(self, rotation: Rotation) -> Self {
+    Self::new(self.radius, self.angle + rotation)
+  }
+
+  pub fn as_p_tex_coords(&self) -> PTexCoords {
+    let offset = Vec2::from_angle(self.angle.0) * self.radius / 2.0;
+    //texture coordinates grow downwards
+    PTexCoords::new(0.5 + offset.x, 0.5 - offset.y)
+  }
+}
+
+impl From<PTexCoords> for Polar {
+  fn from(value: PTexCoords) -> Self {
+    let offset = Vec2::new(value.x - 0.5, 0.5 - value.y) * 2.0;
+    Self::new(offset.length(), Rotation(offset.y.atan2(offset.x)))
+  }
+}
+
+impl Mul<Rotation> for Polar {
+  type Output = Self;
+
+  fn mul(self, rhs: Rotation) -> Self::Output {
+    self.rotated(rhs)
+  }
+}
+
+impl From<Polar> for TexCoords {
+  fn from(value: Polar) -> Self {
+    value.as_p_tex_coords().into()
+  }
+}
+
+impl From<Polar> for Clip {
+  fn from(value: Polar) -> Self {
+    Self::Screen(value.into())
+  }
+}
+
+impl Div<Polar> for Square {
+  type Output = Clip;
+
+  fn div(self, rhs: Polar) -> Self::Output {
+    self / TexCoords::from(rhs)
+  }
+}
+
+impl Display for Polar {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "({} {})", self.radius, self.angle)
+  }
+}
+
+impl WgslRepr for Polar {
+  const SIZE: usize = <(f32, Rotation)>::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    (self.radius, self.angle).write_wgsl_repr(data);
+  }
+}
+
+///Rotates the square around its center
+impl Mul<Rotation> for Square {
+  type Output = Square;
+
+  fn mul(self, rhs: Rotation) -> Self::Output {
+    let corners = self.as_array().map(|clip| {
+      let p_clip = clip.as_p_clip();
+      p_clip.xyz() / p_clip.w
+    });
+    let center = (corners[0] + corners[2]) / 2.0;
+    let rotation = Vec2::from_angle(rhs.0);
+    corners
+      .map(|corner| {
+        let rotated = center.truncate() + rotation.rotate((corner - center).truncate());
+        Clip::from(PClip::new(rotated.x, rotated.y, corner.z))
+      })
+      .into()
+  }
+}
+
+#[cfg(test)]
+mod test_polar {
+  use std::f32::consts::FRAC_PI_2;
+
+  use game_logic::position::Position;
+
+  use crate::renderer::coordinates::p_clip::PClip;
+  use crate::renderer::coordinates::polar::{Polar, Rotation};
+  use crate::renderer::coordinates::{Square, TexCoords};
+
+  fn assert_near(expected: (f32, f32), actual: (f32, f32)) {
+    assert!(
+      (expected.0 - actual.0).abs() < 1e-5 && (expected.1 - actual.1).abs() < 1e-5,
+      "expected {expected:?}, got {actual:?}"
+    );
+  }
+
+  #[test]
+  fn test_polar_position() {
+    let top = Polar::new(1.0, Rotation(FRAC_PI_2)).as_p_tex_coords();
+    assert_near((0.5, 0.0), (top.x, top.y));
+
+    //the outermost field of c3 is right above the 3 o'clock position
+    let polar = Polar::of_position(Position::at(3, 2).unwrap(), 0.2);
+    assert_near(
+      (0.9, 15f32.to_radians()),
+      (polar.radius, polar.angle.radians()),
+    );
+    assert_near(
+      (15f32.to_radians(), -75f32.to_radians()),
+      (polar.angle.radians(), polar.outward().radians()),
+    );
+
+    let clip = (Square::default() / polar).as_p_clip();
+    let expected = TexCoords::from(polar).as_p_tex_coords();
+    assert_near(
+      (expected.x * 2.0 - 1.0, 1.0 - expected.y * 2.0),
+      (clip.x, clip.y),
+    );
+  }
+
+  #[test]
+  fn test_rotate_square() {
+    let [top_left, bottom_left, bottom_right] = (Square::default() * Rotation::degrees(90.0))
+      .as_array()
+      .map(|clip| clip.as_p_clip());
+    let corner = |p_clip: PClip| (p_clip.x, p_clip.y);
+    assert_near((-1.0, -1.0), corner(top_left));
+    assert_near((1.0, -1.0), corner(bottom_left));
+    assert_near((1.0, 1.0), corner(bottom_right));
+  }
+}
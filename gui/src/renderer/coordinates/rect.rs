@@ -1,6 +1,7 @@
 use std::fmt::{Display, Formatter};
 
 use crate::renderer::coordinates::pixel::Pixel;
+use crate::renderer::coordinates::WgslRepr;
 
 ///A rectangle described by two [`Pixel`]s which denote the top left corner and the bottom right one
 /// of the rectangle
@@ -33,3 +34,11 @@ impl Display for Rect {
     write!(f, "{}->{}", self.top_left, self.bottom_right)
   }
 }
+
+impl WgslRepr for Rect {
+  const SIZE: usize = <[Pixel; 2]>::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    [self.top_left, self.bottom_right].write_wgsl_repr(data);
+  }
+}
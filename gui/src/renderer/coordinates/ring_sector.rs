@@ -0,0 +1,96 @@
+use std::f32::consts::TAU;
+use std::fmt::{Display, Formatter};
+
+use crate::renderer::coordinates::p_tex_coords::PTexCoords;
+use crate::renderer::coordinates::{Polar, Rotation, WgslRepr};
+
+///A part of a ring around the center of a square area, like a field of the arena. With an inner
+/// radius of 0.0 it is a circle sector, with a sweep of a full turn it is the whole ring or circle.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RingSector {
+  pub inner_radius: f32,
+  pub outer_radius: f32,
+  ///where the sector starts
+  pub start: Rotation,
+  ///how far the sector spans counter-clockwise from its start
+  pub sweep: Rotation,
+}
+
+impl RingSector {
+  pub fn new(inner_radius: f32, outer_radius: f32, start: Rotation, sweep: Rotation) -> Self {
+    Self {
+      inner_radius,
+      outer_radius,
+      start,
+      sweep,
+    }
+  }
+
+  pub fn ring(inner_radius: f32, outer_radius: f32) -> Self {
+    Self::new(inner_radius, outer_radius, Rotation(0.0), Rotation(TAU))
+  }
+
+  pub fn circle(radius: f32) -> Self {
+    Self::ring(0.0, radius)
+  }
+
+  ///Whether a point, relative to the square area the sector lies in, is part of the sector
+  pub fn contains(&self, point: PTexCoords) -> bool {
+    let polar = Polar::from(point);
+    let angle = (polar.angle.radians() - self.start.radians()).rem_euclid(TAU);
+    (self.inner_radius..=self.outer_radius).contains(&polar.radius)
+      && (self.sweep.radians() >= TAU || angle <= self.sweep.radians())
+  }
+}
+
+impl Display for RingSector {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "({}..{} {}+{})",
+      self.inner_radius, self.outer_radius, self.start, self.sweep
+    )
+  }
+}
+
+impl WgslRepr for RingSector {
+  const SIZE: usize = <(f32, f32, Rotation, Rotation)>::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    (self.inner_radius, self.outer_radius, self.start, self.sweep).write_wgsl_repr(data);
+  }
+}
+
+#[cfg(test)]
+mod test_ring_sector {
+  use crate::renderer::coordinates::p_tex_coords::PTexCoords;
+  use crate::renderer::coordinates::{Clip, Polar, RingSector, Rotation, Square, TexCoords, TexRect};
+
+  #[test]
+  fn test_contains() {
+    //the top right quarter of a ring
+    let sector = RingSector::new(0.5, 1.0, Rotation(0.0), Rotation::degrees(90.0));
+    assert!(sector.contains(Polar::new(0.75, Rotation::degrees(45.0)).as_p_tex_coords()));
+    assert!(!sector.contains(Polar::new(0.25, Rotation::degrees(45.0)).as_p_tex_coords()));
+    assert!(!sector.contains(Polar::new(0.75, Rotation::degrees(-45.0)).as_p_tex_coords()));
+    assert!(RingSector::circle(1.0).contains(PTexCoords::new(0.5, 0.5)));
+
+    let tex_rect = TexRect::new(PTexCoords::new(0.25, 0.25), PTexCoords::new(0.5, 1.0));
+    assert!(tex_rect.contains(PTexCoords::new(0.5, 0.3)));
+    assert!(!tex_rect.contains(PTexCoords::new(0.6, 0.3)));
+
+    //the right half of the screen, turned upside down
+    let square = Square::new(
+      Clip::new(1.0, -1.0, 0.0),
+      Clip::new(1.0, 1.0, 0.0),
+      Clip::new(0.0, 1.0, 0.0),
+    );
+    assert!(square.contains(PTexCoords::new(0.75, 0.5)));
+    assert!(!square.contains(PTexCoords::new(0.25, 0.5)));
+    let relative = square
+      .relative(PTexCoords::new(0.9, 0.2))
+      .expect("square has an area");
+    let screen = (square / TexCoords::from(relative)).as_p_clip();
+    assert!((0.8 - screen.x).abs() < 1e-5 && (0.6 - screen.y).abs() < 1e-5);
+  }
+}
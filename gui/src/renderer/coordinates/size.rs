@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter};
 
+use crate::renderer::coordinates::WgslRepr;
+
 ///Describes the size of a canvas, texture or square area
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Size {
@@ -18,3 +20,11 @@ impl Display for Size {
     write!(f, "{}x{}", self.width, self.height)
   }
 }
+
+impl WgslRepr for Size {
+  const SIZE: usize = <[u32; 2]>::SIZE;
+
+  fn write_wgsl_repr(&self, data: &mut Vec<u8>) {
+    [self.width, self.height].write_wgsl_repr(data);
+  }
+}
@@ -0,0 +1,151 @@
+use std::f32::consts::TAU;
+
+///What shakes or flashes when an action isn't possible
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FeedbackTarget {
+  ///everything drawn of the arena, like when solving an arena without enemies
+  Arena,
+  ///a row of the settings screen, like when a setting is already at its maximum
+  SettingsRow(usize),
+  ///an entry of the recent arenas, like when moving the focus past the last one
+  RecentRow(usize),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FeedbackKind {
+  ///moves the target back and forth
+  Shake,
+  ///tints the target with the [error color](Feedback::flash), fading out
+  Flash,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct RunningFeedback {
+  target: FeedbackTarget,
+  kind: FeedbackKind,
+  ///ticks since the feedback started, scaled by the animation speed
+  elapsed: f32,
+}
+
+impl RunningFeedback {
+  ///How far the feedback is, from 0.0 to 1.0
+  fn progress(&self) -> f32 {
+    (self.elapsed / Feedback::DURATION_TICKS).min(1.0)
+  }
+}
+
+///Shakes and flashes which tell the user that an action was invalid, without having to read
+/// anything. They are driven by the animation tick.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Feedback {
+  running: Vec<RunningFeedback>,
+}
+
+impl Feedback {
+  ///How many animation ticks a feedback lasts at normal animation speed
+  pub const DURATION_TICKS: f32 = 8.0;
+  ///How far a shake moves its target at most, relative to the window width
+  pub const SHAKE_AMPLITUDE: f32 = 0.02;
+  ///How often a shake moves back and forth
+  const SHAKES: f32 = 3.0;
+
+  ///Starts a feedback on the target, restarting it if it is already running
+  pub fn trigger(&mut self, target: FeedbackTarget, kind: FeedbackKind) {
+    self
+      .running
+      .retain(|feedback| feedback.target != target || feedback.kind != kind);
+    self.running.push(RunningFeedback {
+      target,
+      kind,
+      elapsed: 0.0,
+    });
+  }
+
+  ///Shakes and flashes the target, which is how invalid actions are shown
+  pub fn reject(&mut self, target: FeedbackTarget) {
+    self.trigger(target, FeedbackKind::Shake);
+    self.trigger(target, FeedbackKind::Flash);
+  }
+
+  ///Advances all feedback by one animation tick at the given animation speed and drops the finished
+  /// ones. Returns whether any feedback was running.
+  pub fn tick(&mut self, animation_speed: f32) -> bool {
+    let was_running = self.is_running();
+    for feedback in &mut self.running {
+      feedback.elapsed += animation_speed;
+    }
+    self
+      .running
+      .retain(|feedback| feedback.elapsed < Self::DURATION_TICKS);
+    was_running
+  }
+
+  pub fn is_running(&self) -> bool {
+    !self.running.is_empty()
+  }
+
+  fn progress(&self, target: FeedbackTarget, kind: FeedbackKind) -> Option<f32> {
+    self
+      .running
+      .iter()
+      .find(|feedback| feedback.target == target && feedback.kind == kind)
+      .map(RunningFeedback::progress)
+  }
+
+  ///How far the target is currently moved to the right, relative to the window width. The shake
+  /// calms down towards its end.
+  pub fn shake_offset(&self, target: FeedbackTarget) -> f32 {
+    self
+      .progress(target, FeedbackKind::Shake)
+      .map_or(0.0, |progress| {
+        Self::SHAKE_AMPLITUDE * (1.0 - progress) * (progress * Self::SHAKES * TAU).sin()
+      })
+  }
+
+  ///How strongly the target is tinted with the error color, from 0.0 to 1.0
+  pub fn flash(&self, target: FeedbackTarget) -> f32 {
+    self
+      .progress(target, FeedbackKind::Flash)
+      .map_or(0.0, |progress| 1.0 - progress)
+  }
+}
+
+#[cfg(test)]
+mod test_feedback {
+  use crate::renderer::feedback::{Feedback, FeedbackKind, FeedbackTarget};
+
+  #[test]
+  fn test_reject() {
+    let mut feedback = Feedback::default();
+    assert!(!feedback.tick(1.0));
+    feedback.reject(FeedbackTarget::SettingsRow(1));
+    assert_eq!(1.0, feedback.flash(FeedbackTarget::SettingsRow(1)));
+    assert_eq!(0.0, feedback.flash(FeedbackTarget::Arena));
+
+    assert!(feedback.tick(1.0));
+    let offset = feedback.shake_offset(FeedbackTarget::SettingsRow(1));
+    assert!(offset > 0.0 && offset <= Feedback::SHAKE_AMPLITUDE);
+    assert_eq!(0.0, feedback.shake_offset(FeedbackTarget::SettingsRow(0)));
+
+    //twice the speed finishes in half the ticks
+    for _ in 0..3 {
+      feedback.tick(2.0);
+    }
+    assert!(feedback.is_running());
+    assert!(feedback.tick(2.0));
+    assert!(!feedback.is_running());
+    assert_eq!(0.0, feedback.flash(FeedbackTarget::SettingsRow(1)));
+  }
+
+  #[test]
+  fn test_restart() {
+    let mut feedback = Feedback::default();
+    feedback.trigger(FeedbackTarget::Arena, FeedbackKind::Flash);
+    feedback.tick(4.0);
+    assert_eq!(0.5, feedback.flash(FeedbackTarget::Arena));
+    assert_eq!(0.0, feedback.shake_offset(FeedbackTarget::Arena));
+
+    feedback.trigger(FeedbackTarget::Arena, FeedbackKind::Flash);
+    assert_eq!(1.0, feedback.flash(FeedbackTarget::Arena));
+  }
+}
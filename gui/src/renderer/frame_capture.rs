@@ -0,0 +1,195 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use image::ImageError;
+use wgpu::{CommandEncoder, Device, Texture};
+
+use crate::renderer::readback::{Readback, ReadbackError};
+
+///Where captured frames are written to, relative to the working directory
+pub const CAPTURE_DIRECTORY: &str = "frame_captures";
+
+///What to capture of the next frame
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CaptureMode {
+  ///only the draw list
+  DrawList,
+  ///the draw list and a png of each render pass
+  WithImages,
+}
+
+///Describes the content of a buffer without dumping all of it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BufferSummary {
+  pub label: String,
+  pub elements: u32,
+  pub bytes: usize,
+  ///FNV-1a hash of the content, to compare buffers between devices
+  pub checksum: u64,
+}
+
+impl BufferSummary {
+  pub fn of<S>(label: S, elements: u32, data: &[u8]) -> Self
+  where
+    S: ToString,
+  {
+    let checksum = data.iter().fold(0xcbf29ce484222325_u64, |hash, &byte| {
+      (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    Self {
+      label: label.to_string(),
+      elements,
+      bytes: data.len(),
+      checksum,
+    }
+  }
+}
+
+impl Display for BufferSummary {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}: {} elements, {} bytes, checksum {:016x}",
+      self.label, self.elements, self.bytes, self.checksum
+    )
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DrawCall {
+  pub pass: &'static str,
+  pub pipeline: &'static str,
+  ///the vertices, or indices for indexed draws
+  pub vertices: Range<u32>,
+  pub instances: Range<u32>,
+  pub buffers: Vec<BufferSummary>,
+}
+
+impl Display for DrawCall {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "[{}] {}: vertices {:?}, instances {:?}",
+      self.pass, self.pipeline, self.vertices, self.instances
+    )?;
+    for buffer in &self.buffers {
+      write!(f, "\n  {}", buffer)?;
+    }
+    Ok(())
+  }
+}
+
+///Everything which was drawn during a frame
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct FrameCapture {
+  pub draws: Vec<DrawCall>,
+  ///the png of each render pass, if images were captured
+  pub pass_images: Vec<PathBuf>,
+}
+
+impl FrameCapture {
+  ///Writes the draw list as text file into the directory and returns its path
+  pub fn write_draw_list(&self, directory: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(directory)?;
+    let path = directory.join("draw_list.txt");
+    fs::write(&path, self.to_string())?;
+    Ok(path)
+  }
+}
+
+impl Display for FrameCapture {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} draw calls", self.draws.len())?;
+    for draw in &self.draws {
+      write!(f, "\n{}", draw)?;
+    }
+    for image in &self.pass_images {
+      write!(f, "\nimage: {}", image.display())?;
+    }
+    Ok(())
+  }
+}
+
+///A copy of the target of a render pass, which can be saved as png once the commands were submitted
+#[derive(Debug)]
+pub struct PassReadback {
+  pub pass: &'static str,
+  readback: Readback,
+}
+
+impl PassReadback {
+  ///Records copying the texture, which needs to be created with
+  /// [`COPY_SRC`](wgpu::TextureUsages::COPY_SRC) usage
+  pub fn record(
+    device: &Device,
+    encoder: &mut CommandEncoder,
+    texture: &Texture,
+    pass: &'static str,
+  ) -> Self {
+    Self {
+      pass,
+      readback: Readback::record(device, encoder, texture),
+    }
+  }
+
+  ///Waits for the copy to finish and saves it as png. Must be called after the encoder the copy was
+  /// recorded with was submitted.
+  pub fn save(self, device: &Device, path: &Path) -> Result<(), CaptureError> {
+    let image = self.readback.read(device).map_err(CaptureError::Readback)?;
+    if let Some(directory) = path.parent() {
+      fs::create_dir_all(directory).map_err(|e| CaptureError::Image(e.into()))?;
+    }
+    image.save(path).map_err(CaptureError::Image)
+  }
+}
+
+#[derive(Debug)]
+pub enum CaptureError {
+  Readback(ReadbackError),
+  Image(ImageError),
+}
+
+impl Display for CaptureError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      CaptureError::Readback(e) => write!(f, "{}", e),
+      CaptureError::Image(e) => write!(f, "failed to save image: {}", e),
+    }
+  }
+}
+
+impl Error for CaptureError {}
+
+#[cfg(test)]
+mod test_frame_capture {
+  use crate::renderer::frame_capture::{BufferSummary, DrawCall, FrameCapture};
+
+  #[test]
+  fn test_draw_list() {
+    let capture = FrameCapture {
+      draws: vec![DrawCall {
+        pass: "Render Pass",
+        pipeline: "tutorial",
+        vertices: 0..9,
+        instances: 0..1,
+        buffers: vec![BufferSummary::of("Index Buffer", 9, &[0; 18])],
+      }],
+      pass_images: vec![],
+    };
+    let dump = capture.to_string();
+    assert!(dump.starts_with("1 draw calls\n[Render Pass] tutorial: vertices 0..9"));
+    assert!(dump.contains("Index Buffer: 9 elements, 18 bytes"));
+
+    assert_eq!(
+      BufferSummary::of("a", 1, &[1, 2]).checksum,
+      BufferSummary::of("b", 1, &[1, 2]).checksum
+    );
+    assert_ne!(
+      BufferSummary::of("a", 1, &[1, 2]).checksum,
+      BufferSummary::of("a", 1, &[2, 1]).checksum
+    );
+  }
+}
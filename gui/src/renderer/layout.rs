@@ -0,0 +1,231 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use glam::Vec2;
+
+use game_logic::position::Dimension::{Column, Row};
+use game_logic::position::Position;
+
+use crate::renderer::coordinates::{Clip, PTexCoords, Polar, RingSector, Rotation, Square, TexCoords};
+
+///The number of rings of the arena
+pub const RINGS: usize = Row.size() as usize;
+
+///How far a field is moved away from its [`Position`] while a move is animated
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct AnimationOffset {
+  ///in columns, positive values turn clockwise like a ring move
+  pub rotation: f32,
+  ///in rings, positive values shift away from the center like a column move
+  pub shift: f32,
+}
+
+impl AnimationOffset {
+  pub fn rotation(rotation: f32) -> Self {
+    Self {
+      rotation,
+      ..Default::default()
+    }
+  }
+
+  pub fn shift(shift: f32) -> Self {
+    Self {
+      shift,
+      ..Default::default()
+    }
+  }
+}
+
+///Maps the fields of the arena to screen geometry
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ArenaLayout {
+  ///the borders between the rings from the inside out, relative to the arena area where 1.0
+  /// touches its edges
+  ring_radii: [f32; RINGS + 1],
+  ///where the arena is drawn
+  area: Square,
+}
+
+impl Default for ArenaLayout {
+  fn default() -> Self {
+    Self::new([0.25, 0.4375, 0.625, 0.8125, 1.0])
+  }
+}
+
+impl ArenaLayout {
+  ///## Panics
+  /// If the radii aren't ascending
+  pub fn new(ring_radii: [f32; RINGS + 1]) -> Self {
+    assert!(
+      ring_radii.windows(2).all(|pair| pair[0] < pair[1]),
+      "ring radii must be ascending: {ring_radii:?}"
+    );
+    Self {
+      ring_radii,
+      area: Square::default(),
+    }
+  }
+
+  pub fn with_area<S>(mut self, area: S) -> Self
+  where
+    S: Into<Square>,
+  {
+    self.area = area.into();
+    self
+  }
+
+  pub fn ring_radii(&self) -> &[f32; RINGS + 1] {
+    &self.ring_radii
+  }
+
+  pub fn area(&self) -> Square {
+    self.area
+  }
+
+  ///The center of a field. Shifting a field past the center moves it to the opposite side, just
+  /// like a column move does.
+  pub fn field_center(&self, position: Position, offset: AnimationOffset) -> Polar {
+    //the coordinate along the whole diameter, where the opposite side starts at RINGS
+    let diameter_period = 2.0 * RINGS as f32;
+    let along_diameter = (position.row as f32 + 0.5 + offset.shift).rem_euclid(diameter_period);
+    let (ring, column) = if along_diameter < RINGS as f32 {
+      (along_diameter, position.column as f32)
+    } else {
+      (
+        diameter_period - along_diameter,
+        position.column as f32 + Column.size() as f32 / 2.0,
+      )
+    };
+
+    //columns start at the top and go clockwise
+    let column_angle = TAU / Column.size() as f32;
+    Polar::new(
+      self.radius_at(ring),
+      Rotation(FRAC_PI_2 - (column + 0.5 + offset.rotation) * column_angle),
+    )
+  }
+
+  pub fn field_clip(&self, position: Position, offset: AnimationOffset) -> Clip {
+    self.area / self.field_center(position, offset)
+  }
+
+  ///An upright square fitting into the ring of a field, rotated to face away from the center, to
+  /// draw things like enemies on
+  pub fn field_square(&self, position: Position, offset: AnimationOffset) -> Square {
+    let center = self.field_center(position, offset);
+    let ring = (position.row as usize).min(RINGS - 1);
+    //a radius of 1.0 spans half of the area
+    let half_size = (self.ring_radii[ring + 1] - self.ring_radii[ring]) / 4.0;
+    let rotation = Vec2::from_angle(center.outward().radians());
+    let center = TexCoords::from(center).as_p_tex_coords();
+    let corners = [(-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)].map(|(x, y)| {
+      let corner = rotation.rotate(Vec2::new(x, y) * half_size);
+      //texture coordinates grow downwards
+      TexCoords::new(center.x + corner.x, center.y - corner.y)
+    });
+    (self.area / corners).into()
+  }
+
+  ///The area a field covers while it isn't animated
+  pub fn field_sector(&self, position: Position) -> RingSector {
+    let ring = position.row as usize;
+    let column_angle = TAU / Column.size() as f32;
+    RingSector::new(
+      self.ring_radii[ring],
+      self.ring_radii[ring + 1],
+      Rotation(FRAC_PI_2 - (position.column as f32 + 1.0) * column_angle),
+      Rotation(column_angle),
+    )
+  }
+
+  ///The field at a point on the screen, like where the arena was tapped
+  pub fn position_at(&self, point: PTexCoords) -> Option<Position> {
+    let relative = self.area.relative(point)?;
+    (0..Row.size())
+      .flat_map(|row| (0..Column.size()).map(move |column| Position { row, column }))
+      .find(|&position| self.field_sector(position).contains(relative))
+  }
+
+  ///Interpolates the radius at a coordinate measured in rings from the innermost border
+  fn radius_at(&self, ring: f32) -> f32 {
+    let index = (ring.max(0.0) as usize).min(RINGS - 1);
+    let inner = self.ring_radii[index];
+    let outer = self.ring_radii[index + 1];
+    inner + (ring - index as f32) * (outer - inner)
+  }
+}
+
+#[cfg(test)]
+mod test_layout {
+  use std::f32::consts::TAU;
+
+  use game_logic::position::Position;
+
+  use crate::renderer::coordinates::{PTexCoords, Polar};
+  use crate::renderer::layout::{AnimationOffset, ArenaLayout};
+
+  fn assert_polar_near(expected: Polar, actual: Polar) {
+    let angle_difference = (expected.angle.radians() - actual.angle.radians()).rem_euclid(TAU);
+    assert!(
+      (expected.radius - actual.radius).abs() < 1e-5
+        && angle_difference.min(TAU - angle_difference) < 1e-5,
+      "expected {expected}, got {actual}"
+    );
+  }
+
+  #[test]
+  fn test_field_center() {
+    let layout = ArenaLayout::new([0.2, 0.4, 0.6, 0.8, 1.0]);
+    let position = Position::at(1, 4).unwrap();
+    assert_polar_near(
+      Polar::of_position(position, 0.2),
+      layout.field_center(position, AnimationOffset::default()),
+    );
+
+    //a full column of rotation ends at the next column
+    assert_polar_near(
+      layout.field_center(Position::at(1, 5).unwrap(), AnimationOffset::default()),
+      layout.field_center(position, AnimationOffset::rotation(1.0)),
+    );
+
+    //shifting the inner ring inwards ends on the opposite side, like the move does
+    let mut moved = Position::at(0, 4).unwrap();
+    moved.apply_move("c5 -1".parse().unwrap());
+    assert_polar_near(
+      layout.field_center(moved, AnimationOffset::default()),
+      layout.field_center(Position::at(0, 4).unwrap(), AnimationOffset::shift(-1.0)),
+    );
+
+    //halfway between two rings
+    let halfway = layout.field_center(position, AnimationOffset::shift(0.5));
+    assert!((0.6 - halfway.radius).abs() < 1e-5);
+  }
+
+  #[test]
+  fn test_field_square() {
+    let layout = ArenaLayout::default();
+    //the top right field faces up and to the right
+    let [top_left, bottom_left, bottom_right] = layout
+      .field_square(Position::at(3, 0).unwrap(), AnimationOffset::default())
+      .as_array()
+      .map(|clip| clip.as_p_clip());
+    let center = layout
+      .field_clip(Position::at(3, 0).unwrap(), AnimationOffset::default())
+      .as_p_clip();
+    assert!(top_left.y > bottom_left.y && bottom_right.x > bottom_left.x);
+    let middle = (top_left + bottom_right) / 2.0;
+    assert!((middle.x - center.x).abs() < 1e-5 && (middle.y - center.y).abs() < 1e-5);
+  }
+
+  #[test]
+  fn test_position_at() {
+    let layout = ArenaLayout::default();
+    for position in [Position::at(0, 0), Position::at(2, 7), Position::at(3, 11)] {
+      let position = position.unwrap();
+      let center = layout.field_center(position, AnimationOffset::default());
+      assert_eq!(Some(position), layout.position_at(center.as_p_tex_coords()));
+    }
+    //the middle of the arena and the corners of the area aren't part of any field
+    assert_eq!(None, layout.position_at(PTexCoords::new(0.5, 0.5)));
+    assert_eq!(None, layout.position_at(PTexCoords::new(0.0, 0.0)));
+  }
+}
@@ -5,6 +5,8 @@ use bytemuck::NoUninit;
 use wgpu::{Buffer, BufferUsages, Device, Queue};
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
 
+use crate::renderer::coordinates::WgslRepr;
+
 pub mod texture_pipeline;
 
 ///A wrapper around a [`Buffer`] which keeps track of the number of elements inside the [`Buffer`]
@@ -38,6 +40,16 @@ impl BufferWrapper {
     self.dirty = true;
   }
 
+  ///Adds a single element in its wgsl representation
+  pub fn push<R>(&mut self, element: &R)
+  where
+    R: WgslRepr,
+  {
+    element.write_wgsl_repr(&mut self.data);
+    self.len += 1;
+    self.dirty = true;
+  }
+
   pub fn clear(&mut self) {
     self.data.clear();
     self.len = 0;
@@ -69,9 +81,19 @@ impl BufferWrapper {
     }
   }
 
+  ///The buffer as of the last [`get_buffer`](Self::get_buffer), None before it was called first
+  pub fn buffer(&self) -> Option<&Buffer> {
+    self.buffer.as_ref()
+  }
+
   pub fn len(&self) -> u32 {
     self.len
   }
+
+  ///The elements in their byte representation
+  pub fn data(&self) -> &[u8] {
+    &self.data
+  }
 }
 
 #[derive(Debug, Clone)]
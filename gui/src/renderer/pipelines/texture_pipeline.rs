@@ -12,12 +12,14 @@ pub struct TexturePipeline {
 }
 
 impl TexturePipeline {
+  ///Queues drawing the src area of the texture onto the dest square
   pub fn add<T, S>(&mut self, src: T, dest: S)
   where
     T: Into<TexRect>,
     S: Into<Square>,
   {
-    todo!("put instruction into buffer")
+    let instance: (TexRect, Square) = (src.into(), dest.into());
+    self.instance_buffer.push(&instance);
   }
 
   pub fn render<'a>(
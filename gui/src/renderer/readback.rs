@@ -0,0 +1,259 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::mpsc;
+
+use image::RgbaImage;
+use wgpu::{
+  BufferAsyncError, BufferDescriptor, BufferUsages, CommandEncoder, Device, Extent3d,
+  ImageCopyBuffer, ImageDataLayout, Maintain, MapMode, Texture, TextureFormat,
+  COPY_BYTES_PER_ROW_ALIGNMENT,
+};
+
+///A copy of a render target into a buffer the cpu can read, once the commands were submitted
+#[derive(Debug)]
+pub struct Readback {
+  buffer: wgpu::Buffer,
+  size: Extent3d,
+  padded_bytes_per_row: u32,
+  format: TextureFormat,
+}
+
+impl Readback {
+  ///Records copying the texture, which needs to be created with
+  /// [`COPY_SRC`](wgpu::TextureUsages::COPY_SRC) usage
+  pub fn record(device: &Device, encoder: &mut CommandEncoder, texture: &Texture) -> Self {
+    let size = texture.size();
+    let padded_bytes_per_row = (size.width * 4).next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+    let buffer = device.create_buffer(&BufferDescriptor {
+      label: Some("Readback Buffer"),
+      size: (padded_bytes_per_row * size.height) as u64,
+      usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+      mapped_at_creation: false,
+    });
+    encoder.copy_texture_to_buffer(
+      texture.as_image_copy(),
+      ImageCopyBuffer {
+        buffer: &buffer,
+        layout: ImageDataLayout {
+          offset: 0,
+          bytes_per_row: Some(padded_bytes_per_row),
+          rows_per_image: Some(size.height),
+        },
+      },
+      Extent3d {
+        depth_or_array_layers: 1,
+        ..size
+      },
+    );
+    Self {
+      buffer,
+      size,
+      padded_bytes_per_row,
+      format: texture.format(),
+    }
+  }
+
+  ///Waits for the copy to finish and returns it as rgba image. Must be called after the encoder the
+  /// copy was recorded with was submitted.
+  pub fn read(self, device: &Device) -> Result<RgbaImage, ReadbackError> {
+    let bgra = match self.format {
+      TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+      TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+      format => return Err(ReadbackError::UnsupportedFormat(format)),
+    };
+    let slice = self.buffer.slice(..);
+    let (sender, receiver) = mpsc::channel();
+    slice.map_async(MapMode::Read, move |result| {
+      //the receiver waits right below
+      let _ = sender.send(result);
+    });
+    device.poll(Maintain::Wait);
+    receiver
+      .recv()
+      .expect("map callback is called when polling")
+      .map_err(ReadbackError::Map)?;
+    let image = unpad_rows(
+      &slice.get_mapped_range(),
+      self.size.width,
+      self.size.height,
+      self.padded_bytes_per_row,
+      bgra,
+    );
+    self.buffer.unmap();
+    Ok(image)
+  }
+}
+
+///Turns rows of pixels, each padded to `padded_bytes_per_row`, into an image
+fn unpad_rows(
+  data: &[u8],
+  width: u32,
+  height: u32,
+  padded_bytes_per_row: u32,
+  bgra: bool,
+) -> RgbaImage {
+  let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+  for row in data
+    .chunks(padded_bytes_per_row as usize)
+    .take(height as usize)
+  {
+    pixels.extend_from_slice(&row[..(width * 4) as usize]);
+  }
+  if bgra {
+    pixels
+      .chunks_exact_mut(4)
+      .for_each(|pixel| pixel.swap(0, 2));
+  }
+  RgbaImage::from_raw(width, height, pixels).expect("enough data for every row")
+}
+
+#[derive(Debug)]
+pub enum ReadbackError {
+  UnsupportedFormat(TextureFormat),
+  Map(BufferAsyncError),
+}
+
+impl Display for ReadbackError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ReadbackError::UnsupportedFormat(format) => {
+        write!(f, "can't read textures of format {:?}", format)
+      }
+      ReadbackError::Map(e) => write!(f, "failed to read texture: {}", e),
+    }
+  }
+}
+
+impl Error for ReadbackError {}
+
+///A device without a surface for tests which render, None if the machine has no usable adapter
+#[cfg(test)]
+pub fn headless_device() -> Option<(Device, wgpu::Queue)> {
+  use pollster::FutureExt;
+  use wgpu::{DeviceDescriptor, Instance, RequestAdapterOptions};
+
+  let adapter = Instance::default()
+    .request_adapter(&RequestAdapterOptions::default())
+    .block_on()?;
+  adapter
+    .request_device(&DeviceDescriptor::default(), None)
+    .block_on()
+    .ok()
+}
+
+#[cfg(test)]
+mod test_readback {
+  use std::iter::once;
+
+  use glam::Vec4;
+  use wgpu::{
+    BufferUsages, Color, CommandEncoderDescriptor, Extent3d, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureUsages, TextureViewDescriptor,
+  };
+  use wgpu::util::{BufferInitDescriptor, DeviceExt};
+  use winit::dpi::PhysicalSize;
+
+  use crate::renderer::coordinates::{PTexCoords, TexRect};
+  use crate::renderer::readback::{headless_device, unpad_rows, Readback};
+  use crate::renderer::rect_style::{rounded_rect_vertices, RectStyle};
+  use crate::renderer::render_graph::{Attachment, Draw, Pass, RenderGraph, Viewport};
+  use crate::renderer::GpuResources;
+
+  #[test]
+  fn test_unpad_rows() {
+    //two bgra pixels per row, padded to 12 bytes
+    let data = [
+      [1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0],
+      [9, 10, 11, 12, 13, 14, 15, 16, 0, 0, 0, 0],
+    ]
+    .concat();
+    let image = unpad_rows(&data, 2, 2, 12, true);
+    assert_eq!([3, 2, 1, 4], image.get_pixel(0, 0).0);
+    assert_eq!([15, 14, 13, 16], image.get_pixel(1, 1).0);
+  }
+
+  #[test]
+  fn test_read_rendered_scene() {
+    let Some((device, queue)) = headless_device() else {
+      eprintln!("skipped: no gpu adapter available");
+      return;
+    };
+    let size = PhysicalSize::new(64, 64);
+    let format = TextureFormat::Rgba8Unorm;
+    let gpu = GpuResources::new(&device, format, 1);
+    let texture = device.create_texture(&TextureDescriptor {
+      label: Some("Test Target"),
+      size: Extent3d {
+        width: size.width,
+        height: size.height,
+        depth_or_array_layers: 1,
+      },
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: TextureDimension::D2,
+      format,
+      usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+      view_formats: &[],
+    });
+    let view = texture.create_view(&TextureViewDescriptor::default());
+
+    //a green square in the middle of a red background
+    let vertices = rounded_rect_vertices(
+      TexRect::new(PTexCoords::new(0.25, 0.25), PTexCoords::new(0.75, 0.75)),
+      RectStyle::new(Vec4::new(0.0, 1.0, 0.0, 1.0)),
+      size,
+    );
+    let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
+      label: Some("Test Vertices"),
+      contents: bytemuck::cast_slice(&vertices),
+      usage: BufferUsages::VERTEX,
+    });
+    let viewport = Viewport {
+      x: 0.0,
+      y: 0.0,
+      width: size.width as f32,
+      height: size.height as f32,
+    };
+    let mut graph = RenderGraph::new();
+    graph.attach(
+      "Canvas",
+      Attachment {
+        view: &view,
+        resolve_target: None,
+        readback: None,
+      },
+    );
+    graph.add_pass(
+      Pass::new("Scene Pass", "Canvas")
+        .clearing(Color::RED)
+        .with_viewport(viewport),
+    );
+    let mut widgets = Pass::new("Widget Pass", "Canvas")
+      .after("Scene Pass")
+      .with_viewport(viewport);
+    widgets.draw(Draw {
+      pipeline_name: "widget",
+      pipeline: &gpu.widget_pipeline,
+      bind_group: None,
+      vertex_buffer: vertex_buffer.slice(..),
+      index_buffer: None,
+      vertices: 0..vertices.len() as u32,
+      buffers: vec![],
+    });
+    graph.add_pass(widgets);
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    graph
+      .encode(&device, &mut encoder, None)
+      .expect("valid graph");
+    let readback = Readback::record(&device, &mut encoder, &texture);
+    queue.submit(once(encoder.finish()));
+    let image = readback.read(&device).expect("rgba is readable");
+
+    assert_eq!((size.width, size.height), image.dimensions());
+    assert_eq!([255, 0, 0, 255], image.get_pixel(2, 2).0);
+    assert_eq!([255, 0, 0, 255], image.get_pixel(61, 61).0);
+    assert_eq!([0, 255, 0, 255], image.get_pixel(32, 32).0);
+    assert_eq!([0, 255, 0, 255], image.get_pixel(20, 43).0);
+  }
+}
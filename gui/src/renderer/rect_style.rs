@@ -0,0 +1,164 @@
+use glam::{Vec2, Vec4};
+use winit::dpi::PhysicalSize;
+
+use crate::renderer::coordinates::{Clip, PTexCoords, TexRect};
+use crate::shader::rounded_rect::RoundedRectVertexRepr;
+
+///How a rectangle of the ui is drawn by the rounded rect pipeline. The corners and the border are
+/// computed per pixel from a signed distance, so they stay crisp however large the rectangle is.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RectStyle {
+  pub fill: Vec4,
+  pub border: Vec4,
+  ///the radius of the corners as share of the shorter side, 0.5 makes the short sides round
+  pub corner_radius: f32,
+  ///in pixels
+  pub border_width: f32,
+}
+
+impl RectStyle {
+  ///A filled rectangle with sharp corners and no border
+  pub fn new(fill: Vec4) -> Self {
+    Self {
+      fill,
+      border: fill,
+      corner_radius: 0.0,
+      border_width: 0.0,
+    }
+  }
+
+  pub fn rounded(mut self, corner_radius: f32) -> Self {
+    self.corner_radius = corner_radius.clamp(0.0, 0.5);
+    self
+  }
+
+  pub fn bordered(mut self, border: Vec4, border_width: f32) -> Self {
+    self.border = border;
+    self.border_width = border_width.max(0.0);
+    self
+  }
+
+  ///Mixes fill and border with another color, where an amount of 1.0 gives the other color
+  pub fn tinted(mut self, color: Vec4, amount: f32) -> Self {
+    self.fill = self.fill.lerp(color, amount);
+    self.border = self.border.lerp(color, amount);
+    self
+  }
+}
+
+///Two triangles covering the rectangle of the screen, drawn with the style. The size of the safe area
+/// the rectangle is relative to is needed to keep corners round on screens which aren't square.
+pub fn rounded_rect_vertices(
+  rect: TexRect,
+  style: RectStyle,
+  size: PhysicalSize<u32>,
+) -> [RoundedRectVertexRepr; 6] {
+  vertices(rect, style, 0.0, size)
+}
+
+///Two triangles covering the rectangle and the glow around it, which fades out over the given number
+/// of pixels. Meant for the glow pipeline, which only uses the fill of the style.
+pub fn glow_vertices(
+  rect: TexRect,
+  style: RectStyle,
+  glow: f32,
+  size: PhysicalSize<u32>,
+) -> [RoundedRectVertexRepr; 6] {
+  vertices(rect, style, glow, size)
+}
+
+fn vertices(
+  rect: TexRect,
+  style: RectStyle,
+  glow: f32,
+  size: PhysicalSize<u32>,
+) -> [RoundedRectVertexRepr; 6] {
+  let [top_left, bottom_right] = rect.as_array().map(|tex| tex.as_p_tex_coords());
+  let pixels = Vec2::new(size.width as f32, size.height as f32);
+  let top_left = Vec2::new(top_left.x, top_left.y);
+  let bottom_right = Vec2::new(bottom_right.x, bottom_right.y);
+  let half_size = ((bottom_right - top_left) * pixels / 2.0).abs();
+  let radius = style.corner_radius * 2.0 * half_size.min_element();
+  let center = (top_left + bottom_right) / 2.0;
+  //the quad grows by the glow on each side, the rectangle inside keeps its size
+  let margin = Vec2::splat(glow) / pixels * (bottom_right - top_left).signum();
+  let (top_left, bottom_right) = (top_left - margin, bottom_right + margin);
+  [
+    (top_left.x, top_left.y),
+    (top_left.x, bottom_right.y),
+    (bottom_right.x, bottom_right.y),
+    (top_left.x, top_left.y),
+    (bottom_right.x, bottom_right.y),
+    (bottom_right.x, top_left.y),
+  ]
+  .map(|(x, y)| RoundedRectVertexRepr {
+    position: Clip::Screen(PTexCoords::new(x, y).into()).as_p_clip().xyz(),
+    radius,
+    fill: style.fill,
+    border: style.border,
+    local: (Vec2::new(x, y) - center) * pixels,
+    half_size,
+    border_width: style.border_width,
+    glow,
+    _padding: Vec2::ZERO,
+  })
+}
+
+#[cfg(test)]
+mod test_rect_style {
+  use glam::{Vec2, Vec4};
+  use winit::dpi::PhysicalSize;
+
+  use crate::renderer::coordinates::{PTexCoords, TexRect};
+  use crate::renderer::rect_style::{glow_vertices, rounded_rect_vertices, RectStyle};
+
+  #[test]
+  fn test_vertices() {
+    let rect = TexRect::new(PTexCoords::new(0.25, 0.5), PTexCoords::new(0.75, 1.0));
+    let style = RectStyle::new(Vec4::ONE)
+      .rounded(0.25)
+      .bordered(Vec4::ZERO, 2.0);
+    let vertices = rounded_rect_vertices(rect, style, PhysicalSize::new(800, 400));
+
+    for vertex in vertices {
+      //400 by 200 pixels, the radius depends on the shorter side only
+      assert_eq!(Vec2::new(200.0, 100.0), vertex.half_size);
+      assert_eq!(50.0, vertex.radius);
+      assert_eq!(2.0, vertex.border_width);
+      assert_eq!(Vec2::new(200.0, 100.0), vertex.local.abs());
+    }
+    assert_eq!(Vec2::new(-200.0, -100.0), vertices[0].local);
+    assert_eq!(Vec2::new(200.0, 100.0), vertices[2].local);
+  }
+
+  #[test]
+  fn test_glow() {
+    let rect = TexRect::new(PTexCoords::new(0.25, 0.5), PTexCoords::new(0.75, 1.0));
+    let vertices = glow_vertices(
+      rect,
+      RectStyle::new(Vec4::ONE),
+      10.0,
+      PhysicalSize::new(800, 400),
+    );
+    for vertex in vertices {
+      assert_eq!(Vec2::new(200.0, 100.0), vertex.half_size);
+      assert!(vertex
+        .local
+        .abs()
+        .abs_diff_eq(Vec2::new(210.0, 110.0), 0.001));
+      assert_eq!(10.0, vertex.glow);
+    }
+  }
+
+  #[test]
+  fn test_style() {
+    let style = RectStyle::new(Vec4::ONE).rounded(2.0);
+    assert_eq!(0.5, style.corner_radius);
+    assert_eq!(Vec4::ONE, style.border);
+
+    let flashed = style.tinted(Vec4::ZERO, 1.0);
+    assert_eq!(Vec4::ZERO, flashed.fill);
+    assert_eq!(Vec4::ZERO, flashed.border);
+    assert_eq!(style.corner_radius, flashed.corner_radius);
+  }
+}
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use wgpu::{
+  BindGroup, BufferSlice, Color, CommandEncoder, Device, IndexFormat, LoadOp, Operations,
+  RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, StoreOp, Texture, TextureView,
+};
+
+use crate::renderer::frame_capture::{BufferSummary, DrawCall, FrameCapture, PassReadback};
+
+///The target passes draw into, registered under a name at the [`RenderGraph`]
+#[derive(Debug, Copy, Clone)]
+pub struct Attachment<'a> {
+  ///what is drawn to, the multisampled texture while msaa is enabled
+  pub view: &'a TextureView,
+  ///where multisampled results are resolved to
+  pub resolve_target: Option<&'a TextureView>,
+  ///the texture holding the final result, copied after every pass if set
+  pub readback: Option<&'a Texture>,
+}
+
+///The part of the attachment a pass draws into, in pixels
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Viewport {
+  pub x: f32,
+  pub y: f32,
+  pub width: f32,
+  pub height: f32,
+}
+
+#[derive(Debug)]
+pub struct Draw<'a> {
+  ///the name of the pipeline in frame captures
+  pub pipeline_name: &'static str,
+  pub pipeline: &'a RenderPipeline,
+  ///bound as group 0, for pipelines which sample a texture
+  pub bind_group: Option<&'a BindGroup>,
+  pub vertex_buffer: BufferSlice<'a>,
+  pub index_buffer: Option<(BufferSlice<'a>, IndexFormat)>,
+  ///the vertices, or indices if there is an index buffer
+  pub vertices: Range<u32>,
+  ///what the buffers contain, only needed while capturing
+  pub buffers: Vec<BufferSummary>,
+}
+
+///A render pass with the attachment it draws into and the passes it has to run after
+#[derive(Debug)]
+pub struct Pass<'a> {
+  name: &'static str,
+  attachment: &'static str,
+  after: Vec<&'static str>,
+  clear: Option<Color>,
+  viewport: Option<Viewport>,
+  draws: Vec<Draw<'a>>,
+}
+
+impl<'a> Pass<'a> {
+  ///A pass which keeps what is already in the attachment and draws on top of it
+  pub fn new(name: &'static str, attachment: &'static str) -> Self {
+    Self {
+      name,
+      attachment,
+      after: vec![],
+      clear: None,
+      viewport: None,
+      draws: vec![],
+    }
+  }
+
+  ///Runs the pass after another one. Passes which aren't part of the graph are ignored, so optional
+  /// passes can be left out without touching the ones after them.
+  pub fn after(mut self, pass: &'static str) -> Self {
+    self.after.push(pass);
+    self
+  }
+
+  ///Fills the attachment with the color before drawing
+  pub fn clearing(mut self, color: Color) -> Self {
+    self.clear = Some(color);
+    self
+  }
+
+  pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+    self.viewport = Some(viewport);
+    self
+  }
+
+  pub fn draw(&mut self, draw: Draw<'a>) {
+    self.draws.push(draw);
+  }
+}
+
+///The passes of a frame. They are encoded in the order their dependencies require, and passes
+/// without dependencies between them in the order they were added.
+#[derive(Debug, Default)]
+pub struct RenderGraph<'a> {
+  attachments: HashMap<&'static str, Attachment<'a>>,
+  passes: Vec<Pass<'a>>,
+}
+
+impl<'a> RenderGraph<'a> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn attach(&mut self, name: &'static str, attachment: Attachment<'a>) {
+    self.attachments.insert(name, attachment);
+  }
+
+  pub fn add_pass(&mut self, pass: Pass<'a>) {
+    self.passes.push(pass);
+  }
+
+  ///The indices of the passes in the order they are encoded
+  pub fn order(&self) -> Result<Vec<usize>, GraphError> {
+    let mut indices = HashMap::new();
+    for (index, pass) in self.passes.iter().enumerate() {
+      if indices.insert(pass.name, index).is_some() {
+        return Err(GraphError::DuplicatePass(pass.name));
+      }
+    }
+    let dependencies: Vec<Vec<usize>> = self
+      .passes
+      .iter()
+      .map(|pass| {
+        pass
+          .after
+          .iter()
+          .filter_map(|name| indices.get(name).copied())
+          .collect()
+      })
+      .collect();
+
+    let mut order = Vec::with_capacity(self.passes.len());
+    let mut done = vec![false; self.passes.len()];
+    while order.len() < self.passes.len() {
+      let next = (0..self.passes.len())
+        .find(|&index| !done[index] && dependencies[index].iter().all(|&dep| done[dep]));
+      let Some(next) = next else {
+        let cycle = (0..self.passes.len())
+          .filter(|&index| !done[index])
+          .map(|index| self.passes[index].name)
+          .collect();
+        return Err(GraphError::Cycle(cycle));
+      };
+      done[next] = true;
+      order.push(next);
+    }
+    Ok(order)
+  }
+
+  ///Encodes every pass into the encoder. Draws are added to the capture if there is one, and the
+  /// copies of attachments with a [readback](Attachment::readback) are returned, to be saved once the
+  /// encoder was submitted.
+  pub fn encode(
+    self,
+    device: &Device,
+    encoder: &mut CommandEncoder,
+    mut capture: Option<&mut FrameCapture>,
+  ) -> Result<Vec<PassReadback>, GraphError> {
+    let order = self.order()?;
+    let Self {
+      attachments,
+      passes,
+    } = self;
+    let mut passes: Vec<Option<Pass>> = passes.into_iter().map(Some).collect();
+    let mut readbacks = vec![];
+    for index in order {
+      let pass = passes[index].take().expect("every pass is ordered once");
+      let attachment = attachments.get(pass.attachment).copied().ok_or(
+        GraphError::MissingAttachment {
+          pass: pass.name,
+          attachment: pass.attachment,
+        },
+      )?;
+      let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+        label: Some(pass.name),
+        color_attachments: &[Some(RenderPassColorAttachment {
+          view: attachment.view,
+          resolve_target: attachment.resolve_target,
+          ops: Operations {
+            load: pass.clear.map_or(LoadOp::Load, LoadOp::Clear),
+            store: StoreOp::Store,
+          },
+        })],
+        ..Default::default()
+      });
+      if let Some(viewport) = pass.viewport {
+        render_pass.set_viewport(
+          viewport.x,
+          viewport.y,
+          viewport.width,
+          viewport.height,
+          0.0,
+          1.0,
+        );
+      }
+      for draw in pass.draws {
+        render_pass.set_pipeline(draw.pipeline);
+        if let Some(bind_group) = draw.bind_group {
+          render_pass.set_bind_group(0, bind_group, &[]);
+        }
+        render_pass.set_vertex_buffer(0, draw.vertex_buffer);
+        if let Some((index_buffer, format)) = draw.index_buffer {
+          render_pass.set_index_buffer(index_buffer, format);
+          render_pass.draw_indexed(draw.vertices.clone(), 0, 0..1);
+        } else {
+          render_pass.draw(draw.vertices.clone(), 0..1);
+        }
+        if let Some(capture) = capture.as_deref_mut() {
+          capture.draws.push(DrawCall {
+            pass: pass.name,
+            pipeline: draw.pipeline_name,
+            vertices: draw.vertices,
+            instances: 0..1,
+            buffers: draw.buffers,
+          });
+        }
+      }
+      drop(render_pass); //must be dropped before the encoder can be used again
+
+      if let Some(texture) = attachment.readback {
+        readbacks.push(PassReadback::record(device, encoder, texture, pass.name));
+      }
+    }
+    Ok(readbacks)
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GraphError {
+  DuplicatePass(&'static str),
+  ///the passes which wait for each other
+  Cycle(Vec<&'static str>),
+  MissingAttachment {
+    pass: &'static str,
+    attachment: &'static str,
+  },
+}
+
+impl Display for GraphError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      GraphError::DuplicatePass(pass) => write!(f, "there is more than one pass named {}", pass),
+      GraphError::Cycle(passes) => {
+        write!(f, "the passes {} wait for each other", passes.join(", "))
+      }
+      GraphError::MissingAttachment { pass, attachment } => write!(
+        f,
+        "{} draws into {}, which isn't attached",
+        pass, attachment
+      ),
+    }
+  }
+}
+
+impl Error for GraphError {}
+
+#[cfg(test)]
+mod test_render_graph {
+  use crate::renderer::render_graph::{GraphError, Pass, RenderGraph};
+
+  fn names(graph: &RenderGraph) -> Result<Vec<&'static str>, GraphError> {
+    graph.order().map(|order| {
+      order
+        .into_iter()
+        .map(|index| graph.passes[index].name)
+        .collect()
+    })
+  }
+
+  #[test]
+  fn test_order() {
+    let mut graph = RenderGraph::new();
+    graph.add_pass(Pass::new("Glow Pass", "Canvas").after("Widget Pass"));
+    graph.add_pass(Pass::new("Widget Pass", "Canvas").after("Scene Pass"));
+    graph.add_pass(Pass::new("Scene Pass", "Canvas"));
+    graph.add_pass(Pass::new("Debug Pass", "Canvas"));
+    assert_eq!(
+      Ok(vec!["Scene Pass", "Widget Pass", "Glow Pass", "Debug Pass"]),
+      names(&graph)
+    );
+  }
+
+  #[test]
+  fn test_missing_dependency_is_ignored() {
+    let mut graph = RenderGraph::new();
+    graph.add_pass(Pass::new("Scene Pass", "Canvas"));
+    graph.add_pass(Pass::new("Glow Pass", "Canvas").after("Widget Pass"));
+    assert_eq!(Ok(vec!["Scene Pass", "Glow Pass"]), names(&graph));
+  }
+
+  #[test]
+  fn test_invalid_graphs() {
+    let mut graph = RenderGraph::new();
+    graph.add_pass(Pass::new("Scene Pass", "Canvas"));
+    graph.add_pass(Pass::new("Scene Pass", "Canvas"));
+    assert_eq!(Err(GraphError::DuplicatePass("Scene Pass")), graph.order());
+
+    let mut graph = RenderGraph::new();
+    graph.add_pass(Pass::new("Scene Pass", "Canvas"));
+    graph.add_pass(Pass::new("Widget Pass", "Canvas").after("Glow Pass"));
+    graph.add_pass(Pass::new("Glow Pass", "Canvas").after("Widget Pass"));
+    assert_eq!(
+      Err(GraphError::Cycle(vec!["Widget Pass", "Glow Pass"])),
+      graph.order()
+    );
+  }
+}
@@ -0,0 +1,112 @@
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+use crate::renderer::coordinates::PTexCoords;
+
+///The margins in pixels at the edges of the window which are covered by notches, cutouts or system
+/// bars. The arena and the widgets are drawn within the remaining safe area, only the background
+/// fills the whole window.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct SafeArea {
+  pub top: u32,
+  pub right: u32,
+  pub bottom: u32,
+  pub left: u32,
+}
+
+impl SafeArea {
+  ///Computes the margins from the part of the window which isn't covered, given by the pixel
+  /// coordinates of its edges like android reports it. An empty content rect means that it isn't
+  /// known yet, which leaves no margins.
+  pub fn from_content_rect(
+    size: PhysicalSize<u32>,
+    left: i32,
+    top: i32,
+    right: i32,
+    bottom: i32,
+  ) -> Self {
+    if right <= left || bottom <= top {
+      return Self::default();
+    }
+    let clamp = |coordinate: i32, max: u32| (coordinate.max(0) as u32).min(max);
+    Self {
+      top: clamp(top, size.height),
+      right: size.width - clamp(right, size.width),
+      bottom: size.height - clamp(bottom, size.height),
+      left: clamp(left, size.width),
+    }
+  }
+
+  ///The top left corner and the size of the safe area in a window of the given size. The safe area
+  /// is at least one pixel large, even if the margins cover the whole window.
+  pub fn inner_rect(&self, size: PhysicalSize<u32>) -> (PhysicalPosition<u32>, PhysicalSize<u32>) {
+    let left = self.left.min(size.width.saturating_sub(1));
+    let top = self.top.min(size.height.saturating_sub(1));
+    let width = size.width.saturating_sub(left + self.right).max(1);
+    let height = size.height.saturating_sub(top + self.bottom).max(1);
+    (
+      PhysicalPosition::new(left, top),
+      PhysicalSize::new(width, height),
+    )
+  }
+
+  ///Where a position in the window lies, relative to the safe area
+  pub fn locate(&self, size: PhysicalSize<u32>, position: PhysicalPosition<f64>) -> PTexCoords {
+    let (top_left, inner_size) = self.inner_rect(size);
+    PTexCoords::new(
+      (position.x as f32 - top_left.x as f32) / inner_size.width as f32,
+      (position.y as f32 - top_left.y as f32) / inner_size.height as f32,
+    )
+  }
+}
+
+#[cfg(test)]
+mod test_safe_area {
+  use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+  use crate::renderer::coordinates::PTexCoords;
+  use crate::renderer::safe_area::SafeArea;
+
+  #[test]
+  fn test_from_content_rect() {
+    let size = PhysicalSize::new(1080, 2400);
+    //a notch at the top and the navigation bar at the bottom
+    let safe_area = SafeArea::from_content_rect(size, 0, 120, 1080, 2280);
+    assert_eq!(
+      SafeArea {
+        top: 120,
+        right: 0,
+        bottom: 120,
+        left: 0,
+      },
+      safe_area
+    );
+    assert_eq!(
+      (PhysicalPosition::new(0, 120), PhysicalSize::new(1080, 2160)),
+      safe_area.inner_rect(size)
+    );
+    assert_eq!(
+      PTexCoords::new(0.5, 0.5),
+      safe_area.locate(size, PhysicalPosition::new(540.0, 1200.0))
+    );
+
+    assert_eq!(
+      SafeArea::default(),
+      SafeArea::from_content_rect(size, 0, 0, 0, 0)
+    );
+  }
+
+  #[test]
+  fn test_covered_window() {
+    let size = PhysicalSize::new(100, 100);
+    let safe_area = SafeArea {
+      top: 80,
+      right: 0,
+      bottom: 80,
+      left: 0,
+    };
+    assert_eq!(
+      (PhysicalPosition::new(0, 80), PhysicalSize::new(100, 1)),
+      safe_area.inner_rect(size)
+    );
+  }
+}
@@ -0,0 +1,213 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::iter::once;
+use std::path::Path;
+
+use game_logic::render::EnemyMarking;
+use game_logic::solving::SolvableArena;
+use glam::Vec2;
+use image::{ImageError, RgbaImage};
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+  BufferUsages, CommandEncoderDescriptor, Device, Extent3d, Queue, TextureDescriptor,
+  TextureDimension, TextureFormat, TextureUsages, TextureViewDescriptor,
+};
+use winit::dpi::PhysicalSize;
+
+use crate::renderer::board::BoardGrid;
+use crate::renderer::coordinates::{Clip, PTexCoords, TexRect};
+use crate::renderer::readback::{Readback, ReadbackError};
+use crate::renderer::rect_style::rounded_rect_vertices;
+use crate::renderer::render_graph::{Attachment, Draw, Pass, RenderGraph};
+use crate::renderer::{background_color, board_style, GpuResources, CANVAS, SCENE_PASS};
+use crate::settings::Theme;
+use crate::shader::texture_shader::VertexRepr;
+
+///The width and height of thumbnails, in pixels
+pub const THUMBNAIL_SIZE: u32 = 128;
+
+///The name the thumbnail of an arena file is loaded as texture with
+pub fn thumbnail_texture(arena_file: &Path) -> String {
+  format!("Thumbnail {}", arena_file.display())
+}
+
+///Draws the enemies of the arena offscreen, the way the overlay shows them, and reads the picture
+/// back. Thumbnails are only rendered when an arena is loaded, so the pipelines aren't kept.
+pub fn render_thumbnail(
+  device: &Device,
+  queue: &Queue,
+  arena: &SolvableArena,
+  theme: Theme,
+  marking: EnemyMarking,
+) -> Result<RgbaImage, ReadbackError> {
+  let size = PhysicalSize::new(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+  let format = TextureFormat::Rgba8UnormSrgb;
+  let gpu = GpuResources::new(device, format, 1);
+  let texture = device.create_texture(&TextureDescriptor {
+    label: Some("Thumbnail Texture"),
+    size: Extent3d {
+      width: size.width,
+      height: size.height,
+      depth_or_array_layers: 1,
+    },
+    mip_level_count: 1,
+    sample_count: 1,
+    dimension: TextureDimension::D2,
+    format,
+    usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+    view_formats: &[],
+  });
+  let view = texture.create_view(&TextureViewDescriptor::default());
+
+  //the board is three times as wide as high, centered in the square
+  let board = BoardGrid::new(TexRect::new(
+    PTexCoords::new(0.03, 0.34),
+    PTexCoords::new(0.97, 0.66),
+  ));
+  let vertices: Vec<_> = board
+    .parts(arena.enemies.iter().copied(), None)
+    .into_iter()
+    .flat_map(|(rect, part)| rounded_rect_vertices(rect, board_style(theme, marking, part), size))
+    .collect();
+  let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
+    label: Some("Thumbnail Vertices"),
+    contents: bytemuck::cast_slice(&vertices),
+    usage: BufferUsages::VERTEX,
+  });
+
+  let mut graph = RenderGraph::new();
+  graph.attach(
+    CANVAS,
+    Attachment {
+      view: &view,
+      resolve_target: None,
+      readback: None,
+    },
+  );
+  let mut scene = Pass::new(SCENE_PASS, CANVAS).clearing(background_color(theme));
+  scene.draw(Draw {
+    pipeline_name: "widget",
+    pipeline: &gpu.widget_pipeline,
+    bind_group: None,
+    vertex_buffer: vertex_buffer.slice(..),
+    index_buffer: None,
+    vertices: 0..vertices.len() as u32,
+    buffers: vec![],
+  });
+  graph.add_pass(scene);
+
+  let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+    label: Some("Thumbnail Encoder"),
+  });
+  graph
+    .encode(device, &mut encoder, None)
+    .expect("the thumbnail graph is valid");
+  let readback = Readback::record(device, &mut encoder, &texture);
+  queue.submit(once(encoder.finish()));
+  readback.read(device)
+}
+
+///A quad showing a whole texture in the rectangle, drawn as triangle strip by the texture pipeline
+pub fn textured_vertices(rect: TexRect) -> [VertexRepr; 4] {
+  let [top_left, bottom_right] = rect.as_array().map(|tex| tex.as_p_tex_coords());
+  //counter-clockwise, so the front faces the viewer
+  [
+    (top_left, Vec2::new(0.0, 0.0)),
+    (
+      PTexCoords::new(top_left.x, bottom_right.y),
+      Vec2::new(0.0, 1.0),
+    ),
+    (
+      PTexCoords::new(bottom_right.x, top_left.y),
+      Vec2::new(1.0, 0.0),
+    ),
+    (bottom_right, Vec2::new(1.0, 1.0)),
+  ]
+  .map(|(corner, tex_coords)| VertexRepr {
+    position: Clip::screen(corner).as_p_clip().xyz(),
+    tex_coords,
+  })
+}
+
+#[derive(Debug)]
+pub enum ThumbnailError {
+  Readback(ReadbackError),
+  Image(ImageError),
+}
+
+impl From<ReadbackError> for ThumbnailError {
+  fn from(value: ReadbackError) -> Self {
+    Self::Readback(value)
+  }
+}
+
+impl From<ImageError> for ThumbnailError {
+  fn from(value: ImageError) -> Self {
+    Self::Image(value)
+  }
+}
+
+impl Display for ThumbnailError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ThumbnailError::Readback(e) => write!(f, "{}", e),
+      ThumbnailError::Image(e) => write!(f, "failed to save thumbnail: {}", e),
+    }
+  }
+}
+
+impl Error for ThumbnailError {}
+
+#[cfg(test)]
+mod test_thumbnail {
+  use game_logic::position::Position;
+  use game_logic::render::EnemyMarking;
+  use game_logic::solving::{Enemy, SolvableArena};
+  use glam::Vec3;
+
+  use crate::renderer::coordinates::{PTexCoords, TexRect};
+  use crate::renderer::readback::headless_device;
+  use crate::renderer::thumbnail::{render_thumbnail, textured_vertices, THUMBNAIL_SIZE};
+  use crate::settings::Theme;
+
+  #[test]
+  fn test_textured_vertices() {
+    let vertices = textured_vertices(TexRect::new(
+      PTexCoords::new(0.0, 0.0),
+      PTexCoords::new(0.5, 1.0),
+    ));
+    assert!(vertices[0]
+      .position
+      .abs_diff_eq(Vec3::new(-1.0, 1.0, 0.0), 1e-6));
+    assert!(vertices[3]
+      .position
+      .abs_diff_eq(Vec3::new(0.0, -1.0, 0.0), 1e-6));
+    assert_eq!([1.0, 1.0], vertices[3].tex_coords.to_array());
+  }
+
+  #[test]
+  fn test_render_thumbnail() {
+    let Some((device, queue)) = headless_device() else {
+      eprintln!("skipped: no gpu adapter available");
+      return;
+    };
+    let mut arena = SolvableArena::default();
+    arena.add(Enemy {
+      position: Position::at(3, 0).unwrap(),
+      required_attack: None,
+      hp: None,
+    });
+    let thumbnail = render_thumbnail(&device, &queue, &arena, Theme::Dark, EnemyMarking::Shapes)
+      .expect("rgba is readable");
+
+    assert_eq!((THUMBNAIL_SIZE, THUMBNAIL_SIZE), thumbnail.dimensions());
+    let background = thumbnail.get_pixel(2, 2);
+    assert_eq!(background, thumbnail.get_pixel(125, 125));
+    //the enemy in the top left cell and an empty cell of the same row
+    let enemy = thumbnail.get_pixel(9, 49);
+    let cell = thumbnail.get_pixel(69, 49);
+    assert_ne!(background, enemy);
+    assert_ne!(background, cell);
+    assert_ne!(enemy, cell);
+  }
+}
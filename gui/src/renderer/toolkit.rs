@@ -0,0 +1,266 @@
+use crate::renderer::coordinates::{PTexCoords, TexRect};
+use crate::renderer::widgets::{bar_rect, tap_widget, LabeledWidget, Widget, WidgetPart};
+
+///Identifies the elements of a [`Panel`] which can be tapped
+pub type ElementId = &'static str;
+
+///A piece of a retained ui, laid out by the [`Panel`] containing it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Element {
+  ///takes up space without being drawn, its text is only shown in the status for now
+  Label(String),
+  Button {
+    id: ElementId,
+    label: String,
+  },
+  ///a toggle, slider or choice the user can change, drawn like a row of the settings
+  Control {
+    id: ElementId,
+    control: LabeledWidget,
+  },
+  Panel(Panel),
+}
+
+impl Element {
+  pub fn label<S>(text: S) -> Self
+  where
+    S: ToString,
+  {
+    Element::Label(text.to_string())
+  }
+
+  pub fn button<S>(id: ElementId, label: S) -> Self
+  where
+    S: ToString,
+  {
+    Element::Button {
+      id,
+      label: label.to_string(),
+    }
+  }
+
+  pub fn toggle<S>(id: ElementId, label: S, value: bool) -> Self
+  where
+    S: ToString,
+  {
+    Self::control(id, label, Widget::Toggle { value })
+  }
+
+  pub fn slider<S>(id: ElementId, label: S, value: f32, min: f32, max: f32, step: f32) -> Self
+  where
+    S: ToString,
+  {
+    Self::control(
+      id,
+      label,
+      Widget::Slider {
+        value,
+        min,
+        max,
+        step,
+      },
+    )
+  }
+
+  pub fn control<S>(id: ElementId, label: S, widget: Widget) -> Self
+  where
+    S: ToString,
+  {
+    Element::Control {
+      id,
+      control: LabeledWidget::new(label, widget),
+    }
+  }
+
+  pub fn id(&self) -> Option<ElementId> {
+    match self {
+      Element::Button { id, .. } | Element::Control { id, .. } => Some(id),
+      Element::Label(_) | Element::Panel(_) => None,
+    }
+  }
+}
+
+///How a [`Panel`] arranges its children
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Direction {
+  ///below each other
+  Vertical,
+  ///next to each other
+  Horizontal,
+}
+
+///Divides its area evenly between its children, which may be panels themselves
+#[derive(Debug, Clone, PartialEq)]
+pub struct Panel {
+  direction: Direction,
+  children: Vec<Element>,
+}
+
+impl Panel {
+  ///The share of a child's space left empty between two children
+  const SPACING: f32 = 0.2;
+
+  pub fn new(direction: Direction, children: Vec<Element>) -> Self {
+    Self {
+      direction,
+      children,
+    }
+  }
+
+  pub fn vertical(children: Vec<Element>) -> Self {
+    Self::new(Direction::Vertical, children)
+  }
+
+  pub fn horizontal(children: Vec<Element>) -> Self {
+    Self::new(Direction::Horizontal, children)
+  }
+
+  ///The area of a child, the space after it up to the next one is left empty
+  fn child_rect(&self, area: TexRect, index: usize) -> TexRect {
+    let [top_left, bottom_right] = area.as_array().map(|tex| tex.as_p_tex_coords());
+    let count = self.children.len() as f32;
+    match self.direction {
+      Direction::Vertical => {
+        let height = (bottom_right.y - top_left.y) / count;
+        let top = top_left.y + index as f32 * height;
+        TexRect::new(
+          PTexCoords::new(top_left.x, top),
+          PTexCoords::new(bottom_right.x, top + height * (1.0 - Self::SPACING)),
+        )
+      }
+      Direction::Horizontal => {
+        let width = (bottom_right.x - top_left.x) / count;
+        let left = top_left.x + index as f32 * width;
+        TexRect::new(
+          PTexCoords::new(left, top_left.y),
+          PTexCoords::new(left + width * (1.0 - Self::SPACING), bottom_right.y),
+        )
+      }
+    }
+  }
+
+  ///Every element with the area it covers in the panel's area, panels before their children
+  pub fn layout(&self, area: TexRect) -> Vec<(TexRect, &Element)> {
+    let mut layout = vec![];
+    for (index, child) in self.children.iter().enumerate() {
+      let rect = self.child_rect(area, index);
+      layout.push((rect, child));
+      if let Element::Panel(panel) = child {
+        layout.extend(panel.layout(rect));
+      }
+    }
+    layout
+  }
+
+  ///Where the element with the id is drawn
+  pub fn rect_of(&self, area: TexRect, id: ElementId) -> Option<TexRect> {
+    self
+      .layout(area)
+      .into_iter()
+      .find(|(_, element)| element.id() == Some(id))
+      .map(|(rect, _)| rect)
+  }
+
+  ///The rectangles to draw, back to front. Labels and panels aren't drawn.
+  pub fn parts(&self, area: TexRect) -> Vec<(TexRect, WidgetPart)> {
+    let mut parts = vec![];
+    for (rect, element) in self.layout(area) {
+      match element {
+        Element::Button { .. } => parts.push((rect, WidgetPart::Row { focused: false })),
+        Element::Control { control, .. } => {
+          parts.push((rect, WidgetPart::Row { focused: false }));
+          parts.push((bar_rect(rect, &control.widget), WidgetPart::Bar));
+        }
+        Element::Label(_) | Element::Panel(_) => {}
+      }
+    }
+    parts
+  }
+
+  ///Taps the element at the point: buttons are pressed and controls change like the rows of the
+  /// settings do. Returns the id of the tapped element.
+  pub fn tap(&mut self, area: TexRect, point: PTexCoords) -> Option<ElementId> {
+    let index =
+      (0..self.children.len()).find(|&index| self.child_rect(area, index).contains(point))?;
+    let rect = self.child_rect(area, index);
+    match &mut self.children[index] {
+      Element::Button { id, .. } => Some(id),
+      Element::Control { id, control } => {
+        tap_widget(rect, &mut control.widget, point);
+        Some(id)
+      }
+      Element::Panel(panel) => panel.tap(rect, point),
+      Element::Label(_) => None,
+    }
+  }
+
+  ///The control with the id, to read its value
+  pub fn control(&self, id: ElementId) -> Option<&LabeledWidget> {
+    self.children.iter().find_map(|child| match child {
+      Element::Control {
+        id: control_id,
+        control,
+      } if *control_id == id => Some(control),
+      Element::Panel(panel) => panel.control(id),
+      _ => None,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test_toolkit {
+  use crate::renderer::coordinates::{PTexCoords, TexRect};
+  use crate::renderer::toolkit::{Element, Panel};
+  use crate::renderer::widgets::WidgetPart;
+
+  fn area() -> TexRect {
+    TexRect::new(PTexCoords::new(0.0, 0.0), PTexCoords::new(1.0, 1.0))
+  }
+
+  #[test]
+  fn test_tap() {
+    let mut panel = Panel::vertical(vec![
+      Element::label("playback"),
+      Element::Panel(Panel::horizontal(vec![
+        Element::button("back", "back"),
+        Element::button("next", "next"),
+      ])),
+      Element::slider("speed", "speed", 1.0, 0.0, 2.0, 0.5),
+    ]);
+
+    assert_eq!(None, panel.tap(area(), PTexCoords::new(0.5, 0.1)));
+    assert_eq!(Some("back"), panel.tap(area(), PTexCoords::new(0.1, 0.5)));
+    assert_eq!(Some("next"), panel.tap(area(), PTexCoords::new(0.6, 0.5)));
+    //between the buttons
+    assert_eq!(None, panel.tap(area(), PTexCoords::new(0.45, 0.5)));
+
+    assert_eq!(Some("speed"), panel.tap(area(), PTexCoords::new(1.0, 0.7)));
+    assert_eq!("2", panel.control("speed").unwrap().widget.value_text());
+  }
+
+  #[test]
+  fn test_layout() {
+    let panel = Panel::horizontal(vec![
+      Element::toggle("sound", "sound", true),
+      Element::button("close", "close"),
+    ]);
+    let close = panel.rect_of(area(), "close").unwrap();
+    assert!(close.contains(PTexCoords::new(0.7, 0.5)));
+    assert!(!close.contains(PTexCoords::new(0.3, 0.5)));
+    assert_eq!(None, panel.rect_of(area(), "open"));
+
+    let parts: Vec<WidgetPart> = panel
+      .parts(area())
+      .into_iter()
+      .map(|(_, part)| part)
+      .collect();
+    assert_eq!(
+      vec![
+        WidgetPart::Row { focused: false },
+        WidgetPart::Bar,
+        WidgetPart::Row { focused: false }
+      ],
+      parts
+    );
+  }
+}
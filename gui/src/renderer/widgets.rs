@@ -0,0 +1,305 @@
+use crate::renderer::coordinates::{PTexCoords, TexRect};
+
+///A value the user can change, drawn as a row with a bar showing the value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Widget {
+  Toggle {
+    value: bool,
+  },
+  Slider {
+    value: f32,
+    min: f32,
+    max: f32,
+    step: f32,
+  },
+  ///one of several options, like an enum
+  Choice {
+    options: Vec<String>,
+    selected: usize,
+  },
+}
+
+impl Widget {
+  ///Changes the value by a number of steps, negative steps go down. Toggles flip on any step,
+  /// choices wrap around.
+  pub fn step(&mut self, steps: i32) {
+    match self {
+      Widget::Toggle { value } => *value ^= steps % 2 != 0,
+      Widget::Slider {
+        value,
+        min,
+        max,
+        step,
+      } => *value = (*value + steps as f32 * *step).clamp(*min, *max),
+      Widget::Choice { options, selected } => {
+        *selected = (*selected as i32 + steps).rem_euclid(options.len() as i32) as usize
+      }
+    }
+  }
+
+  ///What pressing enter or tapping without a position does
+  pub fn activate(&mut self) {
+    if !matches!(self, Widget::Slider { .. }) {
+      self.step(1);
+    }
+  }
+
+  ///Sets the value from a position along the bar, where 0.0 is the minimum and 1.0 the maximum
+  pub fn set_fill(&mut self, fill: f32) {
+    let fill = fill.clamp(0.0, 1.0);
+    match self {
+      Widget::Toggle { value } => *value = fill >= 0.5,
+      Widget::Slider {
+        value,
+        min,
+        max,
+        step,
+      } => {
+        let steps = ((*max - *min) * fill / *step).round();
+        *value = (*min + steps * *step).clamp(*min, *max);
+      }
+      Widget::Choice { options, selected } => {
+        *selected = ((fill * options.len() as f32) as usize).min(options.len() - 1)
+      }
+    }
+  }
+
+  ///How much of the bar is filled, from 0.0 to 1.0
+  pub fn fill(&self) -> f32 {
+    match self {
+      Widget::Toggle { value } => *value as u8 as f32,
+      Widget::Slider {
+        value, min, max, ..
+      } => (value - min) / (max - min),
+      Widget::Choice { options, selected } => (*selected + 1) as f32 / options.len() as f32,
+    }
+  }
+
+  pub fn value_text(&self) -> String {
+    match self {
+      Widget::Toggle { value } => if *value { "on" } else { "off" }.to_string(),
+      Widget::Slider { value, .. } => format!("{}", value),
+      Widget::Choice { options, selected } => options[*selected].clone(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabeledWidget {
+  pub label: String,
+  pub widget: Widget,
+}
+
+impl LabeledWidget {
+  pub fn new<S>(label: S, widget: Widget) -> Self
+  where
+    S: ToString,
+  {
+    Self {
+      label: label.to_string(),
+      widget,
+    }
+  }
+}
+
+///The parts a widget row is drawn of
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum WidgetPart {
+  Row { focused: bool },
+  Bar,
+}
+
+///Widgets in rows below each other, one of which has the keyboard focus
+#[derive(Debug, Clone, PartialEq)]
+pub struct WidgetList {
+  rows: Vec<LabeledWidget>,
+  focused: usize,
+  ///where the rows are drawn
+  area: TexRect,
+}
+
+impl WidgetList {
+  ///The share of the row height between two rows
+  const SPACING: f32 = 0.2;
+  ///The highest a row gets, relative to the height of the area
+  const MAX_ROW_HEIGHT: f32 = 0.12;
+
+  ///## Panics
+  /// If there are no rows
+  pub fn new(rows: Vec<LabeledWidget>) -> Self {
+    assert!(!rows.is_empty(), "a widget list needs rows");
+    Self {
+      rows,
+      focused: 0,
+      area: TexRect::new(PTexCoords::new(0.1, 0.1), PTexCoords::new(0.9, 0.9)),
+    }
+  }
+
+  pub fn with_area<R>(mut self, area: R) -> Self
+  where
+    R: Into<TexRect>,
+  {
+    self.area = area.into();
+    self
+  }
+
+  pub fn rows(&self) -> &[LabeledWidget] {
+    &self.rows
+  }
+
+  pub fn focused(&self) -> usize {
+    self.focused
+  }
+
+  pub fn focused_row(&self) -> &LabeledWidget {
+    &self.rows[self.focused]
+  }
+
+  pub fn focused_widget_mut(&mut self) -> &mut Widget {
+    &mut self.rows[self.focused].widget
+  }
+
+  ///Focuses a row, clamped to the existing ones
+  pub fn focus(&mut self, row: usize) {
+    self.focused = row.min(self.rows.len() - 1);
+  }
+
+  ///Moves the focus by a number of rows, wrapping around at the ends
+  pub fn move_focus(&mut self, rows: i32) {
+    self.focused = (self.focused as i32 + rows).rem_euclid(self.rows.len() as i32) as usize;
+  }
+
+  pub fn row_rect(&self, row: usize) -> TexRect {
+    let [top_left, bottom_right] = self.area.as_array().map(|tex| tex.as_p_tex_coords());
+    let row_height =
+      ((bottom_right.y - top_left.y) / self.rows.len() as f32).min(Self::MAX_ROW_HEIGHT);
+    let top = top_left.y + row as f32 * row_height;
+    TexRect::new(
+      PTexCoords::new(top_left.x, top),
+      PTexCoords::new(bottom_right.x, top + row_height * (1.0 - Self::SPACING)),
+    )
+  }
+
+  ///The bar of a row which shows the value of its widget, see [`bar_rect`]
+  pub fn bar_rect(&self, row: usize) -> TexRect {
+    bar_rect(self.row_rect(row), &self.rows[row].widget)
+  }
+
+  pub fn row_at(&self, point: PTexCoords) -> Option<usize> {
+    (0..self.rows.len()).find(|&row| self.row_rect(row).contains(point))
+  }
+
+  ///Focuses the row at the point and taps its widget, see [`tap_widget`]. Returns whether a row was
+  /// hit.
+  pub fn tap(&mut self, point: PTexCoords) -> bool {
+    let Some(row) = self.row_at(point) else {
+      return false;
+    };
+    self.focused = row;
+    tap_widget(self.row_rect(row), &mut self.rows[row].widget, point);
+    true
+  }
+
+  ///The rectangles to draw for a row, back to front
+  pub fn row_parts(&self, row: usize) -> [(TexRect, WidgetPart); 2] {
+    [
+      (
+        self.row_rect(row),
+        WidgetPart::Row {
+          focused: row == self.focused,
+        },
+      ),
+      (self.bar_rect(row), WidgetPart::Bar),
+    ]
+  }
+}
+
+///The bar showing the value of the widget drawn in the row, filling the right half of the row
+pub fn bar_rect(row: TexRect, widget: &Widget) -> TexRect {
+  let [top_left, bottom_right] = row.as_array().map(|tex| tex.as_p_tex_coords());
+  let start = (top_left.x + bottom_right.x) / 2.0;
+  TexRect::new(
+    PTexCoords::new(start, top_left.y),
+    PTexCoords::new(
+      start + (bottom_right.x - start) * widget.fill(),
+      bottom_right.y,
+    ),
+  )
+}
+
+///Tapping the right half of the row sets the value of its widget to where the bar was tapped,
+/// tapping the left half activates the widget
+pub fn tap_widget(row: TexRect, widget: &mut Widget, point: PTexCoords) {
+  let [top_left, bottom_right] = row.as_array().map(|tex| tex.as_p_tex_coords());
+  let start = (top_left.x + bottom_right.x) / 2.0;
+  if point.x < start {
+    widget.activate();
+  } else {
+    widget.set_fill((point.x - start) / (bottom_right.x - start));
+  }
+}
+
+#[cfg(test)]
+mod test_widgets {
+  use crate::renderer::coordinates::{PTexCoords, TexRect};
+  use crate::renderer::widgets::{LabeledWidget, Widget, WidgetList};
+
+  fn slider() -> Widget {
+    Widget::Slider {
+      value: 1.0,
+      min: 0.5,
+      max: 2.0,
+      step: 0.5,
+    }
+  }
+
+  #[test]
+  fn test_step() {
+    let mut widget = slider();
+    widget.step(5);
+    assert_eq!("2", widget.value_text());
+    widget.step(-1);
+    assert_eq!(2.0 / 3.0, widget.fill());
+
+    let mut choice = Widget::Choice {
+      options: vec!["a".to_string(), "b".to_string()],
+      selected: 0,
+    };
+    choice.step(-1);
+    assert_eq!("b", choice.value_text());
+    choice.activate();
+    assert_eq!("a", choice.value_text());
+
+    let mut toggle = Widget::Toggle { value: false };
+    toggle.step(-1);
+    assert_eq!("on", toggle.value_text());
+  }
+
+  #[test]
+  fn test_navigation() {
+    let mut list = WidgetList::new(vec![
+      LabeledWidget::new("toggle", Widget::Toggle { value: false }),
+      LabeledWidget::new("slider", slider()),
+    ])
+    .with_area(TexRect::new(
+      PTexCoords::new(0.0, 0.0),
+      PTexCoords::new(1.0, 0.2),
+    ));
+    list.move_focus(-1);
+    assert_eq!("slider", list.focused_row().label);
+    list.move_focus(1);
+    assert_eq!(0, list.focused());
+
+    //the left half of the second row activates, which does nothing for sliders
+    assert!(list.tap(PTexCoords::new(0.25, 0.15)));
+    assert_eq!(1, list.focused());
+    assert_eq!(slider(), list.rows()[1].widget);
+    //the far right end of the bar sets the maximum
+    assert!(list.tap(PTexCoords::new(1.0, 0.15)));
+    assert_eq!("2", list.focused_row().widget.value_text());
+    //between the rows
+    assert!(!list.tap(PTexCoords::new(0.5, 0.09)));
+    assert!(list.tap(PTexCoords::new(0.1, 0.05)));
+    assert_eq!("on", list.focused_row().widget.value_text());
+  }
+}
@@ -1,9 +1,23 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::io::Cursor;
+use std::thread;
+use std::thread::JoinHandle;
 
-use image::ImageError;
+use image::error::{ParameterError, ParameterErrorKind};
+use image::imageops::FilterType;
+use image::{imageops, ImageError, ImageReader, RgbaImage};
+use wgpu::util::{DeviceExt, TextureDataOrder};
+use wgpu::{
+  Device, Extent3d, Queue, Texture, TextureDescriptor, TextureDimension, TextureFormat,
+  TextureUsages, TextureView, TextureViewDescriptor,
+};
 use winit::window::{BadIcon, Icon};
 
+///The size of window icons at a scale factor of 1.0
+pub const ICON_SIZE: u32 = 32;
+
 pub fn load_icon(bytes: &[u8]) -> Result<Icon, IconError> {
   let rgba = image::load_from_memory(bytes)?.to_rgba8();
   let (width, height) = rgba.dimensions();
@@ -11,6 +25,248 @@ pub fn load_icon(bytes: &[u8]) -> Result<Icon, IconError> {
   Ok(icon)
 }
 
+///Loads the variant of an icon which fits the scale factor of the display best
+pub fn load_scaled_icon(variants: &[&[u8]], scale_factor: f64) -> Result<Icon, IconError> {
+  let wanted_size = (ICON_SIZE as f64 * scale_factor).round() as u32;
+  load_icon(pick_variant(variants, wanted_size)?)
+}
+
+///Picks the variant of an image which fits a wanted size best: the smallest one which is at least
+/// as big, or the biggest one if all are smaller. Only the image headers get decoded.
+pub fn pick_variant<'a>(variants: &[&'a [u8]], wanted_size: u32) -> Result<&'a [u8], ImageError> {
+  let mut sized_variants = variants
+    .iter()
+    .map(|&bytes| {
+      let (width, height) = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .into_dimensions()?;
+      Ok((width.max(height), bytes))
+    })
+    .collect::<Result<Vec<_>, ImageError>>()?;
+  sized_variants.sort_by_key(|&(size, _)| size);
+  sized_variants
+    .iter()
+    .find(|&&(size, _)| size >= wanted_size)
+    .or(sized_variants.last())
+    .map(|&(_, bytes)| bytes)
+    .ok_or(ImageError::Parameter(ParameterError::from_kind(
+      ParameterErrorKind::Generic("no image variants given".to_string()),
+    )))
+}
+
+///The number of mip levels of a texture, down to a size of 1x1
+pub fn mip_level_count(width: u32, height: u32) -> u32 {
+  u32::BITS - width.max(height).max(1).leading_zeros()
+}
+
+///Generates every mip level of an image, starting with the image itself
+pub fn generate_mipmaps(image: &RgbaImage) -> Vec<RgbaImage> {
+  let mut levels = vec![image.clone()];
+  for level in 1..mip_level_count(image.width(), image.height()) {
+    let previous = levels.last().expect("first level is the image");
+    let width = (image.width() >> level).max(1);
+    let height = (image.height() >> level).max(1);
+    levels.push(imageops::resize(
+      previous,
+      width,
+      height,
+      FilterType::Triangle,
+    ));
+  }
+  levels
+}
+
+///A texture on the gpu together with its default view
+#[derive(Debug)]
+pub struct LoadedTexture {
+  pub texture: Texture,
+  pub view: TextureView,
+}
+
+///Uploads an image as srgb texture including all its mip levels
+pub fn upload_texture(
+  device: &Device,
+  queue: &Queue,
+  label: &str,
+  image: &RgbaImage,
+) -> LoadedTexture {
+  upload_mip_levels(device, queue, label, &generate_mipmaps(image))
+}
+
+///Uploads the mip levels of an image as srgb texture, starting with the full sized one
+pub fn upload_mip_levels(
+  device: &Device,
+  queue: &Queue,
+  label: &str,
+  levels: &[RgbaImage],
+) -> LoadedTexture {
+  let (width, height) = levels.first().expect("no mip levels given").dimensions();
+  let data: Vec<u8> = levels
+    .iter()
+    .flat_map(|level| level.as_raw())
+    .copied()
+    .collect();
+  let texture = device.create_texture_with_data(
+    queue,
+    &TextureDescriptor {
+      label: Some(label),
+      size: Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+      },
+      mip_level_count: levels.len() as u32,
+      sample_count: 1,
+      dimension: TextureDimension::D2,
+      format: TextureFormat::Rgba8UnormSrgb,
+      usage: TextureUsages::TEXTURE_BINDING,
+      view_formats: &[],
+    },
+    TextureDataOrder::LayerMajor,
+    &data,
+  );
+  let view = texture.create_view(&TextureViewDescriptor::default());
+  LoadedTexture { texture, view }
+}
+
+///A texture which should be loaded, given as all variants of the image
+#[derive(Debug, Copy, Clone)]
+pub struct TextureRequest {
+  pub name: &'static str,
+  pub variants: &'static [&'static [u8]],
+  pub wanted_size: u32,
+}
+
+impl TextureRequest {
+  ///Decodes the best fitting variant and generates its mip levels, which is the expensive part of
+  /// loading a texture
+  pub fn decode(&self) -> Result<DecodedTexture, TextureLoadError> {
+    let decode = || {
+      let image = image::load_from_memory(pick_variant(self.variants, self.wanted_size)?)?;
+      Ok(generate_mipmaps(&image.to_rgba8()))
+    };
+    decode()
+      .map(|mip_levels| DecodedTexture {
+        name: self.name.to_string(),
+        mip_levels,
+      })
+      .map_err(|error| TextureLoadError {
+        name: self.name.to_string(),
+        error,
+      })
+  }
+}
+
+///A texture which is ready to be uploaded to the gpu
+#[derive(Debug)]
+pub struct DecodedTexture {
+  pub name: String,
+  pub mip_levels: Vec<RgbaImage>,
+}
+
+///Decodes the requested textures one after another on a separate thread, so the event loop doesn't
+/// freeze meanwhile. Each result is passed to `on_decoded`.
+pub fn decode_in_background<F>(requests: Vec<TextureRequest>, on_decoded: F) -> JoinHandle<()>
+where
+  F: Fn(Result<DecodedTexture, TextureLoadError>) + Send + 'static,
+{
+  thread::spawn(move || {
+    for request in requests {
+      on_decoded(request.decode());
+    }
+  })
+}
+
+///Keeps loaded textures by name, so every texture is decoded and uploaded only once
+#[derive(Debug, Default)]
+pub struct ResourceManager {
+  textures: HashMap<String, LoadedTexture>,
+  ///the decoded mip levels of every texture, kept to upload them again after the device was lost
+  mip_levels: HashMap<String, Vec<RgbaImage>>,
+}
+
+impl ResourceManager {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  ///Loads the variant of a texture which fits the wanted size best, unless a texture with the name
+  /// is loaded already
+  pub fn load_texture(
+    &mut self,
+    device: &Device,
+    queue: &Queue,
+    name: &str,
+    variants: &[&[u8]],
+    wanted_size: u32,
+  ) -> Result<&LoadedTexture, ImageError> {
+    if !self.textures.contains_key(name) {
+      let image = image::load_from_memory(pick_variant(variants, wanted_size)?)?.to_rgba8();
+      let mip_levels = generate_mipmaps(&image);
+      let texture = upload_mip_levels(device, queue, name, &mip_levels);
+      self.textures.insert(name.to_string(), texture);
+      self.mip_levels.insert(name.to_string(), mip_levels);
+    }
+    Ok(&self.textures[name])
+  }
+
+  ///Uploads a texture decoded in the background. A texture which failed to load is replaced by a
+  /// magenta placeholder, so whatever waits for it can go on.
+  pub fn insert_decoded(
+    &mut self,
+    device: &Device,
+    queue: &Queue,
+    decoded: Result<DecodedTexture, TextureLoadError>,
+  ) -> &LoadedTexture {
+    let (name, mip_levels) = match decoded {
+      Ok(DecodedTexture { name, mip_levels }) => (name, mip_levels),
+      Err(e) => {
+        eprintln!("{}", e);
+        let placeholder = RgbaImage::from_pixel(1, 1, [255, 0, 255, 255].into());
+        (e.name, vec![placeholder])
+      }
+    };
+    let texture = upload_mip_levels(device, queue, &name, &mip_levels);
+    self.mip_levels.insert(name.clone(), mip_levels);
+    self.textures.entry(name).insert_entry(texture).into_mut()
+  }
+
+  ///Uploads all textures again to a new device, like after the old one was lost
+  pub fn reupload(&mut self, device: &Device, queue: &Queue) {
+    for (name, mip_levels) in &self.mip_levels {
+      let texture = upload_mip_levels(device, queue, name, mip_levels);
+      self.textures.insert(name.clone(), texture);
+    }
+  }
+
+  pub fn texture(&self, name: &str) -> Option<&LoadedTexture> {
+    self.textures.get(name)
+  }
+
+  pub fn is_loaded(&self, name: &str) -> bool {
+    self.textures.contains_key(name)
+  }
+
+  pub fn unload(&mut self, name: &str) -> Option<LoadedTexture> {
+    self.mip_levels.remove(name);
+    self.textures.remove(name)
+  }
+}
+
+#[derive(Debug)]
+pub struct TextureLoadError {
+  pub name: String,
+  pub error: ImageError,
+}
+
+impl Display for TextureLoadError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "failed to load texture {}: {}", self.name, self.error)
+  }
+}
+
+impl Error for TextureLoadError {}
+
 #[derive(Debug)]
 pub enum IconError {
   Image(ImageError),
@@ -97,3 +353,66 @@ mod test_include {
     assert_eq!("foo bar", include_resource_str!(test / test_data.txt));
   }
 }
+
+#[cfg(test)]
+mod test_resolution {
+  use std::io::Cursor;
+
+  use image::{ImageFormat, RgbaImage};
+
+  use crate::resources::{generate_mipmaps, mip_level_count, pick_variant, TextureRequest};
+
+  fn png(size: u32) -> Vec<u8> {
+    let mut bytes = vec![];
+    RgbaImage::new(size, size)
+      .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+      .expect("failed to encode png");
+    bytes
+  }
+
+  #[test]
+  fn test_pick_variant() {
+    let (small, medium, large) = (png(16), png(32), png(64));
+    let variants = [&large[..], &small[..], &medium[..]];
+    assert_eq!(&medium[..], pick_variant(&variants, 20).unwrap());
+    assert_eq!(&medium[..], pick_variant(&variants, 32).unwrap());
+    assert_eq!(&large[..], pick_variant(&variants, 128).unwrap());
+    assert!(pick_variant(&[], 32).is_err());
+  }
+
+  #[test]
+  fn test_generate_mipmaps() {
+    assert_eq!(1, mip_level_count(1, 1));
+    assert_eq!(7, mip_level_count(64, 20));
+
+    let levels = generate_mipmaps(&RgbaImage::new(64, 20));
+    assert_eq!(7, levels.len());
+    assert_eq!((32, 10), levels[1].dimensions());
+    assert_eq!((1, 1), levels[6].dimensions());
+  }
+  #[test]
+  fn test_decode_texture() {
+    let decoded = TextureRequest {
+      name: "logo",
+      variants: &[include_resource_bytes!(texture / steptech_logo.png)],
+      wanted_size: 64,
+    }
+    .decode()
+    .expect("valid texture");
+    assert_eq!("logo", decoded.name);
+    let (width, height) = decoded.mip_levels[0].dimensions();
+    assert_eq!(
+      mip_level_count(width, height) as usize,
+      decoded.mip_levels.len()
+    );
+
+    let error = TextureRequest {
+      name: "broken",
+      variants: &[b"no image"],
+      wanted_size: 64,
+    }
+    .decode()
+    .expect_err("invalid texture");
+    assert_eq!("broken", error.name);
+  }
+}
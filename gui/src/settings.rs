@@ -0,0 +1,369 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use game_logic::data_dir::{create_parent, data_file};
+use game_logic::render::EnemyMarking;
+use game_logic::solving::move_filter::MoveFilter;
+use game_logic::solving::{Comparator, Strategy};
+
+///The environment variable pointing to the file the settings are stored in
+pub const SETTINGS_VAR: &str = "TOK_SETTINGS";
+///The file in the [data directory](game_logic::data_dir::data_dir) the settings are stored in if
+/// [`SETTINGS_VAR`] isn't set
+pub const DEFAULT_SETTINGS_FILE: &str = "settings.txt";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum Theme {
+  #[default]
+  Dark,
+  Light,
+}
+
+impl Theme {
+  pub const ALL: [Theme; 2] = [Theme::Dark, Theme::Light];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Theme::Dark => "dark",
+      Theme::Light => "light",
+    }
+  }
+
+  pub fn by_name(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|theme| theme.name() == name)
+  }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum Language {
+  #[default]
+  English,
+  German,
+}
+
+impl Language {
+  pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+  ///The language code used in the settings file
+  pub fn code(&self) -> &'static str {
+    match self {
+      Language::English => "en",
+      Language::German => "de",
+    }
+  }
+
+  pub fn by_code(code: &str) -> Option<Self> {
+    Self::ALL
+      .into_iter()
+      .find(|language| language.code() == code)
+  }
+
+  ///The name of the language in the language itself
+  pub fn native_name(&self) -> &'static str {
+    match self {
+      Language::English => "English",
+      Language::German => "Deutsch",
+    }
+  }
+}
+
+///What the user can configure, see [`SettingsStore`] for how it is persisted
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Settings {
+  ///smooths edges by rendering with 4 samples per pixel
+  pub msaa: bool,
+  pub theme: Theme,
+  ///how enemies needing different attacks are told apart, shapes don't rely on colors
+  pub enemy_marking: EnemyMarking,
+  ///how fast animations play, where 1.0 is normal speed
+  pub animation_speed: f32,
+  ///which strategy searches for solutions, see [`Strategy::all`]
+  pub solver_strategy: Strategy,
+  ///which of the solutions with the fewest moves is the best one, see [`Comparator::all`]
+  pub solution_order: Comparator,
+  ///moves the solver must not use
+  pub move_filter: MoveFilter,
+  pub language: Language,
+  ///hides the system bars on android (immersive mode), borderless fullscreen on desktop
+  pub fullscreen: bool,
+  ///the seconds the game gives for the puzzle of an arena, counted down when an arena is loaded.
+  /// None disables the timer, see [`Settings::PUZZLE_TIMES`].
+  pub puzzle_timer: Option<u32>,
+  ///keeps the window above other windows like the game, only on desktop
+  pub always_on_top: bool,
+  ///enlarges the buttons and the overlay, see [`Settings::UI_SCALES`]
+  pub ui_scale: f32,
+  ///beeps each move of the solution when it is up next, see [`crate::audio::move_cue`]
+  pub audio_cues: bool,
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Self {
+      msaa: false,
+      theme: Theme::default(),
+      enemy_marking: EnemyMarking::default(),
+      animation_speed: 1.0,
+      solver_strategy: Strategy::default(),
+      solution_order: Comparator::default(),
+      move_filter: MoveFilter::new(),
+      language: Language::default(),
+      fullscreen: cfg!(target_os = "android"),
+      puzzle_timer: None,
+      always_on_top: false,
+      ui_scale: 1.0,
+      audio_cues: false,
+    }
+  }
+}
+
+impl Settings {
+  pub const ANIMATION_SPEEDS: RangeInclusive<f32> = 0.25..=4.0;
+  pub const UI_SCALES: RangeInclusive<f32> = 1.0..=2.0;
+  ///The puzzle times which can be chosen, in seconds
+  pub const PUZZLE_TIMES: [Option<u32>; 6] =
+    [None, Some(30), Some(45), Some(60), Some(90), Some(120)];
+
+  ///Sets a setting from its representation in the settings file
+  fn set(&mut self, key: &str, value: &str) -> Option<()> {
+    match key {
+      "msaa" => self.msaa = value.parse().ok()?,
+      "theme" => self.theme = Theme::by_name(value)?,
+      "enemy_marking" => self.enemy_marking = EnemyMarking::by_name(value)?,
+      "animation_speed" => {
+        self.animation_speed = value
+          .parse()
+          .ok()
+          .filter(|speed| Self::ANIMATION_SPEEDS.contains(speed))?
+      }
+      "solver_strategy" => self.solver_strategy = Strategy::by_name(value)?,
+      "solution_order" => self.solution_order = Comparator::by_name(value)?,
+      "move_filter" => self.move_filter = value.parse().ok()?,
+      "language" => self.language = Language::by_code(value)?,
+      "fullscreen" => self.fullscreen = value.parse().ok()?,
+      "always_on_top" => self.always_on_top = value.parse().ok()?,
+      "audio_cues" => self.audio_cues = value.parse().ok()?,
+      "ui_scale" => {
+        self.ui_scale = value
+          .parse()
+          .ok()
+          .filter(|scale| Self::UI_SCALES.contains(scale))?
+      }
+      "puzzle_timer" => {
+        self.puzzle_timer = match value {
+          "off" => None,
+          secs => Some(secs.parse().ok()?),
+        };
+        if !Self::PUZZLE_TIMES.contains(&self.puzzle_timer) {
+          return None;
+        }
+      }
+      _ => return None,
+    }
+    Some(())
+  }
+}
+
+///Parses the settings file, where each line has the format `<key> = <value>`. Settings which aren't
+/// listed keep their default value.
+impl TryFrom<&str> for Settings {
+  type Error = SettingsError;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    let mut settings = Settings::default();
+    for line in value
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+      line
+        .split_once('=')
+        .and_then(|(key, value)| settings.set(key.trim(), value.trim()))
+        .ok_or(SettingsError::InvalidLine(line.to_string()))?;
+    }
+    Ok(settings)
+  }
+}
+
+impl Display for Settings {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    writeln!(f, "msaa = {}", self.msaa)?;
+    writeln!(f, "theme = {}", self.theme.name())?;
+    writeln!(f, "enemy_marking = {}", self.enemy_marking.name())?;
+    writeln!(f, "animation_speed = {}", self.animation_speed)?;
+    writeln!(f, "solver_strategy = {}", self.solver_strategy)?;
+    writeln!(f, "solution_order = {}", self.solution_order)?;
+    writeln!(f, "move_filter = {}", self.move_filter)?;
+    writeln!(f, "language = {}", self.language.code())?;
+    writeln!(f, "fullscreen = {}", self.fullscreen)?;
+    writeln!(f, "always_on_top = {}", self.always_on_top)?;
+    writeln!(f, "ui_scale = {}", self.ui_scale)?;
+    writeln!(f, "audio_cues = {}", self.audio_cues)?;
+    match self.puzzle_timer {
+      Some(secs) => writeln!(f, "puzzle_timer = {}", secs),
+      None => writeln!(f, "puzzle_timer = off"),
+    }
+  }
+}
+
+///The settings saved in a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsStore {
+  path: PathBuf,
+  settings: Settings,
+}
+
+impl SettingsStore {
+  ///Loads the settings from the file [`SETTINGS_VAR`] points to, or from [`DEFAULT_SETTINGS_FILE`]
+  pub fn load_default() -> Result<Self, SettingsError> {
+    Self::load(Self::default_path())
+  }
+
+  ///Loads the settings from the given file, which doesn't have to exist yet
+  pub fn load<P>(path: P) -> Result<Self, SettingsError>
+  where
+    P: AsRef<Path>,
+  {
+    let path = path.as_ref().to_path_buf();
+    let settings = match fs::read_to_string(&path) {
+      Ok(content) => Settings::try_from(content.as_str())?,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => Settings::default(),
+      Err(e) => return Err(e.into()),
+    };
+    Ok(Self { path, settings })
+  }
+
+  ///Falls back to the default settings if they can't be loaded, since a broken settings file
+  /// shouldn't keep the app from starting. Saving overwrites the file then.
+  pub fn load_default_or_reset() -> Self {
+    Self::load_default().unwrap_or_else(|e| {
+      eprintln!("failed to load settings, using the defaults: {}", e);
+      Self {
+        path: Self::default_path(),
+        settings: Settings::default(),
+      }
+    })
+  }
+
+  fn default_path() -> PathBuf {
+    data_file(SETTINGS_VAR, DEFAULT_SETTINGS_FILE)
+  }
+
+  pub fn settings(&self) -> &Settings {
+    &self.settings
+  }
+
+  ///A file in the directory of the settings file, for data which belongs to the same user
+  pub fn beside(&self, file_name: &str) -> PathBuf {
+    self.path.with_file_name(file_name)
+  }
+
+  ///Replaces the settings and writes them to the file, if they changed
+  pub fn set(&mut self, settings: Settings) -> io::Result<()> {
+    if self.settings == settings {
+      return Ok(());
+    }
+    self.settings = settings;
+    create_parent(&self.path)?;
+    fs::write(&self.path, self.settings.to_string())
+  }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+  IO(io::Error),
+  InvalidLine(String),
+}
+
+impl From<io::Error> for SettingsError {
+  fn from(value: io::Error) -> Self {
+    Self::IO(value)
+  }
+}
+
+impl Display for SettingsError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      SettingsError::IO(e) => write!(f, "IO error: {}", e),
+      SettingsError::InvalidLine(line) => {
+        write!(
+          f,
+          "Invalid setting '{}', needs to be '<key> = <value>'",
+          line
+        )
+      }
+    }
+  }
+}
+
+impl Error for SettingsError {}
+
+#[cfg(test)]
+mod test_settings {
+  use std::env;
+  use std::fs;
+
+  use game_logic::render::EnemyMarking;
+  use game_logic::solving::move_filter::MoveFilter;
+  use game_logic::solving::{Comparator, Strategy};
+
+  use crate::settings::{Language, Settings, SettingsStore, Theme};
+
+  #[test]
+  fn test_parse() {
+    let settings = Settings::try_from("theme = light\n# comment\nanimation_speed=2.5\n")
+      .expect("valid settings");
+    assert_eq!(Theme::Light, settings.theme);
+    assert_eq!(2.5, settings.animation_speed);
+    assert!(!settings.msaa);
+
+    let settings = Settings {
+      msaa: true,
+      enemy_marking: EnemyMarking::Shapes,
+      solver_strategy: Strategy::BIDIRECTIONAL,
+      solution_order: Comparator::by_name("time").unwrap(),
+      move_filter: MoveFilter::new().forbid_rows().forbid_column(3),
+      language: Language::German,
+      fullscreen: true,
+      puzzle_timer: Some(90),
+      always_on_top: true,
+      ui_scale: 1.5,
+      audio_cues: true,
+      ..settings
+    };
+    assert_eq!(
+      settings,
+      Settings::try_from(settings.to_string().as_str()).unwrap()
+    );
+
+    assert!(Settings::try_from("theme = purple").is_err());
+    assert!(Settings::try_from("enemy_marking = sounds").is_err());
+    assert!(Settings::try_from("animation_speed = 100").is_err());
+    assert!(Settings::try_from("ui_scale = 0.5").is_err());
+    assert!(Settings::try_from("volume = 5").is_err());
+    assert!(Settings::try_from("puzzle_timer = 50").is_err());
+    assert!(Settings::try_from("msaa").is_err());
+  }
+
+  #[test]
+  fn test_save_and_load() {
+    let path = env::temp_dir().join(format!("tok_settings_{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let mut store = SettingsStore::load(&path).expect("missing file has the defaults");
+    assert_eq!(&Settings::default(), store.settings());
+    let changed = Settings {
+      theme: Theme::Light,
+      ..Settings::default()
+    };
+    store.set(changed).expect("failed to save");
+
+    let store = SettingsStore::load(&path).expect("failed to load");
+    assert_eq!(&changed, store.settings());
+    let _ = fs::remove_file(&path);
+  }
+}
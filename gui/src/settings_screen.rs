@@ -0,0 +1,374 @@
+use game_logic::preset::{PresetStore, SolverPreset};
+use game_logic::render::EnemyMarking;
+use game_logic::solving::{Comparator, Strategy};
+
+use crate::renderer::{LabeledWidget, PTexCoords, TexRect, Widget, WidgetList};
+use crate::settings::{Language, Settings, Theme};
+
+///The rows of the settings screen, in the order they are shown
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum SettingRow {
+  Msaa,
+  Theme,
+  AnimationSpeed,
+  SolverPreset,
+  SolverStrategy,
+  SolutionOrder,
+  Language,
+  Fullscreen,
+  PuzzleTimer,
+  AlwaysOnTop,
+  EnemyMarking,
+  UiScale,
+  AudioCues,
+}
+
+impl SettingRow {
+  const ALL: [SettingRow; 13] = [
+    SettingRow::Msaa,
+    SettingRow::Theme,
+    SettingRow::AnimationSpeed,
+    SettingRow::SolverPreset,
+    SettingRow::SolverStrategy,
+    SettingRow::SolutionOrder,
+    SettingRow::Language,
+    SettingRow::Fullscreen,
+    SettingRow::PuzzleTimer,
+    SettingRow::AlwaysOnTop,
+    SettingRow::EnemyMarking,
+    SettingRow::UiScale,
+    SettingRow::AudioCues,
+  ];
+
+  fn label(&self, language: Language) -> &'static str {
+    match (self, language) {
+      (SettingRow::Msaa, Language::English) => "Anti-aliasing",
+      (SettingRow::Msaa, Language::German) => "Kantenglättung",
+      (SettingRow::Theme, Language::English) => "Theme",
+      (SettingRow::Theme, Language::German) => "Farbschema",
+      (SettingRow::AnimationSpeed, Language::English) => "Animation speed",
+      (SettingRow::AnimationSpeed, Language::German) => "Animationstempo",
+      (SettingRow::SolverPreset, Language::English) => "Solver preset",
+      (SettingRow::SolverPreset, Language::German) => "Löser-Vorlage",
+      (SettingRow::SolverStrategy, Language::English) => "Solver strategy",
+      (SettingRow::SolverStrategy, Language::German) => "Lösungsstrategie",
+      (SettingRow::SolutionOrder, Language::English) => "Best solution",
+      (SettingRow::SolutionOrder, Language::German) => "Beste Lösung",
+      (SettingRow::Language, _) => "Language / Sprache",
+      (SettingRow::Fullscreen, Language::English) => "Fullscreen",
+      (SettingRow::Fullscreen, Language::German) => "Vollbild",
+      (SettingRow::PuzzleTimer, Language::English) => "Puzzle timer",
+      (SettingRow::PuzzleTimer, Language::German) => "Rätsel-Timer",
+      (SettingRow::AlwaysOnTop, Language::English) => "Always on top",
+      (SettingRow::AlwaysOnTop, Language::German) => "Immer im Vordergrund",
+      (SettingRow::EnemyMarking, Language::English) => "Enemy marking",
+      (SettingRow::EnemyMarking, Language::German) => "Gegner-Markierung",
+      (SettingRow::UiScale, Language::English) => "UI scale",
+      (SettingRow::UiScale, Language::German) => "UI-Skalierung",
+      (SettingRow::AudioCues, Language::English) => "Audio cues",
+      (SettingRow::AudioCues, Language::German) => "Audio-Hinweise",
+    }
+  }
+
+  fn widget(&self, settings: &Settings) -> Widget {
+    match self {
+      SettingRow::Msaa => Widget::Toggle {
+        value: settings.msaa,
+      },
+      SettingRow::Theme => Widget::Choice {
+        options: Theme::ALL.map(|theme| theme.name().to_string()).to_vec(),
+        selected: Theme::ALL
+          .iter()
+          .position(|&theme| theme == settings.theme)
+          .unwrap_or_default(),
+      },
+      SettingRow::AnimationSpeed => Widget::Slider {
+        value: settings.animation_speed,
+        min: *Settings::ANIMATION_SPEEDS.start(),
+        max: *Settings::ANIMATION_SPEEDS.end(),
+        step: 0.25,
+      },
+      SettingRow::SolverPreset => {
+        let presets = presets();
+        let mut options = vec!["custom".to_string()];
+        options.extend(presets.iter().map(|preset| preset.name.clone()));
+        Widget::Choice {
+          options,
+          selected: presets
+            .iter()
+            .position(|preset| uses_preset(settings, preset))
+            .map_or(0, |index| index + 1),
+        }
+      }
+      SettingRow::SolverStrategy => Widget::Choice {
+        options: Strategy::all()
+          .map(|strategy| strategy.name().to_string())
+          .collect(),
+        selected: Strategy::all()
+          .position(|strategy| strategy == settings.solver_strategy)
+          .unwrap_or_default(),
+      },
+      SettingRow::SolutionOrder => Widget::Choice {
+        options: Comparator::all()
+          .map(|comparator| comparator.name().to_string())
+          .collect(),
+        selected: Comparator::all()
+          .position(|comparator| comparator == settings.solution_order)
+          .unwrap_or_default(),
+      },
+      SettingRow::Language => Widget::Choice {
+        options: Language::ALL
+          .map(|language| language.native_name().to_string())
+          .to_vec(),
+        selected: Language::ALL
+          .iter()
+          .position(|&language| language == settings.language)
+          .unwrap_or_default(),
+      },
+      SettingRow::Fullscreen => Widget::Toggle {
+        value: settings.fullscreen,
+      },
+      SettingRow::PuzzleTimer => Widget::Choice {
+        options: Settings::PUZZLE_TIMES
+          .map(|secs| match secs {
+            Some(secs) => format!("{} s", secs),
+            None => "off".to_string(),
+          })
+          .to_vec(),
+        selected: Settings::PUZZLE_TIMES
+          .iter()
+          .position(|&secs| secs == settings.puzzle_timer)
+          .unwrap_or_default(),
+      },
+      SettingRow::AlwaysOnTop => Widget::Toggle {
+        value: settings.always_on_top,
+      },
+      SettingRow::EnemyMarking => Widget::Choice {
+        options: EnemyMarking::ALL
+          .map(|marking| marking.name().to_string())
+          .to_vec(),
+        selected: EnemyMarking::ALL
+          .iter()
+          .position(|&marking| marking == settings.enemy_marking)
+          .unwrap_or_default(),
+      },
+      SettingRow::UiScale => Widget::Slider {
+        value: settings.ui_scale,
+        min: *Settings::UI_SCALES.start(),
+        max: *Settings::UI_SCALES.end(),
+        step: 0.25,
+      },
+      SettingRow::AudioCues => Widget::Toggle {
+        value: settings.audio_cues,
+      },
+    }
+  }
+
+  ///Writes the value of the widget back into the settings
+  fn apply(&self, widget: &Widget, settings: &mut Settings) {
+    match (self, widget) {
+      (SettingRow::Msaa, &Widget::Toggle { value }) => settings.msaa = value,
+      (SettingRow::Theme, &Widget::Choice { selected, .. }) => {
+        settings.theme = Theme::ALL[selected]
+      }
+      (SettingRow::AnimationSpeed, &Widget::Slider { value, .. }) => {
+        settings.animation_speed = value
+      }
+      (SettingRow::SolverPreset, &Widget::Choice { selected, .. }) => {
+        if let Some(preset) = selected
+          .checked_sub(1)
+          .and_then(|index| presets().into_iter().nth(index))
+        {
+          settings.solver_strategy = preset.params.strategy;
+          settings.solution_order = preset.params.comparator;
+          settings.move_filter = preset.params.move_filter;
+        }
+      }
+      (SettingRow::SolverStrategy, &Widget::Choice { selected, .. }) => {
+        if let Some(strategy) = Strategy::all().nth(selected) {
+          settings.solver_strategy = strategy;
+        }
+      }
+      (SettingRow::SolutionOrder, &Widget::Choice { selected, .. }) => {
+        if let Some(comparator) = Comparator::all().nth(selected) {
+          settings.solution_order = comparator;
+        }
+      }
+      (SettingRow::Language, &Widget::Choice { selected, .. }) => {
+        settings.language = Language::ALL[selected]
+      }
+      (SettingRow::Fullscreen, &Widget::Toggle { value }) => settings.fullscreen = value,
+      (SettingRow::PuzzleTimer, &Widget::Choice { selected, .. }) => {
+        settings.puzzle_timer = Settings::PUZZLE_TIMES[selected]
+      }
+      (SettingRow::AlwaysOnTop, &Widget::Toggle { value }) => settings.always_on_top = value,
+      (SettingRow::EnemyMarking, &Widget::Choice { selected, .. }) => {
+        settings.enemy_marking = EnemyMarking::ALL[selected]
+      }
+      (SettingRow::UiScale, &Widget::Slider { value, .. }) => settings.ui_scale = value,
+      (SettingRow::AudioCues, &Widget::Toggle { value }) => settings.audio_cues = value,
+      (row, widget) => unreachable!("{:?} isn't shown as {:?}", row, widget),
+    }
+  }
+}
+
+///The solver presets the user can choose from, the built-in ones if the custom ones can't be loaded
+fn presets() -> Vec<SolverPreset> {
+  PresetStore::load_default().map_or_else(|_| SolverPreset::presets(), |store| store.all())
+}
+
+///Whether the settings solve like the preset. The number of turns and whether to stop at the first
+/// solution aren't settings, since the app always improves the solution while the timer runs.
+fn uses_preset(settings: &Settings, preset: &SolverPreset) -> bool {
+  settings.solver_strategy == preset.params.strategy
+    && settings.solution_order == preset.params.comparator
+    && settings.move_filter == preset.params.move_filter
+}
+
+///Shows the [`Settings`] as widgets, which can be navigated with the keyboard or by tapping them
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsScreen {
+  widgets: WidgetList,
+}
+
+impl SettingsScreen {
+  pub fn new(settings: &Settings) -> Self {
+    Self {
+      widgets: WidgetList::new(
+        SettingRow::ALL
+          .iter()
+          .map(|row| LabeledWidget::new(row.label(settings.language), row.widget(settings)))
+          .collect(),
+      )
+      .with_area(TexRect::new(
+        PTexCoords::new(0.1, 0.15),
+        PTexCoords::new(0.9, 0.95),
+      )),
+    }
+  }
+
+  pub fn widgets(&self) -> &WidgetList {
+    &self.widgets
+  }
+
+  ///Lets the widgets be edited, then shows the new values of the settings. Returns the changed
+  /// settings if the edit changed anything.
+  pub fn edit<F>(&mut self, settings: &Settings, edit: F) -> Option<Settings>
+  where
+    F: FnOnce(&mut WidgetList),
+  {
+    let before = self.widgets.clone();
+    edit(&mut self.widgets);
+    let mut changed = *settings;
+    //only edited rows are applied, since a preset changes the values of other rows
+    for ((row, labeled), previous) in SettingRow::ALL
+      .iter()
+      .zip(self.widgets.rows())
+      .zip(before.rows())
+    {
+      if labeled.widget != previous.widget {
+        row.apply(&labeled.widget, &mut changed);
+      }
+    }
+
+    let edited = before.rows() != self.widgets.rows();
+    self.refresh(&changed);
+    edited.then_some(changed)
+  }
+
+  ///Shows new values of the settings, keeping the focus
+  pub fn refresh(&mut self, settings: &Settings) {
+    //labels change with the language
+    let focused = self.widgets.focused();
+    *self = Self::new(settings);
+    self.widgets.focus(focused);
+  }
+
+  ///Describes the focused setting, shown in the title bar until the app can render text
+  pub fn status(&self) -> String {
+    let row = self.widgets.focused_row();
+    format!("{}: {}", row.label, row.widget.value_text())
+  }
+}
+
+#[cfg(test)]
+mod test_settings_screen {
+  use game_logic::solving::Strategy;
+
+  use crate::hud::{hud, hud_area, SETTINGS_BUTTON};
+  use crate::renderer::{PTexCoords, Widget};
+  use crate::settings::{Language, Settings, Theme};
+  use crate::settings_screen::SettingsScreen;
+
+  #[test]
+  fn test_edit() {
+    let settings = Settings::default();
+    let mut screen = SettingsScreen::new(&settings);
+    assert_eq!("Anti-aliasing: off", screen.status());
+
+    let changed = screen
+      .edit(&settings, |widgets| {
+        widgets.move_focus(1);
+        widgets.focused_widget_mut().activate();
+      })
+      .expect("theme changed");
+    assert_eq!(Theme::Light, changed.theme);
+    assert_eq!(1, screen.widgets().focused());
+
+    //moving the focus changes nothing
+    assert_eq!(None, screen.edit(&changed, |widgets| widgets.move_focus(1)));
+
+    let changed = screen
+      .edit(&changed, |widgets| {
+        widgets.move_focus(4);
+        widgets.focused_widget_mut().step(1);
+      })
+      .expect("language changed");
+    assert_eq!(Language::German, changed.language);
+    assert_eq!("Kantenglättung", screen.widgets().rows()[0].label);
+
+    let changed = screen
+      .edit(&changed, |widgets| {
+        widgets.tap(widgets.bar_rect(2).as_array()[0].as_p_tex_coords());
+      })
+      .expect("animation speed changed");
+    assert_eq!(0.25, changed.animation_speed);
+    assert!(matches!(
+      screen.widgets().focused_row().widget,
+      Widget::Slider { .. }
+    ));
+    let open_button = hud().rect_of(hud_area(1.0), SETTINGS_BUTTON).unwrap();
+    assert!(open_button.contains(PTexCoords::new(0.95, 0.05)));
+  }
+
+  #[test]
+  fn test_preset() {
+    let settings = Settings::default();
+    let mut screen = SettingsScreen::new(&settings);
+    let changed = screen
+      .edit(&settings, |widgets| {
+        widgets.move_focus(3);
+        widgets.focused_widget_mut().step(1);
+      })
+      .expect("preset chosen");
+    assert_eq!(
+      Strategy::by_name("heuristic"),
+      Some(changed.solver_strategy)
+    );
+    assert_eq!("Solver preset: casual", screen.status());
+
+    //changing a setting of the preset makes it custom
+    let changed = screen
+      .edit(&changed, |widgets| {
+        widgets.move_focus(1);
+        widgets.focused_widget_mut().step(1);
+      })
+      .expect("strategy changed");
+    assert_ne!(
+      Strategy::by_name("heuristic"),
+      Some(changed.solver_strategy)
+    );
+    screen.widgets.move_focus(-1);
+    assert_eq!("Solver preset: custom", screen.status());
+  }
+}
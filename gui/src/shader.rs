@@ -0,0 +1,2 @@
+//generated by build.rs, set SHADER_PRE_PROCESSOR_WRITE_TO_SRC while building to see the generated code here
+include!(concat!(env!("OUT_DIR"), "/shader.rs"));
@@ -0,0 +1,119 @@
+use game_logic::stats::SolveRecord;
+
+use crate::renderer::{PTexCoords, TexRect};
+
+///How many of the latest solves the chart shows
+pub const CHART_LIMIT: usize = 20;
+
+///Where the chart of the solves is drawn, see
+/// [`Screen::Stats`](crate::app_state::screen::Screen::Stats)
+pub fn chart_rect() -> TexRect {
+  TexRect::new(PTexCoords::new(0.05, 0.15), PTexCoords::new(0.95, 0.85))
+}
+
+///One bar per solve of the latest [`CHART_LIMIT`] records, the oldest on the left. A bar is as high
+/// as the solution has turns compared to the longest one, unsolved arenas reach the top. Also tells
+/// for each bar whether the solve was missed: the arena wasn't solved or the user didn't follow the
+/// optimal line.
+pub fn chart_bars(records: &[SolveRecord]) -> Vec<(TexRect, bool)> {
+  let records = &records[records.len().saturating_sub(CHART_LIMIT)..];
+  let longest = records
+    .iter()
+    .filter_map(|record| record.turns)
+    .max()
+    .unwrap_or(0)
+    .max(1);
+  let [top_left, bottom_right] = chart_rect()
+    .as_array()
+    .map(|tex_coords| tex_coords.as_p_tex_coords());
+  let inset = 0.02;
+  let (left, top, bottom) = (
+    top_left.x + inset,
+    top_left.y + inset,
+    bottom_right.y - inset,
+  );
+  let width = (bottom_right.x - inset - left) / CHART_LIMIT as f32;
+  records
+    .iter()
+    .enumerate()
+    .map(|(index, record)| {
+      //solutions without moves still get a visible bar
+      let height = record
+        .turns
+        .map_or(1.0, |turns| (turns as f32 / longest as f32).max(0.05));
+      let x = left + index as f32 * width;
+      let rect = TexRect::new(
+        PTexCoords::new(x + width * 0.1, bottom - (bottom - top) * height),
+        PTexCoords::new(x + width * 0.9, bottom),
+      );
+      let missed = record.turns.is_none() || record.followed_optimal == Some(false);
+      (rect, missed)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test_stats_chart {
+  use std::time::Duration;
+
+  use game_logic::solving::SolvableArena;
+  use game_logic::stats::SolveRecord;
+
+  use crate::renderer::{PTexCoords, TexRect};
+  use crate::stats_chart::{chart_bars, chart_rect, CHART_LIMIT};
+
+  fn record(turns: Option<usize>, followed_optimal: Option<bool>) -> SolveRecord {
+    SolveRecord {
+      turns,
+      solve_time: Some(Duration::from_millis(5)),
+      followed_optimal,
+      ..SolveRecord::now(&SolvableArena::default())
+    }
+  }
+
+  fn corners(rect: TexRect) -> [PTexCoords; 2] {
+    rect
+      .as_array()
+      .map(|tex_coords| tex_coords.as_p_tex_coords())
+  }
+
+  #[test]
+  fn test_bars() {
+    assert!(chart_bars(&[]).is_empty());
+    let records = [
+      record(Some(2), None),
+      record(Some(4), Some(true)),
+      record(None, None),
+      record(Some(2), Some(false)),
+    ];
+    let bars = chart_bars(&records);
+    assert_eq!(
+      vec![false, false, true, true],
+      bars.iter().map(|&(_, missed)| missed).collect::<Vec<_>>()
+    );
+    let [half_top, half_bottom] = corners(bars[0].0);
+    let [full_top, full_bottom] = corners(bars[1].0);
+    let [unsolved_top, _] = corners(bars[2].0);
+    assert_eq!(half_bottom.y, full_bottom.y);
+    assert!(full_top.y < half_top.y);
+    assert_eq!(full_top.y, unsolved_top.y);
+    //the oldest solve is on the left
+    assert!(half_top.x < full_top.x);
+    for (bar, _) in bars {
+      let [top_left, bottom_right] = corners(bar);
+      assert!(chart_rect().contains(top_left) && chart_rect().contains(bottom_right));
+    }
+  }
+
+  #[test]
+  fn test_limit() {
+    let records: Vec<SolveRecord> = (0..CHART_LIMIT + 5)
+      .map(|turns| record(Some(turns), None))
+      .collect();
+    let bars = chart_bars(&records);
+    assert_eq!(CHART_LIMIT, bars.len());
+    //only the latest solves are shown, the longest of them reaches the top
+    let [top_left, _] = corners(bars[CHART_LIMIT - 1].0);
+    assert!((top_left.y - 0.17).abs() < 1e-5);
+  }
+}
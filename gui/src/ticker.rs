@@ -0,0 +1,188 @@
+use std::env;
+use std::ops::RangeInclusive;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::events::{EventSender, TimerEvent};
+
+///The environment variable setting the animation ticks per second, see [`Ticker::RATES`]
+pub const TICK_RATE_VAR: &str = "TOK_TICK_RATE";
+
+///Sends [`TimerEvent::AnimationTick`]s to the event loop from a background thread. The thread stops
+/// when the ticker is dropped or the event loop is gone.
+#[derive(Debug)]
+pub struct Ticker {
+  ///ticks per second
+  rate: u32,
+  paused: bool,
+  ///disconnects when the ticker is dropped, which stops the thread
+  signals: Option<Sender<Signal>>,
+  thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Signal {
+  Pause,
+  Resume,
+}
+
+impl Ticker {
+  ///The ticks per second animations are made for
+  pub const DEFAULT_RATE: u32 = 20;
+  pub const RATES: RangeInclusive<u32> = 1..=240;
+
+  ///Starts ticking at the rate, which is clamped to [`Self::RATES`]
+  pub fn start(events: EventSender, rate: u32) -> Self {
+    let rate = rate.clamp(*Self::RATES.start(), *Self::RATES.end());
+    let interval = Duration::from_secs(1) / rate;
+    let (signals, receiver) = mpsc::channel();
+    let thread = thread::spawn(move || {
+      //fails only if the event loop is gone
+      tick_until_dropped(receiver, interval, || events.send(TimerEvent::AnimationTick));
+    });
+    Self {
+      rate,
+      paused: false,
+      signals: Some(signals),
+      thread: Some(thread),
+    }
+  }
+
+  ///Stops sending ticks until the ticker is [resumed](Self::resume), like while the app is in the
+  /// background. Animations continue where they were instead of skipping the time in between.
+  pub fn pause(&mut self) {
+    self.signal(Signal::Pause, true);
+  }
+
+  pub fn resume(&mut self) {
+    self.signal(Signal::Resume, false);
+  }
+
+  fn signal(&mut self, signal: Signal, paused: bool) {
+    if self.paused != paused {
+      self.paused = paused;
+      if let Some(signals) = &self.signals {
+        //fails only if the thread stopped because the event loop is gone
+        let _ = signals.send(signal);
+      }
+    }
+  }
+
+  ///The rate [`TICK_RATE_VAR`] is set to, or the [default](Self::DEFAULT_RATE)
+  pub fn configured_rate() -> u32 {
+    let Ok(value) = env::var(TICK_RATE_VAR) else {
+      return Self::DEFAULT_RATE;
+    };
+    parse_rate(&value).unwrap_or_else(|| {
+      eprintln!(
+        "invalid tick rate {}, expected {} to {} ticks per second",
+        value,
+        Self::RATES.start(),
+        Self::RATES.end()
+      );
+      Self::DEFAULT_RATE
+    })
+  }
+
+  ///How many ticks at the [default rate](Self::DEFAULT_RATE) a tick is worth, which keeps animations
+  /// equally fast at any rate
+  pub fn tick_scale(&self) -> f32 {
+    Self::DEFAULT_RATE as f32 / self.rate as f32
+  }
+}
+
+impl Drop for Ticker {
+  fn drop(&mut self) {
+    self.signals.take();
+    if let Some(thread) = self.thread.take() {
+      //the thread only waits for the next tick, so it stops right away
+      let _ = thread.join();
+    }
+  }
+}
+
+///Calls `tick` once per interval while not paused, until it returns false or the sender of the
+/// signals is dropped
+fn tick_until_dropped<T>(signals: Receiver<Signal>, interval: Duration, mut tick: T)
+where
+  T: FnMut() -> bool,
+{
+  let mut paused = false;
+  loop {
+    let signal = if paused {
+      signals
+        .recv()
+        .map_err(|_| RecvTimeoutError::Disconnected)
+    } else {
+      signals.recv_timeout(interval)
+    };
+    match signal {
+      Ok(Signal::Pause) => paused = true,
+      Ok(Signal::Resume) => paused = false,
+      Err(RecvTimeoutError::Timeout) => {
+        if !tick() {
+          break;
+        }
+      }
+      Err(RecvTimeoutError::Disconnected) => break,
+    }
+  }
+}
+
+fn parse_rate(value: &str) -> Option<u32> {
+  value
+    .trim()
+    .parse()
+    .ok()
+    .filter(|rate| Ticker::RATES.contains(rate))
+}
+
+#[cfg(test)]
+mod test_ticker {
+  use std::sync::atomic::{AtomicU32, Ordering};
+  use std::sync::{mpsc, Arc};
+  use std::thread;
+  use std::time::{Duration, Instant};
+
+  use crate::ticker::{parse_rate, tick_until_dropped, Signal};
+
+  #[test]
+  fn test_pause() {
+    let ticks = Arc::new(AtomicU32::new(0));
+    let (signals, receiver) = mpsc::channel();
+    //paused before the first tick is due
+    signals.send(Signal::Pause).unwrap();
+    let thread = thread::spawn({
+      let ticks = ticks.clone();
+      move || {
+        tick_until_dropped(receiver, Duration::from_millis(1), || {
+          ticks.fetch_add(1, Ordering::Relaxed);
+          true
+        })
+      }
+    });
+
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(0, ticks.load(Ordering::Relaxed));
+
+    signals.send(Signal::Resume).unwrap();
+    let start = Instant::now();
+    while ticks.load(Ordering::Relaxed) == 0 {
+      assert!(start.elapsed() < Duration::from_secs(5), "no tick after resuming");
+      thread::sleep(Duration::from_millis(1));
+    }
+
+    drop(signals);
+    thread.join().unwrap();
+  }
+
+  #[test]
+  fn test_parse_rate() {
+    assert_eq!(Some(60), parse_rate("60"));
+    assert_eq!(Some(20), parse_rate(" 20\n"));
+    assert_eq!(None, parse_rate("0"));
+    assert_eq!(None, parse_rate("1000"));
+    assert_eq!(None, parse_rate("fast"));
+  }
+}
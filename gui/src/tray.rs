@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Mutex;
+
+use image::ImageError;
+use tray_icon::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem};
+use tray_icon::{BadIcon, Icon, TrayIcon, TrayIconBuilder};
+
+use crate::events::{EventSender, InputEvent, QuickAction};
+use crate::resources::{pick_variant, ICON_SIZE};
+
+///The tray icon with the [`QuickAction`]s in its menu, which is removed when this is dropped
+pub struct Tray {
+  _icon: TrayIcon,
+  always_on_top: CheckMenuItem,
+}
+
+impl Tray {
+  ///Creates the tray icon from the icon variant fitting the scale factor best. Its menu items send
+  /// [`InputEvent::QuickAction`]s. Needs to be called once the event loop runs.
+  pub fn new(
+    tooltip: &str,
+    icon_variants: &[&[u8]],
+    scale_factor: f64,
+    always_on_top: bool,
+    events: EventSender,
+  ) -> Result<Self, TrayError> {
+    let wanted_size = (ICON_SIZE as f64 * scale_factor).round() as u32;
+    let rgba = image::load_from_memory(pick_variant(icon_variants, wanted_size)?)?.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let icon = Icon::from_rgba(rgba.into_raw(), width, height)?;
+
+    let toggle_window = MenuItem::new("Show / hide window", true, None);
+    let clear_arena = MenuItem::new("Clear arena", true, None);
+    let always_on_top = CheckMenuItem::new("Always on top", true, always_on_top, None);
+    let toggle_overlay = MenuItem::new("Overlay", true, None);
+    let menu = Menu::new();
+    menu.append_items(&[
+      &toggle_window,
+      &clear_arena,
+      &always_on_top,
+      &toggle_overlay,
+    ])?;
+
+    let actions = [
+      (toggle_window.id().clone(), QuickAction::ToggleWindow),
+      (clear_arena.id().clone(), QuickAction::ClearArena),
+      (always_on_top.id().clone(), QuickAction::ToggleAlwaysOnTop),
+      (toggle_overlay.id().clone(), QuickAction::ToggleOverlay),
+    ];
+    let events = Mutex::new(events);
+    MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+      if let Some(&(_, action)) = actions.iter().find(|(id, _)| *id == event.id) {
+        //fails only if the event loop is gone, then nobody needs the action anymore
+        events
+          .lock()
+          .expect("event sender is poisoned")
+          .send(InputEvent::QuickAction(action));
+      }
+    }));
+
+    let icon = TrayIconBuilder::new()
+      .with_menu(Box::new(menu))
+      .with_tooltip(tooltip)
+      .with_icon(icon)
+      .build()?;
+    Ok(Self {
+      _icon: icon,
+      always_on_top,
+    })
+  }
+
+  ///Shows whether the window is always on top, since the setting can also be changed in the app
+  pub fn set_always_on_top(&self, always_on_top: bool) {
+    self.always_on_top.set_checked(always_on_top);
+  }
+}
+
+impl Debug for Tray {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Tray").finish_non_exhaustive()
+  }
+}
+
+#[derive(Debug)]
+pub enum TrayError {
+  Image(ImageError),
+  Icon(BadIcon),
+  Menu(tray_icon::menu::Error),
+  Tray(tray_icon::Error),
+}
+
+impl From<ImageError> for TrayError {
+  fn from(value: ImageError) -> Self {
+    Self::Image(value)
+  }
+}
+
+impl From<BadIcon> for TrayError {
+  fn from(value: BadIcon) -> Self {
+    Self::Icon(value)
+  }
+}
+
+impl From<tray_icon::menu::Error> for TrayError {
+  fn from(value: tray_icon::menu::Error) -> Self {
+    Self::Menu(value)
+  }
+}
+
+impl From<tray_icon::Error> for TrayError {
+  fn from(value: tray_icon::Error) -> Self {
+    Self::Tray(value)
+  }
+}
+
+impl Display for TrayError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      TrayError::Image(e) => write!(f, "Failed to load image: {}", e),
+      TrayError::Icon(e) => write!(f, "Invalid icon: {}", e),
+      TrayError::Menu(e) => write!(f, "Failed to create menu: {}", e),
+      TrayError::Tray(e) => write!(f, "Failed to create tray icon: {}", e),
+    }
+  }
+}
+
+impl Error for TrayError {}
@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use once_cell_regex::regex;
+
+///A resource a shader declares with `@group(g) @binding(b) var ...`
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Binding {
+  pub group: u32,
+  pub binding: u32,
+  pub name: String,
+  pub kind: BindingKind,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum BindingKind {
+  Texture {
+    ///the `wgpu::TextureSampleType` expression
+    sample_type: &'static str,
+    ///the `wgpu::TextureViewDimension` variant
+    view_dimension: &'static str,
+    multisampled: bool,
+  },
+  Sampler {
+    comparison: bool,
+  },
+  Uniform,
+  Storage {
+    read_only: bool,
+  },
+}
+
+impl BindingKind {
+  ///Parses the address space and type of a `var` declaration, like `<uniform>` and `Camera` or no
+  /// address space and `texture_2d<f32>`
+  pub fn parse(address_space: Option<&str>, type_name: &str) -> Option<Self> {
+    if let Some(address_space) = address_space {
+      let mut args = address_space.split(',').map(str::trim);
+      return match (args.next(), args.next()) {
+        (Some("uniform"), None) => Some(Self::Uniform),
+        (Some("storage"), None | Some("read")) => Some(Self::Storage { read_only: true }),
+        (Some("storage"), Some("read_write")) => Some(Self::Storage { read_only: false }),
+        _ => None,
+      };
+    }
+
+    match type_name {
+      "sampler" => return Some(Self::Sampler { comparison: false }),
+      "sampler_comparison" => return Some(Self::Sampler { comparison: true }),
+      _ => {}
+    }
+
+    let (texture, sampled_type) = match type_name.split_once('<') {
+      Some((texture, sampled_type)) => (texture, sampled_type.strip_suffix('>')?.trim()),
+      None => (type_name, "f32"),
+    };
+    let (view_dimension, multisampled, depth) = match texture {
+      "texture_1d" => ("D1", false, false),
+      "texture_2d" => ("D2", false, false),
+      "texture_2d_array" => ("D2Array", false, false),
+      "texture_3d" => ("D3", false, false),
+      "texture_cube" => ("Cube", false, false),
+      "texture_cube_array" => ("CubeArray", false, false),
+      "texture_multisampled_2d" => ("D2", true, false),
+      "texture_depth_2d" => ("D2", false, true),
+      "texture_depth_2d_array" => ("D2Array", false, true),
+      "texture_depth_cube" => ("Cube", false, true),
+      "texture_depth_cube_array" => ("CubeArray", false, true),
+      "texture_depth_multisampled_2d" => ("D2", true, true),
+      _ => return None,
+    };
+    let sample_type = match sampled_type {
+      _ if depth => "wgpu::TextureSampleType::Depth",
+      //multisampled textures can't be filtered
+      "f32" if multisampled => "wgpu::TextureSampleType::Float { filterable: false }",
+      "f32" => "wgpu::TextureSampleType::Float { filterable: true }",
+      "i32" => "wgpu::TextureSampleType::Sint",
+      "u32" => "wgpu::TextureSampleType::Uint",
+      _ => return None,
+    };
+    Some(Self::Texture {
+      sample_type,
+      view_dimension,
+      multisampled,
+    })
+  }
+
+  ///The type of the field holding the resource in a generated bind group
+  fn resource_type(&self) -> &'static str {
+    match self {
+      BindingKind::Texture { .. } => "&'a wgpu::TextureView",
+      BindingKind::Sampler { .. } => "&'a wgpu::Sampler",
+      BindingKind::Uniform | BindingKind::Storage { .. } => "wgpu::BufferBinding<'a>",
+    }
+  }
+
+  fn binding_resource(&self, field: &str) -> String {
+    match self {
+      BindingKind::Texture { .. } => format!("wgpu::BindingResource::TextureView(self.{field})"),
+      BindingKind::Sampler { .. } => format!("wgpu::BindingResource::Sampler(self.{field})"),
+      BindingKind::Uniform | BindingKind::Storage { .. } => {
+        format!("wgpu::BindingResource::Buffer(self.{field})")
+      }
+    }
+  }
+
+  fn binding_type(&self) -> String {
+    match self {
+      BindingKind::Texture {
+        sample_type,
+        view_dimension,
+        multisampled,
+      } => format!(
+        "wgpu::BindingType::Texture {{ sample_type: {sample_type}, view_dimension: \
+         wgpu::TextureViewDimension::{view_dimension}, multisampled: {multisampled} }}"
+      ),
+      BindingKind::Sampler { comparison } => format!(
+        "wgpu::BindingType::Sampler(wgpu::SamplerBindingType::{})",
+        if *comparison {
+          "Comparison"
+        } else {
+          "Filtering"
+        }
+      ),
+      BindingKind::Uniform => "wgpu::BindingType::Buffer { ty: wgpu::BufferBindingType::Uniform, \
+                               has_dynamic_offset: false, min_binding_size: None }"
+        .to_string(),
+      BindingKind::Storage { read_only } => format!(
+        "wgpu::BindingType::Buffer {{ ty: wgpu::BufferBindingType::Storage {{ read_only: \
+         {read_only} }}, has_dynamic_offset: false, min_binding_size: None }}"
+      ),
+    }
+  }
+}
+
+///Finds every resource binding in pre-processed shader code
+pub fn parse_bindings<S>(shader_source: S) -> Result<Vec<Binding>, BindingParseError>
+where
+  S: AsRef<str>,
+{
+  let attribute_regex = regex!(r"@(?<attribute>\w+)\s*\(\s*(?<value>\d+)\s*\)");
+  let mut bindings = vec![];
+  for captures in
+    regex!(r"(?<attributes>(@\w+\s*\(\s*\w+\s*\)\s*)+)var\s*(<(?<address_space>[^>]*)>)?\s*(?<name>\w+)\s*:\s*(?<type>[^;]+);")
+      .captures_iter(shader_source.as_ref())
+  {
+    let name = captures
+      .name("name")
+      .expect("missing capture group")
+      .as_str()
+      .to_string();
+    let attributes = captures
+      .name("attributes")
+      .expect("missing capture group")
+      .as_str();
+    let attribute = |attribute: &'static str| {
+      attribute_regex
+        .captures_iter(attributes)
+        .find(|captures| &captures["attribute"] == attribute)
+        .and_then(|captures| captures["value"].parse().ok())
+        .ok_or(BindingParseError::MissingAttribute {
+          name: name.clone(),
+          attribute,
+        })
+    };
+    let type_name = captures
+      .name("type")
+      .expect("missing capture group")
+      .as_str()
+      .trim();
+    let kind = BindingKind::parse(
+      captures.name("address_space").map(|m| m.as_str()),
+      type_name,
+    )
+    .ok_or(BindingParseError::UnsupportedType {
+      name: name.clone(),
+      type_name: type_name.to_string(),
+    })?;
+
+    bindings.push(Binding {
+      group: attribute("group")?,
+      binding: attribute("binding")?,
+      name,
+      kind,
+    });
+  }
+  Ok(bindings)
+}
+
+///Generates a `bind_group_builders` module with a struct for every bind group of the shader. Each
+/// struct holds the resources of its group and can create the matching layout and bind group, so
+/// creating bind groups works the same for every shader.
+pub fn generate_bind_group_helpers<S>(shader_source: S) -> Result<String, BindingParseError>
+where
+  S: AsRef<str>,
+{
+  let shader_source = shader_source.as_ref();
+  let visibility = shader_stages(shader_source);
+
+  let mut groups: BTreeMap<u32, Vec<Binding>> = BTreeMap::new();
+  for binding in parse_bindings(shader_source)? {
+    groups.entry(binding.group).or_default().push(binding);
+  }
+
+  let mut helpers = String::from("pub mod bind_group_builders {\n");
+  for (group, mut bindings) in groups {
+    bindings.sort_by_key(|binding| binding.binding);
+    let struct_name = format!("BindGroup{group}");
+
+    helpers += &format!("  #[derive(Debug, Clone)]\n  pub struct {struct_name}<'a> {{\n");
+    for binding in &bindings {
+      helpers += &format!(
+        "    pub {}: {},\n",
+        binding.name,
+        binding.kind.resource_type()
+      );
+    }
+    helpers += "  }\n\n";
+
+    helpers += &format!("  impl<'a> {struct_name}<'a> {{\n");
+    helpers += &format!(
+      "    pub const LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; {}] = [\n",
+      bindings.len()
+    );
+    for binding in &bindings {
+      helpers += &format!(
+        "      wgpu::BindGroupLayoutEntry {{ binding: {}, visibility: {visibility}, ty: {}, count: \
+         None }},\n",
+        binding.binding,
+        binding.kind.binding_type()
+      );
+    }
+    helpers += "    ];\n\n";
+
+    helpers += "    pub fn create_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {\n";
+    helpers += &format!(
+      "      device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {{ label: \
+       Some(\"{struct_name}\"), entries: &Self::LAYOUT_ENTRIES }})\n"
+    );
+    helpers += "    }\n\n";
+
+    helpers += "    pub fn build(self, device: &wgpu::Device, layout: &wgpu::BindGroupLayout) -> \
+                wgpu::BindGroup {\n";
+    helpers += &format!(
+      "      device.create_bind_group(&wgpu::BindGroupDescriptor {{ label: Some(\"{struct_name}\"), \
+       layout, entries: &[\n"
+    );
+    for binding in &bindings {
+      helpers += &format!(
+        "        wgpu::BindGroupEntry {{ binding: {}, resource: {} }},\n",
+        binding.binding,
+        binding.kind.binding_resource(&binding.name)
+      );
+    }
+    helpers += "      ] })\n    }\n  }\n";
+  }
+  helpers += "}\n";
+  Ok(helpers)
+}
+
+///The `wgpu::ShaderStages` expression for the kinds of entry points the shader has
+fn shader_stages(shader_source: &str) -> String {
+  let stages: Vec<_> = [
+    ("@vertex", "VERTEX"),
+    ("@fragment", "FRAGMENT"),
+    ("@compute", "COMPUTE"),
+  ]
+  .into_iter()
+  .filter(|(attribute, _)| shader_source.contains(attribute))
+  .map(|(_, stage)| format!("wgpu::ShaderStages::{stage}"))
+  .collect();
+  match &stages[..] {
+    [] => "wgpu::ShaderStages::NONE".to_string(),
+    [first, others @ ..] => others.iter().fold(first.clone(), |stages, stage| {
+      format!("{stages}.union({stage})")
+    }),
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum BindingParseError {
+  MissingAttribute {
+    name: String,
+    attribute: &'static str,
+  },
+  UnsupportedType {
+    name: String,
+    type_name: String,
+  },
+}
+
+impl Display for BindingParseError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      BindingParseError::MissingAttribute { name, attribute } => {
+        write!(f, "binding {name} is missing the @{attribute} attribute")
+      }
+      BindingParseError::UnsupportedType { name, type_name } => {
+        write!(f, "binding {name} has unsupported type '{type_name}'")
+      }
+    }
+  }
+}
+
+impl Error for BindingParseError {}
+
+#[cfg(test)]
+mod test_bind_groups {
+  use crate::bind_groups::{
+    generate_bind_group_helpers, parse_bindings, Binding, BindingKind, BindingParseError,
+  };
+
+  const SOURCE: &str = r"
+@group(0) @binding(1)
+var t_sampler: sampler;
+@group(0) @binding(0)
+var texture: texture_2d<f32>;
+@binding(0) @group(1)
+var<uniform> camera: Camera;
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+  return textureSample(texture, t_sampler, vec2<f32>());
+}";
+
+  #[test]
+  fn test_parse_bindings() {
+    let bindings = parse_bindings(SOURCE).expect("valid bindings");
+    assert_eq!(3, bindings.len());
+    assert_eq!(
+      Binding {
+        group: 0,
+        binding: 0,
+        name: "texture".to_string(),
+        kind: BindingKind::Texture {
+          sample_type: "wgpu::TextureSampleType::Float { filterable: true }",
+          view_dimension: "D2",
+          multisampled: false,
+        },
+      },
+      bindings[1]
+    );
+    assert_eq!(BindingKind::Uniform, bindings[2].kind);
+    assert_eq!(1, bindings[2].group);
+
+    assert_eq!(
+      Err(BindingParseError::UnsupportedType {
+        name: "image".to_string(),
+        type_name: "texture_storage_2d<rgba8unorm, write>".to_string()
+      }),
+      parse_bindings("@group(0) @binding(0) var image: texture_storage_2d<rgba8unorm, write>;")
+    );
+    assert!(parse_bindings("@group(0) var t_sampler: sampler;").is_err());
+  }
+
+  #[test]
+  fn test_generate_bind_group_helpers() {
+    let helpers = generate_bind_group_helpers(SOURCE).expect("valid bindings");
+    assert!(helpers.contains("pub struct BindGroup0<'a>"));
+    assert!(helpers.contains("pub struct BindGroup1<'a>"));
+    assert!(helpers.contains("pub camera: wgpu::BufferBinding<'a>,"));
+    //bindings are sorted by their index
+    assert!(helpers.find("pub texture").unwrap() < helpers.find("pub t_sampler").unwrap());
+    assert!(helpers.contains("visibility: wgpu::ShaderStages::FRAGMENT,"));
+  }
+}
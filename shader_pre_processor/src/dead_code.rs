@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+
+///A top level item of wgsl code, like a struct, a function or a global variable
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct Item<'a> {
+  ///the code of the item, including the comments and whitespace in front of it
+  source: &'a str,
+  kind: ItemKind,
+  name: Option<String>,
+  ///every identifier used by the item outside of comments
+  identifiers: Vec<String>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum ItemKind {
+  Struct,
+  Function {
+    entry_point: bool,
+  },
+  ///anything else, like constants, bindings or directives, which is always kept
+  Other,
+}
+
+impl<'a> Item<'a> {
+  ///Parses an item from its source and the same source with comments blanked out
+  fn parse(source: &'a str, code: &str) -> Self {
+    let identifiers: Vec<String> = code
+      .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+      .filter(|word| word.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+      .map(|word| word.to_string())
+      .collect();
+
+    let mut kind = ItemKind::Other;
+    let mut name = None;
+    //attributes are identifiers too, so look for the first keyword
+    for (index, identifier) in identifiers.iter().enumerate() {
+      kind = match identifier.as_str() {
+        "struct" => ItemKind::Struct,
+        "fn" => ItemKind::Function {
+          entry_point: identifiers[..index]
+            .iter()
+            .any(|attribute| ["vertex", "fragment", "compute"].contains(&attribute.as_str())),
+        },
+        "const" | "var" | "override" | "alias" | "enable" | "requires" | "diagnostic" => break,
+        _ => continue,
+      };
+      name = identifiers.get(index + 1).cloned();
+      break;
+    }
+
+    Self {
+      source,
+      kind,
+      name,
+      identifiers,
+    }
+  }
+
+  fn is_entry_point(&self) -> bool {
+    self.kind == ItemKind::Function { entry_point: true }
+  }
+}
+
+///Removes every struct and function which isn't reachable from an entry point (`@vertex`,
+/// `@fragment` or `@compute` function) or another kept item. Code without entry points is returned
+/// unchanged since there is nothing to start from.
+pub fn strip_unused_items(shader_source: &str) -> String {
+  let (items, remainder) = split_items(shader_source);
+  if !items.iter().any(Item::is_entry_point) {
+    return shader_source.to_string();
+  }
+
+  let strippable: HashMap<&str, &Item> = items
+    .iter()
+    .filter(|item| item.kind != ItemKind::Other && !item.is_entry_point())
+    .filter_map(|item| Some((item.name.as_deref()?, item)))
+    .collect();
+  let mut reachable: HashSet<&str> = HashSet::new();
+  let mut to_visit: Vec<&Item> = items
+    .iter()
+    .filter(|item| !strippable.contains_key(item.name.as_deref().unwrap_or_default()))
+    .collect();
+  while let Some(item) = to_visit.pop() {
+    for identifier in &item.identifiers {
+      if let Some((&name, &referenced)) = strippable.get_key_value(identifier.as_str()) {
+        if reachable.insert(name) {
+          to_visit.push(referenced);
+        }
+      }
+    }
+  }
+
+  items
+    .iter()
+    .filter(|item| {
+      item
+        .name
+        .as_deref()
+        .is_none_or(|name| !strippable.contains_key(name) || reachable.contains(name))
+    })
+    .map(|item| item.source)
+    .chain([remainder])
+    .collect()
+}
+
+///Splits the code into its top level items and the trailing whitespace and comments
+fn split_items(shader_source: &str) -> (Vec<Item<'_>>, &str) {
+  let code = blank_comments(shader_source);
+  let mut items: Vec<Item> = vec![];
+  let mut item_start = 0;
+  let mut depth = 0usize;
+  for (index, c) in code.char_indices() {
+    match c {
+      '{' => depth += 1,
+      '}' => depth = depth.saturating_sub(1),
+      _ => {}
+    }
+    if depth > 0 || !(c == ';' || c == '}') {
+      continue;
+    }
+
+    let end = index + 1;
+    match items.last_mut() {
+      //a semicolon after a struct belongs to the struct
+      Some(previous) if c == ';' && code[item_start..index].trim().is_empty() => {
+        let previous_start = item_start - previous.source.len();
+        previous.source = &shader_source[previous_start..end];
+      }
+      _ => items.push(Item::parse(
+        &shader_source[item_start..end],
+        &code[item_start..end],
+      )),
+    }
+    item_start = end;
+  }
+  (items, &shader_source[item_start..])
+}
+
+///Replaces the content of comments with spaces, keeping the byte positions of everything else
+pub(crate) fn blank_comments(shader_source: &str) -> String {
+  let mut code = String::with_capacity(shader_source.len());
+  let mut chars = shader_source.chars().peekable();
+  let blank = |code: &mut String, c: char| {
+    code.extend((0..c.len_utf8()).map(|_| if c == '\n' { '\n' } else { ' ' }));
+  };
+  while let Some(c) = chars.next() {
+    match (c, chars.peek()) {
+      ('/', Some('/')) => {
+        blank(&mut code, c);
+        while let Some(c) = chars.next_if(|&c| c != '\n') {
+          blank(&mut code, c);
+        }
+      }
+      ('/', Some('*')) => {
+        blank(&mut code, c);
+        let mut previous = ' ';
+        for c in chars.by_ref() {
+          blank(&mut code, c);
+          if previous == '*' && c == '/' {
+            break;
+          }
+          previous = c;
+        }
+      }
+      _ => code.push(c),
+    }
+  }
+  code
+}
+
+#[cfg(test)]
+mod test_dead_code {
+  use crate::dead_code::strip_unused_items;
+
+  #[test]
+  fn test_strip_unused_items() {
+    let source = r"const SCALE: f32 = 2.0;
+
+struct Unused {
+  x: f32,
+};
+
+//the position of a vertex
+struct Vertex {
+  @location(0) position: vec2<f32>,
+}
+
+fn scale(v: vec2<f32>) -> vec2<f32> {
+  return v * SCALE;
+}
+
+fn unused() -> Unused {
+  // scale() is used elsewhere
+  return Unused(1.0);
+}
+
+@vertex
+fn vs_main(in: Vertex) -> @builtin(position) vec4<f32> {
+  /* unused() isn't called */
+  return vec4<f32>(scale(in.position), 0.0, 1.0);
+}
+";
+    let stripped = strip_unused_items(source);
+    assert!(!stripped.contains("Unused"));
+    assert!(!stripped.contains("fn unused"));
+    assert!(stripped.contains("//the position of a vertex\nstruct Vertex {"));
+    assert!(stripped.contains("fn scale"));
+    assert!(stripped.starts_with("const SCALE"));
+    assert!(stripped.ends_with("}\n"));
+
+    let library = "struct Unused {\n  x: f32,\n}\n";
+    assert_eq!(library, strip_unused_items(library));
+  }
+}
@@ -1,12 +1,37 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
+use crate::find_statement_usages;
+use crate::statement_handler::{StatementHandler, StatementHandlerError};
 use crate::type_analysis::defined_type::DefinedType;
 
-#[derive(Debug, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct PreProcessingEnvironment {
-  primitive_and_native_types: HashMap<String, DefinedType>,
+  primitive_and_native_types: BTreeMap<String, DefinedType>,
+  ///the wgsl declarations of constants shared with every shader, by name
+  constants: BTreeMap<String, String>,
+  ///custom statements, applied in the order they were added
+  statement_handlers: Vec<Arc<dyn StatementHandler>>,
 }
 
+///Statement handlers are compared by their name
+impl PartialEq for PreProcessingEnvironment {
+  fn eq(&self, other: &Self) -> bool {
+    let handler_names = |environment: &Self| {
+      environment
+        .statement_handlers
+        .iter()
+        .map(|handler| handler.name().to_string())
+        .collect::<Vec<_>>()
+    };
+    self.primitive_and_native_types == other.primitive_and_native_types
+      && self.constants == other.constants
+      && handler_names(self) == handler_names(other)
+  }
+}
+
+impl Eq for PreProcessingEnvironment {}
+
 ///A native type is a type which is native in wgsl but can not be translated by wgsl_to_wgpu, like mat4x4<f32>.
 /// Every type added which is not a [`PrimitiveType`] will be considered native.  
 impl PreProcessingEnvironment {
@@ -37,7 +62,173 @@ impl PreProcessingEnvironment {
     );
   }
 
-  pub fn types(&self) -> &HashMap<String, DefinedType> {
+  pub fn types(&self) -> &BTreeMap<String, DefinedType> {
     &self.primitive_and_native_types
   }
+
+  pub fn with_const<S, V>(mut self, name: S, value: V) -> Self
+  where
+    S: ToString,
+    V: WgslLiteral,
+  {
+    self.define_const(name, value);
+    self
+  }
+
+  ///Defines a constant which gets declared at the top of every processed shader, so values like the
+  /// arena dimensions only need to be defined once on the rust side
+  pub fn define_const<S, V>(&mut self, name: S, value: V)
+  where
+    S: ToString,
+    V: WgslLiteral,
+  {
+    let name = name.to_string();
+    let declaration = format!(
+      "const {}: {} = {};",
+      name,
+      V::WGSL_TYPE,
+      value.wgsl_literal()
+    );
+    self.constants.insert(name, declaration);
+  }
+
+  ///The declarations of all defined constants, sorted by name
+  pub fn const_declarations(&self) -> impl Iterator<Item = &str> {
+    self.constants.values().map(String::as_str)
+  }
+
+  pub fn with_statement_handler<H>(mut self, handler: H) -> Self
+  where
+    H: StatementHandler + 'static,
+  {
+    self.add_statement_handler(handler);
+    self
+  }
+
+  ///Registers a custom statement. Handlers see the source of every processed file, included ones
+  /// too, before the built-in statements get processed.
+  pub fn add_statement_handler<H>(&mut self, handler: H)
+  where
+    H: StatementHandler + 'static,
+  {
+    self.statement_handlers.push(Arc::new(handler));
+  }
+
+  pub fn statement_handlers(&self) -> &[Arc<dyn StatementHandler>] {
+    &self.statement_handlers
+  }
+
+  ///Lets every registered handler whose statement is used transform the shader source
+  pub fn handle_custom_statements(
+    &self,
+    shader_source: &str,
+  ) -> Result<String, StatementHandlerError> {
+    let mut shader_source = shader_source.to_string();
+    for handler in &self.statement_handlers {
+      let usages: Vec<_> = find_statement_usages(handler.name(), &shader_source).collect();
+      if !usages.is_empty() {
+        shader_source = handler.handle(&usages, &shader_source)?;
+      }
+    }
+    Ok(shader_source)
+  }
+}
+
+///A rust value which can be written as wgsl literal
+pub trait WgslLiteral {
+  const WGSL_TYPE: &'static str;
+
+  fn wgsl_literal(&self) -> String;
+}
+
+impl WgslLiteral for u32 {
+  const WGSL_TYPE: &'static str = "u32";
+
+  fn wgsl_literal(&self) -> String {
+    format!("{self}u")
+  }
+}
+
+impl WgslLiteral for i32 {
+  const WGSL_TYPE: &'static str = "i32";
+
+  fn wgsl_literal(&self) -> String {
+    format!("{self}i")
+  }
+}
+
+impl WgslLiteral for f32 {
+  const WGSL_TYPE: &'static str = "f32";
+
+  fn wgsl_literal(&self) -> String {
+    //debug formatting always includes a decimal point or an exponent
+    format!("{self:?}f")
+  }
+}
+
+impl WgslLiteral for bool {
+  const WGSL_TYPE: &'static str = "bool";
+
+  fn wgsl_literal(&self) -> String {
+    self.to_string()
+  }
+}
+
+#[cfg(test)]
+mod test_environment {
+  use crate::environment::PreProcessingEnvironment;
+  use crate::statement_handler::{StatementHandler, StatementHandlerError};
+  use crate::StatementUsage;
+
+  ///`#repeat <n>` repeats the next line n times
+  #[derive(Debug)]
+  struct Repeat;
+
+  impl StatementHandler for Repeat {
+    fn name(&self) -> &str {
+      "repeat"
+    }
+
+    fn handle(
+      &self,
+      usages: &[StatementUsage],
+      shader_source: &str,
+    ) -> Result<String, StatementHandlerError> {
+      let mut lines: Vec<String> = shader_source.lines().map(str::to_string).collect();
+      for usage in usages.iter().rev() {
+        let times: usize = usage
+          .arg_str
+          .parse()
+          .map_err(|e| StatementHandlerError::new(usage, e))?;
+        let index = usage.line_nr.get() - 1;
+        let repeated = lines.get(index + 1).cloned().unwrap_or_default();
+        lines.splice(index..index + 2, vec![repeated; times]);
+      }
+      Ok(lines.join("\n"))
+    }
+  }
+
+  #[test]
+  fn test_statement_handler() {
+    let environment = PreProcessingEnvironment::new().with_statement_handler(Repeat);
+    assert_eq!(
+      "a\nb\nb\nb\nc",
+      environment
+        .handle_custom_statements("a\n#repeat 3\nb\nc")
+        .expect("valid usage")
+    );
+    assert_eq!(
+      "untouched\n#include foo.wgsl",
+      environment
+        .handle_custom_statements("untouched\n#include foo.wgsl")
+        .expect("no usage")
+    );
+
+    let error = environment
+      .handle_custom_statements("a\n\n#repeat often\nb")
+      .expect_err("invalid argument");
+    assert_eq!(3, error.line_nr.get());
+
+    assert_ne!(PreProcessingEnvironment::new(), environment);
+  }
 }
@@ -0,0 +1,67 @@
+use crate::dead_code::blank_comments;
+
+///The indentation of one block level in formatted code
+pub const INDENT: &str = "  ";
+
+///Formats composed wgsl code so the output only depends on the code itself, not on how the included
+/// files were indented: every line is re-indented by its block depth, trailing whitespace and
+/// repeated or leading empty lines are removed and the code ends with exactly one line break.
+pub fn format_wgsl(shader_source: &str) -> String {
+  let code = blank_comments(shader_source);
+  let mut formatted = String::with_capacity(shader_source.len());
+  let mut depth = 0usize;
+  let mut previous_empty = true;
+  for (line, code_line) in shader_source.lines().zip(code.lines()) {
+    let line = line.trim();
+    if line.is_empty() {
+      if !previous_empty {
+        formatted.push('\n');
+      }
+      previous_empty = true;
+      continue;
+    }
+
+    let code_line = code_line.trim();
+    let leading_closings = code_line
+      .chars()
+      .take_while(|&c| c == '}' || c == ')' || c.is_whitespace())
+      .filter(|&c| c == '}' || c == ')')
+      .count();
+    formatted += &INDENT.repeat(depth.saturating_sub(leading_closings));
+    formatted += line;
+    formatted.push('\n');
+    previous_empty = false;
+
+    for c in code_line.chars() {
+      match c {
+        '{' | '(' => depth += 1,
+        '}' | ')' => depth = depth.saturating_sub(1),
+        _ => {}
+      }
+    }
+  }
+
+  let trimmed_len = formatted.trim_end().len();
+  formatted.truncate(trimmed_len);
+  formatted.push('\n');
+  formatted
+}
+
+#[cfg(test)]
+mod test_formatter {
+  use crate::formatter::format_wgsl;
+
+  #[test]
+  fn test_format_wgsl() {
+    let source = "\n\nstruct Vertex {   \n      @location(0) position: vec2<f32>,\n}\n\n\n\n\
+                  @vertex\nfn vs_main(\n in: Vertex,\n) -> @builtin(position) vec4<f32> {\n    \
+                  // a comment with an unmatched {\n    return vec4<f32>(in.position,\n0.0, 1.0);\n\
+                  }\n\n";
+    let expected = "struct Vertex {\n  @location(0) position: vec2<f32>,\n}\n\n@vertex\n\
+                    fn vs_main(\n  in: Vertex,\n) -> @builtin(position) vec4<f32> {\n  \
+                    // a comment with an unmatched {\n  return vec4<f32>(in.position,\n    0.0, \
+                    1.0);\n}\n";
+    assert_eq!(expected, format_wgsl(source));
+    assert_eq!(expected, format_wgsl(expected));
+  }
+}
@@ -1,5 +1,8 @@
+use crate::dead_code::strip_unused_items;
 use crate::environment::PreProcessingEnvironment;
+use crate::formatter::format_wgsl;
 use crate::primitive_composition::SimpleStructNameResolver;
+use crate::source::{normalize_path, Source};
 use crate::struct_definition::StructDefinition;
 use crate::type_analysis::named_type::NamedType;
 use crate::type_analysis::source_location::SourceLocation;
@@ -9,20 +12,28 @@ use primitive_composition::PrimitiveComposition;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::io;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
-use std::{fs, io};
 use struct_layout::StructLayout;
-use type_analysis::source_location::Declaration;
-use type_analysis::TypeDefinitionParseError;
+use type_analysis::source_location::{Declaration, DeclarationInfo};
+use type_analysis::{parse_type_declarations, TypeDefinitionParseError};
+use vertex_layout::generate_vertex_repr;
 
+pub mod bind_groups;
+pub mod dead_code;
 pub mod environment;
+pub mod formatter;
 pub mod memory_layout;
+pub mod output;
 pub mod pre_processing_cache;
 pub mod primitive_composition;
+pub mod source;
+pub mod statement_handler;
 pub mod struct_definition;
 pub mod struct_layout;
 pub mod type_analysis;
+pub mod vertex_layout;
 
 ///The prefix of every pre-processor statement
 pub const STMT_PREFIX: &str = "#";
@@ -49,6 +60,11 @@ pub enum Statement {
   /// FooRepr would be generated
   #[assoc(as_str = "data")]
   Data,
+  ///\[repr_name] - Must annotate a struct used as vertex input. Generates a representation like
+  /// [`Statement::Data`] together with the `wgpu::VertexBufferLayout` for it. Every member which is
+  /// no builtin needs a location.
+  #[assoc(as_str = "vertex")]
+  Vertex,
 }
 
 impl Statement {
@@ -64,20 +80,28 @@ impl Statement {
     &self,
     shader_source: &'a str,
   ) -> impl Iterator<Item = StatementUsage> + 'a {
-    let pattern = format!("{STMT_PREFIX}{}", self.as_str());
-    shader_source
-      .lines()
-      .enumerate()
-      .filter_map(move |(line_index, line)| {
-        line.trim_start().strip_prefix(&pattern).map(|arg_str| {
-          let arg_str = arg_str.trim().to_string();
-          let line_nr = unsafe { NonZeroUsize::new_unchecked(line_index + 1) };
-          StatementUsage { line_nr, arg_str }
-        })
-      })
+    find_statement_usages(self.as_str(), shader_source)
   }
 }
 
+///Finds the usages of a statement by its name, which also works for custom statements
+pub fn find_statement_usages<'a>(
+  statement_name: &str,
+  shader_source: &'a str,
+) -> impl Iterator<Item = StatementUsage> + 'a {
+  let pattern = format!("{STMT_PREFIX}{statement_name}");
+  shader_source
+    .lines()
+    .enumerate()
+    .filter_map(move |(line_index, line)| {
+      line.trim_start().strip_prefix(&pattern).map(|arg_str| {
+        let arg_str = arg_str.trim().to_string();
+        let line_nr = unsafe { NonZeroUsize::new_unchecked(line_index + 1) };
+        StatementUsage { line_nr, arg_str }
+      })
+    })
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct StatementUsage {
   pub line_nr: NonZeroUsize,
@@ -98,6 +122,28 @@ pub fn pre_process_shader<P, C>(
   pre_processing_cache: &mut PreProcessingCache,
   environment: &PreProcessingEnvironment,
 ) -> Result<Option<String>, PreProcessingError>
+where
+  P: AsRef<Path>,
+  C: Into<ProcessContext>,
+{
+  pre_process_source(
+    &Source::FileSystem,
+    shader_file,
+    context,
+    pre_processing_cache,
+    environment,
+  )
+}
+
+///Like [`pre_process_shader`], but reads the shader file and its includes from the given
+/// [`Source`]
+pub fn pre_process_source<P, C>(
+  source: &Source,
+  shader_file: P,
+  context: C,
+  pre_processing_cache: &mut PreProcessingCache,
+  environment: &PreProcessingEnvironment,
+) -> Result<Option<String>, PreProcessingError>
 where
   P: AsRef<Path>,
   C: Into<ProcessContext>,
@@ -105,10 +151,21 @@ where
   let shader_file = shader_file.as_ref();
   let context = context.into();
 
-  let orig_shader_source = fs::read_to_string(shader_file).map_err(|e| PreProcessingError::IO {
-    error: e,
-    file: shader_file.to_path_buf(),
-  })?;
+  let orig_shader_source = source
+    .read(shader_file)
+    .map_err(|e| PreProcessingError::IO {
+      error: e,
+      file: shader_file.to_path_buf(),
+    })?;
+  let orig_shader_source = environment
+    .handle_custom_statements(&orig_shader_source)
+    .map_err(|e| {
+      let line = orig_shader_source
+        .lines()
+        .nth(e.line_nr.get() - 1)
+        .unwrap_or_default();
+      PreProcessingError::statement(shader_file, e.line_nr.get(), line, e.detail_message)
+    })?;
 
   //TODO first handle imports, after that analyse source code
   let mut line_replacements: HashMap<usize, String> = HashMap::new();
@@ -125,7 +182,21 @@ where
     }
   }
 
+  //make the structs of this file known to statements like #data
+  for (source_location, declaration) in parse_type_declarations(&orig_shader_source, shader_file) {
+    if let Ok(declaration) = declaration {
+      pre_processing_cache.insert(DeclarationInfo::new(source_location).with(declaration));
+    }
+  }
+
   let mut source_code = String::new();
+  //declare shared constants once, at the top of the shader which is processed standalone
+  if context == ProcessContext::Standalone {
+    for declaration in environment.const_declarations() {
+      source_code += &format!("{declaration}\n");
+    }
+  }
+
   for (line_index, line) in orig_shader_source.lines().enumerate() {
     let line_nr = line_index + 1;
 
@@ -137,7 +208,10 @@ where
     }
 
     if Statement::IncludeOnlyOnce.match_line(line).is_some() {
-      if pre_processing_cache.includes.contains(shader_file) {
+      if !pre_processing_cache
+        .includes
+        .insert(normalize_path(shader_file))
+      {
         return Ok(None);
       }
 
@@ -151,7 +225,8 @@ where
         .expect("can't access shader directory")
         .join(to_include);
 
-      if let Some(include_code) = pre_process_shader(
+      if let Some(include_code) = pre_process_source(
+        source,
         include_path,
         ProcessContext::Include,
         pre_processing_cache,
@@ -163,12 +238,37 @@ where
       continue;
     }
 
+    if let Some(stmt_info) = Statement::Vertex.match_line(line) {
+      let declaration = parse_type_declarations(&orig_shader_source, shader_file)
+        .into_iter()
+        .find(|(source_location, _)| source_location.line_nr == line_nr + 1)
+        .ok_or(PreProcessingError::statement(
+          shader_file,
+          line_nr,
+          line,
+          "statement may only annotate a struct",
+        ))?
+        .1
+        .map_err(|e| PreProcessingError::statement(shader_file, line_nr + 1, line, e))?;
+
+      let repr_name = Some(stmt_info.arg_str.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or(format!("{}Repr", declaration.name()));
+      let vertex_repr = generate_vertex_repr(&declaration, &repr_name)
+        .map_err(|e| PreProcessingError::statement(shader_file, line_nr, line, e))?;
+      pre_processing_cache.generated_code.push(vertex_repr);
+
+      continue;
+    }
+
     if let Some(stmt_info) = Statement::Data.match_line(line) {
       //make sure next line has definition
       let mut declaration = pre_processing_cache
         .structs()
         .values()
-        .find(|declaration| declaration.info.source_location.line_nr == line_nr + 1)
+        .find(|declaration| {
+          declaration.info.source_location == SourceLocation::at(shader_file, line_nr + 1)
+        })
         .ok_or(PreProcessingError::statement(
           shader_file,
           line_nr,
@@ -196,6 +296,11 @@ where
     source_code += &format!("{line}\n");
   }
 
+  //includes are done once the standalone shader is complete, so everything unused is known now
+  if context == ProcessContext::Standalone {
+    source_code = format_wgsl(&strip_unused_items(&source_code));
+  }
+
   Ok(Some(source_code))
 }
 
@@ -209,7 +314,7 @@ fn create_primitive_composition(
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct PreProcessingResult {
   //TODO result -> Result<ProcessedSource, Vec<PreProcessingError>>
-  pub warnings: Vec<PreProcessingWarning>
+  pub warnings: Vec<PreProcessingWarning>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -220,7 +325,11 @@ pub struct PreProcessingWarning {
 
 impl Display for PreProcessingWarning {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    write!(f, "[WARNING] {} (at {})", self.detail_message, self.source_location)
+    write!(
+      f,
+      "[WARNING] {} (at {})",
+      self.detail_message, self.source_location
+    )
   }
 }
 
@@ -244,8 +353,8 @@ pub enum PreProcessingError {
     line: String,
     detail_message: String,
   },
-  InvalidStructDefinition(Declaration<TypeDefinitionParseError>),
-  StructNameDuplication(Declaration<StructLayout>),
+  InvalidStructDefinition(Box<Declaration<TypeDefinitionParseError>>),
+  StructNameDuplication(Box<Declaration<StructLayout>>),
 }
 
 impl PreProcessingError {
@@ -322,8 +431,34 @@ where
 mod test {
   use crate::environment::PreProcessingEnvironment;
   use crate::pre_processing_cache::PreProcessingCache;
-  use crate::{pre_process_shader, ProcessContext, Statement, StatementUsage};
+  use crate::source::Source;
+  use crate::{
+    pre_process_shader, pre_process_source, PreProcessingError, ProcessContext, Statement,
+    StatementUsage,
+  };
+  use std::io::ErrorKind;
   use std::num::NonZeroUsize;
+  use std::path::Path;
+
+  ///A shader including the same utility twice, once directly and once via another include
+  fn source_with_includes() -> Source {
+    Source::in_memory()
+      .with_file(
+        "shader/main.wgsl",
+        "#include util/math.wgsl\n#include util/shapes.wgsl\n\n@fragment\nfn fs_main() -> \
+         @location(0) vec4<f32> {\n  return circle(half(1.0));\n}\n",
+      )
+      .with_file(
+        "shader/util/math.wgsl",
+        "#no-standalone\n#once\nfn half(x: f32) -> vec4<f32> {\n  return vec4<f32>(x / \
+         2.0);\n}\n",
+      )
+      .with_file(
+        "shader/util/shapes.wgsl",
+        "#no-standalone\n#include ../util/./math.wgsl\nfn circle(v: vec4<f32>) -> vec4<f32> \
+         {\n  return v;\n}\n",
+      )
+  }
 
   #[test]
   fn test_pre_processing() {
@@ -336,6 +471,149 @@ mod test {
     .expect("failed to pre-process valid shader code");
   }
 
+  #[test]
+  fn test_strips_unused_includes() {
+    let source_code = pre_process_shader(
+      env!("CARGO_MANIFEST_DIR").to_string() + "/../gui/resources/shader/texture_shader.wgsl",
+      ProcessContext::Standalone,
+      &mut PreProcessingCache::default(),
+      &PreProcessingEnvironment::new(),
+    )
+    .expect("failed to pre-process valid shader code")
+    .expect("shader is standalone");
+    //the coordinate types are included but not used by the texture shader
+    assert!(!source_code.contains("struct Pixel"));
+    assert!(source_code.contains("struct VertexOutput"));
+    assert!(source_code.contains("var t_sampler: sampler;"));
+  }
+
+  #[test]
+  fn test_shared_constants() {
+    let environment = PreProcessingEnvironment::new()
+      .with_const("NUM_COLUMNS", 12u32)
+      .with_const("RING_WIDTH", 0.5f32)
+      .with_const("DEBUG", false);
+    let source_code = pre_process_shader(
+      env!("CARGO_MANIFEST_DIR").to_string() + "/../gui/resources/shader/texture_shader.wgsl",
+      ProcessContext::Standalone,
+      &mut PreProcessingCache::default(),
+      &environment,
+    )
+    .expect("failed to pre-process valid shader code")
+    .expect("shader is standalone");
+    assert!(source_code.starts_with(
+      "const DEBUG: bool = false;\nconst NUM_COLUMNS: u32 = 12u;\nconst RING_WIDTH: f32 = 0.5f;\n"
+    ));
+    //included files don't declare the constants again
+    assert_eq!(1, source_code.matches("const NUM_COLUMNS").count());
+  }
+
+  #[test]
+  fn test_vertex_statement() {
+    let mut pre_processing_cache = PreProcessingCache::default();
+    let source_code = pre_process_shader(
+      env!("CARGO_MANIFEST_DIR").to_string() + "/../gui/resources/shader/shader.wgsl",
+      ProcessContext::Standalone,
+      &mut pre_processing_cache,
+      &PreProcessingEnvironment::new(),
+    )
+    .expect("failed to pre-process valid shader code")
+    .expect("shader is standalone");
+    assert!(!source_code.contains("#vertex"));
+    assert!(source_code.starts_with("struct VertexInput {"));
+    assert_eq!(1, pre_processing_cache.generated_code.len());
+    assert!(pre_processing_cache.generated_code[0].contains("pub struct VertexInputRepr {"));
+  }
+
+  #[test]
+  fn test_in_memory_includes() {
+    let source = source_with_includes();
+    let mut pre_processing_cache = PreProcessingCache::default();
+    let source_code = pre_process_source(
+      &source,
+      "shader/main.wgsl",
+      ProcessContext::Standalone,
+      &mut pre_processing_cache,
+      &PreProcessingEnvironment::new(),
+    )
+    .expect("failed to pre-process valid shader code")
+    .expect("shader is standalone");
+    assert!(!source_code.contains('#'));
+    assert!(source_code.contains("fn circle"));
+    //included twice, but the second include is skipped because of #once
+    assert_eq!(1, source_code.matches("fn half").count());
+    assert_eq!(1, pre_processing_cache.includes.len());
+
+    assert_eq!(
+      None,
+      pre_process_source(
+        &source,
+        "shader/util/shapes.wgsl",
+        ProcessContext::Standalone,
+        &mut PreProcessingCache::default(),
+        &PreProcessingEnvironment::new(),
+      )
+      .expect("no-standalone file is valid")
+    );
+  }
+
+  #[test]
+  fn test_data_statement() {
+    let source = Source::in_memory()
+      .with_file(
+        "data.wgsl",
+        "#no-standalone\n#data\nstruct Foo {\n  x: f32,\n}\n",
+      )
+      .with_file("no_struct.wgsl", "#data FooRepr\nfn foo() {}\n");
+    pre_process_source(
+      &source,
+      "data.wgsl",
+      ProcessContext::Include,
+      &mut PreProcessingCache::default(),
+      &PreProcessingEnvironment::new(),
+    )
+    .expect("#data annotates a struct");
+
+    let error = pre_process_source(
+      &source,
+      "no_struct.wgsl",
+      ProcessContext::Standalone,
+      &mut PreProcessingCache::default(),
+      &PreProcessingEnvironment::new(),
+    )
+    .expect_err("#data doesn't annotate a struct");
+    assert!(
+      matches!(error, PreProcessingError::Statement { line_nr: 1, ref line, .. } if line == "#data FooRepr")
+    );
+  }
+
+  #[test]
+  fn test_pre_processing_errors() {
+    let source = Source::in_memory()
+      .with_file("shader/main.wgsl", "#include missing.wgsl\n")
+      .with_file("shader/vertex.wgsl", "#vertex\nfn vs_main() {}\n");
+    let pre_process = |shader_file: &str| {
+      pre_process_source(
+        &source,
+        shader_file,
+        ProcessContext::Standalone,
+        &mut PreProcessingCache::default(),
+        &PreProcessingEnvironment::new(),
+      )
+    };
+
+    let Err(PreProcessingError::IO { error, file }) = pre_process("shader/main.wgsl") else {
+      panic!("missing include should be an IO error");
+    };
+    assert_eq!(ErrorKind::NotFound, error.kind());
+    assert_eq!(Path::new("shader/missing.wgsl"), file);
+
+    assert!(matches!(
+      pre_process("shader/vertex.wgsl"),
+      Err(PreProcessingError::Statement { line_nr: 1, .. })
+    ));
+  }
+
   #[test]
   fn test_find_statement_usages() {
     let source = "#include foo\n  #include bar\n//#include var";
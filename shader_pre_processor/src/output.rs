@@ -0,0 +1,73 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+///Setting this environment variable while building makes [`emit_module`] write the generated code
+/// into the source tree instead of the include shim, for IDEs which can't expand `include!`
+pub const WRITE_TO_SRC_VAR: &str = "SHADER_PRE_PROCESSOR_WRITE_TO_SRC";
+
+///Writes a module generated by a build script to `OUT_DIR` and makes the module file at
+/// `module_path` include it, so generated code never dirties the working tree. The module file is
+/// only touched if its content changes, which keeps rustfmt and IDEs from racing the build script.
+pub fn emit_module<P>(module_path: P, source: &str) -> io::Result<PathBuf>
+where
+  P: AsRef<Path>,
+{
+  let module_path = module_path.as_ref();
+  let file_name = module_path
+    .file_name()
+    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "module path has no file name"))?;
+  let out_dir = env::var_os("OUT_DIR")
+    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "OUT_DIR is not set"))?;
+  let generated_path = Path::new(&out_dir).join(file_name);
+  write_if_changed(&generated_path, source)?;
+
+  println!("cargo::rerun-if-env-changed={WRITE_TO_SRC_VAR}");
+  if env::var_os(WRITE_TO_SRC_VAR).is_some() {
+    write_if_changed(module_path, source)?;
+  } else {
+    write_if_changed(module_path, &include_shim(&file_name.to_string_lossy()))?;
+  }
+  Ok(generated_path)
+}
+
+///The content of a module file which includes the generated file with the given name from `OUT_DIR`
+pub fn include_shim(file_name: &str) -> String {
+  format!(
+    "//generated by build.rs, set {WRITE_TO_SRC_VAR} while building to see the generated code here\n\
+     include!(concat!(env!(\"OUT_DIR\"), \"/{file_name}\"));\n"
+  )
+}
+
+///Writes the file unless it already has the given content. Returns whether it has been written.
+pub fn write_if_changed<P>(path: P, content: &str) -> io::Result<bool>
+where
+  P: AsRef<Path>,
+{
+  let path = path.as_ref();
+  if fs::read_to_string(path).is_ok_and(|current| current == content) {
+    return Ok(false);
+  }
+  fs::write(path, content)?;
+  Ok(true)
+}
+
+#[cfg(test)]
+mod test_output {
+  use std::env;
+  use std::fs;
+
+  use crate::output::{include_shim, write_if_changed};
+
+  #[test]
+  fn test_write_if_changed() {
+    let path = env::temp_dir().join(format!("spp_output_{}.rs", std::process::id()));
+    let shim = include_shim("shader.rs");
+    assert!(shim.ends_with("include!(concat!(env!(\"OUT_DIR\"), \"/shader.rs\"));\n"));
+    assert!(write_if_changed(&path, &shim).expect("failed to write"));
+    assert!(!write_if_changed(&path, &shim).expect("failed to write"));
+    assert!(write_if_changed(&path, "pub mod shader {}\n").expect("failed to write"));
+    let _ = fs::remove_file(&path);
+  }
+}
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
@@ -9,8 +9,11 @@ use crate::type_analysis::source_location::Declaration;
 
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct PreProcessingCache {
-  pub includes: HashSet<PathBuf>,
-  struct_layouts: HashMap<String, Declaration<DeclaredType>>,
+  pub includes: BTreeSet<PathBuf>,
+  ///Rust code generated by statements like `#vertex`, which belongs into the module of the processed
+  /// shader
+  pub generated_code: Vec<String>,
+  struct_layouts: BTreeMap<String, Declaration<DeclaredType>>,
 }
 
 impl PreProcessingCache {
@@ -18,11 +21,11 @@ impl PreProcessingCache {
     Self::default()
   }
   
-  pub fn structs(&self) -> &HashMap<String, Declaration<DeclaredType>> {
+  pub fn structs(&self) -> &BTreeMap<String, Declaration<DeclaredType>> {
     &self.struct_layouts
   }
 
-  pub fn structs_mut(&mut self) -> &mut HashMap<String, Declaration<DeclaredType>> {
+  pub fn structs_mut(&mut self) -> &mut BTreeMap<String, Declaration<DeclaredType>> {
     &mut self.struct_layouts
   }
 
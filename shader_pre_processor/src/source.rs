@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+///Where the pre-processor reads shader files from
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub enum Source {
+  #[default]
+  FileSystem,
+  ///Shader files kept in memory by their path, so pre-processing can be tested without touching
+  /// the file system
+  InMemory(BTreeMap<PathBuf, String>),
+}
+
+impl Source {
+  pub fn in_memory() -> Self {
+    Self::InMemory(BTreeMap::new())
+  }
+
+  ///Adds an in-memory file, turning a [`Source::FileSystem`] into an in-memory source
+  pub fn with_file<P, S>(self, path: P, content: S) -> Self
+  where
+    P: AsRef<Path>,
+    S: ToString,
+  {
+    let mut files = match self {
+      Source::FileSystem => BTreeMap::new(),
+      Source::InMemory(files) => files,
+    };
+    files.insert(normalize_path(path), content.to_string());
+    Self::InMemory(files)
+  }
+
+  pub fn read<P>(&self, path: P) -> io::Result<String>
+  where
+    P: AsRef<Path>,
+  {
+    match self {
+      Source::FileSystem => fs::read_to_string(path),
+      Source::InMemory(files) => files
+        .get(&normalize_path(path))
+        .cloned()
+        .ok_or(io::Error::from(io::ErrorKind::NotFound)),
+    }
+  }
+}
+
+///Resolves `.` and `..` components without accessing the file system, so the same file is always
+/// referred to by the same path, no matter from where it was included
+pub fn normalize_path<P>(path: P) -> PathBuf
+where
+  P: AsRef<Path>,
+{
+  let mut normalized = PathBuf::new();
+  for component in path.as_ref().components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        if !matches!(
+          normalized.components().next_back(),
+          Some(Component::Normal(_))
+        ) {
+          normalized.push(component);
+        } else {
+          normalized.pop();
+        }
+      }
+      _ => normalized.push(component),
+    }
+  }
+  normalized
+}
+
+#[cfg(test)]
+mod test_source {
+  use std::io::ErrorKind;
+  use std::path::Path;
+
+  use crate::source::{normalize_path, Source};
+
+  #[test]
+  fn test_in_memory_source() {
+    assert_eq!(
+      Path::new("shader/util/coordinates.wgsl"),
+      normalize_path("shader/./lib/../util/coordinates.wgsl")
+    );
+    assert_eq!(
+      Path::new("../util.wgsl"),
+      normalize_path("shader/../../util.wgsl")
+    );
+
+    let source = Source::in_memory().with_file("shader/util.wgsl", "fn util() {}");
+    assert_eq!(
+      "fn util() {}",
+      source
+        .read("shader/lib/../util.wgsl")
+        .expect("file is in memory")
+    );
+    assert_eq!(
+      ErrorKind::NotFound,
+      source.read("shader/missing.wgsl").unwrap_err().kind()
+    );
+  }
+}
@@ -0,0 +1,49 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::num::NonZeroUsize;
+
+use crate::StatementUsage;
+
+///A custom pre-processor statement which can be registered at a
+/// [`PreProcessingEnvironment`](crate::environment::PreProcessingEnvironment), so consumers can
+/// experiment with own directives without touching the built-in [`Statement`](crate::Statement)s.
+pub trait StatementHandler: Debug + Send + Sync {
+  ///The name of the statement following the [`STMT_PREFIX`](crate::STMT_PREFIX), like
+  /// `ifdef_platform` for `#ifdef_platform`
+  fn name(&self) -> &str;
+
+  ///Transforms the source of a shader which uses the statement at least once. The returned source
+  /// replaces the shader source before the built-in statements get processed, so it must no
+  /// longer contain the usages of the statement.
+  fn handle(
+    &self,
+    usages: &[StatementUsage],
+    shader_source: &str,
+  ) -> Result<String, StatementHandlerError>;
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct StatementHandlerError {
+  pub line_nr: NonZeroUsize,
+  pub detail_message: String,
+}
+
+impl StatementHandlerError {
+  pub fn new<S>(usage: &StatementUsage, detail_message: S) -> Self
+  where
+    S: ToString,
+  {
+    Self {
+      line_nr: usage.line_nr,
+      detail_message: detail_message.to_string(),
+    }
+  }
+}
+
+impl Display for StatementHandlerError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} (in line {})", self.detail_message, self.line_nr)
+  }
+}
+
+impl Error for StatementHandlerError {}
@@ -0,0 +1,218 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::type_analysis::named_type::NamedType;
+use crate::type_analysis::type_declaration::TypeDeclaration;
+
+///Returns the `wgpu::VertexFormat` variant and the rust equivalent of a wgsl type which may be used
+/// as vertex attribute
+pub fn vertex_format(type_name: &str) -> Option<(&'static str, &'static str)> {
+  Some(match type_name {
+    "f32" => ("Float32", "f32"),
+    "vec2<f32>" => ("Float32x2", "glam::Vec2"),
+    "vec3<f32>" => ("Float32x3", "glam::Vec3"),
+    "vec4<f32>" => ("Float32x4", "glam::Vec4"),
+    "u32" => ("Uint32", "u32"),
+    "vec2<u32>" => ("Uint32x2", "glam::UVec2"),
+    "vec3<u32>" => ("Uint32x3", "glam::UVec3"),
+    "vec4<u32>" => ("Uint32x4", "glam::UVec4"),
+    "i32" => ("Sint32", "i32"),
+    "vec2<i32>" => ("Sint32x2", "glam::IVec2"),
+    "vec3<i32>" => ("Sint32x3", "glam::IVec3"),
+    "vec4<i32>" => ("Sint32x4", "glam::IVec4"),
+    _ => return None,
+  })
+}
+
+///A member of a vertex input struct which is read from the vertex buffer
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct VertexAttribute {
+  pub name: String,
+  pub shader_location: u32,
+  ///the `wgpu::VertexFormat` variant
+  pub format: &'static str,
+  pub rust_type: &'static str,
+}
+
+///Analyzes the members of a vertex input struct. Builtin members like `@builtin(vertex_index)` are
+/// not part of the vertex buffer and get skipped.
+pub fn vertex_attributes(
+  declaration: &TypeDeclaration,
+) -> Result<Vec<VertexAttribute>, VertexLayoutError> {
+  let mut attributes = vec![];
+  for member in &declaration.members {
+    if member
+      .annotation_values
+      .iter()
+      .any(|annotation| annotation.starts_with("builtin"))
+    {
+      continue;
+    }
+
+    let shader_location = member
+      .annotation_values
+      .iter()
+      .find_map(|annotation| {
+        annotation
+          .strip_prefix("location(")?
+          .strip_suffix(')')?
+          .trim()
+          .parse()
+          .ok()
+      })
+      .ok_or(VertexLayoutError::MissingLocation {
+        member_name: member.name.clone(),
+      })?;
+    let (format, rust_type) =
+      vertex_format(&member.r#type).ok_or(VertexLayoutError::UnsupportedType {
+        member_name: member.name.clone(),
+        type_name: member.r#type.clone(),
+      })?;
+    attributes.push(VertexAttribute {
+      name: member.name.clone(),
+      shader_location,
+      format,
+      rust_type,
+    });
+  }
+  Ok(attributes)
+}
+
+///Generates a rust representation of a vertex input struct which can be written to vertex buffers,
+/// together with its vertex attributes and `wgpu::VertexBufferLayout`. Offsets are taken from the
+/// rust struct, so members may need explicit padding in the shader to satisfy `bytemuck::Pod`.
+pub fn generate_vertex_repr(
+  declaration: &TypeDeclaration,
+  repr_name: &str,
+) -> Result<String, VertexLayoutError> {
+  let attributes = vertex_attributes(declaration)?;
+
+  let mut repr = format!(
+    "///Vertex input `{}` as written to vertex buffers\n#[repr(C)]\n#[derive(Debug, Copy, Clone, \
+     PartialEq, bytemuck::Pod, bytemuck::Zeroable)]\npub struct {repr_name} {{\n",
+    declaration.name()
+  );
+  for attribute in &attributes {
+    repr += &format!("  pub {}: {},\n", attribute.name, attribute.rust_type);
+  }
+  repr += "}\n\n";
+
+  repr += &format!("impl {repr_name} {{\n");
+  repr += &format!(
+    "  pub const ATTRIBUTES: [wgpu::VertexAttribute; {}] = [\n",
+    attributes.len()
+  );
+  for attribute in &attributes {
+    repr += &format!(
+      "    wgpu::VertexAttribute {{ format: wgpu::VertexFormat::{}, offset: \
+       std::mem::offset_of!({repr_name}, {}) as wgpu::BufferAddress, shader_location: {} }},\n",
+      attribute.format, attribute.name, attribute.shader_location
+    );
+  }
+  repr += "  ];\n\n";
+  repr += "  pub const fn layout(step_mode: wgpu::VertexStepMode) -> \
+           wgpu::VertexBufferLayout<'static> {\n";
+  repr += "    wgpu::VertexBufferLayout { array_stride: std::mem::size_of::<Self>() as \
+           wgpu::BufferAddress, step_mode, attributes: &Self::ATTRIBUTES }\n";
+  repr += "  }\n}\n";
+  Ok(repr)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum VertexLayoutError {
+  MissingLocation {
+    member_name: String,
+  },
+  UnsupportedType {
+    member_name: String,
+    type_name: String,
+  },
+}
+
+impl Display for VertexLayoutError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      VertexLayoutError::MissingLocation { member_name } => {
+        write!(f, "vertex input member {member_name} needs a @location")
+      }
+      VertexLayoutError::UnsupportedType {
+        member_name,
+        type_name,
+      } => write!(
+        f,
+        "vertex input member {member_name} has type '{type_name}', which is no vertex format"
+      ),
+    }
+  }
+}
+
+impl Error for VertexLayoutError {}
+
+#[cfg(test)]
+mod test_vertex_layout {
+  use crate::type_analysis::member::Member;
+  use crate::type_analysis::type_declaration::TypeDeclaration;
+  use crate::vertex_layout::{
+    generate_vertex_repr, vertex_attributes, VertexAttribute, VertexLayoutError,
+  };
+
+  #[test]
+  fn test_vertex_attributes() {
+    let declaration = TypeDeclaration::new("Vertex")
+      .with_member(Member::new_annotated(
+        &["builtin(vertex_index)"],
+        "index",
+        "u32",
+      ))
+      .with_member(Member::new_annotated(
+        &["location(1)"],
+        "color",
+        "vec4<f32>",
+      ))
+      .with_member(Member::new_annotated(
+        &["location(0)"],
+        "position",
+        "vec3<f32>",
+      ));
+    let attributes = vertex_attributes(&declaration).expect("valid vertex input");
+    assert_eq!(
+      vec![
+        VertexAttribute {
+          name: "color".to_string(),
+          shader_location: 1,
+          format: "Float32x4",
+          rust_type: "glam::Vec4",
+        },
+        VertexAttribute {
+          name: "position".to_string(),
+          shader_location: 0,
+          format: "Float32x3",
+          rust_type: "glam::Vec3",
+        },
+      ],
+      attributes
+    );
+
+    let repr = generate_vertex_repr(&declaration, "VertexRepr").expect("valid vertex input");
+    assert!(repr.contains("pub struct VertexRepr {\n  pub color: glam::Vec4,\n  pub position"));
+    assert!(repr.contains("offset_of!(VertexRepr, position)"));
+    assert!(!repr.contains("index"));
+
+    assert_eq!(
+      Err(VertexLayoutError::MissingLocation {
+        member_name: "position".to_string()
+      }),
+      vertex_attributes(
+        &TypeDeclaration::new("Vertex").with_member(Member::new("position", "vec3<f32>"))
+      )
+    );
+    assert!(vertex_attributes(
+      &TypeDeclaration::new("Vertex").with_member(Member::new_annotated(
+        &["location(0)"],
+        "transform",
+        "mat4x4<f32>"
+      ))
+    )
+    .is_err());
+  }
+}